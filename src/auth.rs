@@ -3,6 +3,7 @@
 use crate::protocol::Protocol;
 use crate::{ConnectionConfig, Error, Result};
 use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
 
 /// Authentication handler
 pub struct Authenticator {
@@ -31,7 +32,7 @@ impl Authenticator {
     fn detect_auth_method(&self) -> AuthMethod {
         if self.config.user.is_empty() && self.config.password.is_empty() {
             AuthMethod::External
-        } else if self.config.password.starts_with("TOKEN:") {
+        } else if self.config.password.expose_secret().starts_with("TOKEN:") {
             AuthMethod::Token
         } else {
             AuthMethod::Password
@@ -46,7 +47,8 @@ impl Authenticator {
         // 3. Send response (AUTH_SESSKEY)
         // 4. Handle success/failure
 
-        let _password_hash = self.hash_password(&self.config.password, b"server_salt");
+        let _password_hash =
+            self.hash_password(self.config.password.expose_secret(), b"server_salt");
 
         // Mock successful authentication
         Ok(())
@@ -64,6 +66,7 @@ impl Authenticator {
         let token = self
             .config
             .password
+            .expose_secret()
             .strip_prefix("TOKEN:")
             .ok_or_else(|| Error::AuthenticationFailed("Invalid token format".into()))?;
 
@@ -77,12 +80,15 @@ impl Authenticator {
         Ok(())
     }
 
-    /// Hash password for Oracle authentication
-    fn hash_password(&self, password: &str, salt: &[u8]) -> Vec<u8> {
+    /// Hash password for Oracle authentication; the digest is wiped on drop
+    fn hash_password(&self, password: &str, salt: &[u8]) -> Zeroizing<Vec<u8>> {
         let mut hasher = Sha256::new();
         hasher.update(password.as_bytes());
         hasher.update(salt);
-        hasher.finalize().to_vec()
+        let mut digest = hasher.finalize();
+        let hash = Zeroizing::new(digest.to_vec());
+        digest.as_mut_slice().fill(0);
+        hash
     }
 }
 
@@ -0,0 +1,125 @@
+// Blocking connection facade
+
+//! A synchronous [`Connection`] for code that does not run on an async
+//! executor: sync web handlers, CLI tools, background daemons.
+//!
+//! Each connection owns a single-threaded tokio runtime that the driver runs
+//! on, so calls block the current thread. Use it from synchronous code or
+//! `spawn_blocking`, never directly inside an async task. Pool these
+//! connections with the `r2d2` feature.
+//!
+//! ```rust,ignore
+//! use oracledb_rs::blocking::Connection;
+//!
+//! let mut conn = Connection::connect(config)?;
+//! let rows = conn.query("SELECT ename FROM emp WHERE deptno = :1", &[&10])?;
+//! conn.execute_dml("UPDATE emp SET sal = sal * 1.1 WHERE deptno = :1", &[&10])?;
+//! conn.commit()?;
+//! ```
+
+use crate::connection::ConnectionInfo;
+use crate::statement::ResultSet;
+use crate::types::ToSql;
+use crate::{ConnectionConfig, Error, Result};
+
+/// Blocking Oracle Database connection
+pub struct Connection {
+    inner: crate::Connection,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl std::fmt::Debug for Connection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Connection")
+            .field("inner", &self.inner.info())
+            .finish()
+    }
+}
+
+impl Connection {
+    /// Establish a connection to Oracle Database
+    pub fn connect(config: ConnectionConfig) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| Error::Connection(format!("failed to start runtime: {}", e)))?;
+        let inner = runtime.block_on(crate::Connection::connect(config))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Wrap a connection opened on `runtime`
+    #[cfg(all(test, feature = "testing"))]
+    pub(crate) fn with_runtime(inner: crate::Connection, runtime: tokio::runtime::Runtime) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Execute a SQL statement
+    pub fn execute(&self, sql: &str, params: &[&dyn ToSql]) -> Result<ResultSet> {
+        self.runtime.block_on(self.inner.execute(sql, params))
+    }
+
+    /// Execute a query and return results
+    pub fn query(&self, sql: &str, params: &[&dyn ToSql]) -> Result<ResultSet> {
+        self.runtime.block_on(self.inner.query(sql, params))
+    }
+
+    /// Execute a DML statement (INSERT, UPDATE, DELETE)
+    pub fn execute_dml(&self, sql: &str, params: &[&dyn ToSql]) -> Result<u64> {
+        self.runtime.block_on(self.inner.execute_dml(sql, params))
+    }
+
+    /// Execute DML once per row of binds, in batches
+    pub fn execute_many<I>(&self, sql: &str, rows: I) -> Result<Vec<u64>>
+    where
+        I: IntoIterator,
+        I::Item: IntoIterator,
+        <I::Item as IntoIterator>::Item: ToSql,
+    {
+        self.runtime.block_on(self.inner.execute_many(sql, rows))
+    }
+
+    /// Commit the current transaction
+    pub fn commit(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.commit())
+    }
+
+    /// Rollback the current transaction
+    pub fn rollback(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.rollback())
+    }
+
+    /// Ping the database to check connection health
+    pub fn ping(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.ping())
+    }
+
+    /// Close the connection
+    pub fn close(self) -> Result<()> {
+        self.runtime.block_on(self.inner.close())
+    }
+
+    /// Get connection information
+    pub fn info(&self) -> ConnectionInfo {
+        self.inner.info()
+    }
+
+    /// The underlying async connection
+    pub fn inner(&self) -> &crate::Connection {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_connect() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let conn = Connection::connect(config).unwrap();
+        let info = conn.info();
+        assert!(info.is_open);
+        assert_eq!(info.user, "user");
+        conn.close().unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+// Memory budget for fetched rows
+
+//! Counts the bytes of fetched rows a connection is holding, so a runaway
+//! query fails with [`Error::ResultTooLarge`] or, when streamed, stops
+//! fetching until the caller catches up, instead of exhausting memory.
+//!
+//! Sizes are measured in wire bytes, which is close to what a row costs
+//! while it stays undecoded.
+
+use crate::{Error, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Shared count of row bytes buffered on one connection
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MemoryBudget {
+    /// Cap in bytes, 0 for none
+    limit: usize,
+    used: Arc<AtomicUsize>,
+}
+
+impl MemoryBudget {
+    /// Budget of `limit` bytes (0 = unlimited)
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            used: Arc::default(),
+        }
+    }
+
+    /// Bytes currently held
+    pub(crate) fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Whether `bytes` more fit without passing the cap
+    pub(crate) fn has_room(&self, bytes: usize) -> bool {
+        self.limit == 0 || self.used() + bytes <= self.limit
+    }
+
+    /// Fail if `size` more bytes would not fit next to what is held
+    pub(crate) fn check(&self, size: usize) -> Result<()> {
+        check_limit(self.used() + size, self.limit)
+    }
+
+    /// Count `bytes` as held
+    pub(crate) fn reserve(&self, bytes: usize) {
+        self.used.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Stop counting `bytes`
+    pub(crate) fn release(&self, bytes: usize) {
+        // Saturate rather than wrap if a caller releases twice
+        let _ = self
+            .used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                Some(used.saturating_sub(bytes))
+            });
+    }
+}
+
+/// Fail with [`Error::ResultTooLarge`] if `size` exceeds a nonzero `limit`
+pub(crate) fn check_limit(size: usize, limit: usize) -> Result<()> {
+    if limit > 0 && size > limit {
+        return Err(Error::ResultTooLarge { size, limit });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_shared_across_clones() {
+        let budget = MemoryBudget::new(100);
+        let stream = budget.clone();
+        stream.reserve(80);
+        assert!(budget.has_room(20));
+        assert!(!budget.has_room(21));
+        assert!(matches!(
+            budget.check(30),
+            Err(Error::ResultTooLarge {
+                size: 110,
+                limit: 100
+            })
+        ));
+
+        stream.reserve(20);
+        assert!(!budget.has_room(1));
+        stream.release(100);
+        stream.release(1);
+        assert_eq!(budget.used(), 0);
+        assert!(budget.check(100).is_ok());
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let budget = MemoryBudget::new(0);
+        budget.reserve(usize::MAX / 2);
+        assert!(budget.has_room(1));
+        assert!(budget.check(1).is_ok());
+    }
+}
@@ -0,0 +1,183 @@
+// Reusable I/O buffers
+
+//! Each session keeps a small pool of [`BytesMut`] buffers for packet reads
+//! and writes, bind encoding and fetch scratch space, so tight statement
+//! loops stop allocating once the pool is warm. The notification listener
+//! keeps one for the packets it reads.
+//!
+//! A buffer goes back to the pool when its [`PooledBuffer`] is dropped.
+//! Data split off and frozen into [`Bytes`](bytes::Bytes) (fetched rows
+//! keep such slices) stays valid: the pool only reclaims the allocation
+//! once every slice of it is gone, and allocates around it until then.
+
+use bytes::BytesMut;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Buffers kept per session
+pub(crate) const DEFAULT_POOLED_BUFFERS: usize = 4;
+
+/// Initial capacity of a buffer, one SDU
+pub(crate) const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Buffers grown beyond this are freed instead of pooled
+const MAX_RETAINED_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug)]
+struct Inner {
+    buffers: Mutex<Vec<BytesMut>>,
+    max_buffers: usize,
+    buffer_size: usize,
+    reused: AtomicU64,
+    allocated: AtomicU64,
+}
+
+/// Pool of reusable byte buffers
+#[derive(Debug, Clone)]
+pub(crate) struct BufferPool {
+    inner: Arc<Inner>,
+}
+
+impl BufferPool {
+    /// Keep up to `max_buffers` buffers of at least `buffer_size` bytes
+    pub(crate) fn new(max_buffers: usize, buffer_size: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                buffers: Mutex::new(Vec::with_capacity(max_buffers)),
+                max_buffers,
+                buffer_size,
+                reused: AtomicU64::new(0),
+                allocated: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Take an empty buffer from the pool, allocating if none is free
+    pub(crate) fn get(&self) -> PooledBuffer {
+        let pooled = self
+            .inner
+            .buffers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop();
+        let buf = match pooled {
+            Some(mut buf) => {
+                // Reclaims the whole allocation once frozen slices are dropped
+                buf.reserve(self.inner.buffer_size);
+                self.inner.reused.fetch_add(1, Ordering::Relaxed);
+                buf
+            }
+            None => {
+                self.inner.allocated.fetch_add(1, Ordering::Relaxed);
+                BytesMut::with_capacity(self.inner.buffer_size)
+            }
+        };
+        PooledBuffer {
+            buf: Some(buf),
+            pool: self.inner.clone(),
+        }
+    }
+
+    /// Buffers handed out from the pool and freshly allocated
+    pub(crate) fn stats(&self) -> (u64, u64) {
+        (
+            self.inner.reused.load(Ordering::Relaxed),
+            self.inner.allocated.load(Ordering::Relaxed),
+        )
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_POOLED_BUFFERS, DEFAULT_BUFFER_SIZE)
+    }
+}
+
+/// Buffer on loan from a [`BufferPool`]
+#[derive(Debug)]
+pub(crate) struct PooledBuffer {
+    buf: Option<BytesMut>,
+    pool: Arc<Inner>,
+}
+
+impl Deref for PooledBuffer {
+    type Target = BytesMut;
+
+    fn deref(&self) -> &BytesMut {
+        self.buf.as_ref().expect("buffer present until drop")
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut BytesMut {
+        self.buf.as_mut().expect("buffer present until drop")
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        let Some(mut buf) = self.buf.take() else {
+            return;
+        };
+        if buf.capacity() > MAX_RETAINED_SIZE {
+            return;
+        }
+        buf.clear();
+        let mut buffers = self.pool.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        if buffers.len() < self.pool.max_buffers {
+            buffers.push(buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BufMut;
+
+    #[test]
+    fn test_buffers_are_reused() {
+        let pool = BufferPool::new(2, 64);
+        let mut buf = pool.get();
+        buf.put_slice(b"packet");
+        let ptr = buf.as_ptr();
+        drop(buf);
+
+        let buf = pool.get();
+        assert!(buf.is_empty());
+        assert_eq!(buf.as_ptr(), ptr);
+        assert_eq!(pool.stats(), (1, 1));
+
+        let mut big = pool.get();
+        big.reserve(MAX_RETAINED_SIZE + 1);
+        drop(big);
+        drop(buf);
+        assert_eq!(pool.inner.buffers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_frozen_slices_outlive_loan() {
+        let pool = BufferPool::new(1, 64);
+        let mut buf = pool.get();
+        buf.put_slice(b"row data");
+        let rows = buf.split().freeze();
+        let ptr = rows.as_ptr();
+        drop(buf);
+
+        // Once the rows are gone the whole allocation is reclaimed
+        drop(rows);
+        let mut buf = pool.get();
+        assert_eq!(buf.as_ptr(), ptr);
+        assert!(buf.capacity() >= 64);
+
+        // While rows hold it, the pool allocates around them
+        buf.put_slice(b"row data");
+        let rows = buf.split().freeze();
+        drop(buf);
+        let mut buf = pool.get();
+        buf.put_slice(b"next batch");
+        assert_eq!(&rows[..], b"row data");
+        assert_ne!(buf.as_ptr(), ptr);
+    }
+}
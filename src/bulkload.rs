@@ -0,0 +1,450 @@
+// Bulk loading
+
+//! A small SQL*Loader: reads rows from CSV or an iterator and inserts them
+//! into a table with array DML.
+//!
+//! Rows are sent in batches of [`BulkLoader::batch_size`]. Each batch runs
+//! behind a savepoint; when it fails, the batch is rolled back and retried
+//! row by row so that only the offending rows are rejected. Rejected rows
+//! are collected in the [`LoadReport`] and the load stops once more than
+//! [`BulkLoader::max_errors`] have been rejected, like SQL*Loader's `ERRORS`.
+//!
+//! CSV fields are bound as strings, leaving conversion to the column types
+//! to Oracle; empty fields load as NULL. Table and column names are written
+//! into the SQL verbatim and must come from trusted input.
+//!
+//! The loader does not commit unless [`BulkLoader::commit_each_batch`] is
+//! set, so by default the whole load can still be rolled back.
+//!
+//! ```rust,ignore
+//! use oracledb_rs::bulkload::BulkLoader;
+//!
+//! let loader = BulkLoader::new("EMP")
+//!     .map("employee id", "EMPNO")
+//!     .map("name", "ENAME")
+//!     .batch_size(5_000)
+//!     .max_errors(100)
+//!     .on_progress(|p| eprintln!("{} rows loaded", p.rows_loaded));
+//!
+//! let report = loader.load_csv(&mut conn, File::open("emp.csv")?).await?;
+//! conn.commit().await?;
+//! ```
+
+use crate::connection::Connection;
+use crate::types::{ToSql, Value};
+use crate::{Error, Result};
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+/// Default number of rows per array DML batch
+pub const DEFAULT_BATCH_SIZE: usize = 1_000;
+
+const SAVEPOINT: &str = "bulkload_batch";
+
+/// Load progress, reported after each batch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Source rows read so far
+    pub rows_read: u64,
+    /// Rows inserted so far
+    pub rows_loaded: u64,
+    /// Batches executed so far
+    pub batches: u64,
+}
+
+/// A source row that could not be loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedRow {
+    /// 1-based position of the row in the source, excluding any CSV header
+    pub row: u64,
+    /// Why the row was rejected
+    pub error: String,
+}
+
+/// Outcome of a load
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    /// Final progress counters
+    pub progress: Progress,
+    /// Rows that were not loaded
+    pub rejected: Vec<RejectedRow>,
+    /// Whether the load stopped early because of `max_errors`
+    pub aborted: bool,
+}
+
+impl LoadReport {
+    /// Whether every source row was read and none were rejected
+    pub fn is_complete(&self) -> bool {
+        !self.aborted && self.rejected.is_empty()
+    }
+}
+
+/// Callback invoked with load progress
+pub type ProgressCallback = Arc<dyn Fn(&Progress) + Send + Sync>;
+
+#[derive(Debug, Clone)]
+enum Source {
+    Position(usize),
+    Header(String),
+}
+
+#[derive(Debug, Clone)]
+struct Column {
+    target: String,
+    source: Source,
+}
+
+/// Loads rows into a table with batched array DML
+#[derive(Clone)]
+pub struct BulkLoader {
+    table: String,
+    columns: Vec<Column>,
+    batch_size: usize,
+    max_errors: usize,
+    commit_each_batch: bool,
+    progress: Option<ProgressCallback>,
+}
+
+impl BulkLoader {
+    /// Load into `table`
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            columns: Vec::new(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            max_errors: 0,
+            commit_each_batch: false,
+            progress: None,
+        }
+    }
+
+    /// Add a target column fed by the next source field by position
+    pub fn column(mut self, target: impl Into<String>) -> Self {
+        let position = self.columns.len();
+        self.columns.push(Column {
+            target: target.into(),
+            source: Source::Position(position),
+        });
+        self
+    }
+
+    /// Add a target column fed by the CSV field under `header`
+    ///
+    /// Header names are matched case-insensitively. Iterator sources ignore
+    /// the header and supply values in column order.
+    pub fn map(mut self, header: impl Into<String>, target: impl Into<String>) -> Self {
+        self.columns.push(Column {
+            target: target.into(),
+            source: Source::Header(header.into()),
+        });
+        self
+    }
+
+    /// Rows per array DML batch
+    pub fn batch_size(mut self, rows: usize) -> Self {
+        self.batch_size = rows.max(1);
+        self
+    }
+
+    /// Rejected rows tolerated before the load stops (default 0)
+    pub fn max_errors(mut self, rows: usize) -> Self {
+        self.max_errors = rows;
+        self
+    }
+
+    /// Commit after every successful batch
+    pub fn commit_each_batch(mut self, enabled: bool) -> Self {
+        self.commit_each_batch = enabled;
+        self
+    }
+
+    /// Call `callback` after each batch
+    pub fn on_progress(mut self, callback: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// The INSERT statement used for each row
+    pub fn insert_sql(&self) -> String {
+        let columns: Vec<&str> = self.columns.iter().map(|c| c.target.as_str()).collect();
+        let binds: Vec<String> = (1..=self.columns.len())
+            .map(|i| format!(":{}", i))
+            .collect();
+        format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            self.table,
+            columns.join(", "),
+            binds.join(", ")
+        )
+    }
+
+    /// Load rows whose values are in column order
+    pub async fn load<I>(&self, conn: &mut Connection, rows: I) -> Result<LoadReport>
+    where
+        I: IntoIterator<Item = Result<Vec<Value>>>,
+    {
+        if self.columns.is_empty() {
+            return Err(Error::InvalidConfiguration(
+                "bulk load needs at least one column".into(),
+            ));
+        }
+
+        let sql = self.insert_sql();
+        let mut report = LoadReport::default();
+        let mut batch: Vec<(u64, Vec<Value>)> = Vec::with_capacity(self.batch_size);
+
+        for row in rows {
+            report.progress.rows_read += 1;
+            let number = report.progress.rows_read;
+            match row {
+                Ok(values) if values.len() == self.columns.len() => batch.push((number, values)),
+                Ok(values) => self.reject(
+                    &mut report,
+                    number,
+                    format!(
+                        "expected {} values, found {}",
+                        self.columns.len(),
+                        values.len()
+                    ),
+                ),
+                Err(e) => self.reject(&mut report, number, e.to_string()),
+            }
+            if report.aborted {
+                return Ok(report);
+            }
+            if batch.len() == self.batch_size {
+                self.flush(conn, &sql, &mut batch, &mut report).await?;
+                if report.aborted {
+                    return Ok(report);
+                }
+            }
+        }
+        if !batch.is_empty() {
+            self.flush(conn, &sql, &mut batch, &mut report).await?;
+        }
+        Ok(report)
+    }
+
+    /// Load CSV with a header row
+    pub async fn load_csv<R: Read>(&self, conn: &mut Connection, reader: R) -> Result<LoadReport> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .flexible(true)
+            .from_reader(reader);
+        let headers = reader
+            .headers()
+            .map_err(|e| Error::InvalidData(format!("CSV header: {}", e)))?
+            .clone();
+        let positions = self.resolve(&headers)?;
+
+        let rows = reader.into_records().map(|record| {
+            let record = record.map_err(|e| Error::InvalidData(format!("CSV: {}", e)))?;
+            positions
+                .iter()
+                .map(|&i| match record.get(i) {
+                    Some("") => Ok(Value::Null),
+                    Some(field) => Ok(Value::String(field.to_string())),
+                    None => Err(Error::InvalidData(format!("missing field {}", i + 1))),
+                })
+                .collect()
+        });
+        self.load(conn, rows).await
+    }
+
+    /// CSV field index for each target column
+    fn resolve(&self, headers: &csv::StringRecord) -> Result<Vec<usize>> {
+        self.columns
+            .iter()
+            .map(|column| match &column.source {
+                Source::Position(i) => Ok(*i),
+                Source::Header(name) => headers
+                    .iter()
+                    .position(|h| h.trim().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        Error::InvalidConfiguration(format!("CSV has no column '{}'", name))
+                    }),
+            })
+            .collect()
+    }
+
+    fn reject(&self, report: &mut LoadReport, row: u64, error: String) {
+        report.rejected.push(RejectedRow { row, error });
+        if report.rejected.len() > self.max_errors {
+            report.aborted = true;
+        }
+    }
+
+    /// Insert a batch, isolating failing rows if the array DML fails
+    async fn flush(
+        &self,
+        conn: &mut Connection,
+        sql: &str,
+        batch: &mut Vec<(u64, Vec<Value>)>,
+        report: &mut LoadReport,
+    ) -> Result<()> {
+        report.progress.batches += 1;
+        conn.execute_dml(&format!("SAVEPOINT {}", SAVEPOINT), &[])
+            .await?;
+
+        let params: Vec<Vec<&dyn ToSql>> = batch
+            .iter()
+            .map(|(_, values)| values.iter().map(|v| v as &dyn ToSql).collect())
+            .collect();
+        match conn.execute_many(sql, &params).await {
+            Ok(counts) => report.progress.rows_loaded += counts.iter().sum::<u64>(),
+            Err(e) if e.is_connection_error() => return Err(e),
+            Err(_) => {
+                conn.execute_dml(&format!("ROLLBACK TO SAVEPOINT {}", SAVEPOINT), &[])
+                    .await?;
+                for ((number, _), row) in batch.iter().zip(&params) {
+                    match conn.execute_dml(sql, row).await {
+                        Ok(count) => report.progress.rows_loaded += count,
+                        Err(e) if e.is_connection_error() => return Err(e),
+                        Err(e) => {
+                            self.reject(report, *number, e.to_string());
+                            if report.aborted {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        batch.clear();
+
+        if self.commit_each_batch && !report.aborted {
+            conn.commit().await?;
+        }
+        if let Some(progress) = &self.progress {
+            progress(&report.progress);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BulkLoader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkLoader")
+            .field("table", &self.table)
+            .field("columns", &self.columns)
+            .field("batch_size", &self.batch_size)
+            .field("max_errors", &self.max_errors)
+            .field("commit_each_batch", &self.commit_each_batch)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_sql_and_headers() {
+        let loader = BulkLoader::new("EMP")
+            .map("Employee ID", "EMPNO")
+            .map("name", "ENAME");
+        assert_eq!(
+            loader.insert_sql(),
+            "INSERT INTO EMP (EMPNO, ENAME) VALUES (:1, :2)"
+        );
+
+        let headers = csv::StringRecord::from(vec!["NAME", "employee id"]);
+        assert_eq!(loader.resolve(&headers).unwrap(), vec![1, 0]);
+
+        let loader = loader.map("hired", "HIREDATE");
+        assert!(matches!(
+            loader.resolve(&headers),
+            Err(Error::InvalidConfiguration(_))
+        ));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_failed_batch_rolls_back_to_savepoint() {
+        let mut conn = crate::testing::unscripted().await.unwrap();
+        let loader = BulkLoader::new("DOCS")
+            .column("ID")
+            .column("BODY")
+            .batch_size(3)
+            .max_errors(1);
+
+        // The oversized RAW fails the array insert, so the batch is
+        // rolled back and retried row by row
+        let rows = vec![
+            Ok(vec![Value::Integer(1), Value::Bytes(vec![0; 16])]),
+            Ok(vec![Value::Integer(2), Value::Bytes(vec![0; 32768])]),
+            Ok(vec![Value::Integer(3), Value::Bytes(vec![0; 16])]),
+        ];
+        let report = loader.load(&mut conn, rows).await.unwrap();
+        assert!(!report.aborted);
+        assert_eq!(report.progress.rows_loaded, 2);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].row, 2);
+        assert!(report.rejected[0].error.contains("32768 bytes"));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_failed_batch_isolates_rows() {
+        use crate::testing::{Expectation, MockConnection};
+
+        let insert = "INSERT INTO EMP (EMPNO, ENAME) VALUES (:1, :2)";
+        let row = |id: &str, name: &str| vec![Value::String(id.into()), Value::String(name.into())];
+        let mock = MockConnection::new()
+            .expect(Expectation::dml("SAVEPOINT bulkload_batch", 0))
+            .expect(Expectation::dml(insert, 1).params(row("1", "KING")))
+            .expect(
+                Expectation::dml(insert, 0)
+                    .params(vec![Value::String("x".into()), Value::Null])
+                    .fails(Error::oracle(1722, "invalid number")),
+            )
+            .expect(Expectation::dml("ROLLBACK TO SAVEPOINT bulkload_batch", 0))
+            .expect(Expectation::dml(insert, 1).params(row("1", "KING")))
+            .expect(
+                Expectation::dml(insert, 0)
+                    .params(vec![Value::String("x".into()), Value::Null])
+                    .fails(Error::oracle(1722, "invalid number")),
+            )
+            .expect(Expectation::dml("SAVEPOINT bulkload_batch", 0))
+            .expect(Expectation::dml(insert, 1).params(row("3", "FORD")));
+        let mut conn = mock.connect().await.unwrap();
+
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let loader = BulkLoader::new("EMP")
+            .map("empno", "EMPNO")
+            .map("ename", "ENAME")
+            .batch_size(2)
+            .max_errors(1)
+            .on_progress(move |p| sink.lock().unwrap().push(*p));
+
+        let csv = "ename,empno\nKING,1\n,x\nFORD,3\n";
+        let report = loader.load_csv(&mut conn, csv.as_bytes()).await.unwrap();
+        mock.verify();
+
+        assert!(!report.aborted);
+        assert_eq!(report.progress.rows_read, 3);
+        assert_eq!(report.progress.rows_loaded, 2);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].row, 2);
+        assert!(report.rejected[0].error.contains("ORA-01722"));
+        assert_eq!(seen.lock().unwrap().len(), 2);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_max_errors_aborts() {
+        use crate::testing::MockConnection;
+
+        let mut conn = MockConnection::new().connect().await.unwrap();
+        let loader = BulkLoader::new("EMP").column("EMPNO").column("ENAME");
+        let rows = vec![Ok(vec![Value::Integer(1)]), Ok(vec![Value::Integer(2)])];
+
+        let report = loader.load(&mut conn, rows).await.unwrap();
+        assert!(report.aborted);
+        assert!(!report.is_complete());
+        assert_eq!(report.progress.rows_read, 1);
+        assert_eq!(report.rejected[0].error, "expected 2 values, found 1");
+    }
+}
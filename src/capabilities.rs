@@ -0,0 +1,210 @@
+// Driver capabilities
+
+//! Which driver features a connection can use, given its mode and the
+//! server release.
+//!
+//! Some features are not implemented in thin mode, and some need a recent
+//! server. Thick mode does not implement any feature yet, so a thick
+//! connection reports none of them. Rather than
+//! attempting a call and handling [`Error::UnsupportedFeature`], ask the
+//! connection first:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::capabilities::Feature;
+//!
+//! let caps = conn.capabilities();
+//! if caps.supports(Feature::Vector) {
+//!     conn.execute("SELECT id FROM docs ORDER BY VECTOR_DISTANCE(embedding, :1) \
+//!                   FETCH FIRST 5 ROWS ONLY", &[&query]).await?;
+//! }
+//! ```
+//!
+//! [`Feature::ALL`] with [`Feature::in_thin`], [`Feature::in_thick`] and
+//! [`Feature::min_server_version`] gives the full parity matrix, for
+//! example to skip integration tests a CI database cannot run.
+
+use crate::connection::ConnectionMode;
+use crate::{Error, Result};
+use std::fmt;
+
+/// A driver feature whose availability depends on mode or server release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// BOOLEAN binds in PL/SQL
+    PlSqlBoolean,
+    /// BOOLEAN columns and binds in SQL
+    SqlBoolean,
+    /// Native JSON columns, exchanged as OSON
+    NativeJson,
+    /// VECTOR columns and vector search
+    Vector,
+    /// Several statements sent in one round trip
+    Pipelining,
+    /// Two-phase commit of global (XA) transactions
+    TwoPhaseCommit,
+    /// Client result cache for `RESULT_CACHE` queries
+    ClientResultCache,
+    /// Advanced Queuing enqueue and dequeue
+    AdvancedQueuing,
+    /// Continuous Query Notification subscriptions
+    ContinuousQueryNotification,
+    /// Sharding keys for routing to a shard
+    Sharding,
+    /// Implicit connection pooling, releasing pooled servers at statement
+    /// or transaction boundaries
+    ImplicitPooling,
+}
+
+impl Feature {
+    /// Every feature, in declaration order
+    pub const ALL: &'static [Feature] = &[
+        Feature::PlSqlBoolean,
+        Feature::SqlBoolean,
+        Feature::NativeJson,
+        Feature::Vector,
+        Feature::Pipelining,
+        Feature::TwoPhaseCommit,
+        Feature::ClientResultCache,
+        Feature::AdvancedQueuing,
+        Feature::ContinuousQueryNotification,
+        Feature::Sharding,
+        Feature::ImplicitPooling,
+    ];
+
+    /// Whether thin mode implements the feature
+    pub fn in_thin(self) -> bool {
+        !matches!(
+            self,
+            Feature::AdvancedQueuing | Feature::ContinuousQueryNotification | Feature::Sharding
+        )
+    }
+
+    /// Whether thick mode implements the feature
+    ///
+    /// Always false until thick mode goes beyond loading the client
+    /// libraries.
+    pub fn in_thick(self) -> bool {
+        false
+    }
+
+    /// Whether `mode` implements the feature
+    pub fn in_mode(self, mode: ConnectionMode) -> bool {
+        match mode {
+            ConnectionMode::Thin => self.in_thin(),
+            ConnectionMode::Thick => self.in_thick(),
+        }
+    }
+
+    /// Oldest server release that supports the feature, if it needs more
+    /// than the driver's minimum
+    pub fn min_server_version(self) -> Option<(u8, u8)> {
+        match self {
+            Feature::SqlBoolean | Feature::Pipelining | Feature::ImplicitPooling => Some((23, 0)),
+            Feature::Vector => Some((23, 4)),
+            Feature::NativeJson => Some((21, 0)),
+            Feature::Sharding => Some((12, 2)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Feature::PlSqlBoolean => "PL/SQL BOOLEAN binds",
+            Feature::SqlBoolean => "SQL BOOLEAN",
+            Feature::NativeJson => "native JSON",
+            Feature::Vector => "VECTOR",
+            Feature::Pipelining => "pipelining",
+            Feature::TwoPhaseCommit => "two-phase commit",
+            Feature::ClientResultCache => "client result cache",
+            Feature::AdvancedQueuing => "Advanced Queuing",
+            Feature::ContinuousQueryNotification => "Continuous Query Notification",
+            Feature::Sharding => "sharding",
+            Feature::ImplicitPooling => "implicit connection pooling",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Features available to one connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Mode of the connection
+    pub mode: ConnectionMode,
+    /// Major and minor release of the server
+    pub server_version: (u8, u8),
+}
+
+impl Capabilities {
+    /// Capabilities of a `mode` connection to a `server_version` server
+    pub fn new(mode: ConnectionMode, server_version: (u8, u8)) -> Self {
+        Self {
+            mode,
+            server_version,
+        }
+    }
+
+    /// Whether the connection can use `feature`
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.require(feature).is_ok()
+    }
+
+    /// Features the connection can use
+    pub fn available(&self) -> Vec<Feature> {
+        Feature::ALL
+            .iter()
+            .copied()
+            .filter(|&f| self.supports(f))
+            .collect()
+    }
+
+    /// Fail with [`Error::UnsupportedFeature`], saying why, unless the
+    /// connection can use `feature`
+    pub fn require(&self, feature: Feature) -> Result<()> {
+        if !feature.in_mode(self.mode) {
+            let mode = match self.mode {
+                ConnectionMode::Thin => "thin",
+                ConnectionMode::Thick => "thick",
+            };
+            return Err(Error::UnsupportedFeature(format!(
+                "{} is not implemented in {} mode",
+                feature, mode
+            )));
+        }
+        match feature.min_server_version() {
+            Some(required) if self.server_version < required => {
+                Err(Error::UnsupportedFeature(format!(
+                    "{} needs Oracle {}.{} or later, server is {}.{}",
+                    feature, required.0, required.1, self.server_version.0, self.server_version.1
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_and_version() {
+        let thin = Capabilities::new(ConnectionMode::Thin, (19, 0));
+        assert!(thin.supports(Feature::PlSqlBoolean));
+        assert!(!thin.supports(Feature::NativeJson));
+        let err = thin.require(Feature::AdvancedQueuing).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Advanced Queuing is not implemented in thin mode"));
+
+        let thin = Capabilities::new(ConnectionMode::Thin, (23, 4));
+        assert!(thin.supports(Feature::Vector));
+        assert!(!thin.supports(Feature::Sharding));
+
+        // Thick mode is a stub, so it claims nothing
+        let thick = Capabilities::new(ConnectionMode::Thick, (23, 4));
+        assert!(thick.available().is_empty());
+        assert!(!thick.supports(Feature::PlSqlBoolean));
+    }
+}
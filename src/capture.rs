@@ -0,0 +1,396 @@
+// Wire capture and replay
+
+//! Records the bytes a session exchanges with the server and plays them
+//! back later, so the codec layers can be tested against real server
+//! traffic without a database in CI.
+//!
+//! [`Recorder`] wraps any transport stream and captures what passes through
+//! it. Save the [`Capture`] to a file, check it in, and hand a [`Replay`] of
+//! it to the code under test: the replay serves the recorded server packets
+//! and fails with [`io::ErrorKind::InvalidData`] as soon as the client sends
+//! something other than what was recorded.
+//!
+//! ```rust,ignore
+//! use oracledb_rs::capture::{Capture, Recorder, Replay};
+//! use oracledb_rs::runtime::{DefaultRuntime, Runtime, SocketOptions};
+//!
+//! // Once, against a live server
+//! let stream = DefaultRuntime::connect_tcp("db.example.com", 1521, &SocketOptions::default()).await?;
+//! let mut recorder = Recorder::new(stream);
+//! run_handshake(&mut recorder).await?;
+//! recorder.capture().save("tests/captures/handshake.ocap")?;
+//!
+//! // In CI
+//! let mut replay = Replay::new(Capture::load("tests/captures/handshake.ocap")?);
+//! run_handshake(&mut replay).await?;
+//! assert!(replay.is_finished());
+//! ```
+//!
+//! Capture files start with the magic bytes `OCAP` and a version byte,
+//! followed by one record per exchange: a direction byte (`C` or `S`), the
+//! payload length as a big-endian `u32`, and the payload.
+
+use crate::{Error, Result};
+use futures::io::{AsyncRead, AsyncWrite};
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+const MAGIC: &[u8; 4] = b"OCAP";
+const VERSION: u8 = 1;
+
+/// Which side sent an exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Client to server
+    Client,
+    /// Server to client
+    Server,
+}
+
+/// Bytes sent in one direction before the other side answered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exchange {
+    /// Sender
+    pub direction: Direction,
+    /// Raw bytes as they crossed the wire
+    pub data: Vec<u8>,
+}
+
+/// Recorded conversation between a client and the server
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capture {
+    exchanges: Vec<Exchange>,
+}
+
+impl Capture {
+    /// Create an empty capture
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recorded exchanges in order
+    pub fn exchanges(&self) -> &[Exchange] {
+        &self.exchanges
+    }
+
+    /// Append bytes, merging them into the last exchange if it has the same sender
+    pub fn push(&mut self, direction: Direction, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        match self.exchanges.last_mut() {
+            Some(last) if last.direction == direction => last.data.extend_from_slice(data),
+            _ => self.exchanges.push(Exchange {
+                direction,
+                data: data.to_vec(),
+            }),
+        }
+    }
+
+    /// Serialize to the capture file format
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(
+            5 + self
+                .exchanges
+                .iter()
+                .map(|e| 5 + e.data.len())
+                .sum::<usize>(),
+        );
+        buf.extend_from_slice(MAGIC);
+        buf.push(VERSION);
+        for exchange in &self.exchanges {
+            buf.push(match exchange.direction {
+                Direction::Client => b'C',
+                Direction::Server => b'S',
+            });
+            buf.extend_from_slice(&(exchange.data.len() as u32).to_be_bytes());
+            buf.extend_from_slice(&exchange.data);
+        }
+        buf
+    }
+
+    /// Parse the capture file format
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let rest = bytes
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| Error::InvalidData("not a capture file".into()))?;
+        let (&version, mut rest) = rest
+            .split_first()
+            .ok_or_else(|| Error::InvalidData("truncated capture header".into()))?;
+        if version != VERSION {
+            return Err(Error::InvalidData(format!(
+                "unsupported capture version {}",
+                version
+            )));
+        }
+
+        let mut capture = Self::new();
+        while let Some((&tag, tail)) = rest.split_first() {
+            let direction = match tag {
+                b'C' => Direction::Client,
+                b'S' => Direction::Server,
+                other => {
+                    return Err(Error::InvalidData(format!(
+                        "invalid exchange direction {:#04x}",
+                        other
+                    )))
+                }
+            };
+            if tail.len() < 4 {
+                return Err(Error::InvalidData("truncated exchange header".into()));
+            }
+            let (len, tail) = tail.split_at(4);
+            let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+            if tail.len() < len {
+                return Err(Error::InvalidData("truncated exchange payload".into()));
+            }
+            let (data, tail) = tail.split_at(len);
+            capture.exchanges.push(Exchange {
+                direction,
+                data: data.to_vec(),
+            });
+            rest = tail;
+        }
+        Ok(capture)
+    }
+
+    /// Read a capture file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    /// Write a capture file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.encode())?;
+        Ok(())
+    }
+}
+
+/// Transport wrapper that records traffic in both directions
+#[derive(Debug)]
+pub struct Recorder<S> {
+    inner: S,
+    capture: Capture,
+}
+
+impl<S> Recorder<S> {
+    /// Record traffic passing through `inner`
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            capture: Capture::new(),
+        }
+    }
+
+    /// Traffic recorded so far
+    pub fn capture(&self) -> &Capture {
+        &self.capture
+    }
+
+    /// Unwrap the stream and the recorded traffic
+    pub fn into_parts(self) -> (S, Capture) {
+        (self.inner, self.capture)
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Recorder<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let n = futures::ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.capture.push(Direction::Server, &buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Recorder<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let n = futures::ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        this.capture.push(Direction::Client, &buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+/// Transport that serves a capture back to the client
+#[derive(Debug)]
+pub struct Replay {
+    capture: Capture,
+    index: usize,
+    offset: usize,
+}
+
+impl Replay {
+    /// Replay `capture` from the start
+    pub fn new(capture: Capture) -> Self {
+        Self {
+            capture,
+            index: 0,
+            offset: 0,
+        }
+    }
+
+    /// Whether every recorded exchange has been consumed
+    pub fn is_finished(&self) -> bool {
+        self.index >= self.capture.exchanges.len()
+    }
+
+    /// Remaining bytes of the current exchange if it was sent by `direction`
+    fn pending(&self, direction: Direction) -> io::Result<Option<&[u8]>> {
+        match self.capture.exchanges.get(self.index) {
+            None => Ok(None),
+            Some(exchange) if exchange.direction == direction => {
+                Ok(Some(&exchange.data[self.offset..]))
+            }
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "replay expected {:?} data at exchange {}",
+                    self.capture.exchanges[self.index].direction, self.index
+                ),
+            )),
+        }
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.offset += n;
+        if self.offset == self.capture.exchanges[self.index].data.len() {
+            self.index += 1;
+            self.offset = 0;
+        }
+    }
+}
+
+impl AsyncRead for Replay {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let Some(pending) = self.pending(Direction::Server)? else {
+            return Poll::Ready(Ok(0));
+        };
+        let n = pending.len().min(buf.len());
+        buf[..n].copy_from_slice(&pending[..n]);
+        self.advance(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for Replay {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let index = self.index;
+        let pending = self
+            .pending(Direction::Client)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "replay has no data left"))?;
+        let n = pending.len().min(buf.len());
+        if buf[..n] != pending[..n] {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("client data differs from capture at exchange {}", index),
+            )));
+        }
+        self.advance(n);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+
+    async fn record() -> Capture {
+        let mut recorder = Recorder::new(Cursor::new(b"____pong".to_vec()));
+        recorder.write_all(b"pi").await.unwrap();
+        recorder.write_all(b"ng").await.unwrap();
+        let mut reply = [0u8; 4];
+        recorder.read_exact(&mut reply).await.unwrap();
+        assert_eq!(&reply, b"pong");
+        recorder.into_parts().1
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let capture = record().await;
+        assert_eq!(
+            capture.exchanges(),
+            &[
+                Exchange {
+                    direction: Direction::Client,
+                    data: b"ping".to_vec()
+                },
+                Exchange {
+                    direction: Direction::Server,
+                    data: b"pong".to_vec()
+                },
+            ]
+        );
+
+        let mut replay = Replay::new(capture);
+        replay.write_all(b"ping").await.unwrap();
+        let mut reply = Vec::new();
+        replay.read_to_end(&mut reply).await.unwrap();
+        assert_eq!(reply, b"pong");
+        assert!(replay.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_replay_rejects_divergence() {
+        let mut replay = Replay::new(record().await);
+        let err = replay.write_all(b"peng").await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut replay = Replay::new(record().await);
+        let mut reply = [0u8; 4];
+        assert!(replay.read_exact(&mut reply).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_round_trip() {
+        let capture = record().await;
+        let path =
+            std::env::temp_dir().join(format!("oracledb-capture-{}.ocap", std::process::id()));
+        capture.save(&path).unwrap();
+        let loaded = Capture::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, capture);
+
+        let mut truncated = capture.encode();
+        truncated.pop();
+        assert!(matches!(
+            Capture::decode(&truncated),
+            Err(Error::InvalidData(_))
+        ));
+        assert!(Capture::decode(b"nope").is_err());
+    }
+}
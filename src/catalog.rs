@@ -0,0 +1,598 @@
+// Schema introspection via the Oracle data dictionary
+
+use crate::statement::{FromRow, Row};
+use crate::types::OracleType;
+use crate::{Connection, Result};
+
+/// Data dictionary queries bound to a connection
+///
+/// Object and owner names are matched exactly as stored in the dictionary,
+/// which for unquoted identifiers means upper case.
+pub struct Catalog<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> Catalog<'a> {
+    /// Create a catalog reader for a connection
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// List schemas visible to the current user
+    pub async fn schemas(&self) -> Result<Vec<SchemaInfo>> {
+        self.conn
+            .query(
+                "SELECT username, created, oracle_maintained FROM all_users ORDER BY username",
+                &[],
+            )
+            .await?
+            .as_typed()
+    }
+
+    /// List tables owned by a schema
+    pub async fn tables(&self, owner: &str) -> Result<Vec<TableInfo>> {
+        self.conn
+            .query(
+                "SELECT t.owner, t.table_name, t.tablespace_name, t.temporary, \
+                 t.partitioned, t.num_rows, c.comments \
+                 FROM all_tables t \
+                 LEFT JOIN all_tab_comments c \
+                 ON c.owner = t.owner AND c.table_name = t.table_name \
+                 WHERE t.owner = :1 ORDER BY t.table_name",
+                &[&owner],
+            )
+            .await?
+            .as_typed()
+    }
+
+    /// List views owned by a schema
+    pub async fn views(&self, owner: &str) -> Result<Vec<ViewInfo>> {
+        self.conn
+            .query(
+                "SELECT owner, view_name, text_vc, read_only \
+                 FROM all_views WHERE owner = :1 ORDER BY view_name",
+                &[&owner],
+            )
+            .await?
+            .as_typed()
+    }
+
+    /// List the columns of a table or view in declaration order
+    pub async fn columns(&self, owner: &str, table: &str) -> Result<Vec<TableColumn>> {
+        self.conn
+            .query(
+                "SELECT column_id, column_name, data_type, data_length, char_length, \
+                 data_precision, data_scale, nullable, data_default \
+                 FROM all_tab_columns \
+                 WHERE owner = :1 AND table_name = :2 ORDER BY column_id",
+                &[&owner, &table],
+            )
+            .await?
+            .as_typed()
+    }
+
+    /// List constraints defined on a table, with their columns
+    pub async fn constraints(&self, owner: &str, table: &str) -> Result<Vec<ConstraintInfo>> {
+        let rows = self
+            .conn
+            .query(
+                "SELECT c.constraint_name, c.constraint_type, c.search_condition_vc, \
+                 c.r_owner, c.r_constraint_name, c.delete_rule, c.status, cc.column_name \
+                 FROM all_constraints c \
+                 LEFT JOIN all_cons_columns cc \
+                 ON cc.owner = c.owner AND cc.constraint_name = c.constraint_name \
+                 WHERE c.owner = :1 AND c.table_name = :2 \
+                 ORDER BY c.constraint_name, cc.position",
+                &[&owner, &table],
+            )
+            .await?;
+
+        let mut constraints: Vec<ConstraintInfo> = Vec::new();
+        for row in rows.rows() {
+            let name: String = row.get_typed_by_name("CONSTRAINT_NAME")?;
+            let column: Option<String> = row.get_typed_by_name("COLUMN_NAME")?;
+
+            match constraints.last_mut() {
+                Some(last) if last.name == name => last.columns.extend(column),
+                _ => {
+                    let mut constraint = ConstraintInfo::from_row(row)?;
+                    constraint.columns.extend(column);
+                    constraints.push(constraint);
+                }
+            }
+        }
+
+        Ok(constraints)
+    }
+
+    /// List indexes defined on a table, with their columns
+    pub async fn indexes(&self, owner: &str, table: &str) -> Result<Vec<IndexInfo>> {
+        let rows = self
+            .conn
+            .query(
+                "SELECT i.owner, i.index_name, i.index_type, i.uniqueness, i.status, \
+                 ic.column_name \
+                 FROM all_indexes i \
+                 LEFT JOIN all_ind_columns ic \
+                 ON ic.index_owner = i.owner AND ic.index_name = i.index_name \
+                 WHERE i.table_owner = :1 AND i.table_name = :2 \
+                 ORDER BY i.index_name, ic.column_position",
+                &[&owner, &table],
+            )
+            .await?;
+
+        let mut indexes: Vec<IndexInfo> = Vec::new();
+        for row in rows.rows() {
+            let name: String = row.get_typed_by_name("INDEX_NAME")?;
+            let column: Option<String> = row.get_typed_by_name("COLUMN_NAME")?;
+
+            match indexes.last_mut() {
+                Some(last) if last.name == name => last.columns.extend(column),
+                _ => {
+                    let mut index = IndexInfo::from_row(row)?;
+                    index.columns.extend(column);
+                    indexes.push(index);
+                }
+            }
+        }
+
+        Ok(indexes)
+    }
+
+    /// List sequences owned by a schema
+    pub async fn sequences(&self, owner: &str) -> Result<Vec<SequenceInfo>> {
+        self.conn
+            .query(
+                "SELECT sequence_owner, sequence_name, min_value, max_value, \
+                 increment_by, cycle_flag, cache_size, last_number \
+                 FROM all_sequences WHERE sequence_owner = :1 ORDER BY sequence_name",
+                &[&owner],
+            )
+            .await?
+            .as_typed()
+    }
+}
+
+impl Connection {
+    /// Access data dictionary introspection for this connection
+    pub fn catalog(&self) -> Catalog<'_> {
+        Catalog::new(self)
+    }
+}
+
+/// Schema (database user) information
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaInfo {
+    /// Schema name
+    pub name: String,
+    /// Creation date as reported by the dictionary
+    pub created: Option<chrono::NaiveDateTime>,
+    /// Whether the schema is maintained by Oracle (SYS, SYSTEM, ...)
+    pub oracle_maintained: bool,
+}
+
+/// Table information
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableInfo {
+    /// Owning schema
+    pub owner: String,
+    /// Table name
+    pub name: String,
+    /// Tablespace, if the table is not partitioned or temporary
+    pub tablespace: Option<String>,
+    /// Global temporary table
+    pub temporary: bool,
+    /// Partitioned table
+    pub partitioned: bool,
+    /// Row count from the last statistics gathering
+    pub num_rows: Option<i64>,
+    /// Table comment
+    pub comment: Option<String>,
+}
+
+/// View information
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewInfo {
+    /// Owning schema
+    pub owner: String,
+    /// View name
+    pub name: String,
+    /// View query text (truncated to 4000 characters by the dictionary)
+    pub text: Option<String>,
+    /// Created WITH READ ONLY
+    pub read_only: bool,
+}
+
+/// Column definition from the data dictionary
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumn {
+    /// 1-based column position
+    pub position: u32,
+    /// Column name
+    pub name: String,
+    /// Declared type name as reported by the dictionary, e.g. `TIMESTAMP(6)`
+    pub data_type: String,
+    /// Mapped Oracle type, if the declared type is a known built-in
+    pub oracle_type: Option<OracleType>,
+    /// Length in bytes
+    pub data_length: u32,
+    /// Length in characters for character types
+    pub char_length: Option<u32>,
+    /// Precision for numeric types
+    pub precision: Option<u8>,
+    /// Scale for numeric types
+    pub scale: Option<i8>,
+    /// Nullable
+    pub nullable: bool,
+    /// Default value expression
+    pub default: Option<String>,
+}
+
+/// Constraint kinds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintKind {
+    /// Primary key (`P`)
+    PrimaryKey,
+    /// Unique key (`U`)
+    Unique,
+    /// Foreign key (`R`)
+    ForeignKey,
+    /// Check or NOT NULL constraint (`C`)
+    Check,
+    /// Any other dictionary constraint type (views, REFs, ...)
+    Other,
+}
+
+impl ConstraintKind {
+    /// Map a `CONSTRAINT_TYPE` code from the dictionary
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "P" => ConstraintKind::PrimaryKey,
+            "U" => ConstraintKind::Unique,
+            "R" => ConstraintKind::ForeignKey,
+            "C" => ConstraintKind::Check,
+            _ => ConstraintKind::Other,
+        }
+    }
+}
+
+/// Constraint information
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintInfo {
+    /// Constraint name
+    pub name: String,
+    /// Constraint kind
+    pub kind: ConstraintKind,
+    /// Constrained columns in key order
+    pub columns: Vec<String>,
+    /// Check condition text
+    pub condition: Option<String>,
+    /// Referenced schema for foreign keys
+    pub referenced_owner: Option<String>,
+    /// Referenced unique/primary key constraint for foreign keys
+    pub referenced_constraint: Option<String>,
+    /// Delete rule for foreign keys (`CASCADE`, `SET NULL`, `NO ACTION`)
+    pub delete_rule: Option<String>,
+    /// Whether the constraint is enabled
+    pub enabled: bool,
+}
+
+/// Index information
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexInfo {
+    /// Owning schema of the index
+    pub owner: String,
+    /// Index name
+    pub name: String,
+    /// Index type, e.g. `NORMAL` or `FUNCTION-BASED NORMAL`
+    pub index_type: String,
+    /// Unique index
+    pub unique: bool,
+    /// Indexed columns in key order
+    pub columns: Vec<String>,
+    /// Whether the index is usable
+    pub valid: bool,
+}
+
+/// Sequence information
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceInfo {
+    /// Owning schema
+    pub owner: String,
+    /// Sequence name
+    pub name: String,
+    /// Minimum value; `NOMINVALUE` on a descending sequence is -(10^27 - 1)
+    pub min_value: Option<i128>,
+    /// Maximum value; `NOMAXVALUE` is 10^28 - 1
+    pub max_value: Option<i128>,
+    /// Increment
+    pub increment_by: i64,
+    /// Wraps around after reaching the limit
+    pub cycle: bool,
+    /// Number of cached values
+    pub cache_size: i64,
+    /// Last number written to disk
+    pub last_number: Option<i128>,
+}
+
+/// Map a dictionary `DATA_TYPE` name to an [`OracleType`]
+///
+/// Precision qualifiers such as `TIMESTAMP(6)` or `INTERVAL DAY(2) TO SECOND(6)`
+/// are ignored.
+pub fn parse_data_type(name: &str) -> Option<OracleType> {
+    let mut base = String::with_capacity(name.len());
+    let mut depth = 0usize;
+    for c in name.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => base.push(c.to_ascii_uppercase()),
+            _ => {}
+        }
+    }
+    let base = base.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let ty = match base.as_str() {
+        "VARCHAR2" | "VARCHAR" => OracleType::Varchar2,
+        "NVARCHAR2" => OracleType::NVarchar2,
+        "CHAR" => OracleType::Char,
+        "NCHAR" => OracleType::NChar,
+        "NUMBER" | "FLOAT" | "INTEGER" => OracleType::Number,
+        "BINARY_FLOAT" => OracleType::BinaryFloat,
+        "BINARY_DOUBLE" => OracleType::BinaryDouble,
+        "DATE" => OracleType::Date,
+        "TIMESTAMP" => OracleType::Timestamp,
+        "TIMESTAMP WITH TIME ZONE" => OracleType::TimestampTz,
+        "TIMESTAMP WITH LOCAL TIME ZONE" => OracleType::TimestampLtz,
+        "INTERVAL YEAR TO MONTH" => OracleType::IntervalYM,
+        "INTERVAL DAY TO SECOND" => OracleType::IntervalDS,
+        "RAW" => OracleType::Raw,
+        "LONG" => OracleType::Long,
+        "LONG RAW" => OracleType::LongRaw,
+        "ROWID" => OracleType::Rowid,
+        "UROWID" => OracleType::URowid,
+        "CLOB" => OracleType::Clob,
+        "NCLOB" => OracleType::NClob,
+        "BLOB" => OracleType::Blob,
+        "BFILE" => OracleType::BFile,
+        "JSON" => OracleType::Json,
+        "XMLTYPE" => OracleType::XmlType,
+        "BOOLEAN" => OracleType::Boolean,
+        "VECTOR" => OracleType::Vector,
+        _ => return None,
+    };
+    Some(ty)
+}
+
+/// Decode a `YES`/`NO` or `Y`/`N` dictionary flag
+fn flag(row: &Row, name: &str) -> Result<bool> {
+    let value: Option<String> = row.get_typed_by_name(name)?;
+    Ok(matches!(value.as_deref().map(str::trim), Some("Y" | "YES")))
+}
+
+fn to_u32(value: Option<i64>) -> Option<u32> {
+    value.and_then(|v| u32::try_from(v).ok())
+}
+
+impl FromRow for SchemaInfo {
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            name: row.get_typed_by_name("USERNAME")?,
+            created: match row.get_by_name("CREATED") {
+                Some(crate::Value::Timestamp(ts)) => Some(*ts),
+                Some(crate::Value::Date(d)) => d.and_hms_opt(0, 0, 0),
+                _ => None,
+            },
+            oracle_maintained: flag(row, "ORACLE_MAINTAINED")?,
+        })
+    }
+}
+
+impl FromRow for TableInfo {
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            owner: row.get_typed_by_name("OWNER")?,
+            name: row.get_typed_by_name("TABLE_NAME")?,
+            tablespace: row.get_typed_by_name("TABLESPACE_NAME")?,
+            temporary: flag(row, "TEMPORARY")?,
+            partitioned: flag(row, "PARTITIONED")?,
+            num_rows: row.get_typed_by_name("NUM_ROWS")?,
+            comment: row.get_typed_by_name("COMMENTS")?,
+        })
+    }
+}
+
+impl FromRow for ViewInfo {
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            owner: row.get_typed_by_name("OWNER")?,
+            name: row.get_typed_by_name("VIEW_NAME")?,
+            text: row.get_typed_by_name("TEXT_VC")?,
+            read_only: flag(row, "READ_ONLY")?,
+        })
+    }
+}
+
+impl FromRow for TableColumn {
+    fn from_row(row: &Row) -> Result<Self> {
+        let data_type: String = row.get_typed_by_name("DATA_TYPE")?;
+        let char_length = to_u32(row.get_typed_by_name("CHAR_LENGTH")?).filter(|&len| len > 0);
+
+        Ok(Self {
+            position: to_u32(row.get_typed_by_name("COLUMN_ID")?).unwrap_or(0),
+            name: row.get_typed_by_name("COLUMN_NAME")?,
+            oracle_type: parse_data_type(&data_type),
+            data_type,
+            data_length: to_u32(row.get_typed_by_name("DATA_LENGTH")?).unwrap_or(0),
+            char_length,
+            precision: row
+                .get_typed_by_name::<Option<i64>>("DATA_PRECISION")?
+                .and_then(|v| u8::try_from(v).ok()),
+            scale: row
+                .get_typed_by_name::<Option<i64>>("DATA_SCALE")?
+                .and_then(|v| i8::try_from(v).ok()),
+            nullable: flag(row, "NULLABLE")?,
+            default: row
+                .get_typed_by_name::<Option<String>>("DATA_DEFAULT")?
+                .map(|d| d.trim().to_string()),
+        })
+    }
+}
+
+impl FromRow for ConstraintInfo {
+    fn from_row(row: &Row) -> Result<Self> {
+        let kind: String = row.get_typed_by_name("CONSTRAINT_TYPE")?;
+        let status: Option<String> = row.get_typed_by_name("STATUS")?;
+
+        Ok(Self {
+            name: row.get_typed_by_name("CONSTRAINT_NAME")?,
+            kind: ConstraintKind::from_code(&kind),
+            columns: Vec::new(),
+            condition: row.get_typed_by_name("SEARCH_CONDITION_VC")?,
+            referenced_owner: row.get_typed_by_name("R_OWNER")?,
+            referenced_constraint: row.get_typed_by_name("R_CONSTRAINT_NAME")?,
+            delete_rule: row.get_typed_by_name("DELETE_RULE")?,
+            enabled: status.as_deref() == Some("ENABLED"),
+        })
+    }
+}
+
+impl FromRow for IndexInfo {
+    fn from_row(row: &Row) -> Result<Self> {
+        let uniqueness: String = row.get_typed_by_name("UNIQUENESS")?;
+        let status: Option<String> = row.get_typed_by_name("STATUS")?;
+
+        Ok(Self {
+            owner: row.get_typed_by_name("OWNER")?,
+            name: row.get_typed_by_name("INDEX_NAME")?,
+            index_type: row.get_typed_by_name("INDEX_TYPE")?,
+            unique: uniqueness == "UNIQUE",
+            columns: Vec::new(),
+            valid: matches!(status.as_deref(), Some("VALID" | "N/A")),
+        })
+    }
+}
+
+impl FromRow for SequenceInfo {
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            owner: row.get_typed_by_name("SEQUENCE_OWNER")?,
+            name: row.get_typed_by_name("SEQUENCE_NAME")?,
+            min_value: row.get_typed_by_name("MIN_VALUE")?,
+            max_value: row.get_typed_by_name("MAX_VALUE")?,
+            increment_by: row.get_typed_by_name("INCREMENT_BY")?,
+            cycle: flag(row, "CYCLE_FLAG")?,
+            cache_size: row.get_typed_by_name("CACHE_SIZE")?,
+            last_number: row.get_typed_by_name("LAST_NUMBER")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    #[test]
+    fn test_parse_data_type() {
+        assert_eq!(parse_data_type("VARCHAR2"), Some(OracleType::Varchar2));
+        assert_eq!(parse_data_type("TIMESTAMP(6)"), Some(OracleType::Timestamp));
+        assert_eq!(
+            parse_data_type("TIMESTAMP(6) WITH TIME ZONE"),
+            Some(OracleType::TimestampTz)
+        );
+        assert_eq!(
+            parse_data_type("INTERVAL DAY(2) TO SECOND(6)"),
+            Some(OracleType::IntervalDS)
+        );
+        assert_eq!(parse_data_type("LONG"), Some(OracleType::Long));
+        assert_eq!(parse_data_type("long  raw"), Some(OracleType::LongRaw));
+        assert_eq!(parse_data_type("SDO_GEOMETRY"), None);
+    }
+
+    #[test]
+    fn test_table_column_from_row() {
+        let row = Row::new(
+            vec![
+                Value::Integer(1),
+                Value::String("SALARY".into()),
+                Value::String("NUMBER".into()),
+                Value::Integer(22),
+                Value::Integer(0),
+                Value::Integer(8),
+                Value::Integer(2),
+                Value::String("N".into()),
+                Value::Null,
+            ],
+            [
+                "COLUMN_ID",
+                "COLUMN_NAME",
+                "DATA_TYPE",
+                "DATA_LENGTH",
+                "CHAR_LENGTH",
+                "DATA_PRECISION",
+                "DATA_SCALE",
+                "NULLABLE",
+                "DATA_DEFAULT",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+
+        let col = TableColumn::from_row(&row).unwrap();
+        assert_eq!(col.position, 1);
+        assert_eq!(col.oracle_type, Some(OracleType::Number));
+        assert_eq!(col.char_length, None);
+        assert_eq!(col.precision, Some(8));
+        assert_eq!(col.scale, Some(2));
+        assert!(!col.nullable);
+    }
+
+    #[test]
+    fn test_sequence_from_row() {
+        let columns = [
+            "SEQUENCE_OWNER",
+            "SEQUENCE_NAME",
+            "MIN_VALUE",
+            "MAX_VALUE",
+            "INCREMENT_BY",
+            "CYCLE_FLAG",
+            "CACHE_SIZE",
+            "LAST_NUMBER",
+        ];
+        let values = vec![
+            Value::String("HR".into()),
+            Value::String("COUNTDOWN".into()),
+            Value::String("-999999999999999999999999999".into()),
+            Value::Integer(-1),
+            Value::Integer(-1),
+            Value::String("N".into()),
+            Value::Integer(20),
+            Value::Integer(-21),
+        ];
+        let row = Row::new(
+            values.clone(),
+            columns.iter().map(|s| s.to_string()).collect(),
+        );
+
+        let seq = SequenceInfo::from_row(&row).unwrap();
+        assert_eq!(seq.min_value, Some(1 - 10i128.pow(27)));
+        assert_eq!(seq.max_value, Some(-1));
+        assert_eq!(seq.last_number, Some(-21));
+
+        // A missing column is an error, not a silent None
+        let row = Row::new(
+            values[..7].to_vec(),
+            columns[..7].iter().map(|s| s.to_string()).collect(),
+        );
+        assert!(SequenceInfo::from_row(&row).is_err());
+    }
+
+    #[test]
+    fn test_constraint_kind() {
+        assert_eq!(ConstraintKind::from_code("P"), ConstraintKind::PrimaryKey);
+        assert_eq!(ConstraintKind::from_code("R"), ConstraintKind::ForeignKey);
+        assert_eq!(ConstraintKind::from_code("V"), ConstraintKind::Other);
+    }
+}
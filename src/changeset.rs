@@ -0,0 +1,158 @@
+// Changesets
+
+//! A [`Changeset`] is a sequence of statements, each with its own SQL and
+//! binds, applied on one connection in as few round trips as possible:
+//!
+//! ```rust,ignore
+//! let changes = Changeset::new()
+//!     .add("INSERT INTO orders (id, total) VALUES (:1, :2)", &[&1, &9.5])
+//!     .add("INSERT INTO orders (id, total) VALUES (:1, :2)", &[&2, &3.0])
+//!     .add("UPDATE stock SET qty = qty - 1 WHERE sku = :1", &[&"A-1"])
+//!     .commit(true);
+//! let result = pool.get_connection().await?.apply(&changes).await?;
+//! assert_eq!(result.row_counts.len(), 3);
+//! ```
+//!
+//! Consecutive statements with the same SQL go out as one array DML call,
+//! and with [`Changeset::commit`] the commit rides on the last call rather
+//! than costing a round trip of its own. Application stops at the first
+//! failing statement.
+
+use crate::protocol::{Protocol, StatementType};
+use crate::statement::ExecutionResult;
+use crate::types::{BindType, ToSql, Value};
+use crate::{Error, Result};
+
+/// Statement of a changeset with its binds
+#[derive(Debug, Clone)]
+struct Change {
+    sql: String,
+    params: Vec<Value>,
+    types: Vec<Option<BindType>>,
+}
+
+/// Statements with different SQL, applied together
+#[derive(Debug, Clone, Default)]
+pub struct Changeset {
+    changes: Vec<Change>,
+    commit: bool,
+}
+
+impl Changeset {
+    /// Start an empty changeset
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a DML, PL/SQL or DDL statement
+    pub fn add(mut self, sql: impl Into<String>, params: &[&dyn ToSql]) -> Self {
+        self.changes.push(Change {
+            sql: sql.into(),
+            params: params.iter().map(|p| p.to_sql()).collect(),
+            types: params.iter().map(|p| p.bind_type()).collect(),
+        });
+        self
+    }
+
+    /// Commit with the last statement once every statement succeeded
+    ///
+    /// A failure then rolls the transaction back, so none of the changeset
+    /// stays applied, along with any uncommitted work before it. DDL
+    /// commits as it runs and could not be rolled back, so a committing
+    /// changeset refuses it.
+    pub fn commit(mut self, commit: bool) -> Self {
+        self.commit = commit;
+        self
+    }
+
+    /// Number of queued statements
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Check if nothing is queued
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Apply the statements in order, reporting each one's row count
+    pub(crate) async fn apply(&self, protocol: &mut Protocol) -> Result<ExecutionResult> {
+        for change in &self.changes {
+            match Protocol::parse_statement_type(&change.sql)? {
+                StatementType::Select => {
+                    return Err(Error::InvalidSql(format!(
+                        "a changeset cannot hold queries: {}",
+                        change.sql
+                    )))
+                }
+                StatementType::Ddl if self.commit => {
+                    return Err(Error::InvalidSql(format!(
+                        "a committing changeset cannot hold DDL, which commits as it runs: {}",
+                        change.sql
+                    )))
+                }
+                _ => {}
+            }
+        }
+
+        let result = self.run(protocol).await;
+        if result.is_err() && self.commit {
+            // A lost session takes its transaction with it
+            if let Err(e) = protocol.rollback().await {
+                log::debug!("changeset rollback failed: {}", e);
+            }
+        }
+        let row_counts = result?;
+        Ok(ExecutionResult {
+            rows_affected: row_counts.iter().fold(0, |n, &c| n.saturating_add(c)),
+            row_counts,
+            ..Default::default()
+        })
+    }
+
+    /// Send each run of statements sharing SQL as one call
+    async fn run(&self, protocol: &mut Protocol) -> Result<Vec<u64>> {
+        let mut row_counts = Vec::with_capacity(self.changes.len());
+        let mut rest = self.changes.as_slice();
+        while let Some(first) = rest.first() {
+            let run = rest.iter().take_while(|c| c.sql == first.sql).count();
+            let (group, tail) = rest.split_at(run);
+            rest = tail;
+            let last = self.commit && rest.is_empty();
+            if Protocol::parse_statement_type(&first.sql)?.is_dml() {
+                let rows: Vec<Vec<Value>> = group.iter().map(|c| c.params.clone()).collect();
+                protocol.define_binds(first.types.clone());
+                if last {
+                    protocol.commit_on_success();
+                }
+                row_counts.extend(protocol.execute_batch(&first.sql, &rows).await?);
+            } else {
+                for (i, change) in group.iter().enumerate() {
+                    protocol.define_binds(change.types.clone());
+                    if last && i + 1 == group.len() {
+                        protocol.commit_on_success();
+                    }
+                    row_counts.push(protocol.execute_dml(&change.sql, &change.params).await?);
+                }
+            }
+        }
+        Ok(row_counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changeset_builder() {
+        let changes = Changeset::new()
+            .add("INSERT INTO t (n) VALUES (:1)", &[&1])
+            .add("DELETE FROM u", &[])
+            .commit(true);
+        assert_eq!(changes.len(), 2);
+        assert!(changes.commit);
+        assert_eq!(changes.changes[0].params, [Value::Integer(1)]);
+        assert!(Changeset::new().is_empty());
+    }
+}
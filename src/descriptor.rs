@@ -0,0 +1,402 @@
+// TNS connect descriptors
+
+//! Builds `(DESCRIPTION=...)` connect descriptors from typed parts instead
+//! of hand-concatenated strings:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::descriptor::{ConnectDescriptor, TransportProtocol};
+//!
+//! let descriptor = ConnectDescriptor::new()
+//!     .host("db.example.com")
+//!     .port(2484)
+//!     .service("orclpdb1")
+//!     .protocol(TransportProtocol::Tcps)
+//!     .retry_count(3)
+//!     .build()?;
+//! let config = ConnectionConfig::new(descriptor, "app", "secret");
+//! ```
+
+use crate::{Error, Result};
+use std::borrow::Cow;
+use std::fmt;
+use std::time::Duration;
+
+/// Transport a descriptor address connects over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransportProtocol {
+    /// Plain TCP
+    #[default]
+    Tcp,
+    /// TCP with TLS
+    Tcps,
+    /// Unix domain socket of a listener on the same host
+    Ipc,
+}
+
+impl fmt::Display for TransportProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransportProtocol::Tcp => "TCP",
+            TransportProtocol::Tcps => "TCPS",
+            TransportProtocol::Ipc => "IPC",
+        })
+    }
+}
+
+/// Typed builder for a TNS connect descriptor
+#[derive(Debug, Clone)]
+pub struct ConnectDescriptor {
+    host: Option<String>,
+    port: u16,
+    protocol: TransportProtocol,
+    ipc_key: Option<String>,
+    service_name: Option<String>,
+    sid: Option<String>,
+    retry_count: Option<u32>,
+    retry_delay: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    transport_connect_timeout: Option<Duration>,
+}
+
+impl Default for ConnectDescriptor {
+    fn default() -> Self {
+        Self {
+            host: None,
+            port: crate::constants::DEFAULT_PORT,
+            protocol: TransportProtocol::Tcp,
+            ipc_key: None,
+            service_name: None,
+            sid: None,
+            retry_count: None,
+            retry_delay: None,
+            connect_timeout: None,
+            transport_connect_timeout: None,
+        }
+    }
+}
+
+impl ConnectDescriptor {
+    /// Create an empty descriptor on the default port
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the listener host
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Set the listener port
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Set the transport
+    pub fn protocol(mut self, protocol: TransportProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Connect over the local listener's IPC socket for `key`, as in
+    /// `(ADDRESS=(PROTOCOL=IPC)(KEY=key))`, instead of a host and port
+    pub fn ipc_key(mut self, key: impl Into<String>) -> Self {
+        self.protocol = TransportProtocol::Ipc;
+        self.ipc_key = Some(key.into());
+        self
+    }
+
+    /// Connect to a service name
+    pub fn service(mut self, service_name: impl Into<String>) -> Self {
+        self.service_name = Some(service_name.into());
+        self
+    }
+
+    /// Connect to a SID instead of a service
+    pub fn sid(mut self, sid: impl Into<String>) -> Self {
+        self.sid = Some(sid.into());
+        self
+    }
+
+    /// Attempts the client makes after the first one fails
+    pub fn retry_count(mut self, count: u32) -> Self {
+        self.retry_count = Some(count);
+        self
+    }
+
+    /// Wait between connection attempts (whole seconds on the wire)
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = Some(delay);
+        self
+    }
+
+    /// Time allowed for each connection attempt
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Time allowed to open the transport before trying the next address
+    pub fn transport_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.transport_connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Render the descriptor string
+    pub fn build(&self) -> Result<String> {
+        let address = match self.protocol {
+            TransportProtocol::Ipc => {
+                let key = self
+                    .ipc_key
+                    .as_deref()
+                    .filter(|k| !k.is_empty())
+                    .ok_or_else(|| {
+                        Error::InvalidConfiguration("IPC descriptor is missing a key".into())
+                    })?;
+                format!("(ADDRESS=(PROTOCOL=IPC)(KEY={}))", quote(key))
+            }
+            protocol => {
+                let host = self
+                    .host
+                    .as_deref()
+                    .filter(|h| !h.is_empty())
+                    .ok_or_else(|| {
+                        Error::InvalidConfiguration("descriptor is missing a host".into())
+                    })?;
+                format!(
+                    "(ADDRESS=(PROTOCOL={})(HOST={})(PORT={}))",
+                    protocol,
+                    quote(host),
+                    self.port
+                )
+            }
+        };
+        let connect_data = match (&self.service_name, &self.sid) {
+            (Some(service), None) => format!("(SERVICE_NAME={})", quote(service)),
+            (None, Some(sid)) => format!("(SID={})", quote(sid)),
+            (Some(_), Some(_)) => {
+                return Err(Error::InvalidConfiguration(
+                    "descriptor takes a service name or a SID, not both".into(),
+                ))
+            }
+            (None, None) => {
+                return Err(Error::InvalidConfiguration(
+                    "descriptor is missing a service name or SID".into(),
+                ))
+            }
+        };
+
+        let mut out = String::from("(DESCRIPTION=");
+        if let Some(timeout) = self.connect_timeout {
+            out.push_str(&format!("(CONNECT_TIMEOUT={}ms)", timeout.as_millis()));
+        }
+        if let Some(timeout) = self.transport_connect_timeout {
+            out.push_str(&format!(
+                "(TRANSPORT_CONNECT_TIMEOUT={}ms)",
+                timeout.as_millis()
+            ));
+        }
+        if let Some(count) = self.retry_count {
+            out.push_str(&format!("(RETRY_COUNT={})", count));
+        }
+        if let Some(delay) = self.retry_delay {
+            out.push_str(&format!("(RETRY_DELAY={})", delay.as_secs()));
+        }
+        out.push_str(&format!("{}(CONNECT_DATA={}))", address, connect_data));
+        Ok(out)
+    }
+}
+
+/// Double-quote values that would otherwise break the descriptor syntax
+pub(crate) fn quote(value: &str) -> String {
+    if value
+        .chars()
+        .any(|c| matches!(c, '(' | ')' | '=' | '\'' | '"' | '\\') || c.is_whitespace())
+    {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Value of the first `(KEY=value)` in a descriptor, matching keys
+/// case-insensitively and allowing whitespace around the `=`
+///
+/// A double-quoted value is returned without its quotes and with the
+/// escapes written by [`quote`] undone.
+pub(crate) fn descriptor_value<'a>(descriptor: &'a str, key: &str) -> Option<Cow<'a, str>> {
+    let upper = descriptor.to_ascii_uppercase();
+    let key = key.to_ascii_uppercase();
+    let mut from = 0;
+    while let Some(found) = upper[from..].find(&key) {
+        let start = from + found;
+        from = start + key.len();
+        if !upper[..start].trim_end().ends_with('(') {
+            continue;
+        }
+        let Some(rest) = descriptor[from..].trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        return match rest.strip_prefix('"') {
+            Some(quoted) => unquote(quoted),
+            None => Some(Cow::Borrowed(rest[..rest.find(')')?].trim())),
+        };
+    }
+    None
+}
+
+/// Text up to the closing quote of a value that began with `"`, with `\"`
+/// and `\\` unescaped
+fn unquote(quoted: &str) -> Option<Cow<'_, str>> {
+    let mut chars = quoted.char_indices();
+    let mut unescaped: Option<String> = None;
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                return Some(match unescaped {
+                    Some(value) => Cow::Owned(value),
+                    None => Cow::Borrowed(&quoted[..i]),
+                })
+            }
+            '\\' => {
+                let (_, escaped) = chars.next()?;
+                unescaped
+                    .get_or_insert_with(|| quoted[..i].to_string())
+                    .push(escaped);
+            }
+            c => {
+                if let Some(value) = &mut unescaped {
+                    value.push(c);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Every `(key=...)` section of `descriptor`, parentheses included
+pub(crate) fn descriptor_sections<'a>(descriptor: &'a str, key: &str) -> Vec<&'a str> {
+    let upper = descriptor.to_ascii_uppercase();
+    let key = key.to_ascii_uppercase();
+    let mut sections = Vec::new();
+    let mut from = 0;
+    while let Some(found) = upper[from..].find(&key) {
+        let start = from + found;
+        from = start + key.len();
+        let Some(open) = upper[..start].trim_end().strip_suffix('(').map(str::len) else {
+            continue;
+        };
+        if !descriptor[from..].trim_start().starts_with('=') {
+            continue;
+        }
+        let (mut depth, mut quoted, mut escaped) = (0, false, false);
+        for (i, c) in descriptor[open..].char_indices() {
+            if std::mem::take(&mut escaped) {
+                continue;
+            }
+            match c {
+                '\\' if quoted => escaped = true,
+                '"' => quoted = !quoted,
+                '(' if !quoted => depth += 1,
+                ')' if !quoted => {
+                    depth -= 1;
+                    if depth == 0 {
+                        sections.push(&descriptor[open..open + i + 1]);
+                        from = open + i + 1;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if depth != 0 {
+            break;
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_descriptor() {
+        let descriptor = ConnectDescriptor::new()
+            .host("db.example.com")
+            .port(2484)
+            .service("orclpdb1")
+            .protocol(TransportProtocol::Tcps)
+            .retry_count(3)
+            .retry_delay(Duration::from_secs(2))
+            .build()
+            .unwrap();
+        assert_eq!(
+            descriptor,
+            "(DESCRIPTION=(RETRY_COUNT=3)(RETRY_DELAY=2)\
+             (ADDRESS=(PROTOCOL=TCPS)(HOST=db.example.com)(PORT=2484))\
+             (CONNECT_DATA=(SERVICE_NAME=orclpdb1)))"
+        );
+        assert_eq!(
+            descriptor_value(&descriptor, "host").as_deref(),
+            Some("db.example.com")
+        );
+        assert_eq!(
+            descriptor_value(&descriptor, "service_name").as_deref(),
+            Some("orclpdb1")
+        );
+
+        let descriptor = ConnectDescriptor::new()
+            .host("db")
+            .sid("ORCL (test)")
+            .build()
+            .unwrap();
+        assert!(descriptor.contains("(SID=\"ORCL (test)\")"));
+        assert_eq!(
+            descriptor_value(&descriptor, "SID").as_deref(),
+            Some("ORCL (test)")
+        );
+
+        // Escaped quotes and backslashes round-trip through the parser
+        let descriptor = ConnectDescriptor::new()
+            .host("db")
+            .service(r#"say "hi" \ (now)"#)
+            .build()
+            .unwrap();
+        assert!(descriptor.contains(r#"(SERVICE_NAME="say \"hi\" \\ (now)")"#));
+        assert_eq!(
+            descriptor_value(&descriptor, "SERVICE_NAME").as_deref(),
+            Some(r#"say "hi" \ (now)"#)
+        );
+        assert_eq!(descriptor_sections(&descriptor, "CONNECT_DATA").len(), 1);
+
+        let descriptor = ConnectDescriptor::new()
+            .ipc_key("EXTPROC1")
+            .service("orcl")
+            .build()
+            .unwrap();
+        assert_eq!(
+            descriptor,
+            "(DESCRIPTION=(ADDRESS=(PROTOCOL=IPC)(KEY=EXTPROC1))\
+             (CONNECT_DATA=(SERVICE_NAME=orcl)))"
+        );
+        assert_eq!(
+            descriptor_value("(METHOD_DATA = ( DIRECTORY = /wallet ))", "directory").as_deref(),
+            Some("/wallet")
+        );
+    }
+
+    #[test]
+    fn test_incomplete_descriptor() {
+        assert!(ConnectDescriptor::new().service("svc").build().is_err());
+        assert!(ConnectDescriptor::new().host("db").build().is_err());
+        assert!(ConnectDescriptor::new()
+            .host("db")
+            .service("svc")
+            .sid("ORCL")
+            .build()
+            .is_err());
+    }
+}
@@ -0,0 +1,217 @@
+// Session diagnostics
+
+//! Session statistics measured around a piece of work.
+//!
+//! [`measure`] snapshots the session's `V$MYSTAT` counters and
+//! `V$SESS_TIME_MODEL` timings before and after running a future, and
+//! returns the difference alongside the future's output:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::diag;
+//!
+//! let (rows, stats) = diag::measure(&conn, || conn.query(sql, &[])).await?;
+//! println!(
+//!     "{} consistent gets, {} physical reads, {:?} CPU",
+//!     stats.consistent_gets, stats.physical_reads, stats.cpu_time
+//! );
+//! ```
+//!
+//! The snapshots are queries on the same session, so they add a user call
+//! and a round trip each to the counts; other work on the connection
+//! while the future runs is counted too. The session needs `SELECT` on
+//! `V$MYSTAT`, `V$STATNAME` and `V$SESS_TIME_MODEL`.
+
+use crate::{Connection, Result};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Counters and timings of the current session
+const SNAPSHOT_QUERY: &str = "\
+SELECT n.name, s.value FROM v$mystat s \
+JOIN v$statname n ON n.statistic# = s.statistic# \
+WHERE n.name IN ('session logical reads', 'consistent gets', 'db block gets', \
+'physical reads', 'redo size', 'sorts (memory)', 'sorts (disk)', 'execute count', \
+'parse count (total)', 'parse count (hard)', 'user calls', \
+'SQL*Net roundtrips to/from client') \
+UNION ALL \
+SELECT stat_name, value FROM v$sess_time_model \
+WHERE sid = SYS_CONTEXT('USERENV', 'SID') AND stat_name IN ('DB time', 'DB CPU')";
+
+/// Statistic values of the session at one moment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    values: HashMap<String, i64>,
+    taken: Option<Instant>,
+}
+
+impl Snapshot {
+    /// Value of statistic `name` as named in `V$STATNAME` or
+    /// `V$SESS_TIME_MODEL`
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.values.get(name).copied()
+    }
+
+    /// Work done between this snapshot and `later`
+    pub fn delta(&self, later: &Snapshot) -> SessionStats {
+        let values: HashMap<String, i64> = later
+            .values
+            .iter()
+            .map(|(name, value)| {
+                let before = self.values.get(name).copied().unwrap_or(0);
+                (name.clone(), value - before)
+            })
+            .collect();
+        let count = |name: &str| values.get(name).map_or(0, |&v| v.max(0) as u64);
+        let micros = |name: &str| Duration::from_micros(count(name));
+        SessionStats {
+            logical_reads: count("session logical reads"),
+            consistent_gets: count("consistent gets"),
+            db_block_gets: count("db block gets"),
+            physical_reads: count("physical reads"),
+            redo_size: count("redo size"),
+            sorts_memory: count("sorts (memory)"),
+            sorts_disk: count("sorts (disk)"),
+            executions: count("execute count"),
+            parses: count("parse count (total)"),
+            hard_parses: count("parse count (hard)"),
+            user_calls: count("user calls"),
+            round_trips: count("SQL*Net roundtrips to/from client"),
+            db_time: micros("DB time"),
+            cpu_time: micros("DB CPU"),
+            elapsed: match (self.taken, later.taken) {
+                (Some(start), Some(end)) => end.saturating_duration_since(start),
+                _ => Duration::ZERO,
+            },
+            values,
+        }
+    }
+}
+
+/// Session work done between two snapshots
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStats {
+    /// Blocks read from the buffer cache, in either mode
+    pub logical_reads: u64,
+    /// Blocks read in consistent mode
+    pub consistent_gets: u64,
+    /// Blocks read in current mode
+    pub db_block_gets: u64,
+    /// Blocks read from disk
+    pub physical_reads: u64,
+    /// Bytes of redo generated
+    pub redo_size: u64,
+    /// Sorts done in memory
+    pub sorts_memory: u64,
+    /// Sorts that spilled to disk
+    pub sorts_disk: u64,
+    /// Statement executions
+    pub executions: u64,
+    /// Parse calls, soft and hard
+    pub parses: u64,
+    /// Parses that built a new cursor
+    pub hard_parses: u64,
+    /// Calls from the client
+    pub user_calls: u64,
+    /// Network round trips
+    pub round_trips: u64,
+    /// Time the database spent on the session's calls
+    pub db_time: Duration,
+    /// CPU time the database spent on the session's calls
+    pub cpu_time: Duration,
+    /// Wall-clock time between the snapshots
+    pub elapsed: Duration,
+    /// Every statistic's change, by name
+    pub values: HashMap<String, i64>,
+}
+
+/// Snapshot the session statistics of `conn`
+pub async fn snapshot(conn: &Connection) -> Result<Snapshot> {
+    let rows = conn.query(SNAPSHOT_QUERY, &[]).await?;
+    let mut values = HashMap::new();
+    for row in rows.rows() {
+        let name: String = row.get_typed(0)?;
+        let value: Option<i64> = row.get_typed(1)?;
+        values.insert(name, value.unwrap_or(0));
+    }
+    Ok(Snapshot {
+        values,
+        taken: Some(Instant::now()),
+    })
+}
+
+/// Run `work` and report the session statistics it moved on `conn`
+pub async fn measure<F, Fut, T>(conn: &Connection, work: F) -> Result<(T, SessionStats)>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = T>,
+{
+    let before = snapshot(conn).await?;
+    let output = work().await;
+    let after = snapshot(conn).await?;
+    Ok((output, before.delta(&after)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_delta() {
+        let snapshot = |pairs: &[(&str, i64)]| Snapshot {
+            values: pairs.iter().map(|&(n, v)| (n.to_string(), v)).collect(),
+            taken: None,
+        };
+        let before = snapshot(&[("physical reads", 10), ("DB time", 1_000)]);
+        let after = snapshot(&[
+            ("physical reads", 17),
+            ("DB time", 4_000),
+            ("redo size", 512),
+        ]);
+        let stats = before.delta(&after);
+        assert_eq!(stats.physical_reads, 7);
+        assert_eq!(stats.db_time, Duration::from_millis(3));
+        // A statistic missing from the first snapshot counts from zero
+        assert_eq!(stats.redo_size, 512);
+        assert_eq!(stats.elapsed, Duration::ZERO);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_measure() {
+        use crate::testing::{column, Expectation, MockConnection};
+        use crate::{OracleType, Value};
+
+        let stats = |gets: i64, cpu: i64| {
+            Expectation::query(SNAPSHOT_QUERY).returns(
+                vec![
+                    column("NAME", OracleType::Varchar2),
+                    column("VALUE", OracleType::Number),
+                ],
+                vec![
+                    vec![
+                        Value::String("consistent gets".into()),
+                        Value::Integer(gets),
+                    ],
+                    vec![Value::String("DB CPU".into()), Value::Integer(cpu)],
+                ],
+            )
+        };
+        let update = "UPDATE t SET n = 2";
+        let mock = MockConnection::new()
+            .expect(stats(100, 5_000))
+            .expect(Expectation::dml(update, 3))
+            .expect(stats(142, 7_500));
+        let conn = mock.connect().await.unwrap();
+
+        let (count, stats) = measure(&conn, || conn.execute_dml(update, &[]))
+            .await
+            .unwrap();
+        assert_eq!(count.unwrap(), 3);
+        assert_eq!(stats.consistent_gets, 42);
+        assert_eq!(stats.cpu_time, Duration::from_micros(2_500));
+        assert_eq!(stats.physical_reads, 0);
+        assert_eq!(stats.values["consistent gets"], 42);
+        mock.verify();
+    }
+}
@@ -16,6 +16,10 @@ pub enum Error {
     #[error("Connection is closed")]
     ConnectionClosed,
 
+    /// Connection stayed busy with other calls past the queue timeout
+    #[error("Connection is busy")]
+    ConnectionBusy,
+
     /// Authentication failed
     #[error("Authentication failed: {0}")]
     AuthenticationFailed(String),
@@ -60,6 +64,10 @@ pub enum Error {
     #[error("Unsupported feature: {0}")]
     UnsupportedFeature(String),
 
+    /// Oracle Client libraries missing or unusable (thick mode)
+    #[error("Oracle Client library error: {0}")]
+    ClientLibrary(String),
+
     /// Protocol error
     #[error("Protocol error: {0}")]
     Protocol(String),
@@ -76,6 +84,10 @@ pub enum Error {
     #[error("Operation timeout")]
     Timeout,
 
+    /// Call abandoned through its cancellation token
+    #[error("Operation cancelled")]
+    Cancelled,
+
     /// Oracle-specific error with code
     #[error("Oracle error ORA-{code:05}: {message}")]
     Oracle {
@@ -83,6 +95,8 @@ pub enum Error {
         code: i32,
         /// Error message
         message: String,
+        /// Where the error happened, when raised by a statement
+        context: Option<Box<ErrorContext>>,
     },
 
     /// Transaction error
@@ -93,10 +107,33 @@ pub enum Error {
     #[error("LOB operation error: {0}")]
     Lob(String),
 
+    /// Fetched rows exceed a memory cap
+    #[error("Result too large: {size} bytes exceeds the {limit} byte limit")]
+    ResultTooLarge {
+        /// Bytes the rows would take
+        size: usize,
+        /// Configured cap in bytes
+        limit: usize,
+    },
+
+    /// Opening a cursor would pass the configured cursor limit, before
+    /// the server fails with ORA-01000
+    #[error("Too many open cursors: {limit} open, held by {sql:?}")]
+    TooManyOpenCursors {
+        /// Configured cursor limit
+        limit: usize,
+        /// SQL of the cursors still open for fetching, oldest first
+        sql: Vec<String>,
+    },
+
     /// Invalid data
     #[error("Invalid data: {0}")]
     InvalidData(String),
 
+    /// Schema migration error
+    #[error("Migration error: {0}")]
+    Migration(String),
+
     /// Feature not implemented yet
     #[error("Not implemented: {0}")]
     NotImplemented(String),
@@ -112,6 +149,23 @@ impl Error {
         Self::Oracle {
             code,
             message: message.into(),
+            context: None,
+        }
+    }
+
+    /// Attach `context` to an Oracle error; other errors pass through
+    pub(crate) fn with_context(mut self, context: ErrorContext) -> Self {
+        if let Error::Oracle { context: slot, .. } = &mut self {
+            *slot = Some(Box::new(context));
+        }
+        self
+    }
+
+    /// Session and statement the error came from
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Error::Oracle { context, .. } => context.as_deref(),
+            _ => None,
         }
     }
 
@@ -123,6 +177,31 @@ impl Error {
         )
     }
 
+    /// Check if the session is unusable after this error
+    ///
+    /// Lost connections, killed sessions and protocol desyncs are fatal;
+    /// constraint violations, SQL errors and the like are not.
+    pub fn is_fatal(&self) -> bool {
+        match self {
+            Error::Connection(_) | Error::ConnectionClosed | Error::Io(_) | Error::Protocol(_) => {
+                true
+            }
+            Error::Oracle { code, .. } => matches!(
+                *code,
+                // Session killed, not logged on, shutdown in progress,
+                // instance terminated
+                28 | codes::NOT_LOGGED_ON | 1089 | 1092 |
+                // End of file or lost contact on the channel
+                codes::EOF_COMMUNICATION | codes::NOT_CONNECTED | 3135 |
+                // TNS connection closed or lost
+                12537 | 12547 | 12570 | 12571 |
+                // Client-side socket failures
+                codes::CONNECTION_TIMEOUT | 17008 | 17410
+            ),
+            _ => false,
+        }
+    }
+
     /// Check if error is a pool error
     pub fn is_pool_error(&self) -> bool {
         matches!(
@@ -136,6 +215,7 @@ impl Error {
         match self {
             Error::Timeout => true,
             Error::PoolTimeout => true,
+            Error::ConnectionBusy => true,
             Error::Io(_) => true,
             Error::Oracle { code, .. } => {
                 // Some Oracle errors are retryable
@@ -160,6 +240,364 @@ impl Error {
             _ => None,
         }
     }
+
+    /// Constraint named by a unique, foreign key or check violation
+    pub fn constraint(&self) -> Option<Constraint> {
+        let Error::Oracle { code, message, .. } = self else {
+            return None;
+        };
+        match *code {
+            codes::UNIQUE_CONSTRAINT
+            | codes::CHECK_CONSTRAINT
+            | codes::PARENT_KEY_NOT_FOUND
+            | codes::CHILD_RECORD_FOUND => Constraint::parse(message),
+            _ => None,
+        }
+    }
+
+    /// Likely cause of a common connectivity or login failure
+    ///
+    /// Also recognizes `ORA-`/`TNS-` codes quoted in connection error text.
+    pub fn hint(&self) -> Option<Hint> {
+        match self {
+            Error::Oracle { code, .. } => Hint::from_code(*code),
+            Error::Connection(message) | Error::AuthenticationFailed(message) => {
+                Hint::from_message(message)
+            }
+            _ => None,
+        }
+    }
+
+    /// Classify the error, by ORA code for Oracle errors
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Oracle { code, .. } => ErrorKind::from_code(*code),
+            Error::Connection(_) | Error::ConnectionClosed | Error::Io(_) => {
+                ErrorKind::ConnectionLost
+            }
+            Error::AuthenticationFailed(_) => ErrorKind::AuthenticationFailed,
+            Error::Timeout | Error::Cancelled | Error::ConnectionBusy => ErrorKind::Timeout,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// Longest SQL text kept in an [`ErrorContext`]
+const MAX_CONTEXT_SQL: usize = 1000;
+
+/// Session and statement an error came from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Connection string with credentials masked
+    pub connection_string: String,
+    /// Session ID (`V$SESSION.SID`)
+    pub session_id: Option<u64>,
+    /// Session serial number (`V$SESSION.SERIAL#`)
+    pub serial_number: Option<u32>,
+    /// Database instance that served the session
+    pub instance_name: Option<String>,
+    /// Failing statement, truncated to 1000 bytes
+    pub sql: Option<String>,
+}
+
+impl ErrorContext {
+    /// Context for a session on `connection_string`, masking credentials
+    pub fn new(connection_string: &str) -> Self {
+        Self {
+            connection_string: redact_connection_string(connection_string),
+            ..Default::default()
+        }
+    }
+
+    /// Record the failing statement, truncating long text
+    pub fn sql(mut self, sql: &str) -> Self {
+        let mut sql = sql.trim().to_string();
+        if sql.len() > MAX_CONTEXT_SQL {
+            let mut end = MAX_CONTEXT_SQL;
+            while !sql.is_char_boundary(end) {
+                end -= 1;
+            }
+            sql.truncate(end);
+            sql.push_str("...");
+        }
+        self.sql = Some(sql);
+        self
+    }
+}
+
+/// Mask passwords in `user/password@host` and `password=` parameters
+pub(crate) fn redact_connection_string(connection_string: &str) -> String {
+    let (scheme, body) = match connection_string.split_once("://") {
+        Some((scheme, body)) => (Some(scheme), body),
+        None => (None, connection_string),
+    };
+    let mut out = scheme.map(|s| format!("{}://", s)).unwrap_or_default();
+
+    let rest = match body.rfind('@') {
+        Some(at) => {
+            let credentials = &body[..at];
+            match credentials.find(['/', ':']) {
+                Some(sep) => {
+                    out.push_str(&credentials[..=sep]);
+                    out.push_str("***");
+                }
+                None => out.push_str(credentials),
+            }
+            &body[at..]
+        }
+        None => body,
+    };
+
+    let (address, params) = match rest.split_once('?') {
+        Some((address, params)) => (address, Some(params)),
+        None => (rest, None),
+    };
+    out.push_str(address);
+    if let Some(params) = params {
+        let params: Vec<String> = params
+            .split('&')
+            .map(|param| match param.split_once('=') {
+                Some((key, _)) if key.to_ascii_lowercase().contains("password") => {
+                    format!("{}=***", key)
+                }
+                _ => param.to_string(),
+            })
+            .collect();
+        out.push('?');
+        out.push_str(&params.join("&"));
+    }
+    out
+}
+
+/// Constraint named in a violation message
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Constraint {
+    /// Owning schema, when the message qualifies the name
+    pub schema: Option<String>,
+    /// Constraint name
+    pub name: String,
+}
+
+impl Constraint {
+    /// Parse `... constraint (SCHEMA.NAME) violated ...`
+    fn parse(message: &str) -> Option<Self> {
+        let rest = &message[message.find("constraint (")? + "constraint (".len()..];
+        let qualified = &rest[..rest.find(')')?];
+        let (schema, name) = match qualified.split_once('.') {
+            Some((schema, name)) => (Some(schema.to_string()), name),
+            None => (None, qualified),
+        };
+        if name.is_empty() {
+            return None;
+        }
+        Some(Self {
+            schema,
+            name: name.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.schema {
+            Some(schema) => write!(f, "{}.{}", schema, self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+/// Condition the server reported on a call that still succeeded, such as
+/// ORA-24344 for PL/SQL compiled with errors
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Warning {
+    /// Oracle error code
+    pub code: i32,
+    /// Warning message
+    pub message: String,
+}
+
+impl Warning {
+    /// Days left before the password expires, for ORA-28002
+    pub fn password_grace_days(&self) -> Option<u32> {
+        if self.code != 28002 {
+            return None;
+        }
+        // "the password will expire within 7 days"
+        self.message
+            .split_whitespace()
+            .find_map(|word| word.parse().ok())
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ORA-{:05}: {}", self.code, self.message)
+    }
+}
+
+/// Diagnosis for a common connectivity or login failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Hint {
+    /// ORA-12154: the net service name or alias could not be resolved
+    UnresolvedAlias,
+    /// ORA-12514: the listener does not know the requested service
+    ServiceNotRegistered,
+    /// ORA-12541: nothing is listening on the host and port
+    NoListener,
+    /// ORA-28759: the wallet could not be opened
+    WalletOpenFailed,
+    /// ORA-01017: invalid username or password
+    InvalidCredentials,
+}
+
+impl Hint {
+    /// Hint for an ORA or TNS error code
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            codes::TNS_NO_SERVICE => Some(Hint::UnresolvedAlias),
+            codes::SERVICE_NOT_REGISTERED => Some(Hint::ServiceNotRegistered),
+            codes::NO_LISTENER => Some(Hint::NoListener),
+            codes::WALLET_OPEN_FAILED => Some(Hint::WalletOpenFailed),
+            codes::INVALID_USERNAME_PASSWORD => Some(Hint::InvalidCredentials),
+            _ => None,
+        }
+    }
+
+    /// Hint for the first `ORA-nnnnn` or `TNS-nnnnn` code quoted in `message`
+    fn from_message(message: &str) -> Option<Self> {
+        ["ORA-", "TNS-"]
+            .iter()
+            .filter_map(|prefix| {
+                let start = message.find(prefix)? + prefix.len();
+                let digits: String = message[start..]
+                    .chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect();
+                digits.parse().ok().and_then(Self::from_code)
+            })
+            .next()
+    }
+
+    /// What to check, for people reading the error
+    pub fn message(&self) -> &'static str {
+        match self {
+            Hint::UnresolvedAlias => {
+                "net service name not found; check the alias in tnsnames.ora \
+                 (TNS_ADMIN) or use an EZ Connect string such as host:1521/service"
+            }
+            Hint::ServiceNotRegistered => {
+                "service name not registered with the listener; check \
+                 `lsnrctl services` and that the database is open"
+            }
+            Hint::NoListener => {
+                "no listener at that host and port; check the address, that \
+                 the listener is running (`lsnrctl status`) and firewalls"
+            }
+            Hint::WalletOpenFailed => {
+                "could not open the wallet; check the wallet directory, its \
+                 file permissions and the wallet password"
+            }
+            Hint::InvalidCredentials => {
+                "invalid username or password; check the credentials and \
+                 whether the account is locked or the password expired"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// What went wrong, independent of the exact ORA code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Unique constraint violated (ORA-00001)
+    UniqueViolation,
+    /// Parent key missing or child rows present (ORA-02291, ORA-02292)
+    ForeignKeyViolation,
+    /// NULL into a NOT NULL column (ORA-01400, ORA-01407)
+    NotNullViolation,
+    /// Check constraint violated (ORA-02290)
+    CheckViolation,
+    /// Value too large for its column (ORA-12899, ORA-01438)
+    ValueTooLarge,
+    /// Deadlock detected (ORA-00060)
+    Deadlock,
+    /// Serializable transaction could not proceed (ORA-08177)
+    Serialization,
+    /// Row locked by another session with NOWAIT (ORA-00054)
+    ResourceBusy,
+    /// Missing privilege (ORA-01031, ORA-01749)
+    InsufficientPrivilege,
+    /// Table, view, sequence or other object does not exist
+    ObjectNotFound,
+    /// SELECT INTO found no row (ORA-01403)
+    NoDataFound,
+    /// SELECT INTO found several rows (ORA-01422)
+    TooManyRows,
+    /// Invalid number conversion (ORA-01722)
+    InvalidNumber,
+    /// Syntax or semantic error in the statement
+    InvalidSql,
+    /// Call cancelled or timed out
+    Timeout,
+    /// Credentials rejected or account unusable
+    AuthenticationFailed,
+    /// Session or network connection lost
+    ConnectionLost,
+    /// Anything not classified above
+    Other,
+}
+
+impl ErrorKind {
+    /// Classify an ORA error code
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            codes::UNIQUE_CONSTRAINT => ErrorKind::UniqueViolation,
+            codes::PARENT_KEY_NOT_FOUND | codes::CHILD_RECORD_FOUND => {
+                ErrorKind::ForeignKeyViolation
+            }
+            codes::CANNOT_INSERT_NULL | codes::CANNOT_UPDATE_TO_NULL => ErrorKind::NotNullViolation,
+            codes::CHECK_CONSTRAINT => ErrorKind::CheckViolation,
+            codes::VALUE_TOO_LARGE | codes::PRECISION_TOO_LARGE => ErrorKind::ValueTooLarge,
+            codes::DEADLOCK => ErrorKind::Deadlock,
+            codes::CANNOT_SERIALIZE => ErrorKind::Serialization,
+            codes::RESOURCE_BUSY => ErrorKind::ResourceBusy,
+            codes::INSUFFICIENT_PRIVILEGES | codes::GRANT_TO_SELF => {
+                ErrorKind::InsufficientPrivilege
+            }
+            // Table or view, object, sequence, procedure or function
+            codes::TABLE_NOT_FOUND
+            | codes::OBJECT_NOT_FOUND
+            | codes::SEQUENCE_NOT_FOUND
+            | codes::PLSQL_OBJECT_NOT_FOUND
+            | codes::FUNCTION_NOT_FOUND => ErrorKind::ObjectNotFound,
+            codes::NO_DATA_FOUND => ErrorKind::NoDataFound,
+            codes::TOO_MANY_ROWS => ErrorKind::TooManyRows,
+            codes::INVALID_NUMBER => ErrorKind::InvalidNumber,
+            // ORA-009xx: missing keyword, invalid identifier and friends
+            900..=999 => ErrorKind::InvalidSql,
+            codes::TIMEOUT | codes::INBOUND_CONNECT_TIMEOUT | codes::TNS_CONNECT_TIMEOUT => {
+                ErrorKind::Timeout
+            }
+            codes::INVALID_USERNAME_PASSWORD | codes::ACCOUNT_LOCKED | codes::PASSWORD_EXPIRED => {
+                ErrorKind::AuthenticationFailed
+            }
+            codes::EOF_COMMUNICATION
+            | codes::NOT_CONNECTED
+            | codes::NOT_LOGGED_ON
+            | codes::SESSION_KILLED => ErrorKind::ConnectionLost,
+            codes::CONNECTION_TIMEOUT | codes::CLOSED_CONNECTION | codes::NO_MORE_DATA => {
+                ErrorKind::ConnectionLost
+            }
+            _ => ErrorKind::Other,
+        }
+    }
 }
 
 /// Common Oracle error codes
@@ -167,9 +605,69 @@ pub mod codes {
     /// Unique constraint violated
     pub const UNIQUE_CONSTRAINT: i32 = 1;
 
+    /// Integrity constraint violated - parent key not found
+    pub const PARENT_KEY_NOT_FOUND: i32 = 2291;
+
+    /// Integrity constraint violated - child record found
+    pub const CHILD_RECORD_FOUND: i32 = 2292;
+
+    /// Check constraint violated
+    pub const CHECK_CONSTRAINT: i32 = 2290;
+
+    /// Cannot insert NULL
+    pub const CANNOT_INSERT_NULL: i32 = 1400;
+
+    /// Cannot update to NULL
+    pub const CANNOT_UPDATE_TO_NULL: i32 = 1407;
+
+    /// Value too large for column
+    pub const VALUE_TOO_LARGE: i32 = 12899;
+
+    /// Value larger than specified precision allowed for this column
+    pub const PRECISION_TOO_LARGE: i32 = 1438;
+
+    /// Cannot serialize access for this transaction
+    pub const CANNOT_SERIALIZE: i32 = 8177;
+
+    /// Insufficient privileges
+    pub const INSUFFICIENT_PRIVILEGES: i32 = 1031;
+
+    /// Cannot grant or revoke privileges to or from yourself
+    pub const GRANT_TO_SELF: i32 = 1749;
+
+    /// Table or view does not exist
+    pub const TABLE_NOT_FOUND: i32 = 942;
+
+    /// Object does not exist
+    pub const OBJECT_NOT_FOUND: i32 = 4043;
+
+    /// Sequence does not exist
+    pub const SEQUENCE_NOT_FOUND: i32 = 2289;
+
+    /// Object named in a PL/SQL call does not exist
+    pub const PLSQL_OBJECT_NOT_FOUND: i32 = 6564;
+
+    /// Not a valid function or procedure name
+    pub const FUNCTION_NOT_FOUND: i32 = 6576;
+
+    /// Invalid number
+    pub const INVALID_NUMBER: i32 = 1722;
+
+    /// Not connected to Oracle
+    pub const NOT_CONNECTED: i32 = 3114;
+
     /// Invalid username/password
     pub const INVALID_USERNAME_PASSWORD: i32 = 1017;
 
+    /// Account is locked
+    pub const ACCOUNT_LOCKED: i32 = 28000;
+
+    /// Password has expired
+    pub const PASSWORD_EXPIRED: i32 = 28001;
+
+    /// Session has been killed
+    pub const SESSION_KILLED: i32 = 28;
+
     /// Not logged on
     pub const NOT_LOGGED_ON: i32 = 1012;
 
@@ -188,14 +686,35 @@ pub mod codes {
     /// Timeout occurred
     pub const TIMEOUT: i32 = 1013;
 
+    /// Inbound connection timed out
+    pub const INBOUND_CONNECT_TIMEOUT: i32 = 3136;
+
+    /// TNS: connect timeout occurred
+    pub const TNS_CONNECT_TIMEOUT: i32 = 12170;
+
     /// Connection timeout
     pub const CONNECTION_TIMEOUT: i32 = 17002;
 
+    /// Closed connection
+    pub const CLOSED_CONNECTION: i32 = 17008;
+
+    /// No more data to read from socket
+    pub const NO_MORE_DATA: i32 = 17410;
+
     /// End of file on communication channel
     pub const EOF_COMMUNICATION: i32 = 3113;
 
     /// TNS: could not resolve service name
     pub const TNS_NO_SERVICE: i32 = 12154;
+
+    /// TNS: listener does not currently know of service
+    pub const SERVICE_NOT_REGISTERED: i32 = 12514;
+
+    /// TNS: no listener
+    pub const NO_LISTENER: i32 = 12541;
+
+    /// Could not open wallet
+    pub const WALLET_OPEN_FAILED: i32 = 28759;
 }
 
 #[cfg(test)]
@@ -217,6 +736,115 @@ mod tests {
         assert!(!Error::oracle(1, "unique constraint").is_retryable());
     }
 
+    #[test]
+    fn test_fatal_errors() {
+        assert!(Error::oracle(3113, "end-of-file on communication channel").is_fatal());
+        assert!(Error::oracle(1012, "not logged on").is_fatal());
+        assert!(Error::Protocol("unexpected packet".into()).is_fatal());
+        assert!(!Error::oracle(1, "unique constraint").is_fatal());
+        assert!(!Error::Timeout.is_fatal());
+    }
+
+    #[test]
+    fn test_error_kind() {
+        assert_eq!(
+            Error::oracle(1, "unique constraint").kind(),
+            ErrorKind::UniqueViolation
+        );
+        assert_eq!(
+            Error::oracle(2292, "child record found").kind(),
+            ErrorKind::ForeignKeyViolation
+        );
+        assert_eq!(
+            Error::oracle(942, "table or view does not exist").kind(),
+            ErrorKind::ObjectNotFound
+        );
+        assert_eq!(
+            Error::oracle(936, "missing expression").kind(),
+            ErrorKind::InvalidSql
+        );
+        assert_eq!(Error::oracle(8177, "").kind(), ErrorKind::Serialization);
+        assert_eq!(Error::oracle(20001, "app error").kind(), ErrorKind::Other);
+        assert_eq!(Error::ConnectionClosed.kind(), ErrorKind::ConnectionLost);
+        assert_eq!(Error::Timeout.kind(), ErrorKind::Timeout);
+    }
+
+    #[test]
+    fn test_constraint_name() {
+        let err = Error::oracle(1, "ORA-00001: unique constraint (SCOTT.PK_EMP) violated");
+        let constraint = err.constraint().unwrap();
+        assert_eq!(constraint.schema.as_deref(), Some("SCOTT"));
+        assert_eq!(constraint.name, "PK_EMP");
+        assert_eq!(constraint.to_string(), "SCOTT.PK_EMP");
+
+        let err = Error::oracle(
+            2291,
+            "integrity constraint (HR.FK_DEPT) violated - parent key not found",
+        );
+        assert_eq!(err.constraint().unwrap().name, "FK_DEPT");
+
+        // 23ai adds the table and columns after the name
+        let err = Error::oracle(
+            1,
+            "unique constraint (APP.UQ_EMAIL) violated on table APP.USERS columns (EMAIL)",
+        );
+        assert_eq!(err.constraint().unwrap().name, "UQ_EMAIL");
+
+        assert!(Error::oracle(942, "table or view does not exist")
+            .constraint()
+            .is_none());
+        assert!(Error::oracle(1, "unique constraint violated")
+            .constraint()
+            .is_none());
+    }
+
+    #[test]
+    fn test_error_context() {
+        let context = ErrorContext::new("scott/tiger@db.example.com:1521/ORCLPDB1")
+            .sql(&format!("SELECT {} FROM dual", "x, ".repeat(400)));
+        assert_eq!(
+            context.connection_string,
+            "scott/***@db.example.com:1521/ORCLPDB1"
+        );
+        assert!(context.sql.as_ref().unwrap().len() <= MAX_CONTEXT_SQL + 3);
+
+        let err = Error::oracle(942, "table or view does not exist").with_context(context);
+        assert!(err
+            .context()
+            .unwrap()
+            .sql
+            .as_ref()
+            .unwrap()
+            .starts_with("SELECT x"));
+        assert!(Error::Timeout
+            .with_context(ErrorContext::default())
+            .context()
+            .is_none());
+
+        assert_eq!(
+            redact_connection_string(
+                "tcps://app:s3cret@db:2484/svc?wallet_password=pw&retry_count=3"
+            ),
+            "tcps://app:***@db:2484/svc?wallet_password=***&retry_count=3"
+        );
+        assert_eq!(redact_connection_string("db:1521/svc"), "db:1521/svc");
+    }
+
+    #[test]
+    fn test_error_hints() {
+        let err = Error::oracle(12514, "TNS:listener does not currently know of service");
+        assert_eq!(err.hint(), Some(Hint::ServiceNotRegistered));
+        assert!(err.hint().unwrap().to_string().contains("lsnrctl services"));
+
+        let err = Error::Connection("connect failed: TNS-12541: TNS:no listener".into());
+        assert_eq!(err.hint(), Some(Hint::NoListener));
+        let err = Error::AuthenticationFailed("ORA-01017: invalid username/password".into());
+        assert_eq!(err.hint(), Some(Hint::InvalidCredentials));
+
+        assert_eq!(Error::oracle(942, "no such table").hint(), None);
+        assert_eq!(Error::Connection("connection reset".into()).hint(), None);
+    }
+
     #[test]
     fn test_error_display() {
         let err = Error::oracle(1017, "invalid username/password");
@@ -0,0 +1,342 @@
+// Fetch type handlers
+
+//! Per-query control over how columns are fetched, after node-oracledb's
+//! `fetchTypeHandler`.
+//!
+//! A [`FetchTypeHandler`] set in [`ExecuteOptions::fetch_type_handler`]
+//! is called once for each column of the query, after the describe and
+//! before any row is fetched. It returns `None` to fetch the column as
+//! usual, or a [`FetchType`] to fetch it as another type and pass each
+//! value through a converter:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::fetch_type::{FetchType, FetchTypeHandler};
+//!
+//! let options = ExecuteOptions {
+//!     fetch_type_handler: Some(FetchTypeHandler::new(|column| match column.oracle_type {
+//!         // Keep NUMBER digits exact rather than rounding to f64
+//!         OracleType::Number if column.scale != Some(0) => {
+//!             Some(FetchType::new().fetch_as(OracleType::Varchar2))
+//!         }
+//!         _ if column.name.ends_with("_FLAG") => Some(FetchType::new().converter(|v| {
+//!             Ok(Value::Boolean(v.as_str() == Some("Y")))
+//!         })),
+//!         _ => None,
+//!     })),
+//!     ..Default::default()
+//! };
+//! let rows = stmt.execute_with(&[], &options).await?;
+//! ```
+//!
+//! A failed conversion fails the query, or the stream at that row.
+//! Column metadata still describes the columns as the database does.
+//!
+//! [`ExecuteOptions::fetch_type_handler`]: crate::ExecuteOptions::fetch_type_handler
+
+use crate::statement::Row;
+use crate::types::{ColumnInfo, FromSql, OracleType, Value};
+use crate::{Error, Result};
+use std::fmt;
+use std::sync::Arc;
+
+/// Converter run on each fetched value of a column, NULLs included
+pub type Converter = Arc<dyn Fn(Value) -> Result<Value> + Send + Sync>;
+
+/// How to fetch one column
+#[derive(Clone, Default)]
+pub struct FetchType {
+    fetch_as: Option<OracleType>,
+    converter: Option<Converter>,
+}
+
+impl FetchType {
+    /// Fetch the column as it is
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetch the column as `oracle_type` instead of its own type
+    ///
+    /// Any scalar column can be fetched as character data or CLOB;
+    /// numeric text as NUMBER, BINARY_DOUBLE or BINARY_FLOAT; character
+    /// data as RAW or BLOB, or as JSON when it holds a document. Other
+    /// targets fail the query with [`Error::TypeMismatch`].
+    pub fn fetch_as(mut self, oracle_type: OracleType) -> Self {
+        self.fetch_as = Some(oracle_type);
+        self
+    }
+
+    /// Pass each value through `converter`, after any
+    /// [`fetch_as`](Self::fetch_as) conversion
+    pub fn converter(
+        mut self,
+        converter: impl Fn(Value) -> Result<Value> + Send + Sync + 'static,
+    ) -> Self {
+        self.converter = Some(Arc::new(converter));
+        self
+    }
+
+    fn apply(&self, value: Value) -> Result<Value> {
+        let value = match self.fetch_as {
+            Some(oracle_type) => convert(value, oracle_type)?,
+            None => value,
+        };
+        match &self.converter {
+            Some(converter) => converter(value),
+            None => Ok(value),
+        }
+    }
+}
+
+impl fmt::Debug for FetchType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FetchType")
+            .field("fetch_as", &self.fetch_as)
+            .field("converter", &self.converter.is_some())
+            .finish()
+    }
+}
+
+type HandlerFn = dyn Fn(&ColumnInfo) -> Option<FetchType> + Send + Sync;
+
+/// Callback choosing how each column of a query is fetched
+#[derive(Clone)]
+pub struct FetchTypeHandler(Arc<HandlerFn>);
+
+impl FetchTypeHandler {
+    /// Call `handler` with each column; `None` fetches it as usual
+    pub fn new(handler: impl Fn(&ColumnInfo) -> Option<FetchType> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(handler))
+    }
+
+    /// How each of `columns` is fetched, or `None` when all are fetched
+    /// as usual
+    pub(crate) fn plan(&self, columns: &[ColumnInfo]) -> Option<FetchPlan> {
+        let columns: Vec<Option<FetchType>> = columns.iter().map(|c| (self.0)(c)).collect();
+        columns
+            .iter()
+            .any(Option::is_some)
+            .then_some(FetchPlan { columns })
+    }
+}
+
+impl fmt::Debug for FetchTypeHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FetchTypeHandler")
+    }
+}
+
+/// The handler's choices for the columns of one query
+#[derive(Debug, Clone)]
+pub(crate) struct FetchPlan {
+    columns: Vec<Option<FetchType>>,
+}
+
+impl FetchPlan {
+    /// Convert the values of `row` the plan covers
+    pub(crate) fn apply(&self, row: Row) -> Result<Row> {
+        let columns = row.columns().clone();
+        // NUMBER fetched as text keeps every digit the wire carries
+        let digits: Vec<Option<Value>> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, fetch_type)| {
+                let raw = row.get_raw(i)?;
+                let as_text = fetch_type.as_ref()?.fetch_as.is_some_and(is_text);
+                (as_text && raw.oracle_type() == OracleType::Number && !raw.is_null())
+                    .then(|| String::from_raw(raw).ok().map(Value::String))
+                    .flatten()
+            })
+            .collect();
+        let values = row
+            .into_values()
+            .into_iter()
+            .zip(&self.columns)
+            .zip(digits)
+            .map(|((value, fetch_type), digits)| match fetch_type {
+                Some(fetch_type) => fetch_type.apply(digits.unwrap_or(value)),
+                None => Ok(value),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Row::with_columns(values, columns))
+    }
+}
+
+/// `value` as the database would fetch it into an `oracle_type` define
+fn convert(value: Value, oracle_type: OracleType) -> Result<Value> {
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+    let converted = match oracle_type {
+        OracleType::Clob | OracleType::NClob => text(&value).map(Value::Clob),
+        _ if is_text(oracle_type) => text(&value).map(Value::String),
+        OracleType::Number | OracleType::BinaryDouble | OracleType::BinaryFloat => match &value {
+            Value::Integer(_) if oracle_type == OracleType::Number => Some(value.clone()),
+            Value::Integer(i) => Some(Value::Float(*i as f64)),
+            Value::Float(_) => Some(value.clone()),
+            Value::String(s) => {
+                let s = s.trim();
+                match s.parse::<i64>() {
+                    Ok(i) if oracle_type == OracleType::Number => Some(Value::Integer(i)),
+                    _ => s.parse::<f64>().ok().map(Value::Float),
+                }
+            }
+            _ => None,
+        },
+        OracleType::Raw | OracleType::LongRaw | OracleType::Blob => match &value {
+            Value::Bytes(b) | Value::Blob(b) => Some(b.clone()),
+            Value::String(s) | Value::Clob(s) => Some(s.as_bytes().to_vec()),
+            _ => None,
+        }
+        .map(|b| match oracle_type {
+            OracleType::Blob => Value::Blob(b),
+            _ => Value::Bytes(b),
+        }),
+        OracleType::Json => match &value {
+            Value::Json(_) => Some(value.clone()),
+            Value::String(s) | Value::Clob(s) => serde_json::from_str(s).ok().map(Value::Json),
+            _ => None,
+        },
+        _ => None,
+    };
+    converted.ok_or_else(|| {
+        Error::TypeMismatch(format!("Cannot fetch {:?} as {:?}", value, oracle_type))
+    })
+}
+
+/// Whether `oracle_type` is fetched as a Rust `String`
+fn is_text(oracle_type: OracleType) -> bool {
+    matches!(
+        oracle_type,
+        OracleType::Varchar2
+            | OracleType::NVarchar2
+            | OracleType::Char
+            | OracleType::NChar
+            | OracleType::Long
+    )
+}
+
+/// Text of a scalar value, in ISO formats for datetimes and upper-case
+/// hex for binary data, as Oracle's default conversions give RAW
+fn text(value: &Value) -> Option<String> {
+    Some(match value {
+        Value::String(s) | Value::Clob(s) => s.clone(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Boolean(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Date(d) => d.to_string(),
+        Value::Timestamp(ts) => ts.to_string(),
+        Value::TimestampTz(ts) => ts.to_rfc3339(),
+        #[cfg(feature = "chrono-tz")]
+        Value::TimestampTzRegion(ts) => ts.datetime().to_rfc3339(),
+        Value::IntervalYM(i) => i.to_string(),
+        Value::IntervalDS(i) => i.to_string(),
+        Value::Bytes(b) | Value::Blob(b) => b.iter().map(|b| format!("{:02X}", b)).collect(),
+        Value::Lob(lob) => lob.text()?.ok()?,
+        Value::Json(json) => json.to_string(),
+        Value::Vector(v) => serde_json::Value::from(v.to_f64()).to_string(),
+        Value::Null | Value::Array(_) | Value::Object(_) => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statement::ColumnIndex;
+    use crate::wire::RawValue;
+    use bytes::Bytes;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(
+            convert(Value::Integer(42), OracleType::Varchar2).unwrap(),
+            Value::String("42".into())
+        );
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        assert_eq!(
+            convert(Value::Date(date), OracleType::Varchar2).unwrap(),
+            Value::String("2024-02-29".into())
+        );
+        assert_eq!(
+            convert(Value::Bytes(vec![0xCA, 0xFE]), OracleType::Clob).unwrap(),
+            Value::Clob("CAFE".into())
+        );
+        assert_eq!(
+            convert(Value::String(" 7 ".into()), OracleType::Number).unwrap(),
+            Value::Integer(7)
+        );
+        assert_eq!(
+            convert(Value::String("7".into()), OracleType::BinaryDouble).unwrap(),
+            Value::Float(7.0)
+        );
+        assert_eq!(
+            convert(Value::String(r#"{"a":1}"#.into()), OracleType::Json).unwrap(),
+            Value::Json(serde_json::json!({"a": 1}))
+        );
+        assert_eq!(
+            convert(Value::Null, OracleType::Number).unwrap(),
+            Value::Null
+        );
+        assert!(convert(Value::String("n/a".into()), OracleType::Number).is_err());
+        assert!(convert(Value::Integer(1), OracleType::Date).is_err());
+    }
+
+    #[test]
+    fn test_plan() {
+        let columns = vec![
+            crate::types::ColumnInfo {
+                name: "ID".into(),
+                oracle_type: OracleType::Number,
+                size: 22,
+                precision: None,
+                scale: None,
+                nullable: false,
+                encrypted: false,
+                redacted: false,
+            },
+            crate::types::ColumnInfo {
+                name: "ACTIVE_FLAG".into(),
+                oracle_type: OracleType::Char,
+                size: 1,
+                precision: None,
+                scale: None,
+                nullable: true,
+                encrypted: false,
+                redacted: false,
+            },
+        ];
+        let handler = FetchTypeHandler::new(|column| {
+            column.name.ends_with("_FLAG").then(|| {
+                FetchType::new().converter(|v| Ok(Value::Boolean(v.as_str() == Some("Y"))))
+            })
+        });
+        let plan = handler.plan(&columns).unwrap();
+        let row = Row::new(
+            vec![Value::Integer(1), Value::String("Y".into())],
+            vec!["ID".into(), "ACTIVE_FLAG".into()],
+        );
+        let row = plan.apply(row).unwrap();
+        assert_eq!(row.values(), [Value::Integer(1), Value::Boolean(true)]);
+
+        assert!(FetchTypeHandler::new(|_| None).plan(&columns).is_none());
+
+        // Digits beyond f64 survive a NUMBER fetched as text
+        let handler =
+            FetchTypeHandler::new(|_| Some(FetchType::new().fetch_as(OracleType::Varchar2)));
+        let plan = handler.plan(&columns[..1]).unwrap();
+        let number = [0xC1, 2, 24, 46, 68, 90, 2, 24, 46, 68, 90];
+        let row = Row::from_raw(
+            vec![RawValue::new(
+                OracleType::Number,
+                Some(Bytes::copy_from_slice(&number)),
+            )],
+            Arc::new(ColumnIndex::new(vec!["ID".into()])),
+        );
+        assert_eq!(
+            plan.apply(row).unwrap().values(),
+            [Value::String("1.234567890123456789".into())]
+        );
+    }
+}
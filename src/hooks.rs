@@ -0,0 +1,156 @@
+// Connect hooks
+
+//! Extension points around opening a session, for service discovery and
+//! connection auditing without patching the driver.
+//!
+//! A [`Resolver`] replaces DNS for the hosts of a connection string, for
+//! example to look a logical name up in Consul or to list the pods behind
+//! a Kubernetes headless service. A pre-connect hook sees where each
+//! connection is about to go, with all of its failover addresses, and can
+//! veto it by returning an error. It runs once per connection (reconnects
+//! included), before any address is tried:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::hooks::ConnectHooks;
+//!
+//! let hooks = ConnectHooks::new()
+//!     .resolver(ConsulResolver::new(consul))
+//!     .pre_connect(|target| {
+//!         audit::record(&target.user, &target.addresses);
+//!         Ok(())
+//!     });
+//! let config = ConnectionConfig::new("orcl-primary:1521/sales", "app", password).hooks(hooks);
+//! ```
+//!
+//! Resolved addresses are tried in the order the resolver returns them,
+//! each within the address's transport connect timeout. IPC addresses are
+//! not resolved.
+
+use crate::{Error, Result};
+use futures::future::BoxFuture;
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Resolves the hosts of a connection string to socket addresses
+pub trait Resolver: Send + Sync {
+    /// Addresses to try for `host`, in order
+    ///
+    /// `port` is the one the connection string gives; a resolver may
+    /// return other ports, as service discovery often does.
+    fn resolve<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> BoxFuture<'a, io::Result<Vec<SocketAddr>>>;
+}
+
+/// Callback run once before each connection is opened
+pub type PreConnectHook = Arc<dyn Fn(&ConnectTarget) -> Result<()> + Send + Sync>;
+
+/// One address of a connection string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetAddress {
+    /// Listener reached over TCP
+    Tcp {
+        /// Host name or IP literal, before resolution
+        host: String,
+        /// Listener port
+        port: u16,
+    },
+    /// Local listener reached over its IPC socket
+    Ipc {
+        /// `KEY` of the address
+        key: String,
+    },
+}
+
+impl fmt::Display for TargetAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetAddress::Tcp { host, port } => write!(f, "{}:{}", host, port),
+            TargetAddress::Ipc { key } => write!(f, "IPC key {}", key),
+        }
+    }
+}
+
+/// Where a connection is about to go, as parsed from its connection string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectTarget {
+    /// User logging on
+    pub user: String,
+    /// Addresses in failover order
+    pub addresses: Vec<TargetAddress>,
+    /// Service name, empty when connecting by SID
+    pub service_name: String,
+    /// SID, for descriptors that use one
+    pub sid: Option<String>,
+    /// Whether a DRCP pooled server was asked for
+    pub pooled: bool,
+}
+
+/// Resolver and pre-connect hook of a connection
+#[derive(Clone, Default)]
+pub struct ConnectHooks {
+    resolver: Option<Arc<dyn Resolver>>,
+    pre_connect: Option<PreConnectHook>,
+}
+
+impl ConnectHooks {
+    /// No hooks: hosts resolve through the runtime's DNS lookup
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve hosts with `resolver` instead of DNS
+    pub fn resolver(mut self, resolver: impl Resolver + 'static) -> Self {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Call `hook` once before each connection is opened, ahead of its
+    /// address attempts; an error it returns fails the connect
+    pub fn pre_connect(
+        mut self,
+        hook: impl Fn(&ConnectTarget) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.pre_connect = Some(Arc::new(hook));
+        self
+    }
+
+    /// Custom resolver, if any
+    pub(crate) fn custom_resolver(&self) -> Option<&dyn Resolver> {
+        self.resolver.as_deref()
+    }
+
+    /// Run the pre-connect hook, if any, on `target`
+    pub(crate) fn before_connect(&self, target: &ConnectTarget) -> Result<()> {
+        match &self.pre_connect {
+            Some(hook) => hook(target).map_err(|e| match e {
+                Error::Connection(_) => e,
+                e => Error::Connection(format!("connect to {} refused by hook: {}", target, e)),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ConnectTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let addresses: Vec<String> = self.addresses.iter().map(ToString::to_string).collect();
+        match &self.sid {
+            Some(sid) => write!(f, "SID {} at {}", sid, addresses.join(", ")),
+            None => write!(f, "{} at {}", self.service_name, addresses.join(", ")),
+        }
+    }
+}
+
+impl fmt::Debug for ConnectHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectHooks")
+            .field("resolver", &self.resolver.is_some())
+            .field("pre_connect", &self.pre_connect.is_some())
+            .finish()
+    }
+}
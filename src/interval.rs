@@ -0,0 +1,521 @@
+// INTERVAL values
+
+//! INTERVAL YEAR TO MONTH and INTERVAL DAY TO SECOND values, and date
+//! arithmetic that gives the same answers as the server.
+//!
+//! Oracle adds months and days differently. A day-to-second interval is
+//! an exact span of time, but a year-to-month interval moves the month
+//! and keeps the day, so `TIMESTAMP '2024-01-31 00:00:00' + INTERVAL '1'
+//! MONTH` fails with ORA-01839 rather than rolling into March.
+//! [`IntervalYM::add_to`] fails the same way; [`add_months`] follows
+//! `ADD_MONTHS` instead, which keeps to the end of the month:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::interval::{add_months, IntervalDS, IntervalYM};
+//!
+//! let due = IntervalYM::new(0, 1).add_to(invoiced)?;
+//! let month_end = add_months(invoiced, 1)?;
+//! let elapsed = IntervalDS::between(started, finished);
+//! ```
+
+use crate::{Error, Result};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+/// Offset of the 4-byte day, year and fractional second fields on the wire
+const DURATION_MID: u32 = 0x8000_0000;
+
+/// Offset of the 1-byte month, hour, minute and second fields on the wire
+const DURATION_OFFSET: i32 = 60;
+
+const NANOS_PER_DAY: i128 = 86_400_000_000_000;
+
+/// Oracle's DATE and TIMESTAMP years
+const MIN_YEAR: i32 = -4712;
+const MAX_YEAR: i32 = 9999;
+
+/// An INTERVAL YEAR TO MONTH value
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct IntervalYM {
+    months: i64,
+}
+
+impl IntervalYM {
+    /// `years` and `months`, which may carry into years
+    pub fn new(years: i32, months: i32) -> Self {
+        Self::from_months(years as i64 * 12 + months as i64)
+    }
+
+    /// A span of `months` months
+    pub fn from_months(months: i64) -> Self {
+        Self { months }
+    }
+
+    /// Read the 5-byte wire form: a 4-byte year and a month byte
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let [y0, y1, y2, y3, month] = data.try_into().map_err(|_| {
+            Error::Encoding(format!(
+                "invalid INTERVAL YEAR TO MONTH bytes {:02x?}",
+                data
+            ))
+        })?;
+        let years = u32::from_be_bytes([y0, y1, y2, y3]).wrapping_sub(DURATION_MID) as i32;
+        Ok(Self::new(years, month as i32 - DURATION_OFFSET))
+    }
+
+    /// Whole years, negative for a negative interval
+    pub fn years(&self) -> i64 {
+        self.months / 12
+    }
+
+    /// Months past the whole years, negative for a negative interval
+    pub fn months(&self) -> i64 {
+        self.months % 12
+    }
+
+    /// The whole span in months
+    pub fn total_months(&self) -> i64 {
+        self.months
+    }
+
+    /// `ts + interval` as Oracle computes it
+    ///
+    /// The day of the month is kept, so a day the target month lacks
+    /// fails with ORA-01839, as it does on the server.
+    pub fn add_to(&self, ts: NaiveDateTime) -> Result<NaiveDateTime> {
+        let (year, month) = shift_month(ts.date(), self.months)?;
+        NaiveDate::from_ymd_opt(year, month, ts.day())
+            .map(|date| date.and_time(ts.time()))
+            .ok_or_else(|| {
+                Error::InvalidData(format!(
+                    "ORA-01839: date not valid for month specified: {} + {}",
+                    ts, self
+                ))
+            })
+    }
+
+    /// `self + other`, or None on overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.months.checked_add(other.months).map(Self::from_months)
+    }
+
+    /// `self - other`, or None on overflow
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.months.checked_sub(other.months).map(Self::from_months)
+    }
+
+    /// `-self`, or None on overflow
+    pub fn checked_neg(self) -> Option<Self> {
+        self.months.checked_neg().map(Self::from_months)
+    }
+
+    /// `ts - interval` as Oracle computes it
+    pub fn sub_from(&self, ts: NaiveDateTime) -> Result<NaiveDateTime> {
+        (-*self).add_to(ts)
+    }
+}
+
+/// `ADD_MONTHS(ts, months)`
+///
+/// When `ts` falls on the last day of its month, or on a day the target
+/// month lacks, the result is the last day of the target month. Like
+/// `ADD_MONTHS`, which returns a DATE, fractional seconds are dropped.
+pub fn add_months(ts: NaiveDateTime, months: i64) -> Result<NaiveDateTime> {
+    let date = ts.date();
+    let (year, month) = shift_month(date, months)?;
+    let last = last_day(year, month);
+    let day = if date.day() == last_day(date.year(), date.month()) {
+        last
+    } else {
+        date.day().min(last)
+    };
+    let date = NaiveDate::from_ymd_opt(year, month, day).expect("day within month");
+    Ok(date.and_time(ts.time().with_nanosecond(0).expect("zero nanoseconds")))
+}
+
+/// Year and month `months` after `date`'s
+fn shift_month(date: NaiveDate, months: i64) -> Result<(i32, u32)> {
+    let index = (date.year() as i64 * 12 + date.month0() as i64).checked_add(months);
+    let year = index.map(|i| i.div_euclid(12));
+    match year {
+        Some(year) if (MIN_YEAR as i64..=MAX_YEAR as i64).contains(&year) => {
+            let month = index.expect("index in range").rem_euclid(12) as u32 + 1;
+            Ok((year as i32, month))
+        }
+        _ => Err(year_out_of_range()),
+    }
+}
+
+fn last_day(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+fn year_out_of_range() -> Error {
+    Error::InvalidData("ORA-01841: (full) year must be between -4713 and +9999".into())
+}
+
+/// An INTERVAL DAY TO SECOND value, exact to the nanosecond
+///
+/// The time of day takes the sign of the days, as Oracle keeps it.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct IntervalDS {
+    days: i32,
+    nanos: i64,
+}
+
+impl IntervalDS {
+    /// The sum of the parts, which may carry into larger units
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sum's days do not fit an `i32`.
+    pub fn new(days: i32, hours: i32, minutes: i32, seconds: i32, nanos: i32) -> Self {
+        Self::from_nanos(Self::sum_nanos(days, hours, minutes, seconds, nanos))
+            .expect("interval out of range")
+    }
+
+    /// The parts summed in nanoseconds
+    fn sum_nanos(days: i32, hours: i32, minutes: i32, seconds: i32, nanos: i32) -> i128 {
+        let seconds =
+            ((days as i64 * 24 + hours as i64) * 60 + minutes as i64) * 60 + seconds as i64;
+        seconds as i128 * 1_000_000_000 + nanos as i128
+    }
+
+    /// A span of `nanos` nanoseconds, if its days fit an `i32`
+    pub fn from_nanos(nanos: i128) -> Option<Self> {
+        let days = i32::try_from(nanos / NANOS_PER_DAY).ok()?;
+        let nanos = (nanos % NANOS_PER_DAY) as i64;
+        Some(Self { days, nanos })
+    }
+
+    /// Read the 11-byte wire form: a 4-byte day, hour, minute and second
+    /// bytes, and 4-byte fractional seconds in nanoseconds
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let [d0, d1, d2, d3, hour, minute, second, f0, f1, f2, f3] =
+            data.try_into().map_err(|_| {
+                Error::Encoding(format!(
+                    "invalid INTERVAL DAY TO SECOND bytes {:02x?}",
+                    data
+                ))
+            })?;
+        let days = u32::from_be_bytes([d0, d1, d2, d3]).wrapping_sub(DURATION_MID) as i32;
+        let nanos = u32::from_be_bytes([f0, f1, f2, f3]).wrapping_sub(DURATION_MID) as i32;
+        Self::from_nanos(Self::sum_nanos(
+            days,
+            hour as i32 - DURATION_OFFSET,
+            minute as i32 - DURATION_OFFSET,
+            second as i32 - DURATION_OFFSET,
+            nanos,
+        ))
+        .ok_or_else(|| {
+            Error::Encoding(format!(
+                "INTERVAL DAY TO SECOND out of range: {:02x?}",
+                data
+            ))
+        })
+    }
+
+    /// `later - earlier`, as subtracting TIMESTAMPs gives on the server
+    pub fn between(earlier: NaiveDateTime, later: NaiveDateTime) -> Self {
+        // Oracle's year range spans well under i32::MAX days
+        Self::from(later - earlier)
+    }
+
+    /// Whole days, negative for a negative interval
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    /// Hours past the whole days
+    pub fn hours(&self) -> i32 {
+        (self.nanos / 3_600_000_000_000) as i32
+    }
+
+    /// Minutes past the whole hours
+    pub fn minutes(&self) -> i32 {
+        (self.nanos / 60_000_000_000 % 60) as i32
+    }
+
+    /// Seconds past the whole minutes
+    pub fn seconds(&self) -> i32 {
+        (self.nanos / 1_000_000_000 % 60) as i32
+    }
+
+    /// Nanoseconds past the whole seconds
+    pub fn nanoseconds(&self) -> i32 {
+        (self.nanos % 1_000_000_000) as i32
+    }
+
+    /// The whole span in nanoseconds
+    pub fn total_nanos(&self) -> i128 {
+        self.days as i128 * NANOS_PER_DAY + self.nanos as i128
+    }
+
+    /// `ts + interval`, failing past Oracle's year range
+    pub fn add_to(&self, ts: NaiveDateTime) -> Result<NaiveDateTime> {
+        ts.checked_add_signed(Duration::from(*self))
+            .filter(|ts| (MIN_YEAR..=MAX_YEAR).contains(&ts.year()))
+            .ok_or_else(year_out_of_range)
+    }
+
+    /// `ts - interval`, failing past Oracle's year range
+    pub fn sub_from(&self, ts: NaiveDateTime) -> Result<NaiveDateTime> {
+        self.checked_neg().ok_or_else(year_out_of_range)?.add_to(ts)
+    }
+
+    /// `self + other`, or None if the days overflow an `i32`
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Self::from_nanos(self.total_nanos() + other.total_nanos())
+    }
+
+    /// `self - other`, or None if the days overflow an `i32`
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Self::from_nanos(self.total_nanos() - other.total_nanos())
+    }
+
+    /// `-self`, or None for `i32::MIN` days
+    pub fn checked_neg(self) -> Option<Self> {
+        Self::from_nanos(-self.total_nanos())
+    }
+}
+
+impl From<IntervalDS> for Duration {
+    fn from(interval: IntervalDS) -> Self {
+        Duration::days(interval.days as i64) + Duration::nanoseconds(interval.nanos)
+    }
+}
+
+impl From<Duration> for IntervalDS {
+    /// Spans past `i32::MAX` days saturate
+    fn from(duration: Duration) -> Self {
+        let nanos =
+            duration.num_seconds() as i128 * 1_000_000_000 + duration.subsec_nanos() as i128;
+        Self::from_nanos(nanos).unwrap_or(if nanos < 0 {
+            Self {
+                days: i32::MIN,
+                nanos: 0,
+            }
+        } else {
+            Self {
+                days: i32::MAX,
+                nanos: 0,
+            }
+        })
+    }
+}
+
+/// Panics on overflow; see [`IntervalYM::checked_neg`]
+impl Neg for IntervalYM {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("interval out of range")
+    }
+}
+
+/// Panics on overflow; see [`IntervalYM::checked_add`]
+impl Add for IntervalYM {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other).expect("interval out of range")
+    }
+}
+
+/// Panics on overflow; see [`IntervalYM::checked_sub`]
+impl Sub for IntervalYM {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other).expect("interval out of range")
+    }
+}
+
+/// Panics on overflow; see [`IntervalDS::checked_neg`]
+impl Neg for IntervalDS {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("interval out of range")
+    }
+}
+
+/// Panics on overflow; see [`IntervalDS::checked_add`]
+impl Add for IntervalDS {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other).expect("interval out of range")
+    }
+}
+
+/// Panics on overflow; see [`IntervalDS::checked_sub`]
+impl Sub for IntervalDS {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other).expect("interval out of range")
+    }
+}
+
+/// Oracle's literal form, such as `+02-03`
+impl fmt::Display for IntervalYM {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.months < 0 { '-' } else { '+' };
+        write!(
+            f,
+            "{}{:02}-{:02}",
+            sign,
+            self.years().abs(),
+            self.months().abs()
+        )
+    }
+}
+
+/// Oracle's literal form, such as `+01 02:03:04.500000000`
+impl fmt::Display for IntervalDS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.days < 0 || self.nanos < 0 {
+            '-'
+        } else {
+            '+'
+        };
+        write!(
+            f,
+            "{}{:02} {:02}:{:02}:{:02}.{:09}",
+            sign,
+            self.days.unsigned_abs(),
+            self.hours().abs(),
+            self.minutes().abs(),
+            self.seconds().abs(),
+            self.nanoseconds().abs()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(y: i32, m: u32, d: u32, h: u32, mi: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, mi, s)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_month_arithmetic() {
+        let month = IntervalYM::new(0, 1);
+        assert_eq!(
+            month.add_to(ts(2024, 1, 15, 10, 0, 0)).unwrap(),
+            ts(2024, 2, 15, 10, 0, 0)
+        );
+        assert_eq!(
+            IntervalYM::new(1, 2)
+                .add_to(ts(2023, 11, 30, 0, 0, 0))
+                .unwrap(),
+            ts(2025, 1, 30, 0, 0, 0)
+        );
+        assert_eq!(
+            month.sub_from(ts(2024, 1, 15, 0, 0, 0)).unwrap(),
+            ts(2023, 12, 15, 0, 0, 0)
+        );
+        // TIMESTAMP + INTERVAL keeps the day, so January 31st has no
+        // month later
+        let err = month.add_to(ts(2024, 1, 31, 0, 0, 0)).unwrap_err();
+        assert!(err.to_string().contains("ORA-01839"));
+
+        // ADD_MONTHS clamps, and keeps to the end of the month
+        assert_eq!(
+            add_months(ts(2024, 1, 31, 8, 0, 0), 1).unwrap(),
+            ts(2024, 2, 29, 8, 0, 0)
+        );
+        assert_eq!(
+            add_months(ts(2023, 2, 28, 0, 0, 0), 1).unwrap(),
+            ts(2023, 3, 31, 0, 0, 0)
+        );
+        assert_eq!(
+            add_months(ts(2024, 3, 30, 0, 0, 0), -1).unwrap(),
+            ts(2024, 2, 29, 0, 0, 0)
+        );
+        assert!(add_months(ts(9999, 12, 1, 0, 0, 0), 1).is_err());
+
+        assert_eq!(IntervalYM::new(2, 3).to_string(), "+02-03");
+        assert_eq!((-IntervalYM::new(1, 14)).to_string(), "-02-02");
+    }
+
+    #[test]
+    fn test_day_second_arithmetic() {
+        let start = ts(2024, 2, 28, 22, 0, 0);
+        let end = ts(2024, 3, 1, 1, 30, 15);
+        let elapsed = IntervalDS::between(start, end);
+        assert_eq!(elapsed, IntervalDS::new(1, 3, 30, 15, 0));
+        assert_eq!(elapsed.add_to(start).unwrap(), end);
+        assert_eq!(elapsed.sub_from(end).unwrap(), start);
+        assert_eq!(elapsed.to_string(), "+01 03:30:15.000000000");
+
+        let back = IntervalDS::between(end, start);
+        assert_eq!(back, -elapsed);
+        assert_eq!((back.days(), back.hours(), back.minutes()), (-1, -3, -30));
+        assert_eq!(back.to_string(), "-01 03:30:15.000000000");
+        assert_eq!(
+            (IntervalDS::new(0, 0, 0, 1, 500_000_000) + IntervalDS::new(0, 23, 59, 59, 0))
+                .to_string(),
+            "+01 00:00:00.500000000"
+        );
+        assert!(IntervalDS::new(1, 0, 0, 0, 0)
+            .add_to(ts(9999, 12, 31, 12, 0, 0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        // +02-03
+        let ym = IntervalYM::decode(&[0x80, 0, 0, 2, 63]).unwrap();
+        assert_eq!((ym.years(), ym.months()), (2, 3));
+        // -1 day -2 hours
+        let ds = IntervalDS::decode(&[0x7F, 0xFF, 0xFF, 0xFF, 58, 60, 60, 0x80, 0, 0, 0]).unwrap();
+        assert_eq!(ds, -IntervalDS::new(1, 2, 0, 0, 0));
+        assert!(IntervalDS::decode(&[0x80, 0, 0, 0]).is_err());
+        // i32::MAX days and 24 hours
+        assert!(matches!(
+            IntervalDS::decode(&[0xFF, 0xFF, 0xFF, 0xFF, 84, 60, 60, 0x80, 0, 0, 0]),
+            Err(Error::Encoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let max = IntervalDS::new(i32::MAX, 0, 0, 0, 0);
+        let hour = IntervalDS::new(0, 1, 0, 0, 0);
+        assert_eq!(max.checked_add(IntervalDS::new(1, 0, 0, 0, 0)), None);
+        assert_eq!(
+            max.checked_sub(hour),
+            Some(IntervalDS::new(i32::MAX - 1, 23, 0, 0, 0))
+        );
+        assert_eq!(IntervalDS::new(i32::MIN, 0, 0, 0, 0).checked_neg(), None);
+        assert_eq!(hour.checked_neg(), Some(-hour));
+
+        let max = IntervalYM::from_months(i64::MAX);
+        assert_eq!(max.checked_add(IntervalYM::new(0, 1)), None);
+        assert_eq!(IntervalYM::from_months(i64::MIN).checked_neg(), None);
+        assert_eq!(
+            IntervalYM::new(1, 0).checked_sub(IntervalYM::new(0, 1)),
+            Some(IntervalYM::new(0, 11))
+        );
+    }
+}
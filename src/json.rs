@@ -0,0 +1,308 @@
+// JSON helpers
+
+//! Helpers for JSON columns and the SQL/JSON functions.
+//!
+//! [`Connection::query_json`] returns each row as a `serde_json::Value`.
+//! Oracle takes JSON path expressions only as literals, so a [`JsonPath`]
+//! is validated and quoted into the SQL rather than bound; values the
+//! path compares against are bound through its PASSING clause:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::json::{JsonExists, JsonPath, JsonValue};
+//! use oracledb_rs::sql::Assembler;
+//!
+//! let items = JsonPath::new("$.items[*]?(@.sku == $sku)")?.passing("sku");
+//! let sql = Assembler::new("SELECT id, JSON_VALUE(doc, '$.total' RETURNING NUMBER) FROM orders ")
+//!     .sql("WHERE JSON_EXISTS(doc, ")
+//!     .json_path(&items)
+//!     .sql(")")
+//!     .build()?;
+//! let stmt = conn.prepare(&sql).await?;
+//! for row in stmt.execute_named(&[("sku", &sku)]).await?.rows() {
+//!     let total: JsonValue<f64> = row.get_typed(1)?;
+//! }
+//! ```
+//!
+//! [`Connection::query_json`]: crate::Connection::query_json
+
+use crate::result::value_to_json;
+use crate::sql::{identifier, quote_literal};
+use crate::statement::Row;
+use crate::types::{FromSql, OracleType, Value};
+use crate::{Error, Result};
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+/// A SQL/JSON path expression, with the variables bound to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    path: String,
+    passing: Vec<String>,
+}
+
+impl JsonPath {
+    /// Check `path` is a path expression: `$`, optionally after a `lax`
+    /// or `strict` mode
+    pub fn new(path: impl Into<String>) -> Result<Self> {
+        let path = path.into();
+        let trimmed = path.trim_start();
+        let body = ["lax", "strict"]
+            .iter()
+            .find_map(|mode| {
+                let rest = trimmed.get(mode.len()..)?;
+                (trimmed.get(..mode.len())?.eq_ignore_ascii_case(mode)
+                    && rest.starts_with(char::is_whitespace))
+                .then(|| rest.trim_start())
+            })
+            .unwrap_or(trimmed);
+        if !body.starts_with('$') || path.contains('\0') {
+            return Err(Error::InvalidConfiguration(format!(
+                "not a SQL/JSON path expression: {:?}",
+                path
+            )));
+        }
+        Ok(Self {
+            path,
+            passing: Vec::new(),
+        })
+    }
+
+    /// Bind path variable `$name` to the bind variable `:name`
+    ///
+    /// PASSING is accepted by JSON_EXISTS and, from 23ai, by JSON_VALUE
+    /// and JSON_QUERY.
+    pub fn passing(mut self, name: &str) -> Self {
+        self.passing.push(name.to_string());
+        self
+    }
+
+    /// The path expression
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+
+    /// The path as a string literal, followed by its PASSING clause
+    pub fn to_sql(&self) -> Result<String> {
+        let mut sql = quote_literal(&self.path);
+        for (i, name) in self.passing.iter().enumerate() {
+            let bind = identifier(name)?;
+            if bind.starts_with('"') {
+                return Err(Error::InvalidConfiguration(format!(
+                    "path variable {:?} must be a plain identifier",
+                    name
+                )));
+            }
+            sql.push_str(if i == 0 { " PASSING " } else { ", " });
+            sql.push_str(&format!(":{} AS \"{}\"", bind, name));
+        }
+        Ok(sql)
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+/// Result of JSON_EXISTS, which SQL before 23ai can only select through
+/// a CASE expression
+///
+/// Reads BOOLEAN, and the `1`/`0` or `'true'`/`'false'` such an
+/// expression returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonExists(pub bool);
+
+impl FromSql for JsonExists {
+    fn from_sql(value: &Value) -> Result<Self> {
+        let exists = match value {
+            Value::Boolean(b) => Some(*b),
+            Value::Integer(i) => match i {
+                0 => Some(false),
+                1 => Some(true),
+                _ => None,
+            },
+            Value::String(s) => match s.to_ascii_lowercase().as_str() {
+                "true" | "1" => Some(true),
+                "false" | "0" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        };
+        exists
+            .map(JsonExists)
+            .ok_or_else(|| Error::TypeMismatch(format!("Cannot convert {:?} to JsonExists", value)))
+    }
+}
+
+/// Result of JSON_VALUE, `None` when the path matched nothing
+///
+/// JSON_VALUE returns scalars as SQL text unless told otherwise, so a
+/// string that parses as JSON is read as that JSON: `"42"` gives 42 and
+/// `"true"` gives `true`; any other string is a JSON string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonValue<T>(pub Option<T>);
+
+impl<T: DeserializeOwned> FromSql for JsonValue<T> {
+    fn from_sql(value: &Value) -> Result<Self> {
+        let json = match value {
+            Value::Null => return Ok(JsonValue(None)),
+            Value::String(s) | Value::Clob(s) => serde_json::from_str::<T>(s)
+                .or_else(|_| serde_json::from_value(serde_json::Value::String(s.clone()))),
+            value => serde_json::from_value(value_to_json(value)),
+        };
+        json.map(|v| JsonValue(Some(v))).map_err(|e| {
+            Error::TypeMismatch(format!("Cannot convert {:?} from JSON_VALUE: {}", value, e))
+        })
+    }
+}
+
+/// A row as JSON: a lone JSON column's document, or an object keyed by
+/// column name
+///
+/// Only columns declared JSON are parsed as documents; the rest convert
+/// as [`value_to_json`] does.
+pub(crate) fn row_to_json(row: &Row) -> Result<serde_json::Value> {
+    let columns = row.columns();
+    let is_json = |i| columns.oracle_type(i) == Some(OracleType::Json);
+    match row.try_values()? {
+        [value] if is_json(0) => document(value),
+        values => columns
+            .names()
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(i, (name, value))| {
+                let json = if is_json(i) {
+                    document(value)?
+                } else {
+                    value_to_json(value)
+                };
+                Ok((name.clone(), json))
+            })
+            .collect::<Result<_>>()
+            .map(serde_json::Value::Object),
+    }
+}
+
+/// A JSON column's value, or a JSON document held as text
+fn document(value: &Value) -> Result<serde_json::Value> {
+    match value {
+        Value::Null => Ok(serde_json::Value::Null),
+        Value::Lob(lob) => match lob.text() {
+            Some(text) => serde_json::from_str(&text?)
+                .map_err(|e| Error::TypeMismatch(format!("Invalid JSON: {}", e))),
+            None => Err(Error::TypeMismatch(
+                "JSON LOB locator holds only part of the document; fetch LOBs as values".into(),
+            )),
+        },
+        value => serde_json::Value::from_sql(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_path() {
+        let path = JsonPath::new("$.items[*]?(@.sku == $sku && @.qty > $min)")
+            .unwrap()
+            .passing("sku")
+            .passing("min");
+        assert_eq!(
+            path.to_sql().unwrap(),
+            "'$.items[*]?(@.sku == $sku && @.qty > $min)' PASSING :sku AS \"sku\", :min AS \"min\""
+        );
+        assert_eq!(
+            JsonPath::new("$.name ? (@ == 'O''Brien')")
+                .unwrap()
+                .to_sql()
+                .unwrap(),
+            "'$.name ? (@ == ''O''''Brien'')'"
+        );
+        assert!(JsonPath::new("strict $.a").is_ok());
+        assert!(JsonPath::new("lax$.a").is_err());
+        assert!(JsonPath::new("a.b").is_err());
+        assert!(JsonPath::new("$.a")
+            .unwrap()
+            .passing("x y")
+            .to_sql()
+            .is_err());
+    }
+
+    #[test]
+    fn test_function_results() {
+        assert_eq!(
+            JsonExists::from_sql(&Value::Integer(1)).unwrap(),
+            JsonExists(true)
+        );
+        assert_eq!(
+            JsonExists::from_sql(&Value::String("false".into())).unwrap(),
+            JsonExists(false)
+        );
+        assert!(JsonExists::from_sql(&Value::Integer(2)).is_err());
+
+        let total: JsonValue<f64> = FromSql::from_sql(&Value::String("12.5".into())).unwrap();
+        assert_eq!(total, JsonValue(Some(12.5)));
+        let name: JsonValue<String> = FromSql::from_sql(&Value::String("Ada".into())).unwrap();
+        assert_eq!(name, JsonValue(Some("Ada".to_string())));
+        let flag: JsonValue<bool> = FromSql::from_sql(&Value::Boolean(true)).unwrap();
+        assert_eq!(flag, JsonValue(Some(true)));
+        let missing: JsonValue<i64> = FromSql::from_sql(&Value::Null).unwrap();
+        assert_eq!(missing, JsonValue(None));
+        assert!(JsonValue::<i64>::from_sql(&Value::String("n/a".into())).is_err());
+    }
+
+    /// A row of `values` under `columns` of declared types
+    fn typed_row(columns: &[(&str, OracleType)], values: Vec<Value>) -> Row {
+        let columns: Vec<_> = columns
+            .iter()
+            .map(|&(name, oracle_type)| crate::types::ColumnInfo {
+                name: name.into(),
+                oracle_type,
+                size: 0,
+                precision: None,
+                scale: None,
+                nullable: true,
+                encrypted: false,
+                redacted: false,
+            })
+            .collect();
+        let index = crate::statement::ColumnIndex::from_columns(&columns);
+        Row::with_columns(values, std::sync::Arc::new(index))
+    }
+
+    #[test]
+    fn test_row_to_json() {
+        let doc = typed_row(
+            &[("DOC", OracleType::Json)],
+            vec![Value::String(r#"{"id": 1, "tags": ["a"]}"#.into())],
+        );
+        assert_eq!(row_to_json(&doc).unwrap(), json!({"id": 1, "tags": ["a"]}));
+
+        let row = typed_row(
+            &[("ID", OracleType::Number), ("DOC", OracleType::Json)],
+            vec![Value::Integer(7), Value::String(r#"{"ok": true}"#.into())],
+        );
+        assert_eq!(
+            row_to_json(&row).unwrap(),
+            json!({"ID": 7, "DOC": {"ok": true}})
+        );
+        let bad = typed_row(
+            &[("DOC", OracleType::Json)],
+            vec![Value::String("{".into())],
+        );
+        assert!(row_to_json(&bad).is_err());
+
+        // A lone column of another type is not parsed
+        let name = typed_row(
+            &[("NAME", OracleType::Varchar2)],
+            vec![Value::String("KING".into())],
+        );
+        assert_eq!(row_to_json(&name).unwrap(), json!({"NAME": "KING"}));
+        let untyped = Row::new(vec![Value::String("{".into())], vec!["DOC".into()]);
+        assert_eq!(row_to_json(&untyped).unwrap(), json!({"DOC": "{"}));
+    }
+}
@@ -44,28 +44,140 @@
 //! }
 //! ```
 
+/// Direct-path style bulk inserts
+pub mod append;
 /// Authentication mechanisms for Oracle Database
 pub mod auth;
+/// Blocking connection facade
+#[cfg(feature = "blocking")]
+pub mod blocking;
+/// Memory budget for fetched rows
+pub(crate) mod budget;
+/// Reusable I/O buffers
+pub(crate) mod buffer;
+/// CSV and iterator bulk loading
+#[cfg(feature = "bulkload")]
+pub mod bulkload;
+/// Driver features available per mode and server release
+pub mod capabilities;
+/// Wire capture and replay
+#[cfg(feature = "capture")]
+pub mod capture;
+/// Schema introspection via the data dictionary
+pub mod catalog;
+/// Changesets of statements with different SQL
+pub mod changeset;
 /// Connection management and configuration
 pub mod connection;
+/// TNS connect descriptor builder
+pub mod descriptor;
+/// Session statistics around a piece of work
+pub mod diag;
+/// Diesel backend and blocking connection adapter
+#[cfg(feature = "diesel")]
+pub mod diesel;
 /// Error types and handling
 pub mod error;
+/// Per-column fetch type handlers
+pub mod fetch_type;
+/// Host resolution and pre-connect hooks
+pub mod hooks;
+/// INTERVAL values and date arithmetic
+pub mod interval;
+/// JSON column helpers
+pub mod json;
+/// LOB locators
+pub mod lob;
+/// Column metadata cache for described statements
+pub(crate) mod metadata_cache;
+/// Versioned schema migrations
+#[cfg(feature = "migrations")]
+pub mod migrations;
+/// Background listener for database notifications
+pub mod notify;
+/// OpenTelemetry context propagation
+#[cfg(feature = "otel")]
+pub mod otel;
+/// Statement pipelining
+pub mod pipeline;
+/// Execution plans
+pub mod plan;
 /// Connection pooling functionality
 pub mod pool;
 /// Oracle network protocol implementation
 pub mod protocol;
+/// r2d2 connection manager
+#[cfg(feature = "r2d2")]
+pub mod r2d2;
 /// Result formatting and utilities
 pub mod result;
+/// Client result cache
+pub(crate) mod result_cache;
+/// Retry policy for transient errors
+pub mod retry;
+/// Async runtime abstraction
+pub mod runtime;
+/// SeaORM driver adapter
+#[cfg(feature = "sea-orm")]
+pub mod sea_orm;
+/// sea-query Oracle query builder
+#[cfg(feature = "sea-query")]
+pub mod sea_query;
+/// Redacted, zeroizing credential storage
+pub mod secret;
+/// Slow-query logging
+pub mod slow_query;
+/// Identifier quoting and dynamic SQL assembly
+pub mod sql;
+/// sqlnet.ora profile parameters
+pub mod sqlnet;
+/// sqlx driver implementation
+#[cfg(feature = "sqlx")]
+pub mod sqlx;
 /// SQL statement execution
 pub mod statement;
+/// Statement cache keyed by normalized SQL
+pub(crate) mod stmt_cache;
+/// Streaming query results
+pub mod stream;
+/// Oracle containers for integration tests
+#[cfg(feature = "testcontainers")]
+pub mod testcontainers;
+/// Mock connections for unit tests
+#[cfg(feature = "testing")]
+pub mod testing;
+/// Oracle Client library loading for thick mode
+#[cfg(feature = "thick")]
+pub mod thick;
 /// Oracle data type mappings
 pub mod types;
+/// IANA time zone regions of TIMESTAMP WITH TIME ZONE values
+#[cfg(feature = "chrono-tz")]
+pub mod tz;
+/// VECTOR values and similarity search
+pub mod vector;
+/// Oracle wire formats for column data
+pub mod wire;
+/// Two-phase commit for external transaction managers
+pub mod xa;
 
-pub use connection::{Connection, ConnectionConfig, ConnectionMode};
-pub use error::{Error, Result};
+pub use connection::{Connection, ConnectionConfig, ConnectionMode, Intent, PoolBoundary, Purity};
+pub use descriptor::{ConnectDescriptor, TransportProtocol};
+pub use error::{Constraint, Error, ErrorContext, ErrorKind, Hint, Result, Warning};
+pub use interval::{IntervalDS, IntervalYM};
+pub use lob::Lob;
 pub use pool::{Pool, PoolConfig};
-pub use statement::{ResultSet, Row, Statement};
-pub use types::{OracleType, Value};
+pub use secret::SecretString;
+pub use statement::{
+    BatchProgress, ColumnIndex, Execution, ExecutionResult, IntoStatement, Params, ResultSet, Row,
+    Statement, ToRow,
+};
+pub use stream::RowStream;
+#[cfg(feature = "thick")]
+pub use thick::init_thick;
+pub use tokio_util::sync::CancellationToken;
+pub use types::{BindType, NStr, NumberFormat, OracleType, Out, Param, Raw, Scn, Value};
+pub use vector::Vector;
 
 /// Oracle database connection modes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -87,11 +199,45 @@ pub mod constants {
     /// Default port for Oracle TNS listener
     pub const DEFAULT_PORT: u16 = 1521;
 
+    /// Default time allowed to establish a session
+    pub const DEFAULT_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+    /// Default time allowed to open the TCP (and TLS) transport to one address
+    pub const DEFAULT_TRANSPORT_CONNECT_TIMEOUT: std::time::Duration =
+        std::time::Duration::from_secs(20);
+
+    /// Directory where listeners create the sockets of IPC addresses
+    pub const DEFAULT_IPC_DIRECTORY: &str = "/var/tmp/.oracle";
+
+    /// Default session data unit the client asks for, in bytes
+    pub const DEFAULT_SDU: u32 = 8192;
+
+    /// Largest session data unit a server accepts, in bytes
+    pub const MAX_SDU: u32 = 2 * 1024 * 1024;
+
     /// Default fetch array size
     pub const DEFAULT_FETCH_ARRAY_SIZE: usize = 100;
 
+    /// Default memory budget for one fetch batch (1 MiB)
+    pub const DEFAULT_FETCH_MEMORY_BUDGET: usize = 1024 * 1024;
+
+    /// Largest array size a tuned fetch will ask for
+    pub const MAX_FETCH_ARRAY_SIZE: usize = 32767;
+
+    /// Default staleness allowed of client result cache entries
+    pub const DEFAULT_RESULT_CACHE_LAG: std::time::Duration = std::time::Duration::from_secs(3);
+
+    /// Default rows per `execute_many` round trip
+    pub const DEFAULT_BATCH_SIZE: usize = 1000;
+
     /// Default statement cache size
     pub const DEFAULT_STMT_CACHE_SIZE: usize = 30;
+
+    /// Longest RAW value with the default `MAX_STRING_SIZE = STANDARD`
+    pub const MAX_RAW_SIZE: usize = 2000;
+
+    /// Longest RAW value with `MAX_STRING_SIZE = EXTENDED`, and in PL/SQL
+    pub const MAX_RAW_SIZE_EXTENDED: usize = 32767;
 }
 
 /// Privilege modes for connections
@@ -118,12 +264,36 @@ pub enum Privilege {
 pub struct ExecuteOptions {
     /// Auto-commit after execution
     pub auto_commit: bool,
-    /// Array size for fetch operations
+    /// Array size for fetch operations, or for the first fetch when tuned
     pub fetch_array_size: usize,
+    /// Bytes a fetch batch may take; streams size later fetches to fit
+    /// the observed row width (0 = always use `fetch_array_size`)
+    pub fetch_memory_budget: usize,
     /// Maximum number of rows to fetch (0 = unlimited)
     pub max_rows: usize,
+    /// Bytes of rows a stream may buffer ahead of the caller; it stops
+    /// fetching until rows are consumed (0 = unlimited)
+    pub max_result_bytes: usize,
+    /// Fetch batches a stream keeps in hand, counting the current one
+    /// (2 = fetch the next batch while the current one is consumed)
+    pub prefetch_batches: usize,
     /// Result set format
     pub out_format: OutFormat,
+    /// Abandon the call once cancelled, breaking off a call in progress
+    pub cancel: Option<CancellationToken>,
+    /// Serve a `/*+ RESULT_CACHE */` query from the client result cache
+    /// when possible (see [`ConnectionConfig::result_cache_size`])
+    pub result_cache: bool,
+    /// Longest to wait behind other calls on the connection before
+    /// failing with [`Error::ConnectionBusy`] (None = wait indefinitely,
+    /// zero = fail at once if busy)
+    pub queue_timeout: Option<std::time::Duration>,
+    /// Append the `ORA_ROWSCN` of each row's table to a single-table
+    /// query, read back with [`Row::rowscn`]
+    pub include_rowscn: bool,
+    /// Callback choosing how each column of a query is fetched (see
+    /// [`fetch_type`])
+    pub fetch_type_handler: Option<fetch_type::FetchTypeHandler>,
 }
 
 impl Default for ExecuteOptions {
@@ -131,8 +301,16 @@ impl Default for ExecuteOptions {
         Self {
             auto_commit: false,
             fetch_array_size: constants::DEFAULT_FETCH_ARRAY_SIZE,
+            fetch_memory_budget: constants::DEFAULT_FETCH_MEMORY_BUDGET,
             max_rows: 0,
+            max_result_bytes: 0,
+            prefetch_batches: 2,
             out_format: OutFormat::Object,
+            cancel: None,
+            result_cache: false,
+            queue_timeout: None,
+            include_rowscn: false,
+            fetch_type_handler: None,
         }
     }
 }
@@ -0,0 +1,550 @@
+// LOB locators
+
+//! CLOB, NCLOB and BLOB columns fetched as locators rather than values.
+//!
+//! By default LOB columns are fetched whole, like VARCHAR2 and RAW. With
+//! [`ConnectionConfig::fetch_lobs`] off, each row carries a [`Lob`]
+//! locator instead, and the data is read on demand with
+//! [`Connection::read_lob`]. The server sends the LOB's length and chunk
+//! size with every locator, so [`Connection::lob_len`] costs no round
+//! trip, and up to [`ConnectionConfig::lob_prefetch_size`] bytes of data
+//! too, so small LOBs are read without one either:
+//!
+//! ```rust,ignore
+//! let config = ConnectionConfig::new(dsn, user, password)
+//!     .fetch_lobs(false)
+//!     .lob_prefetch_size(16 * 1024);
+//! let conn = Connection::connect(config).await?;
+//!
+//! for row in conn.query("SELECT id, doc FROM docs", &[]).await?.rows() {
+//!     let doc: Lob = row.get_typed(1)?;
+//!     if conn.lob_len(&doc).await? < 1_000_000 {
+//!         let data = conn.read_lob(&doc, 0, doc.len().unwrap_or(0)).await?;
+//!     }
+//! }
+//! ```
+//!
+//! [`Lob::save_to_file`] and [`Lob::load_from_file`] copy a whole LOB to
+//! or from a file in chunk-sized round trips, and [`Lob::write_to`] and
+//! [`Lob::read_from`] do the same for any async writer or reader.
+//!
+//! A locator is only valid in the session that fetched it.
+//!
+//! [`ConnectionConfig::fetch_lobs`]: crate::ConnectionConfig::fetch_lobs
+//! [`ConnectionConfig::lob_prefetch_size`]: crate::ConnectionConfig::lob_prefetch_size
+//! [`Connection::read_lob`]: crate::Connection::read_lob
+//! [`Connection::lob_len`]: crate::Connection::lob_len
+
+use crate::runtime::{DefaultRuntime, Runtime};
+use crate::types::OracleType;
+use crate::{Connection, Error, Result};
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+/// Length and chunk size follow the flags
+const PREFETCHED_METADATA: u8 = 0x01;
+
+/// Data follows the locator
+const PREFETCHED_DATA: u8 = 0x02;
+
+/// Chunk size assumed for locators sent without one
+const DEFAULT_CHUNK_SIZE: u64 = 8132;
+
+/// Rough units the copy helpers move per round trip
+const TRANSFER_SIZE: u64 = 1024 * 1024;
+
+/// Operation on the LOB behind a locator
+///
+/// Only the mock reads the operands until LOB messages are encoded.
+#[cfg_attr(not(feature = "testing"), allow(dead_code))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum LobOp<'a> {
+    /// Length only
+    Length,
+    /// `amount` units from `offset`
+    Read { offset: u64, amount: u64 },
+    /// Overwrite from `offset`, extending the LOB as needed
+    Write { offset: u64, data: &'a [u8] },
+    /// Cut down to a length
+    Trim(u64),
+}
+
+/// A LOB locator, with whatever the server sent along with it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lob {
+    oracle_type: OracleType,
+    locator: Vec<u8>,
+    len: Option<u64>,
+    chunk_size: Option<u32>,
+    prefetched: Option<Vec<u8>>,
+}
+
+impl Lob {
+    /// Wrap a locator of a `oracle_type` column, with nothing prefetched
+    pub fn new(oracle_type: OracleType, locator: Vec<u8>) -> Self {
+        Self {
+            oracle_type,
+            locator,
+            len: None,
+            chunk_size: None,
+            prefetched: None,
+        }
+    }
+
+    /// Record the length and chunk size sent with the locator
+    pub fn with_metadata(mut self, len: u64, chunk_size: u32) -> Self {
+        self.len = Some(len);
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Record the leading data sent with the locator
+    pub fn with_prefetched(mut self, data: Vec<u8>) -> Self {
+        self.prefetched = Some(data);
+        self
+    }
+
+    /// Read a locator column value as the server sends it
+    ///
+    /// A flags byte says what was prefetched; then come the length (8
+    /// bytes) and chunk size (4 bytes) if prefetched, the locator with a
+    /// 2-byte length, and any prefetched data.
+    pub fn decode(oracle_type: OracleType, data: &[u8]) -> Result<Self> {
+        let truncated = || Error::Protocol("truncated LOB locator".into());
+        let (&flags, mut rest) = data.split_first().ok_or_else(truncated)?;
+        let mut take = |n: usize| -> Result<&[u8]> {
+            let (head, tail) = rest.split_at_checked(n).ok_or_else(truncated)?;
+            rest = tail;
+            Ok(head)
+        };
+
+        let metadata = if flags & PREFETCHED_METADATA != 0 {
+            let len = u64::from_be_bytes(take(8)?.try_into().expect("8 bytes"));
+            let chunk_size = u32::from_be_bytes(take(4)?.try_into().expect("4 bytes"));
+            Some((len, chunk_size))
+        } else {
+            None
+        };
+        let locator_len = u16::from_be_bytes(take(2)?.try_into().expect("2 bytes"));
+        let mut lob = Self::new(oracle_type, take(locator_len as usize)?.to_vec());
+        if let Some((len, chunk_size)) = metadata {
+            lob = lob.with_metadata(len, chunk_size);
+        }
+        if flags & PREFETCHED_DATA != 0 {
+            lob = lob.with_prefetched(rest.to_vec());
+        }
+        Ok(lob)
+    }
+
+    /// Column type: CLOB, NCLOB, BLOB or BFILE
+    pub fn oracle_type(&self) -> OracleType {
+        self.oracle_type
+    }
+
+    /// Locator bytes, opaque to the client
+    pub fn locator(&self) -> &[u8] {
+        &self.locator
+    }
+
+    /// Length in characters for CLOB, UTF-16 code units for NCLOB and
+    /// bytes otherwise, if sent with the locator
+    pub fn len(&self) -> Option<u64> {
+        self.len
+    }
+
+    /// Whether the LOB is known to be empty
+    pub fn is_empty(&self) -> bool {
+        self.len == Some(0)
+    }
+
+    /// Size of the LOB's chunks, if sent with the locator; reads and
+    /// writes in multiples of it are the most efficient
+    pub fn chunk_size(&self) -> Option<u32> {
+        self.chunk_size
+    }
+
+    /// Leading data sent with the locator
+    pub fn prefetched(&self) -> Option<&[u8]> {
+        self.prefetched.as_deref()
+    }
+
+    /// The whole LOB, if all of it was sent with the locator
+    pub fn data(&self) -> Option<&[u8]> {
+        let data = self.prefetched.as_deref()?;
+        (self.units(data)? == self.len?).then_some(data)
+    }
+
+    /// The whole CLOB or NCLOB as text, if all of it was sent with the
+    /// locator
+    ///
+    /// Prefetched NCLOB data is UTF-16, as the server sends it; CLOB data
+    /// is UTF-8.
+    pub fn text(&self) -> Option<Result<String>> {
+        let data = self.data()?;
+        Some(match self.oracle_type {
+            OracleType::NClob => crate::wire::decode_utf16(data),
+            _ => String::from_utf8(data.to_vec())
+                .map_err(|e| Error::Encoding(format!("invalid UTF-8 in CLOB: {}", e))),
+        })
+    }
+
+    /// Copy the whole LOB to `writer`, returning the bytes written
+    ///
+    /// The LOB is read in round trips of a multiple of its chunk size; CLOB
+    /// data is written as UTF-8 and NCLOB data as UTF-16.
+    pub async fn write_to<W: AsyncWrite + Unpin>(
+        &self,
+        conn: &Connection,
+        mut writer: W,
+    ) -> Result<u64> {
+        let len = conn.lob_len(self).await?;
+        let amount = self.transfer_units();
+        let (mut offset, mut written) = (0, 0);
+        while offset < len {
+            let data = conn.read_lob(self, offset, amount).await?;
+            let units = self.units(&data).ok_or_else(|| self.invalid_text())?;
+            if units == 0 {
+                break;
+            }
+            writer.write_all(&data).await.map_err(copy_failed)?;
+            offset += units;
+            written += data.len() as u64;
+        }
+        writer.flush().await.map_err(copy_failed)?;
+        Ok(written)
+    }
+
+    /// Replace the LOB's content with everything `reader` yields,
+    /// returning the new length
+    ///
+    /// CLOB data must be UTF-8 and NCLOB data UTF-16, as
+    /// [`Lob::write_to`] writes them. Like any LOB write, this needs the
+    /// row locked, as by `SELECT ... FOR UPDATE`, and leaves a transaction
+    /// open. BFILEs are read-only.
+    pub async fn read_from<R: AsyncRead + Unpin>(
+        &mut self,
+        conn: &Connection,
+        mut reader: R,
+    ) -> Result<u64> {
+        let mut buf = vec![0; self.transfer_units() as usize];
+        let mut pending = Vec::new();
+        let mut offset = 0;
+        loop {
+            let mut filled = 0;
+            while filled < buf.len() {
+                match reader.read(&mut buf[filled..]).await.map_err(copy_failed)? {
+                    0 => break,
+                    n => filled += n,
+                }
+            }
+            pending.extend_from_slice(&buf[..filled]);
+            let done = filled < buf.len();
+            // Hold back a character split across reads
+            let whole = if done {
+                pending.len()
+            } else {
+                self.whole_units(&pending)
+            };
+            if whole > 0 {
+                let data: Vec<u8> = pending.drain(..whole).collect();
+                let units = self.units(&data).ok_or_else(|| self.invalid_text())?;
+                conn.write_lob(self, offset, &data).await?;
+                offset += units;
+            }
+            if done {
+                break;
+            }
+        }
+        if self.len != Some(offset) {
+            conn.trim_lob(self, offset).await?;
+        }
+        Ok(offset)
+    }
+
+    /// Save the whole LOB to the file at `path`, returning the bytes
+    /// written; works for BFILEs too
+    pub async fn save_to_file(&self, conn: &Connection, path: impl AsRef<Path>) -> Result<u64> {
+        let path = path.as_ref();
+        let file = DefaultRuntime::create_file(path)
+            .await
+            .map_err(|e| file_failed(path, e))?;
+        self.write_to(conn, file).await
+    }
+
+    /// Replace the LOB's content with the file at `path`, returning the
+    /// new length; see [`Lob::read_from`]
+    pub async fn load_from_file(
+        &mut self,
+        conn: &Connection,
+        path: impl AsRef<Path>,
+    ) -> Result<u64> {
+        let path = path.as_ref();
+        let file = DefaultRuntime::open_file(path)
+            .await
+            .map_err(|e| file_failed(path, e))?;
+        self.read_from(conn, file).await
+    }
+
+    /// Record the length the server reports after a write or trim; what
+    /// was prefetched no longer holds
+    pub(crate) fn changed(&mut self, len: u64) {
+        self.len = Some(len);
+        self.prefetched = None;
+    }
+
+    /// Units the copy helpers move per round trip: a multiple of the
+    /// chunk size
+    fn transfer_units(&self) -> u64 {
+        let chunk = self.chunk_size.map_or(DEFAULT_CHUNK_SIZE, u64::from).max(1);
+        chunk * (TRANSFER_SIZE / chunk).max(1)
+    }
+
+    /// Bytes of `data` that hold whole characters of a CLOB or NCLOB
+    fn whole_units(&self, data: &[u8]) -> usize {
+        match self.oracle_type {
+            OracleType::Clob => match std::str::from_utf8(data) {
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                _ => data.len(),
+            },
+            OracleType::NClob => data.len() & !1,
+            _ => data.len(),
+        }
+    }
+
+    fn invalid_text(&self) -> Error {
+        Error::Encoding(match self.oracle_type {
+            OracleType::NClob => "NCLOB data must be UTF-16".into(),
+            _ => "CLOB data must be UTF-8".into(),
+        })
+    }
+
+    /// `amount` units from `offset`, if they were sent with the locator
+    ///
+    /// Reading past the end of a wholly prefetched LOB stops at its end.
+    pub(crate) fn prefetched_range(&self, offset: u64, amount: u64) -> Option<Vec<u8>> {
+        let data = self.prefetched.as_deref()?;
+        let available = self.units(data)?;
+        let end = offset.saturating_add(amount);
+        if end > available && self.len != Some(available) {
+            return None;
+        }
+        let end = end.min(available);
+        let start = offset.min(end);
+        match self.oracle_type {
+            // NCLOB lengths count UTF-16 code units
+            OracleType::NClob => Some(data[start as usize * 2..end as usize * 2].to_vec()),
+            OracleType::Clob => {
+                let text = std::str::from_utf8(data).ok()?;
+                let range: String = text
+                    .chars()
+                    .skip(start as usize)
+                    .take((end - start) as usize)
+                    .collect();
+                Some(range.into_bytes())
+            }
+            _ => Some(data[start as usize..end as usize].to_vec()),
+        }
+    }
+
+    /// Length of `data` in the units of [`Lob::len`]; prefetched CLOB
+    /// data is UTF-8 and NCLOB data UTF-16
+    pub(crate) fn units(&self, data: &[u8]) -> Option<u64> {
+        match self.oracle_type {
+            OracleType::Clob => Some(std::str::from_utf8(data).ok()?.chars().count() as u64),
+            OracleType::NClob => data
+                .len()
+                .is_multiple_of(2)
+                .then_some(data.len() as u64 / 2),
+            _ => Some(data.len() as u64),
+        }
+    }
+}
+
+/// Failure of the local side of a copy, which leaves the session usable
+fn copy_failed(e: io::Error) -> Error {
+    Error::Lob(format!("LOB copy failed: {}", e))
+}
+
+fn file_failed(path: &Path, e: io::Error) -> Error {
+    Error::Lob(format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wire(flags: u8, len: u64, chunk: u32, locator: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![flags];
+        if flags & PREFETCHED_METADATA != 0 {
+            bytes.extend(len.to_be_bytes());
+            bytes.extend(chunk.to_be_bytes());
+        }
+        bytes.extend((locator.len() as u16).to_be_bytes());
+        bytes.extend(locator);
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn test_decode_prefetched_locator() {
+        let bytes = wire(
+            PREFETCHED_METADATA | PREFETCHED_DATA,
+            5,
+            8132,
+            b"LOC",
+            b"hello",
+        );
+        let lob = Lob::decode(OracleType::Blob, &bytes).unwrap();
+        assert_eq!(lob.locator(), b"LOC");
+        assert_eq!(lob.len(), Some(5));
+        assert_eq!(lob.chunk_size(), Some(8132));
+        assert_eq!(lob.data(), Some(&b"hello"[..]));
+        assert_eq!(lob.prefetched_range(1, 3).unwrap(), b"ell");
+        // Reading past the end of a fully prefetched LOB stops at its end
+        assert_eq!(lob.prefetched_range(3, 100).unwrap(), b"lo");
+
+        let bytes = wire(
+            PREFETCHED_METADATA | PREFETCHED_DATA,
+            20,
+            8132,
+            b"LOC",
+            b"hello",
+        );
+        let lob = Lob::decode(OracleType::Blob, &bytes).unwrap();
+        assert_eq!(lob.data(), None);
+        assert!(lob.prefetched_range(3, 10).is_none());
+
+        let lob = Lob::decode(OracleType::Clob, &wire(0, 0, 0, b"LOC", b"")).unwrap();
+        assert_eq!(lob.len(), None);
+        assert!(lob.prefetched().is_none());
+
+        assert!(Lob::decode(OracleType::Clob, &bytes[..6]).is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_prefetched_metadata_saves_round_trips() {
+        use crate::testing::{column, Expectation, MockCall, MockConnection};
+        use crate::types::Value;
+        use crate::ConnectionConfig;
+
+        let small = Lob::new(OracleType::Clob, b"L1".to_vec())
+            .with_metadata(5, 8132)
+            .with_prefetched(b"hello".to_vec());
+        let bare = Lob::new(OracleType::Blob, b"L2".to_vec());
+        let query = || {
+            Expectation::query("SELECT doc, img FROM docs").returns(
+                vec![
+                    column("DOC", OracleType::Clob),
+                    column("IMG", OracleType::Blob),
+                ],
+                vec![vec![Value::Lob(small.clone()), Value::Lob(bare.clone())]],
+            )
+        };
+        let mock = MockConnection::new()
+            .expect(query())
+            .expect(query())
+            .lob("L1", "hello")
+            .lob("L2", vec![7u8; 10]);
+        let config = ConnectionConfig::new("mock:1521/MOCK", "mock", "mock").fetch_lobs(false);
+        let conn = mock
+            .connect_with(config.clone().lob_prefetch_size(16 * 1024))
+            .await
+            .unwrap();
+
+        let rows = conn.query("SELECT doc, img FROM docs", &[]).await.unwrap();
+        let doc: Lob = rows.rows()[0].get_typed(0).unwrap();
+        let img: Lob = rows.rows()[0].get_typed(1).unwrap();
+        let text: String = rows.rows()[0].get_typed(0).unwrap();
+        assert_eq!(text, "hello");
+        assert!(rows.rows()[0].get_typed::<Vec<u8>>(1).is_err());
+
+        let before = conn.stats().await.round_trips;
+        assert_eq!(conn.lob_len(&doc).await.unwrap(), 5);
+        assert_eq!(conn.read_lob(&doc, 1, 3).await.unwrap(), b"ell");
+        assert_eq!(conn.stats().await.round_trips, before);
+
+        assert_eq!(conn.lob_len(&img).await.unwrap(), 10);
+        assert_eq!(conn.read_lob(&img, 8, 5).await.unwrap(), vec![7, 7]);
+        assert_eq!(conn.stats().await.round_trips, before + 2);
+        let lob_calls = mock
+            .calls()
+            .iter()
+            .filter(|c| matches!(c, MockCall::Lob(_)))
+            .count();
+        assert_eq!(lob_calls, 2);
+
+        // A prefetch length too short for the data leaves only what fits
+        let conn = mock
+            .connect_with(config.lob_prefetch_size(2))
+            .await
+            .unwrap();
+        let rows = conn.query("SELECT doc, img FROM docs", &[]).await.unwrap();
+        let doc: Lob = rows.rows()[0].get_typed(0).unwrap();
+        assert_eq!(doc.prefetched(), Some(&b"he"[..]));
+        assert_eq!(doc.len(), Some(5));
+        let before = conn.stats().await.round_trips;
+        assert_eq!(conn.read_lob(&doc, 1, 3).await.unwrap(), b"ell");
+        assert_eq!(conn.stats().await.round_trips, before + 1);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_file_round_trip() {
+        use crate::testing::MockConnection;
+
+        let image: Vec<u8> = (0..1_200_000u32).map(|i| (i % 251) as u8).collect();
+        // A character split across reads of the file
+        let text = format!("a{}", "ż".repeat(530_000));
+        let mock = MockConnection::new()
+            .lob("IMG", image.clone())
+            .lob("DOC", "old content that is longer".repeat(25_000));
+        let conn = mock.connect().await.unwrap();
+        let dir = std::env::temp_dir();
+        let saved = dir.join(format!("oracledb-lob-{}.bin", std::process::id()));
+        let source = dir.join(format!("oracledb-lob-{}.txt", std::process::id()));
+
+        let img = Lob::new(OracleType::Blob, b"IMG".to_vec()).with_metadata(1_200_000, 8132);
+        assert_eq!(img.save_to_file(&conn, &saved).await.unwrap(), 1_200_000);
+        assert_eq!(std::fs::read(&saved).unwrap(), image);
+
+        std::fs::write(&source, &text).unwrap();
+        let mut doc = Lob::new(OracleType::Clob, b"DOC".to_vec());
+        assert_eq!(doc.load_from_file(&conn, &source).await.unwrap(), 530_001);
+        // The old, longer content was trimmed off
+        assert_eq!(mock.lob_data(b"DOC").unwrap(), text.as_bytes());
+        assert_eq!(conn.lob_len(&doc).await.unwrap(), 530_001);
+        assert!(conn.info().transaction_active);
+
+        let mut bfile = Lob::new(OracleType::BFile, b"IMG".to_vec());
+        assert!(matches!(
+            bfile.load_from_file(&conn, &source).await,
+            Err(Error::Lob(_))
+        ));
+        std::fs::remove_file(&saved).unwrap();
+        std::fs::remove_file(&source).unwrap();
+        let missing = doc.load_from_file(&conn, &source).await.unwrap_err();
+        assert!(matches!(missing, Error::Lob(_)));
+        assert!(!conn.is_broken());
+    }
+
+    #[test]
+    fn test_character_ranges() {
+        let lob = Lob::new(OracleType::Clob, b"LOC".to_vec())
+            .with_metadata(4, 8132)
+            .with_prefetched("żółw".as_bytes().to_vec());
+        assert_eq!(lob.prefetched_range(1, 2).unwrap(), "ół".as_bytes());
+
+        // A character beyond the BMP is two code units of an NCLOB
+        let text = "a𝄞b";
+        let utf16: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        let lob = Lob::new(OracleType::NClob, b"LOC".to_vec())
+            .with_metadata(4, 8132)
+            .with_prefetched(utf16.clone());
+        assert_eq!(lob.text().unwrap().unwrap(), text);
+        assert_eq!(lob.prefetched_range(1, 2).unwrap(), &utf16[2..6]);
+        assert!(lob.with_metadata(3, 8132).text().is_none());
+    }
+}
@@ -0,0 +1,113 @@
+// Metadata cache
+
+//! Column metadata of described statements, keyed by the current schema
+//! and normalized SQL, so ORMs and report builders that describe the same
+//! text over and over make one parse-only round trip for it.
+//!
+//! Unqualified names resolve in the current schema, so the same text is
+//! described again after the schema changes. DDL may change what a
+//! statement returns, so it clears the cache.
+
+use crate::sql::normalize;
+use crate::types::ColumnInfo;
+use std::collections::VecDeque;
+
+/// Current schema and normalized SQL of a description
+type Key = (Option<String>, String);
+
+/// Least recently used cache of statement descriptions
+#[derive(Debug)]
+pub(crate) struct MetadataCache {
+    capacity: usize,
+    /// Keys and their columns, most recently used last
+    entries: VecDeque<(Key, Vec<ColumnInfo>)>,
+}
+
+impl MetadataCache {
+    /// Cache up to `capacity` descriptions (0 disables caching)
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Columns of `sql`, if described before in `schema`
+    pub(crate) fn get(&mut self, schema: Option<&str>, sql: &str) -> Option<Vec<ColumnInfo>> {
+        if self.capacity == 0 {
+            return None;
+        }
+        let key = (schema.map(str::to_string), normalize(sql, false));
+        let index = self.entries.iter().position(|(entry, _)| *entry == key)?;
+        let entry = self.entries.remove(index).expect("index in bounds");
+        let columns = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(columns)
+    }
+
+    /// Keep the columns `sql` was described with in `schema`
+    pub(crate) fn insert(&mut self, schema: Option<&str>, sql: &str, columns: &[ColumnInfo]) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = (schema.map(str::to_string), normalize(sql, false));
+        self.entries.retain(|(entry, _)| *entry != key);
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, columns.to_vec()));
+    }
+
+    /// Forget every description
+    pub(crate) fn invalidate(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OracleType;
+
+    #[test]
+    fn test_get_evicts_least_recent() {
+        let id = vec![ColumnInfo {
+            name: "ID".into(),
+            oracle_type: OracleType::Number,
+            size: 22,
+            precision: None,
+            scale: None,
+            nullable: false,
+            encrypted: false,
+            redacted: false,
+        }];
+        let mut cache = MetadataCache::new(2);
+        assert!(cache.get(None, "SELECT id FROM t").is_none());
+        cache.insert(None, "SELECT id FROM t", &id);
+        cache.insert(None, "SELECT id FROM u", &id);
+        assert_eq!(
+            cache.get(None, "SELECT  id\n FROM t").unwrap()[0].name,
+            "ID"
+        );
+        // "FROM u" is now least recent and makes room for "FROM v"
+        cache.insert(None, "SELECT id FROM v", &id);
+        assert!(cache.get(None, "SELECT id FROM u").is_none());
+        assert!(cache.get(None, "SELECT id FROM t").is_some());
+
+        cache.invalidate();
+        assert!(cache.get(None, "SELECT id FROM t").is_none());
+
+        let mut disabled = MetadataCache::new(0);
+        disabled.insert(None, "SELECT id FROM t", &id);
+        assert!(disabled.get(None, "SELECT id FROM t").is_none());
+    }
+
+    #[test]
+    fn test_keyed_by_schema() {
+        let mut cache = MetadataCache::new(4);
+        cache.insert(Some("HR"), "SELECT * FROM emp", &[]);
+        assert!(cache.get(Some("HR"), "SELECT * FROM emp").is_some());
+        assert!(cache.get(None, "SELECT * FROM emp").is_none());
+        assert!(cache.get(Some("SCOTT"), "SELECT * FROM emp").is_none());
+    }
+}
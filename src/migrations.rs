@@ -0,0 +1,527 @@
+// Versioned schema migrations
+
+use crate::{Connection, Error, Result};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Default name of the table recording applied migrations
+pub const DEFAULT_VERSION_TABLE: &str = "SCHEMA_VERSION";
+
+/// A single versioned migration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    /// Version number; migrations are applied in ascending order
+    pub version: i64,
+    /// Human-readable description
+    pub description: String,
+    /// Script applied when migrating up
+    pub up: String,
+    /// Optional script reverting `up`
+    pub down: Option<String>,
+}
+
+impl Migration {
+    /// Create an up-only migration
+    pub fn new(version: i64, description: impl Into<String>, up: impl Into<String>) -> Self {
+        Self {
+            version,
+            description: description.into(),
+            up: up.into(),
+            down: None,
+        }
+    }
+
+    /// Set the script reverting this migration
+    pub fn down(mut self, down: impl Into<String>) -> Self {
+        self.down = Some(down.into());
+        self
+    }
+
+    /// SHA-256 checksum of the up script, insensitive to line endings
+    pub fn checksum(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.up.replace("\r\n", "\n").as_bytes());
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+/// A migration recorded in the version table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    /// Version number
+    pub version: i64,
+    /// Description at the time it was applied
+    pub description: String,
+    /// Checksum at the time it was applied
+    pub checksum: String,
+}
+
+/// Applies [`Migration`]s to a schema and records them in a version table
+///
+/// Concurrent runners are serialized through a `DBMS_LOCK` user lock, so
+/// several service instances starting at once apply each migration exactly
+/// once. Locking requires `EXECUTE` on `DBMS_LOCK` and can be disabled.
+#[derive(Debug, Clone)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+    table: String,
+    locking: bool,
+    lock_timeout: Duration,
+}
+
+impl Migrator {
+    /// Create a migrator from a set of migrations
+    pub fn new(mut migrations: Vec<Migration>) -> Result<Self> {
+        migrations.sort_by_key(|m| m.version);
+        if let Some(dup) = migrations.windows(2).find(|w| w[0].version == w[1].version) {
+            return Err(Error::Migration(format!(
+                "duplicate migration version {}",
+                dup[0].version
+            )));
+        }
+
+        Ok(Self {
+            migrations,
+            table: DEFAULT_VERSION_TABLE.to_string(),
+            locking: true,
+            lock_timeout: Duration::from_secs(60),
+        })
+    }
+
+    /// Load migrations from a directory
+    ///
+    /// Up scripts are named `V<version>__<description>.sql` and optional down
+    /// scripts `U<version>__<description>.sql`. Other files are ignored.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let mut ups = Vec::new();
+        let mut downs = std::collections::HashMap::new();
+
+        for entry in std::fs::read_dir(dir.as_ref())? {
+            let path = entry?.path();
+            let file_name = match path.file_name().and_then(|n| n.to_str()) {
+                Some(name) => name,
+                None => continue,
+            };
+            let Some((kind, version, description)) = parse_file_name(file_name) else {
+                continue;
+            };
+
+            let script = std::fs::read_to_string(&path)?;
+            match kind {
+                'V' => ups.push(Migration::new(version, description, script)),
+                _ => {
+                    downs.insert(version, script);
+                }
+            }
+        }
+
+        for migration in &mut ups {
+            migration.down = downs.remove(&migration.version);
+        }
+        if let Some(version) = downs.keys().next() {
+            return Err(Error::Migration(format!(
+                "undo script for version {} has no matching up script",
+                version
+            )));
+        }
+
+        Self::new(ups)
+    }
+
+    /// Set the version table name
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.table = table.into();
+        self
+    }
+
+    /// Enable or disable `DBMS_LOCK` serialization
+    pub fn locking(mut self, enabled: bool) -> Self {
+        self.locking = enabled;
+        self
+    }
+
+    /// Set how long to wait for the migration lock
+    pub fn lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    /// Migrations known to this migrator, in version order
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// Apply all pending migrations, returning the versions applied
+    ///
+    /// Checksums of already applied migrations are verified first; a mismatch
+    /// aborts the run before anything is changed.
+    pub async fn run(&self, conn: &mut Connection) -> Result<Vec<i64>> {
+        self.lock(conn).await?;
+        let result = self.run_locked(conn).await;
+        self.unlock(conn, result).await
+    }
+
+    /// Revert applied migrations above `target`, newest first
+    ///
+    /// Fails without changes if any migration to revert has no down script.
+    pub async fn undo_to(&self, conn: &mut Connection, target: i64) -> Result<Vec<i64>> {
+        self.lock(conn).await?;
+        let result = self.undo_locked(conn, target).await;
+        self.unlock(conn, result).await
+    }
+
+    /// Read the migrations recorded in the version table
+    pub async fn applied(&self, conn: &Connection) -> Result<Vec<AppliedMigration>> {
+        let rows = conn
+            .query(
+                &format!(
+                    "SELECT version, description, checksum FROM {} ORDER BY version",
+                    self.table
+                ),
+                &[],
+            )
+            .await?;
+
+        rows.rows()
+            .iter()
+            .map(|row| {
+                Ok(AppliedMigration {
+                    version: row.get_typed_by_name("VERSION")?,
+                    description: row.get_typed_by_name("DESCRIPTION")?,
+                    checksum: row.get_typed_by_name("CHECKSUM")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn run_locked(&self, conn: &mut Connection) -> Result<Vec<i64>> {
+        self.ensure_table(conn).await?;
+        let applied = self.applied(conn).await?;
+
+        for record in &applied {
+            if let Some(migration) = self.find(record.version) {
+                if migration.checksum() != record.checksum {
+                    return Err(Error::Migration(format!(
+                        "checksum mismatch for applied version {}",
+                        record.version
+                    )));
+                }
+            }
+        }
+
+        let mut done = Vec::new();
+        for migration in &self.migrations {
+            if applied.iter().any(|a| a.version == migration.version) {
+                continue;
+            }
+
+            let started = Instant::now();
+            self.execute_script(conn, migration.version, &migration.up)
+                .await?;
+            let elapsed = started.elapsed().as_millis() as i64;
+
+            conn.execute_dml(
+                &format!(
+                    "INSERT INTO {} (version, description, checksum, execution_ms) \
+                     VALUES (:1, :2, :3, :4)",
+                    self.table
+                ),
+                &[
+                    &migration.version,
+                    &migration.description.as_str(),
+                    &migration.checksum(),
+                    &elapsed,
+                ],
+            )
+            .await?;
+            conn.commit().await?;
+
+            log::info!(
+                "applied migration {} ({}) in {} ms",
+                migration.version,
+                migration.description,
+                elapsed
+            );
+            done.push(migration.version);
+        }
+
+        Ok(done)
+    }
+
+    async fn undo_locked(&self, conn: &mut Connection, target: i64) -> Result<Vec<i64>> {
+        self.ensure_table(conn).await?;
+        let applied = self.applied(conn).await?;
+
+        let mut to_revert = Vec::new();
+        for record in applied.iter().rev().filter(|a| a.version > target) {
+            let down = self
+                .find(record.version)
+                .and_then(|m| m.down.as_deref())
+                .ok_or_else(|| {
+                    Error::Migration(format!("no down script for version {}", record.version))
+                })?;
+            to_revert.push((record.version, down));
+        }
+
+        let mut done = Vec::new();
+        for (version, down) in to_revert {
+            self.execute_script(conn, version, down).await?;
+            conn.execute_dml(
+                &format!("DELETE FROM {} WHERE version = :1", self.table),
+                &[&version],
+            )
+            .await?;
+            conn.commit().await?;
+
+            log::info!("reverted migration {}", version);
+            done.push(version);
+        }
+
+        Ok(done)
+    }
+
+    fn lock_name(&self) -> String {
+        format!("ORACLEDB_RS_MIGRATE_{}", self.table)
+    }
+
+    async fn lock(&self, conn: &Connection) -> Result<()> {
+        validate_table_name(&self.table)?;
+        if !self.locking {
+            return Ok(());
+        }
+
+        let timeout = self.lock_timeout.as_secs() as i64;
+        conn.execute(
+            "DECLARE h VARCHAR2(128); r INTEGER; \
+             BEGIN DBMS_LOCK.ALLOCATE_UNIQUE(:1, h); \
+             r := DBMS_LOCK.REQUEST(h, DBMS_LOCK.X_MODE, :2, FALSE); \
+             IF r NOT IN (0, 4) THEN \
+             RAISE_APPLICATION_ERROR(-20001, 'migration lock not acquired: ' || r); \
+             END IF; END;",
+            &[&self.lock_name(), &timeout],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Release the lock, preferring the body's error over a release failure
+    async fn unlock<T>(&self, conn: &Connection, result: Result<T>) -> Result<T> {
+        if !self.locking {
+            return result;
+        }
+
+        let released = conn
+            .execute(
+                "DECLARE h VARCHAR2(128); r INTEGER; \
+                 BEGIN DBMS_LOCK.ALLOCATE_UNIQUE(:1, h); r := DBMS_LOCK.RELEASE(h); END;",
+                &[&self.lock_name()],
+            )
+            .await;
+
+        let value = result?;
+        released?;
+        Ok(value)
+    }
+
+    async fn ensure_table(&self, conn: &Connection) -> Result<()> {
+        let ddl = format!(
+            "CREATE TABLE {} (\
+             version NUMBER(19) PRIMARY KEY, \
+             description VARCHAR2(200) NOT NULL, \
+             checksum VARCHAR2(64) NOT NULL, \
+             installed_by VARCHAR2(128) DEFAULT USER NOT NULL, \
+             installed_on TIMESTAMP DEFAULT SYSTIMESTAMP NOT NULL, \
+             execution_ms NUMBER(10) NOT NULL)",
+            self.table
+        );
+
+        match conn.execute(&ddl, &[]).await {
+            Ok(_) => Ok(()),
+            // ORA-00955: name is already used by an existing object
+            Err(e) if e.oracle_code() == Some(955) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn execute_script(&self, conn: &Connection, version: i64, script: &str) -> Result<()> {
+        for sql in split_statements(script) {
+            conn.execute(&sql, &[])
+                .await
+                .map_err(|e| Error::Migration(format!("version {} failed: {}", version, e)))?;
+        }
+        Ok(())
+    }
+
+    fn find(&self, version: i64) -> Option<&Migration> {
+        self.migrations.iter().find(|m| m.version == version)
+    }
+}
+
+/// Parse `V1__create_users.sql` / `U1__create_users.sql`
+fn parse_file_name(name: &str) -> Option<(char, i64, String)> {
+    let stem = name.strip_suffix(".sql")?;
+    let kind = stem.chars().next().filter(|c| *c == 'V' || *c == 'U')?;
+    let (version, description) = stem[1..].split_once("__")?;
+    let version = version.parse().ok()?;
+    Some((kind, version, description.replace('_', " ")))
+}
+
+fn validate_table_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '#' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidConfiguration(format!(
+            "invalid version table name: {}",
+            name
+        )))
+    }
+}
+
+/// Split a script into individually executable statements
+///
+/// A line containing only `/` ends a statement, as in SQL*Plus. Outside
+/// PL/SQL units, a `;` at the end of a line also ends a statement and is
+/// dropped, since Oracle rejects a trailing semicolon on plain SQL.
+pub fn split_statements(script: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+
+    let mut flush = |current: &mut String, strip_semicolon: bool| {
+        let mut sql = current.trim();
+        if strip_semicolon {
+            sql = sql.trim_end_matches(';').trim_end();
+        }
+        if !sql.is_empty() {
+            statements.push(sql.to_string());
+        }
+        current.clear();
+    };
+
+    for line in script.lines() {
+        if line.trim() == "/" {
+            let plsql = is_plsql_unit(&current);
+            flush(&mut current, !plsql);
+            continue;
+        }
+
+        current.push_str(line);
+        current.push('\n');
+
+        if !is_plsql_unit(&current) && line.trim_end().ends_with(';') {
+            flush(&mut current, true);
+        }
+    }
+
+    let plsql = is_plsql_unit(&current);
+    flush(&mut current, !plsql);
+    statements
+}
+
+/// Whether a statement is a PL/SQL block or stored unit, where `;` is part
+/// of the body rather than a terminator
+fn is_plsql_unit(sql: &str) -> bool {
+    let words: Vec<String> = sql
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("--"))
+        .flat_map(|l| l.split_whitespace())
+        .take(6)
+        .map(|w| w.to_uppercase())
+        .collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+    match words.as_slice() {
+        ["BEGIN", ..] | ["DECLARE", ..] => true,
+        ["CREATE", rest @ ..] => {
+            let rest = match rest {
+                ["OR", "REPLACE", rest @ ..] => rest,
+                _ => rest,
+            };
+            let rest = match rest {
+                ["EDITIONABLE" | "NONEDITIONABLE", rest @ ..] => rest,
+                _ => rest,
+            };
+            matches!(
+                rest.first(),
+                Some(&"PROCEDURE" | &"FUNCTION" | &"PACKAGE" | &"TRIGGER" | &"TYPE")
+            )
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        let script = "CREATE TABLE t (id NUMBER);\n\
+                      CREATE INDEX t_ix ON t (id);\n\
+                      CREATE OR REPLACE PROCEDURE p AS\n\
+                      BEGIN\n  NULL;\nEND;\n/\n";
+        let statements = split_statements(script);
+
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], "CREATE TABLE t (id NUMBER)");
+        assert_eq!(statements[1], "CREATE INDEX t_ix ON t (id)");
+        assert!(statements[2].ends_with("END;"));
+    }
+
+    #[test]
+    fn test_parse_file_name() {
+        assert_eq!(
+            parse_file_name("V2__add_users.sql"),
+            Some(('V', 2, "add users".to_string()))
+        );
+        assert_eq!(
+            parse_file_name("U2__add_users.sql"),
+            Some(('U', 2, "add users".to_string()))
+        );
+        assert_eq!(parse_file_name("README.md"), None);
+        assert_eq!(parse_file_name("Vx__bad.sql"), None);
+    }
+
+    #[test]
+    fn test_migrator_ordering_and_checksum() {
+        let migrator = Migrator::new(vec![
+            Migration::new(2, "second", "SELECT 2 FROM dual"),
+            Migration::new(1, "first", "SELECT 1 FROM dual"),
+        ])
+        .unwrap();
+        assert_eq!(migrator.migrations()[0].version, 1);
+
+        let a = Migration::new(1, "a", "SELECT 1\r\nFROM dual");
+        let b = Migration::new(1, "a", "SELECT 1\nFROM dual");
+        assert_eq!(a.checksum(), b.checksum());
+        assert_eq!(a.checksum().len(), 64);
+
+        assert!(Migrator::new(vec![
+            Migration::new(1, "a", "x"),
+            Migration::new(1, "b", "y")
+        ])
+        .is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_script_with_session_settings() {
+        let conn = crate::testing::unscripted().await.unwrap();
+        let migrator = Migrator::new(vec![]).unwrap();
+        let script = "ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY-MM-DD';\n\
+                      CREATE TABLE t (d DATE);\n\
+                      INSERT INTO t VALUES ('2026-01-14');\n";
+        migrator.execute_script(&conn, 1, script).await.unwrap();
+        assert!(conn.info().transaction_active);
+    }
+}
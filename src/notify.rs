@@ -0,0 +1,465 @@
+// Notification listener
+
+//! A background listener for the connections a database opens back to the
+//! client to deliver Continuous Query Notification, Advanced Queuing and
+//! Fast Application Notification messages.
+//!
+//! One [`NotificationListener`] serves every subscription of a process:
+//! register it once, usually through [`PoolConfig::notifications`], and
+//! hand each subscription's registration ID to
+//! [`NotificationListener::subscribe`]:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::notify::{ListenerConfig, NotificationKind};
+//! use futures::StreamExt;
+//!
+//! let pool_config = PoolConfig::new().notifications(ListenerConfig::new().port(47_000));
+//! let pool = Pool::new(config, pool_config).await?;
+//!
+//! let listener = pool.notifications().unwrap();
+//! let mut changes = listener.subscribe(NotificationKind::QueryChange, registration_id);
+//! while let Some(notification) = changes.next().await {
+//!     refresh_cache(&notification.payload);
+//! }
+//! ```
+//!
+//! By default the listener binds the interface the pool reaches the
+//! database from. The database must be able to reach that address: behind
+//! NAT or a firewall, pin [`ListenerConfig::port`] and open it. Messages
+//! for registrations nobody subscribed to are dropped, as are messages for
+//! a subscription whose queue is full. [`Pool::close`] shuts the listener
+//! down, dropping any message still being read.
+//!
+//! [`PoolConfig::notifications`]: crate::PoolConfig::notifications
+//! [`Pool::close`]: crate::Pool::close
+
+use crate::buffer::BufferPool;
+use crate::runtime::{DefaultRuntime, Runtime};
+use crate::{CancellationToken, Error, Result};
+use bytes::Bytes;
+use futures::channel::{mpsc, oneshot};
+use futures::stream::{FuturesUnordered, Stream, StreamExt};
+use futures::{AsyncReadExt, FutureExt};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// TNS packet header length
+const HEADER_LEN: usize = 8;
+
+/// TNS data packet type
+const DATA_PACKET: u8 = 6;
+
+/// How long [`NotificationListener::shutdown`] waits for the background
+/// task to stop
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where the listener accepts connections from the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListenerConfig {
+    /// Local interface to listen on (default: the one the pool reaches
+    /// the database from)
+    pub address: Option<IpAddr>,
+    /// Port to listen on (0 = any free port)
+    pub port: u16,
+    /// Messages each subscription holds before new ones are dropped
+    /// (default: 256)
+    pub queue_depth: usize,
+}
+
+impl Default for ListenerConfig {
+    fn default() -> Self {
+        Self {
+            address: None,
+            port: 0,
+            queue_depth: 256,
+        }
+    }
+}
+
+impl ListenerConfig {
+    /// Listen on any free port of the interface the pool reaches the
+    /// database from
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listen on `address` instead
+    pub fn interface(mut self, address: IpAddr) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Listen on a fixed port
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Hold up to `depth` undelivered messages per subscription
+    pub fn queue_depth(mut self, depth: usize) -> Self {
+        self.queue_depth = depth;
+        self
+    }
+}
+
+/// Source of a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationKind {
+    /// Continuous Query Notification: a registered query's result changed
+    QueryChange,
+    /// Advanced Queuing: a message arrived on a subscribed queue
+    Queue,
+    /// Fast Application Notification: an instance or service went up or
+    /// down, or planned maintenance started
+    Fan,
+}
+
+impl NotificationKind {
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(Self::QueryChange),
+            2 => Some(Self::Queue),
+            3 => Some(Self::Fan),
+            _ => None,
+        }
+    }
+}
+
+/// A message delivered by the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Source of the message
+    pub kind: NotificationKind,
+    /// Registration the message belongs to
+    pub registration_id: u64,
+    /// Message body, as sent by the database
+    pub payload: Bytes,
+}
+
+type Subscribers = Mutex<HashMap<(NotificationKind, u64), mpsc::Sender<Notification>>>;
+
+/// Messages for one registration, ending when the listener shuts down
+#[derive(Debug)]
+pub struct Subscription {
+    receiver: mpsc::Receiver<Notification>,
+}
+
+impl Stream for Subscription {
+    type Item = Notification;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+/// Background task accepting notification connections from the database
+#[derive(Debug)]
+pub struct NotificationListener {
+    local_addr: SocketAddr,
+    queue_depth: usize,
+    subscribers: Arc<Subscribers>,
+    /// Packet bodies, reused across messages and connections
+    buffers: BufferPool,
+    shutdown: CancellationToken,
+    stopped: Mutex<Option<oneshot::Receiver<()>>>,
+}
+
+impl NotificationListener {
+    /// Bind to `config` and start serving in the background
+    ///
+    /// Outside a pool, `config` must name the interface to listen on.
+    pub async fn start(config: ListenerConfig) -> Result<Self> {
+        let address = config.address.ok_or_else(|| {
+            Error::InvalidConfiguration("notification listener needs an interface".into())
+        })?;
+        let addr = SocketAddr::new(address, config.port);
+        let listener = DefaultRuntime::bind_tcp(addr).await.map_err(|e| {
+            Error::InvalidConfiguration(format!(
+                "cannot listen for notifications on {}: {}",
+                addr, e
+            ))
+        })?;
+        let local_addr = DefaultRuntime::local_addr(&listener)?;
+        let subscribers = Arc::new(Subscribers::default());
+        let buffers = BufferPool::default();
+        let shutdown = CancellationToken::new();
+        let (done, stopped) = oneshot::channel();
+        DefaultRuntime::spawn(Box::pin(serve(
+            listener,
+            subscribers.clone(),
+            buffers.clone(),
+            shutdown.clone(),
+            done,
+        )));
+        log::debug!("listening for notifications on {}", local_addr);
+
+        Ok(Self {
+            local_addr,
+            queue_depth: config.queue_depth,
+            subscribers,
+            buffers,
+            shutdown,
+            stopped: Mutex::new(Some(stopped)),
+        })
+    }
+
+    /// Address the listener is bound to, to register subscriptions with
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Receive the messages of `registration_id`
+    ///
+    /// A second subscription to the same registration replaces the first.
+    pub fn subscribe(&self, kind: NotificationKind, registration_id: u64) -> Subscription {
+        let (sender, receiver) = mpsc::channel(self.queue_depth);
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((kind, registration_id), sender);
+        Subscription { receiver }
+    }
+
+    /// Stop delivering the messages of `registration_id`
+    pub fn unsubscribe(&self, kind: NotificationKind, registration_id: u64) {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(kind, registration_id));
+    }
+
+    /// Whether the background task is still serving
+    pub fn is_running(&self) -> bool {
+        !self.shutdown.is_cancelled()
+    }
+
+    /// Stop accepting connections and close open ones, waiting a few
+    /// seconds at most for the background task; subscriptions then end
+    pub async fn shutdown(&self) {
+        self.shutdown.cancel();
+        let stopped = self
+            .stopped
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(stopped) = stopped {
+            if crate::runtime::timeout(SHUTDOWN_TIMEOUT, stopped)
+                .await
+                .is_err()
+            {
+                log::warn!("notification listener did not stop in time");
+            }
+        }
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        let (reused, allocated) = self.buffers.stats();
+        log::debug!(
+            "notification listener stopped; buffers reused {} times, allocated {}",
+            reused,
+            allocated
+        );
+    }
+}
+
+impl Drop for NotificationListener {
+    fn drop(&mut self) {
+        self.shutdown.cancel();
+    }
+}
+
+/// Accept connections until shut down, reading each on the same task
+async fn serve(
+    listener: <DefaultRuntime as Runtime>::TcpListener,
+    subscribers: Arc<Subscribers>,
+    buffers: BufferPool,
+    shutdown: CancellationToken,
+    done: oneshot::Sender<()>,
+) {
+    let mut connections = FuturesUnordered::new();
+    loop {
+        futures::select_biased! {
+            _ = shutdown.cancelled().fuse() => break,
+            () = connections.select_next_some() => {}
+            accepted = DefaultRuntime::accept(&listener).fuse() => match accepted {
+                Ok((stream, peer)) => {
+                    log::debug!("notification connection from {}", peer);
+                    connections.push(read_messages(stream, &subscribers, &buffers, &shutdown));
+                }
+                Err(e) => log::warn!("accepting notification connection: {}", e),
+            },
+        }
+    }
+    drop(listener);
+    while connections.next().await.is_some() {}
+    let _ = done.send(());
+}
+
+/// Dispatch the messages of one connection until it closes or the
+/// listener shuts down
+async fn read_messages(
+    mut stream: <DefaultRuntime as Runtime>::TcpStream,
+    subscribers: &Subscribers,
+    buffers: &BufferPool,
+    shutdown: &CancellationToken,
+) {
+    loop {
+        let mut header = [0u8; HEADER_LEN];
+        let read = futures::select_biased! {
+            _ = shutdown.cancelled().fuse() => return,
+            read = stream.read_exact(&mut header).fuse() => read,
+        };
+        if read.is_err() {
+            return;
+        }
+        let len = u16::from_be_bytes([header[0], header[1]]) as usize;
+        let mut body = buffers.get();
+        body.resize(len.saturating_sub(HEADER_LEN), 0);
+        let read = futures::select_biased! {
+            _ = shutdown.cancelled().fuse() => return,
+            read = stream.read_exact(&mut body[..]).fuse() => read,
+        };
+        if read.is_err() {
+            return;
+        }
+        match decode(header[4], &body[..]) {
+            Ok(notification) => dispatch(subscribers, notification),
+            Err(e) => log::warn!("dropping notification: {}", e),
+        }
+    }
+}
+
+/// Read a notification from the body of a TNS packet of type `packet_type`
+///
+/// This is a placeholder framing, not the server's own: after the data
+/// flags come a kind byte and a big-endian registration ID, and the rest
+/// is passed on as the payload. Real CQN, AQ and FAN messages use the
+/// server's notification encoding, which is not decoded yet.
+fn decode(packet_type: u8, body: &[u8]) -> Result<Notification> {
+    if packet_type != DATA_PACKET {
+        return Err(Error::Protocol(format!(
+            "unexpected packet type {} on notification connection",
+            packet_type
+        )));
+    }
+    if body.len() < 11 {
+        return Err(Error::Protocol("truncated notification".into()));
+    }
+    let kind = NotificationKind::from_code(body[2])
+        .ok_or_else(|| Error::Protocol(format!("unknown notification kind {}", body[2])))?;
+    let registration_id = u64::from_be_bytes(body[3..11].try_into().expect("8 bytes"));
+    Ok(Notification {
+        kind,
+        registration_id,
+        payload: Bytes::copy_from_slice(&body[11..]),
+    })
+}
+
+fn dispatch(subscribers: &Subscribers, notification: Notification) {
+    let key = (notification.kind, notification.registration_id);
+    let mut subscribers = subscribers.lock().unwrap_or_else(|e| e.into_inner());
+    match subscribers.get_mut(&key) {
+        Some(sender) => match sender.try_send(notification) {
+            Ok(()) => {}
+            Err(e) if e.is_full() => log::warn!(
+                "dropping notification for {:?} registration {}: queue full",
+                key.0,
+                key.1
+            ),
+            Err(_) => {
+                subscribers.remove(&key);
+            }
+        },
+        None => log::debug!("no subscriber for {:?} registration {}", key.0, key.1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A data packet carrying one notification
+    fn packet(kind: u8, registration_id: u64, payload: &[u8]) -> Vec<u8> {
+        let len = (HEADER_LEN + 11 + payload.len()) as u16;
+        let mut packet = len.to_be_bytes().to_vec();
+        packet.extend([0, 0, DATA_PACKET, 0, 0, 0]);
+        packet.extend([0, 0, kind]);
+        packet.extend(registration_id.to_be_bytes());
+        packet.extend(payload);
+        packet
+    }
+
+    #[test]
+    fn test_decode() {
+        let packet = packet(1, 42, b"EMP");
+        let notification = decode(packet[4], &packet[HEADER_LEN..]).unwrap();
+        assert_eq!(notification.kind, NotificationKind::QueryChange);
+        assert_eq!(notification.registration_id, 42);
+        assert_eq!(&notification.payload[..], b"EMP");
+
+        assert!(decode(packet[4], &packet[HEADER_LEN..12]).is_err());
+        assert!(decode(2, &packet[HEADER_LEN..]).is_err());
+    }
+
+    #[test]
+    fn test_full_queue_drops_messages() {
+        let subscribers = Subscribers::default();
+        let (sender, mut receiver) = mpsc::channel(0);
+        subscribers
+            .lock()
+            .unwrap()
+            .insert((NotificationKind::Queue, 3), sender);
+        let message = |payload: &'static [u8]| Notification {
+            kind: NotificationKind::Queue,
+            registration_id: 3,
+            payload: Bytes::from_static(payload),
+        };
+        dispatch(&subscribers, message(b"first"));
+        dispatch(&subscribers, message(b"second"));
+        assert_eq!(&receiver.try_next().unwrap().unwrap().payload[..], b"first");
+        assert!(receiver.try_next().is_err());
+        assert_eq!(subscribers.lock().unwrap().len(), 1);
+
+        drop(receiver);
+        dispatch(&subscribers, message(b"third"));
+        assert!(subscribers.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_start_needs_interface() {
+        assert!(matches!(
+            NotificationListener::start(ListenerConfig::new()).await,
+            Err(Error::InvalidConfiguration(_))
+        ));
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[tokio::test]
+    async fn test_delivers_until_shutdown() {
+        use tokio::io::AsyncWriteExt;
+
+        let config = ListenerConfig::new().interface(IpAddr::V4(std::net::Ipv4Addr::LOCALHOST));
+        let listener = NotificationListener::start(config).await.unwrap();
+        let mut changes = listener.subscribe(NotificationKind::QueryChange, 7);
+
+        let mut db = tokio::net::TcpStream::connect(listener.local_addr())
+            .await
+            .unwrap();
+        db.write_all(&packet(1, 99, b"ignored")).await.unwrap();
+        db.write_all(&packet(1, 7, b"ORDERS")).await.unwrap();
+        let notification = changes.next().await.unwrap();
+        assert_eq!(notification.registration_id, 7);
+        assert_eq!(&notification.payload[..], b"ORDERS");
+        // The second packet was read into the first one's buffer
+        assert_eq!(listener.buffers.stats(), (1, 1));
+
+        listener.shutdown().await;
+        assert!(!listener.is_running());
+        assert!(changes.next().await.is_none());
+    }
+}
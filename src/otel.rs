@@ -0,0 +1,126 @@
+// OpenTelemetry context propagation
+
+//! Copies the active OpenTelemetry span into [`SessionTags`] so DBAs can join
+//! `V$SESSION`, `V$SQL` and ASH activity to distributed traces.
+//!
+//! Enable it per connection with
+//! [`ConnectionConfig::trace_context`](crate::ConnectionConfig::trace_context).
+//! Before each round trip the driver reads the current OpenTelemetry
+//! context, and when a valid span is active its IDs travel with that call.
+//! Tags set by the application are overwritten only in the target fields.
+//!
+//! ```rust,ignore
+//! use oracledb_rs::otel::TraceTarget;
+//!
+//! let config = ConnectionConfig::new("localhost:1521/XEPDB1", "hr", "hr")
+//!     .trace_context(TraceTarget::ClientInfo);
+//! ```
+
+use crate::connection::SessionTags;
+use opentelemetry::trace::{SpanContext, TraceContextExt};
+use opentelemetry::Context;
+
+/// Session attributes that receive the trace context
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceTarget {
+    /// CLIENT_INFO holds the W3C `traceparent`
+    ClientInfo,
+    /// MODULE holds the trace ID and ACTION the span ID
+    ModuleAction,
+    /// ECID holds the W3C `traceparent`
+    Ecid,
+}
+
+/// Format a span as a W3C `traceparent` (`00-<trace-id>-<span-id>-<flags>`)
+pub fn traceparent(span: &SpanContext) -> String {
+    format!(
+        "00-{}-{}-{:02x}",
+        span.trace_id(),
+        span.span_id(),
+        span.trace_flags().to_u8()
+    )
+}
+
+/// Session tags for the active span, if there is one
+pub(crate) fn current_tags(target: TraceTarget) -> Option<SessionTags> {
+    let cx = Context::current();
+    let span = cx.span();
+    let span = span.span_context();
+    if !span.is_valid() {
+        return None;
+    }
+
+    let tags = match target {
+        TraceTarget::ClientInfo => SessionTags {
+            client_info: Some(traceparent(span)),
+            ..Default::default()
+        },
+        TraceTarget::ModuleAction => SessionTags {
+            module: Some(span.trace_id().to_string()),
+            action: Some(span.span_id().to_string()),
+            ..Default::default()
+        },
+        TraceTarget::Ecid => SessionTags {
+            ecid: Some(traceparent(span)),
+            ..Default::default()
+        },
+    };
+    Some(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Protocol;
+    use crate::ConnectionConfig;
+    use opentelemetry::trace::{SpanId, TraceFlags, TraceId, TraceState};
+
+    fn span_context() -> Context {
+        let span = SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        Context::new().with_remote_span_context(span)
+    }
+
+    #[test]
+    fn test_current_tags() {
+        assert_eq!(current_tags(TraceTarget::ClientInfo), None);
+
+        let _guard = span_context().attach();
+        let tags = current_tags(TraceTarget::ClientInfo).unwrap();
+        assert_eq!(
+            tags.client_info.as_deref(),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+
+        let tags = current_tags(TraceTarget::ModuleAction).unwrap();
+        assert_eq!(
+            tags.module.as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        assert_eq!(tags.action.as_deref(), Some("00f067aa0ba902b7"));
+        assert_eq!(tags.client_info, None);
+    }
+
+    #[tokio::test]
+    async fn test_propagation_on_round_trip() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass")
+            .trace_context(TraceTarget::Ecid);
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        protocol.ping().await.unwrap();
+        assert_eq!(protocol.session_tags().ecid, None);
+
+        let _guard = span_context().attach();
+        protocol.ping().await.unwrap();
+        assert_eq!(
+            protocol.session_tags().ecid.as_deref(),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+    }
+}
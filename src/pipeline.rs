@@ -0,0 +1,263 @@
+// Statement pipelining
+
+//! Oracle Database 23ai can accept several statements before answering any
+//! of them. A [`Pipeline`] queues independent statements and sends them in
+//! a single round trip, which pays off most over high-latency links:
+//!
+//! ```rust,ignore
+//! let mut outcomes = conn
+//!     .pipeline()
+//!     .add("INSERT INTO audit (msg) VALUES (:1)", &[&"login"])
+//!     .add("SELECT name FROM users WHERE id = :1", &[&42])
+//!     .add_commit()
+//!     .execute()
+//!     .await?;
+//!
+//! let users = outcomes.remove(1)?.rows();
+//! ```
+//!
+//! Outcomes come back in the order the statements were added. A failing
+//! statement does not stop the ones after it; check each outcome. Older
+//! servers fail the whole pipeline with
+//! [`Error::UnsupportedFeature`](crate::Error::UnsupportedFeature)
+//! before anything is sent.
+
+use crate::connection::Connection;
+use crate::statement::ResultSet;
+use crate::types::{BindType, ToSql, Value};
+use crate::Result;
+
+/// Statement queued in a pipeline
+#[derive(Debug, Clone)]
+pub(crate) enum PipelineOp {
+    /// Execute SQL with bind values and their declared types
+    Execute {
+        sql: String,
+        params: Vec<Value>,
+        types: Vec<Option<BindType>>,
+    },
+    /// Commit the transaction
+    Commit,
+}
+
+/// Result of one pipelined statement
+#[derive(Debug)]
+pub enum PipelineOutcome {
+    /// Rows of a query
+    Rows(ResultSet),
+    /// Rows affected by DML
+    RowCount(u64),
+    /// PL/SQL block or commit finished
+    Completed,
+}
+
+impl PipelineOutcome {
+    /// Rows of a query, if this was one
+    pub fn rows(self) -> Option<ResultSet> {
+        match self {
+            PipelineOutcome::Rows(rows) => Some(rows),
+            _ => None,
+        }
+    }
+
+    /// Rows affected by DML, if this was DML
+    pub fn row_count(&self) -> Option<u64> {
+        match self {
+            PipelineOutcome::RowCount(count) => Some(*count),
+            _ => None,
+        }
+    }
+}
+
+/// Statements sent together in one round trip
+pub struct Pipeline<'a> {
+    conn: &'a Connection,
+    ops: Vec<PipelineOp>,
+}
+
+impl std::fmt::Debug for Pipeline<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline").field("ops", &self.ops).finish()
+    }
+}
+
+impl<'a> Pipeline<'a> {
+    /// Start an empty pipeline on `conn`
+    pub(crate) fn new(conn: &'a Connection) -> Self {
+        Self {
+            conn,
+            ops: Vec::new(),
+        }
+    }
+
+    /// Queue a statement
+    pub fn add(mut self, sql: impl Into<String>, params: &[&dyn ToSql]) -> Self {
+        self.ops.push(PipelineOp::Execute {
+            sql: sql.into(),
+            params: params.iter().map(|p| p.to_sql()).collect(),
+            types: params.iter().map(|p| p.bind_type()).collect(),
+        });
+        self
+    }
+
+    /// Queue a commit of everything before it
+    pub fn add_commit(mut self) -> Self {
+        self.ops.push(PipelineOp::Commit);
+        self
+    }
+
+    /// Number of queued statements
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Check if nothing is queued
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Send the statements and collect their outcomes in order
+    pub async fn execute(self) -> Result<Vec<Result<PipelineOutcome>>> {
+        if self.ops.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.conn.execute_pipeline(&self.ops).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Protocol;
+    use crate::{ConnectionConfig, Error};
+
+    #[tokio::test]
+    async fn test_single_round_trip() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+        let before = protocol.round_trips();
+
+        let ops = vec![
+            PipelineOp::Execute {
+                sql: "UPDATE emp SET sal = sal + 1".into(),
+                params: vec![],
+                types: vec![],
+            },
+            PipelineOp::Execute {
+                sql: "SAVEPOINT a".into(),
+                params: vec![],
+                types: vec![],
+            },
+            PipelineOp::Execute {
+                sql: "/* nothing */".into(),
+                params: vec![],
+                types: vec![],
+            },
+            PipelineOp::Execute {
+                sql: "SELECT * FROM emp".into(),
+                params: vec![],
+                types: vec![],
+            },
+            PipelineOp::Commit,
+        ];
+        let outcomes = protocol.execute_pipeline(&ops).await.unwrap();
+        assert_eq!(protocol.round_trips(), before + 1);
+
+        assert_eq!(outcomes.len(), 5);
+        assert_eq!(outcomes[0].as_ref().unwrap().row_count(), Some(1));
+        assert!(outcomes[1].is_ok());
+        assert!(matches!(outcomes[2], Err(Error::NotImplemented(_))));
+        let rows = outcomes
+            .into_iter()
+            .nth(3)
+            .unwrap()
+            .unwrap()
+            .rows()
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_declared_bind_types() {
+        use crate::types::NStr;
+        use crate::OracleType;
+
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        let params: [&dyn ToSql; 2] = [&NStr("Zoë"), &None::<i64>];
+        let ops = vec![
+            PipelineOp::Execute {
+                sql: "UPDATE emp SET name = :1, bonus = :2".into(),
+                params: params.iter().map(|p| p.to_sql()).collect(),
+                types: params.iter().map(|p| p.bind_type()).collect(),
+            },
+            PipelineOp::Execute {
+                sql: "UPDATE emp SET dept = :1".into(),
+                params: vec![Value::Null],
+                types: vec![None],
+            },
+        ];
+        protocol.execute_pipeline(&ops[..1]).await.unwrap();
+        let types: Vec<_> = protocol.binds().iter().map(|b| b.oracle_type).collect();
+        assert_eq!(types, [OracleType::NVarchar2, OracleType::Number]);
+
+        // Declarations apply to their own statement only
+        protocol.execute_pipeline(&ops).await.unwrap();
+        let types: Vec<_> = protocol.binds().iter().map(|b| b.oracle_type).collect();
+        assert_eq!(types, [OracleType::Varchar2]);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_pipeline_on_connection() {
+        use crate::testing::{column, Expectation, MockCall, MockConnection};
+        use crate::OracleType;
+
+        let mock = MockConnection::new()
+            .expect(Expectation::dml("INSERT INTO audit (msg) VALUES (:1)", 1))
+            .expect(
+                Expectation::query("SELECT name FROM users WHERE id = :1")
+                    .params(vec![Value::Integer(42)])
+                    .returns(
+                        vec![column("NAME", OracleType::Varchar2)],
+                        vec![vec![Value::String("ada".into())]],
+                    ),
+            )
+            .server_version(23, 4);
+        let conn = mock.connect().await.unwrap();
+
+        let pipeline = conn
+            .pipeline()
+            .add("INSERT INTO audit (msg) VALUES (:1)", &[&"login"])
+            .add("SELECT name FROM users WHERE id = :1", &[&42])
+            .add_commit();
+        assert_eq!(pipeline.len(), 3);
+        let mut outcomes = pipeline.execute().await.unwrap().into_iter();
+
+        assert_eq!(outcomes.next().unwrap().unwrap().row_count(), Some(1));
+        let users = outcomes.next().unwrap().unwrap().rows().unwrap();
+        assert_eq!(users.rows()[0].get_typed::<String>(0).unwrap(), "ada");
+        assert!(matches!(
+            outcomes.next().unwrap(),
+            Ok(PipelineOutcome::Completed)
+        ));
+
+        mock.verify();
+        assert_eq!(mock.calls().last(), Some(&MockCall::Commit));
+
+        // Servers before 23ai cannot pipeline
+        let old = MockConnection::new().server_version(19, 0);
+        let conn = old.connect().await.unwrap();
+        let err = conn
+            .pipeline()
+            .add("INSERT INTO audit (msg) VALUES (:1)", &[&"login"])
+            .execute()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedFeature(_)));
+        assert!(old.calls().is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+// Execution plans
+
+//! Optimizer plans read back as typed step trees.
+//!
+//! [`Statement::explain_plan`](crate::Statement::explain_plan) asks the
+//! optimizer for the plan it would choose, through `EXPLAIN PLAN`, without
+//! running the statement. [`Connection::last_plan`](crate::Connection::last_plan)
+//! reads the plan the previous statement actually ran with from the cursor
+//! cache, as `DBMS_XPLAN.DISPLAY_CURSOR` does:
+//!
+//! ```rust,ignore
+//! let stmt = conn.prepare("SELECT * FROM emp WHERE deptno = :1").await?;
+//! let plan = stmt.explain_plan().await?;
+//! println!("{}", plan);
+//! for step in plan.children(plan.root().unwrap()) {
+//!     println!("{} costs {:?}", step.operation, step.cost);
+//! }
+//! ```
+
+use crate::statement::{FromRow, Row};
+use crate::{Error, Result};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Columns read for each plan step, shared by `PLAN_TABLE` and `V$SQL_PLAN`
+const PLAN_COLUMNS: &str = "id, parent_id, depth, operation, options, object_owner, \
+                            object_name, cost, cardinality, bytes";
+
+/// Query for the steps `EXPLAIN PLAN` stored under a statement ID
+pub(crate) fn plan_table_query() -> String {
+    format!(
+        "SELECT {} FROM plan_table WHERE statement_id = :1 ORDER BY id",
+        PLAN_COLUMNS
+    )
+}
+
+/// Statement that clears the steps filed under a statement ID
+pub(crate) fn plan_table_delete() -> &'static str {
+    "DELETE FROM plan_table WHERE statement_id = :1"
+}
+
+/// Query for the cached plan of the session's previous statement
+pub(crate) fn last_plan_query() -> String {
+    format!(
+        "SELECT {} FROM v$sql_plan p JOIN v$session s \
+         ON p.sql_id = s.prev_sql_id AND p.child_number = s.prev_child_number \
+         WHERE s.sid = SYS_CONTEXT('USERENV', 'SID') ORDER BY p.id",
+        PLAN_COLUMNS
+            .split(", ")
+            .map(|c| format!("p.{}", c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Fresh `STATEMENT_ID` to file an `EXPLAIN PLAN` under
+pub(crate) fn next_statement_id() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    format!("oracledb_rs_{}", NEXT.fetch_add(1, Ordering::Relaxed))
+}
+
+/// One operation of an execution plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanStep {
+    /// Step number, 0 for the statement itself
+    pub id: u32,
+    /// Step whose input this step produces
+    pub parent_id: Option<u32>,
+    /// Nesting level below the root
+    pub depth: u32,
+    /// Operation, such as `TABLE ACCESS` or `HASH JOIN`
+    pub operation: String,
+    /// Variant of the operation, such as `FULL` or `BY INDEX ROWID`
+    pub options: Option<String>,
+    /// Schema of the object accessed
+    pub object_owner: Option<String>,
+    /// Table, index or view accessed
+    pub object_name: Option<String>,
+    /// Optimizer cost estimate
+    pub cost: Option<u64>,
+    /// Rows the optimizer expects the step to produce
+    pub cardinality: Option<u64>,
+    /// Bytes the optimizer expects the step to produce
+    pub bytes: Option<u64>,
+}
+
+impl FromRow for PlanStep {
+    fn from_row(row: &Row) -> Result<Self> {
+        let count = |name: &str| -> Result<Option<u64>> {
+            let value: Option<i64> = row.get_typed_by_name(name)?;
+            Ok(value.and_then(|v| u64::try_from(v).ok()))
+        };
+        let position = |name: &str| -> Result<Option<u32>> {
+            let value: Option<i64> = row.get_typed_by_name(name)?;
+            Ok(value.and_then(|v| u32::try_from(v).ok()))
+        };
+        Ok(Self {
+            id: position("ID")?.unwrap_or(0),
+            parent_id: position("PARENT_ID")?,
+            depth: position("DEPTH")?.unwrap_or(0),
+            operation: row.get_typed_by_name("OPERATION")?,
+            options: row.get_typed_by_name("OPTIONS")?,
+            object_owner: row.get_typed_by_name("OBJECT_OWNER")?,
+            object_name: row.get_typed_by_name("OBJECT_NAME")?,
+            cost: count("COST")?,
+            cardinality: count("CARDINALITY")?,
+            bytes: count("BYTES")?,
+        })
+    }
+}
+
+/// Execution plan of a statement, as a tree of steps
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Plan {
+    /// Steps in ID order, parents before their children
+    steps: Vec<PlanStep>,
+}
+
+impl Plan {
+    /// Build a plan from its steps, in any order
+    pub fn new(mut steps: Vec<PlanStep>) -> Result<Self> {
+        steps.sort_by_key(|step| step.id);
+        for step in &steps {
+            if let Some(parent) = step.parent_id {
+                if parent >= step.id || steps.binary_search_by_key(&parent, |s| s.id).is_err() {
+                    return Err(Error::Protocol(format!(
+                        "plan step {} has no parent step {}",
+                        step.id, parent
+                    )));
+                }
+            }
+        }
+        Ok(Self { steps })
+    }
+
+    /// All steps in ID order
+    pub fn steps(&self) -> &[PlanStep] {
+        &self.steps
+    }
+
+    /// The statement step at the top of the tree
+    pub fn root(&self) -> Option<&PlanStep> {
+        self.steps.iter().find(|step| step.parent_id.is_none())
+    }
+
+    /// Steps feeding `step`, in ID order
+    pub fn children<'a>(&'a self, step: &PlanStep) -> impl Iterator<Item = &'a PlanStep> {
+        let id = step.id;
+        self.steps.iter().filter(move |s| s.parent_id == Some(id))
+    }
+
+    /// Check if the plan has no steps
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Renders one line per step, indented by depth, in the layout of
+/// `DBMS_XPLAN`
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            let mut operation = format!("{}{}", " ".repeat(step.depth as usize), step.operation);
+            if let Some(options) = &step.options {
+                operation = format!("{} {}", operation, options);
+            }
+            let cost = step.cost.map(|c| c.to_string()).unwrap_or_default();
+            let rows = step.cardinality.map(|c| c.to_string()).unwrap_or_default();
+            writeln!(
+                f,
+                "{:>3} | {:<40} | {:<30} | {:>8} | {:>6}",
+                step.id,
+                operation,
+                step.object_name.as_deref().unwrap_or(""),
+                rows,
+                cost
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: u32, parent_id: Option<u32>, operation: &str) -> PlanStep {
+        PlanStep {
+            id,
+            parent_id,
+            depth: parent_id.map_or(0, |p| p + 1),
+            operation: operation.to_string(),
+            options: None,
+            object_owner: None,
+            object_name: None,
+            cost: None,
+            cardinality: None,
+            bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_tree() {
+        let plan = Plan::new(vec![
+            step(2, Some(1), "TABLE ACCESS"),
+            step(0, None, "SELECT STATEMENT"),
+            step(1, Some(0), "HASH JOIN"),
+            step(3, Some(1), "INDEX"),
+        ])
+        .unwrap();
+        let root = plan.root().unwrap();
+        assert_eq!(root.operation, "SELECT STATEMENT");
+        let join = plan.children(root).next().unwrap();
+        let inputs: Vec<_> = plan.children(join).map(|s| s.id).collect();
+        assert_eq!(inputs, [2, 3]);
+        assert!(plan.to_string().contains("  TABLE ACCESS"));
+
+        assert!(Plan::new(vec![step(1, Some(5), "SORT")]).is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_last_plan() {
+        use crate::testing::{column, Expectation, MockConnection};
+        use crate::{OracleType, Value};
+
+        let columns = PLAN_COLUMNS
+            .split(", ")
+            .map(|name| column(name.to_uppercase(), OracleType::Varchar2))
+            .collect();
+        let text = |s: &str| Value::String(s.into());
+        let mock = MockConnection::new().expect(Expectation::query(last_plan_query()).returns(
+            columns,
+            vec![
+                vec![
+                    Value::Integer(0),
+                    Value::Null,
+                    Value::Integer(0),
+                    text("SELECT STATEMENT"),
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Integer(3),
+                    Value::Integer(14),
+                    Value::Integer(532),
+                ],
+                vec![
+                    Value::Integer(1),
+                    Value::Integer(0),
+                    Value::Integer(1),
+                    text("TABLE ACCESS"),
+                    text("FULL"),
+                    text("SCOTT"),
+                    text("EMP"),
+                    Value::Integer(3),
+                    Value::Integer(14),
+                    Value::Integer(532),
+                ],
+            ],
+        ));
+        let conn = mock.connect().await.unwrap();
+
+        let plan = conn.last_plan().await.unwrap();
+        let scan = plan.children(plan.root().unwrap()).next().unwrap();
+        assert_eq!(scan.options.as_deref(), Some("FULL"));
+        assert_eq!(scan.object_name.as_deref(), Some("EMP"));
+        assert_eq!(scan.cardinality, Some(14));
+        mock.verify();
+    }
+}
@@ -1,9 +1,22 @@
 // Connection pooling
 
-use crate::{Connection, ConnectionConfig, Error, Result};
+use crate::notify::{ListenerConfig, NotificationListener};
+use crate::protocol::Protocol;
+use crate::retry::RetryPolicy;
+use crate::runtime::{self, DefaultRuntime, Mutex, Runtime, Semaphore, SemaphoreGuardArc};
+use crate::{
+    CancellationToken, Connection, ConnectionConfig, Error, ExecuteOptions, Intent, Result,
+};
+use futures::channel::oneshot;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::backtrace::Backtrace;
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::Semaphore;
+use std::time::{Duration, Instant};
 
 /// Connection pool configuration
 #[derive(Debug, Clone)]
@@ -14,18 +27,34 @@ pub struct PoolConfig {
     pub pool_max: usize,
     /// Increment for pool expansion
     pub pool_increment: usize,
-    /// Timeout for acquiring a connection (seconds)
-    pub pool_timeout: u64,
-    /// Connection idle timeout (seconds, 0 = no timeout)
-    pub pool_idle_timeout: u64,
-    /// Maximum lifetime of a connection (seconds, 0 = no limit)
-    pub pool_max_lifetime: u64,
+    /// Timeout for acquiring a connection
+    pub pool_timeout: Duration,
+    /// Connection idle timeout (zero = no timeout)
+    pub pool_idle_timeout: Duration,
+    /// Maximum lifetime of a connection (zero = no limit)
+    pub pool_max_lifetime: Duration,
     /// Enable connection validation on checkout
-    pub pool_ping_interval: u64,
-    /// Queue timeout when pool is full (seconds)
-    pub queue_timeout: u64,
+    pub pool_ping_interval: Duration,
+    /// Queue timeout when pool is full
+    pub queue_timeout: Duration,
     /// Maximum queue size (0 = unlimited)
     pub queue_max: usize,
+    /// Retry opening new connections on transient errors
+    pub acquire_retry: Option<RetryPolicy>,
+    /// Reset session state of returned connections before reuse
+    pub reset_on_release: bool,
+    /// Options every checked-out connection executes under
+    pub default_options: Option<ExecuteOptions>,
+    /// Notification listener the pool runs for its subscriptions
+    pub notifications: Option<ListenerConfig>,
+    /// Size of a separate pool of read-only connections (None = no
+    /// read-only pool)
+    pub read_only_max: Option<usize>,
+    /// Report connections held longer than a threshold
+    pub leak_detection: Option<LeakDetection>,
+    /// Longest a batch request yields to interactive ones (None = no
+    /// priority lanes)
+    pub batch_max_wait: Option<Duration>,
 }
 
 impl Default for PoolConfig {
@@ -34,12 +63,19 @@ impl Default for PoolConfig {
             pool_min: 2,
             pool_max: 10,
             pool_increment: 1,
-            pool_timeout: 60,
-            pool_idle_timeout: 60,
-            pool_max_lifetime: 3600,
-            pool_ping_interval: 60,
-            queue_timeout: 60,
+            pool_timeout: Duration::from_secs(60),
+            pool_idle_timeout: Duration::from_secs(60),
+            pool_max_lifetime: Duration::from_secs(3600),
+            pool_ping_interval: Duration::from_secs(60),
+            queue_timeout: Duration::from_secs(60),
             queue_max: 500,
+            acquire_retry: None,
+            reset_on_release: false,
+            default_options: None,
+            notifications: None,
+            read_only_max: None,
+            leak_detection: None,
+            batch_max_wait: None,
         }
     }
 }
@@ -68,6 +104,84 @@ impl PoolConfig {
         self
     }
 
+    /// Set connection acquisition timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.pool_timeout = timeout;
+        self
+    }
+
+    /// Set idle connection timeout (zero = no timeout)
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Set maximum connection lifetime (zero = no limit)
+    pub fn max_lifetime(mut self, lifetime: Duration) -> Self {
+        self.pool_max_lifetime = lifetime;
+        self
+    }
+
+    /// Retry opening new connections according to `policy`
+    pub fn acquire_retry(mut self, policy: RetryPolicy) -> Self {
+        self.acquire_retry = Some(policy);
+        self
+    }
+
+    /// Give every borrower a clean session
+    ///
+    /// Returned connections have their session state cleared before the
+    /// next checkout, since drop cannot wait for the server; one that
+    /// fails to reset is replaced. Connections returned with an open
+    /// transaction are discarded whether or not this is set.
+    pub fn reset_on_release(mut self, enabled: bool) -> Self {
+        self.reset_on_release = enabled;
+        self
+    }
+
+    /// Apply `options` to every connection handed out, as with
+    /// [`Connection::set_defaults`]
+    pub fn default_options(mut self, options: ExecuteOptions) -> Self {
+        self.default_options = Some(options);
+        self
+    }
+
+    /// Run a notification listener for CQN, AQ and FAN messages while the
+    /// pool is open
+    pub fn notifications(mut self, listener: ListenerConfig) -> Self {
+        self.notifications = Some(listener);
+        self
+    }
+
+    /// Keep up to `max` read-only connections in a pool of their own,
+    /// handed out by [`Pool::get_connection_for`]
+    ///
+    /// They connect with [`Intent::ReadOnly`] to the
+    /// [`ConnectionConfig::read_only_service`], which must be set.
+    pub fn read_only_max(mut self, max: usize) -> Self {
+        self.read_only_max = Some(max);
+        self
+    }
+
+    /// Report connections checked out for longer than `detection`'s
+    /// threshold
+    pub fn leak_detection(mut self, detection: LeakDetection) -> Self {
+        self.leak_detection = Some(detection);
+        self
+    }
+
+    /// Serve [`Priority::Interactive`] requests ahead of queued
+    /// [`Priority::Batch`] ones when the pool is saturated
+    ///
+    /// A batch request that gets a connection while interactive requests
+    /// wait hands it on, until it has waited `max_batch_wait`; from then
+    /// on it keeps its place, so a steady interactive load cannot starve
+    /// background jobs.
+    pub fn priority_lanes(mut self, max_batch_wait: Duration) -> Self {
+        self.batch_max_wait = Some(max_batch_wait);
+        self
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> Result<()> {
         if self.pool_min > self.pool_max {
@@ -84,12 +198,145 @@ impl PoolConfig {
     }
 }
 
+/// Acquisition priority with [`PoolConfig::priority_lanes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Priority {
+    /// Latency-sensitive request, served first
+    #[default]
+    Interactive,
+    /// Background job, served once no interactive request waits
+    Batch,
+}
+
+/// Waiters of the priority lanes
+#[derive(Default)]
+struct Lanes {
+    /// Interactive requests waiting for a permit
+    interactive: usize,
+    /// Batch requests parked until no interactive request waits
+    parked: Vec<oneshot::Sender<()>>,
+}
+
+/// Counts an interactive request as waiting until dropped
+struct InteractiveWait {
+    lanes: Arc<std::sync::Mutex<Lanes>>,
+}
+
+impl InteractiveWait {
+    fn new(lanes: &Arc<std::sync::Mutex<Lanes>>) -> Self {
+        lanes.lock().unwrap_or_else(|e| e.into_inner()).interactive += 1;
+        Self {
+            lanes: lanes.clone(),
+        }
+    }
+}
+
+impl Drop for InteractiveWait {
+    fn drop(&mut self) {
+        let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+        lanes.interactive -= 1;
+        if lanes.interactive == 0 {
+            for parked in lanes.parked.drain(..) {
+                let _ = parked.send(());
+            }
+        }
+    }
+}
+
+/// Callback run for each connection held past the leak threshold
+pub type LeakCallback = Arc<dyn Fn(&LeakReport) + Send + Sync>;
+
+/// Reporting of connections held past a threshold
+///
+/// A connection that is never returned, or is pinned by a long-running
+/// task, starves the pool slowly; acquire timeouts then show up far from
+/// the code at fault. Each report is logged as a warning and passed to
+/// the callback, if any. With the `leak-debug` feature, reports carry
+/// the backtrace of the checkout, which costs a capture per checkout.
+#[derive(Clone)]
+pub struct LeakDetection {
+    threshold: Duration,
+    callback: Option<LeakCallback>,
+}
+
+impl LeakDetection {
+    /// Report connections held longer than `threshold`
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            callback: None,
+        }
+    }
+
+    /// Also call `callback` with each report
+    pub fn on_leak(mut self, callback: impl Fn(&LeakReport) + Send + Sync + 'static) -> Self {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Threshold past which a checkout is reported
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    fn report(&self, report: &LeakReport) {
+        match &report.backtrace {
+            Some(backtrace) => log::warn!(
+                "pooled connection held for {:?}, over the {:?} leak threshold; checked out at:\n{}",
+                report.held_for,
+                report.threshold,
+                backtrace
+            ),
+            None => log::warn!(
+                "pooled connection held for {:?}, over the {:?} leak threshold",
+                report.held_for,
+                report.threshold
+            ),
+        }
+        if let Some(callback) = &self.callback {
+            callback(report);
+        }
+    }
+}
+
+impl fmt::Debug for LeakDetection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeakDetection")
+            .field("threshold", &self.threshold)
+            .field("callback", &self.callback.is_some())
+            .finish()
+    }
+}
+
+/// A connection still checked out past the leak threshold
+#[derive(Debug, Clone)]
+pub struct LeakReport {
+    /// How long the connection had been checked out
+    pub held_for: Duration,
+    /// Threshold in effect
+    pub threshold: Duration,
+    /// Where the connection was checked out, with the `leak-debug` feature
+    pub backtrace: Option<Arc<Backtrace>>,
+}
+
 /// Connection pool
 pub struct Pool {
     config: ConnectionConfig,
     pool_config: PoolConfig,
     semaphore: Arc<Semaphore>,
-    stats: Arc<tokio::sync::Mutex<PoolStats>>,
+    stats: Arc<Mutex<PoolStats>>,
+    /// Returned connections waiting for reuse
+    idle: Arc<std::sync::Mutex<Vec<Connection>>>,
+    /// Listener started for [`PoolConfig::notifications`]
+    listener: Option<Arc<NotificationListener>>,
+    /// Pool of [`PoolConfig::read_only_max`] read-only connections
+    read_only: Option<Arc<Pool>>,
+    /// Whether a read-only request has been served by the primary pool
+    read_only_fallback: Arc<AtomicBool>,
+    /// Waiters by [`Priority`]
+    lanes: Arc<std::sync::Mutex<Lanes>>,
+    /// Sub-pools added with [`Pool::add_partition`], by key
+    partitions: Arc<std::sync::RwLock<HashMap<String, Pool>>>,
 }
 
 /// Pool statistics
@@ -107,18 +354,69 @@ pub struct PoolStats {
     pub connection_requests: u64,
     /// Total failed connection requests
     pub connection_timeouts: u64,
+    /// Connections replaced because the server was draining their service
+    pub connections_drained: u64,
+    /// Checkouts reported as held past the leak threshold
+    pub leaks_detected: u64,
 }
 
 impl Pool {
     /// Create a new connection pool
     pub async fn new(config: ConnectionConfig, pool_config: PoolConfig) -> Result<Self> {
+        Self::with_stats(config, pool_config, Arc::default()).await
+    }
+
+    /// Create a pool counting into `stats`, shared with the pool it is
+    /// a partition of
+    async fn with_stats(
+        config: ConnectionConfig,
+        pool_config: PoolConfig,
+        stats: Arc<Mutex<PoolStats>>,
+    ) -> Result<Self> {
         pool_config.validate()?;
 
+        let listener = match pool_config.notifications {
+            Some(mut listener) => {
+                if listener.address.is_none() {
+                    let interface = Protocol::new(&config).await?.client_interface().await?;
+                    listener.address = Some(interface);
+                }
+                Some(Arc::new(NotificationListener::start(listener).await?))
+            }
+            None => None,
+        };
+        let read_only = match pool_config.read_only_max {
+            Some(_) if config.read_only_service.is_none() => {
+                return Err(Error::InvalidConfiguration(
+                    "read_only_max needs ConnectionConfig::read_only_service, or read-only \
+                     connections go to the primary"
+                        .into(),
+                ))
+            }
+            Some(max) => {
+                let sub_config = PoolConfig {
+                    pool_min: pool_config.pool_min.min(max),
+                    pool_max: max,
+                    notifications: None,
+                    read_only_max: None,
+                    ..pool_config.clone()
+                };
+                let config = config.clone().intent(Intent::ReadOnly);
+                Some(Arc::new(Box::pin(Pool::new(config, sub_config)).await?))
+            }
+            None => None,
+        };
         let pool = Self {
             config,
             pool_config: pool_config.clone(),
             semaphore: Arc::new(Semaphore::new(pool_config.pool_max)),
-            stats: Arc::new(tokio::sync::Mutex::new(PoolStats::default())),
+            stats,
+            idle: Arc::default(),
+            listener,
+            read_only,
+            read_only_fallback: Arc::default(),
+            lanes: Arc::default(),
+            partitions: Arc::default(),
         };
 
         // Initialize minimum connections
@@ -138,46 +436,433 @@ impl Pool {
 
     /// Get a connection from the pool
     pub async fn get_connection(&self) -> Result<PooledConnection> {
-        let timeout = Duration::from_secs(self.pool_config.pool_timeout);
+        self.acquire(self.pool_config.pool_timeout, Priority::Interactive)
+            .await
+    }
+
+    /// Get a connection at `priority`, which orders waiters when the pool
+    /// is configured with [`PoolConfig::priority_lanes`]
+    pub async fn get_connection_priority(&self, priority: Priority) -> Result<PooledConnection> {
+        self.acquire(self.pool_config.pool_timeout, priority).await
+    }
 
+    /// Get a connection, waiting up to `timeout` instead of the pool's
+    /// `pool_timeout`
+    pub async fn get_connection_timeout(&self, timeout: Duration) -> Result<PooledConnection> {
+        self.acquire(timeout, Priority::Interactive).await
+    }
+
+    /// Get a connection, failing with [`Error::PoolTimeout`] if none is
+    /// free by `deadline`
+    pub async fn get_connection_by(&self, deadline: Instant) -> Result<PooledConnection> {
+        self.acquire(
+            deadline.saturating_duration_since(Instant::now()),
+            Priority::Interactive,
+        )
+        .await
+    }
+
+    /// Get a connection, giving up with [`Error::Cancelled`] once `cancel`
+    /// is cancelled
+    pub async fn get_connection_cancellable(
+        &self,
+        cancel: &CancellationToken,
+    ) -> Result<PooledConnection> {
+        let acquire = self.acquire(self.pool_config.pool_timeout, Priority::Interactive);
+        runtime::cancellable(cancel, acquire).await?
+    }
+
+    /// Get a connection for `intent`, from the read-only pool when there
+    /// is one
+    ///
+    /// Without [`PoolConfig::read_only_max`], read-only requests are served
+    /// by the primary; the first is logged as a warning.
+    pub async fn get_connection_for(&self, intent: Intent) -> Result<PooledConnection> {
+        match (intent, &self.read_only) {
+            (Intent::ReadOnly, Some(read_only)) => read_only.get_connection().await,
+            (Intent::ReadOnly, None) => {
+                if !self.read_only_fallback.swap(true, Ordering::Relaxed) {
+                    log::warn!(
+                        "read-only connection requested from a pool without read_only_max; \
+                         serving it from the primary"
+                    );
+                }
+                self.get_connection().await
+            }
+            (Intent::ReadWrite, _) => self.get_connection().await,
+        }
+    }
+
+    /// Run `work` on a connection from the pool, returning it however
+    /// `work` ends
+    ///
+    /// The connection lives only as long as the call, so it cannot be
+    /// held across unrelated awaits or leaked with a forgotten guard.
+    /// Dropping the returned future releases it as well; a call abandoned
+    /// partway through breaks the session, which is then replaced. If
+    /// `work` panics, the connection is discarded and the panic resumes.
+    ///
+    /// ```rust,ignore
+    /// let count = pool
+    ///     .with_connection(|conn| {
+    ///         Box::pin(async move {
+    ///             let rows = conn.query("SELECT COUNT(*) FROM orders", &[]).await?;
+    ///             rows.rows()[0].get_typed::<i64>(0)
+    ///         })
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn with_connection<T, F>(&self, work: F) -> Result<T>
+    where
+        F: for<'c> FnOnce(&'c mut Connection) -> BoxFuture<'c, Result<T>>,
+    {
+        self.scoped(work, false).await
+    }
+
+    /// Run `work` as [`Pool::with_connection`] does, then roll back and
+    /// clear the session state before the connection goes back
+    ///
+    /// A connection that fails to reset is discarded, as is one whose
+    /// future is dropped before the reset has run.
+    pub async fn with_connection_reset<T, F>(&self, work: F) -> Result<T>
+    where
+        F: for<'c> FnOnce(&'c mut Connection) -> BoxFuture<'c, Result<T>>,
+    {
+        self.scoped(work, true).await
+    }
+
+    async fn scoped<T, F>(&self, work: F, reset: bool) -> Result<T>
+    where
+        F: for<'c> FnOnce(&'c mut Connection) -> BoxFuture<'c, Result<T>>,
+    {
+        let mut conn = self.get_connection().await?;
+        // Dropped before the reset, the connection is discarded rather
+        // than handed on with whatever `work` left on the session
+        let guard = DiscardGuard(reset.then(|| conn.broken_flag()));
+        let result = match AssertUnwindSafe(work(&mut conn)).catch_unwind().await {
+            Ok(result) => result,
+            Err(payload) => {
+                // Whatever the panic interrupted is left on the session
+                conn.mark_broken();
+                drop(conn);
+                panic::resume_unwind(payload);
+            }
+        };
+        if reset {
+            if let Err(e) = conn.reset_session().await {
+                log::debug!("discarding connection that failed to reset: {}", e);
+                conn.mark_broken();
+            }
+        }
+        guard.disarm();
+        result
+    }
+
+    /// Pool of read-only connections, when configured with
+    /// [`PoolConfig::read_only_max`]
+    pub fn read_only(&self) -> Option<&Pool> {
+        self.read_only.as_deref()
+    }
+
+    /// Add a sub-pool for `key` that connects to `connection_string`
+    ///
+    /// Partitions reach different databases (a PDB per tenant, a region)
+    /// through one pool: they take the pool's credentials, connection
+    /// settings and [`PoolConfig`], each with `pool_max` connections of
+    /// its own, and count towards the pool's statistics.
+    pub async fn add_partition(
+        &self,
+        key: impl Into<String>,
+        connection_string: impl Into<String>,
+    ) -> Result<()> {
+        let key = key.into();
+        if self.partition(&key).is_some() {
+            return Err(Error::Pool(format!("partition {} already exists", key)));
+        }
+        let config = ConnectionConfig {
+            connection_string: connection_string.into(),
+            ..self.config.clone()
+        };
+        let sub_config = PoolConfig {
+            notifications: None,
+            read_only_max: None,
+            ..self.pool_config.clone()
+        };
+        let partition = Box::pin(Pool::with_stats(config, sub_config, self.stats.clone())).await?;
+
+        let lost = {
+            let mut partitions = self.partitions.write().unwrap_or_else(|e| e.into_inner());
+            if partitions.contains_key(&key) {
+                Some(partition)
+            } else {
+                partitions.insert(key.clone(), partition);
+                None
+            }
+        };
+        // Another call added the key while this one was connecting
+        if let Some(partition) = lost {
+            if let Err(e) = Box::pin(partition.close()).await {
+                log::warn!("closing duplicate partition {} failed: {}", key, e);
+            }
+            return Err(Error::Pool(format!("partition {} already exists", key)));
+        }
+        Ok(())
+    }
+
+    /// Remove the partition for `key` and close its idle connections,
+    /// returning whether there was one
+    ///
+    /// Connections still checked out from it stay usable until released.
+    pub async fn remove_partition(&self, key: &str) -> Result<bool> {
+        let removed = self
+            .partitions
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+        match removed {
+            Some(partition) => {
+                Box::pin(partition.close()).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Sub-pool for `key`, if added
+    pub fn partition(&self, key: &str) -> Option<Pool> {
+        self.partitions
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .cloned()
+    }
+
+    /// Keys of the partitions, sorted
+    pub fn partition_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .partitions
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Get a connection from the partition for `key`, failing with
+    /// [`Error::Pool`] if there is none
+    pub async fn get_connection_in(&self, key: &str) -> Result<PooledConnection> {
+        match self.partition(key) {
+            Some(partition) => partition.get_connection().await,
+            None => Err(Error::Pool(format!("no partition {}", key))),
+        }
+    }
+
+    /// Wait up to `timeout` for a permit, then reuse or open a connection
+    async fn acquire(&self, timeout: Duration, priority: Priority) -> Result<PooledConnection> {
         // Update stats
         {
             let mut stats = self.stats.lock().await;
             stats.connection_requests += 1;
         }
 
-        // Acquire semaphore permit
-        let permit = tokio::time::timeout(timeout, self.semaphore.clone().acquire_owned())
-            .await
-            .map_err(|_| Error::PoolTimeout)?
-            .map_err(|_| Error::PoolClosed)?;
+        let permit = self.acquire_permit(timeout, priority).await?;
 
-        // Create or retrieve connection
-        let conn = Connection::connect(self.config.clone()).await?;
+        // Reuse an idle connection, or create one
+        let (idle, mut evicted) = self.take_idle();
+        let idle = match idle {
+            Some(mut conn) if self.pool_config.reset_on_release => {
+                match conn.reset_session().await {
+                    Ok(()) => Some(conn),
+                    Err(e) => {
+                        log::debug!("replacing connection that failed to reset: {}", e);
+                        evicted += 1;
+                        None
+                    }
+                }
+            }
+            idle => idle,
+        };
+        let reused = idle.is_some();
+        let mut conn = match idle {
+            Some(conn) => conn,
+            None => match &self.pool_config.acquire_retry {
+                Some(policy) => {
+                    policy
+                        .run(|| Connection::connect(self.config.clone()))
+                        .await?
+                }
+                None => Connection::connect(self.config.clone()).await?,
+            },
+        };
+        // Borrowers may have changed the defaults of a reused connection
+        match &self.pool_config.default_options {
+            Some(options) => conn.set_defaults(options.clone()),
+            None => conn.clear_defaults(),
+        }
 
         // Update stats
         {
             let mut stats = self.stats.lock().await;
-            stats.connections_created += 1;
+            if reused {
+                stats.connections_idle = stats.connections_idle.saturating_sub(1);
+            } else {
+                stats.connections_created += 1;
+            }
+            stats.connections_idle = stats.connections_idle.saturating_sub(evicted);
+            stats.connections_closed += evicted as u64;
             stats.connections_in_use += 1;
         }
 
+        let checked_out_at = Instant::now();
+        let released = self
+            .pool_config
+            .leak_detection
+            .as_ref()
+            .map(|detection| self.watch_for_leak(detection.clone(), checked_out_at));
         Ok(PooledConnection {
             connection: Some(conn),
             pool: self.clone(),
+            checked_out_at,
+            released,
             _permit: permit,
         })
     }
 
+    /// Wait up to `timeout` for a semaphore permit, letting interactive
+    /// requests go first while a batch request has waited less than
+    /// [`PoolConfig::batch_max_wait`]
+    async fn acquire_permit(
+        &self,
+        timeout: Duration,
+        priority: Priority,
+    ) -> Result<SemaphoreGuardArc> {
+        let started = Instant::now();
+        let deadline = started + timeout;
+        let max_wait = match (priority, self.pool_config.batch_max_wait) {
+            (Priority::Batch, Some(max_wait)) => max_wait,
+            _ => {
+                let _waiting = InteractiveWait::new(&self.lanes);
+                return runtime::timeout(timeout, self.semaphore.acquire_arc())
+                    .await
+                    .map_err(|_| Error::PoolTimeout);
+            }
+        };
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let permit = runtime::timeout(remaining, self.semaphore.acquire_arc())
+                .await
+                .map_err(|_| Error::PoolTimeout)?;
+            if started.elapsed() >= max_wait {
+                return Ok(permit);
+            }
+            let parked = {
+                let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+                if lanes.interactive == 0 {
+                    return Ok(permit);
+                }
+                let (tx, rx) = oneshot::channel();
+                lanes.parked.push(tx);
+                rx
+            };
+            // Hand the permit on and wait out the interactive requests
+            // without competing for permits
+            drop(permit);
+            let wait = max_wait
+                .saturating_sub(started.elapsed())
+                .min(deadline.saturating_duration_since(Instant::now()));
+            let _ = runtime::timeout(wait, parked).await;
+        }
+    }
+
+    /// Report the checkout made at `checked_out_at` unless the returned
+    /// token is cancelled within the threshold
+    fn watch_for_leak(
+        &self,
+        detection: LeakDetection,
+        checked_out_at: Instant,
+    ) -> CancellationToken {
+        #[cfg(feature = "leak-debug")]
+        let backtrace = Some(Arc::new(Backtrace::force_capture()));
+        #[cfg(not(feature = "leak-debug"))]
+        let backtrace = None;
+
+        let released = CancellationToken::new();
+        let watch = released.clone();
+        let stats = self.stats.clone();
+        DefaultRuntime::spawn(Box::pin(async move {
+            futures::select_biased! {
+                _ = watch.cancelled().fuse() => return,
+                _ = runtime::sleep(detection.threshold).fuse() => {}
+            }
+            stats.lock().await.leaks_detected += 1;
+            detection.report(&LeakReport {
+                held_for: checked_out_at.elapsed(),
+                threshold: detection.threshold,
+                backtrace,
+            });
+        }));
+        released
+    }
+
+    /// Pop the most recently returned healthy connection, dropping broken
+    /// and draining ones
+    fn take_idle(&self) -> (Option<Connection>, usize) {
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        let mut evicted = 0;
+        while let Some(conn) = idle.pop() {
+            if !conn.is_broken() && !conn.is_draining() {
+                return (Some(conn), evicted);
+            }
+            evicted += 1;
+        }
+        (None, evicted)
+    }
+
+    /// Notification listener, when configured with
+    /// [`PoolConfig::notifications`]
+    pub fn notifications(&self) -> Option<&NotificationListener> {
+        self.listener.as_deref()
+    }
+
     /// Get pool statistics
     pub async fn get_stats(&self) -> PoolStats {
         self.stats.lock().await.clone()
     }
 
-    /// Close the pool and all connections
+    /// Close the pool and all connections, and shut down its notification
+    /// listener
     pub async fn close(&self) -> Result<()> {
-        // In a real implementation, we'd close all connections
-        Ok(())
+        if let Some(listener) = &self.listener {
+            listener.shutdown().await;
+        }
+        // Close everything even when some closes fail, reporting the first
+        let mut first_error = None;
+        let mut sub_pools: Vec<Pool> = self
+            .partitions
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        sub_pools.extend(self.read_only.as_deref().cloned());
+        for pool in sub_pools {
+            if let Err(e) = Box::pin(pool.close()).await {
+                first_error.get_or_insert(e);
+            }
+        }
+        let idle = std::mem::take(&mut *self.idle.lock().unwrap_or_else(|e| e.into_inner()));
+        for conn in idle {
+            if let Err(e) = conn.close().await {
+                log::warn!("closing idle pooled connection failed: {}", e);
+                first_error.get_or_insert(e);
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
     }
 
     /// Reconfigure the pool
@@ -195,6 +880,12 @@ impl Clone for Pool {
             pool_config: self.pool_config.clone(),
             semaphore: self.semaphore.clone(),
             stats: self.stats.clone(),
+            idle: self.idle.clone(),
+            listener: self.listener.clone(),
+            read_only: self.read_only.clone(),
+            read_only_fallback: self.read_only_fallback.clone(),
+            lanes: self.lanes.clone(),
+            partitions: self.partitions.clone(),
         }
     }
 }
@@ -202,12 +893,19 @@ impl Clone for Pool {
 /// A connection from the pool
 pub struct PooledConnection {
     connection: Option<Connection>,
-    #[allow(dead_code)]
     pool: Pool,
-    _permit: tokio::sync::OwnedSemaphorePermit,
+    checked_out_at: Instant,
+    /// Cancelled on release to stop the leak watch
+    released: Option<CancellationToken>,
+    _permit: SemaphoreGuardArc,
 }
 
 impl PooledConnection {
+    /// How long the connection has been checked out
+    pub fn checked_out_for(&self) -> Duration {
+        self.checked_out_at.elapsed()
+    }
+
     /// Get a reference to the underlying connection
     pub fn connection(&self) -> &Connection {
         self.connection.as_ref().unwrap()
@@ -233,13 +931,70 @@ impl std::ops::DerefMut for PooledConnection {
     }
 }
 
+/// Marks a connection unusable when dropped while armed
+struct DiscardGuard(Option<Arc<AtomicBool>>);
+
+impl DiscardGuard {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for DiscardGuard {
+    fn drop(&mut self) {
+        if let Some(broken) = &self.0 {
+            broken.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
 impl Drop for PooledConnection {
     fn drop(&mut self) {
-        // Return connection to pool
-        // Update stats
-        if let Some(_conn) = self.connection.take() {
-            // In a real implementation, we'd return the connection to the pool
-            // For now, the permit is automatically released
+        if let Some(released) = &self.released {
+            released.cancel();
+        }
+        // Return connection to pool; the permit is released with `self`
+        let Some(mut conn) = self.connection.take() else {
+            return;
+        };
+        // A connection that hit a fatal error is never handed out again,
+        // nor is one that cannot return to its login schema, nor one with
+        // uncommitted work: its row locks and changes must not pass to the
+        // next borrower, and closing the session rolls them back. Release is
+        // the safe point to replace one the server is draining: the
+        // borrower's work is done, and the next borrower gets a session
+        // the listener routes to an instance still in service.
+        let drained = conn.is_draining();
+        if drained {
+            log::debug!("replacing connection drained by the server");
+        }
+        let info = conn.info();
+        if info.transaction_active {
+            log::debug!("discarding connection released with an open transaction");
+        }
+        let reusable =
+            info.is_open && !info.transaction_active && !drained && conn.restore_schema();
+        if reusable {
+            // A DRCP session goes back to the server's pool too
+            conn.queue_stateless();
+            self.pool
+                .idle
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(conn);
+        }
+
+        // Stats are best effort here, as drop cannot wait for the lock
+        if let Some(mut stats) = self.pool.stats.try_lock() {
+            stats.connections_in_use = stats.connections_in_use.saturating_sub(1);
+            if reusable {
+                stats.connections_idle += 1;
+            } else {
+                stats.connections_closed += 1;
+            }
+            if drained {
+                stats.connections_drained += 1;
+            }
         }
     }
 }
@@ -257,6 +1012,482 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[tokio::test]
+    async fn test_sub_second_acquire_timeout() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool_config = PoolConfig::new()
+            .min(0)
+            .max(1)
+            .timeout(Duration::from_millis(50));
+        let pool = Pool::new(config, pool_config).await.unwrap();
+
+        let _held = pool.get_connection().await.unwrap();
+        let started = std::time::Instant::now();
+        assert!(matches!(
+            pool.get_connection().await,
+            Err(Error::PoolTimeout)
+        ));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_per_call_deadline() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(1))
+            .await
+            .unwrap();
+        let held = pool.get_connection().await.unwrap();
+
+        let started = Instant::now();
+        assert!(matches!(
+            pool.get_connection_timeout(Duration::from_millis(20)).await,
+            Err(Error::PoolTimeout)
+        ));
+        assert!(matches!(
+            pool.get_connection_by(Instant::now()).await,
+            Err(Error::PoolTimeout)
+        ));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        drop(held);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        assert!(pool.get_connection_by(deadline).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_acquire() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(1))
+            .await
+            .unwrap();
+        let _held = pool.get_connection().await.unwrap();
+
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let result = pool.get_connection_cancellable(&cancel).await;
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert_eq!(pool.get_stats().await.connections_in_use, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_reset_replaces_connection() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool_config = PoolConfig::new().min(0).max(1).reset_on_release(true);
+        let pool = Pool::new(config, pool_config).await.unwrap();
+
+        // Plain connects are not logged on here, so the reset fails
+        drop(pool.get_connection().await.unwrap());
+        let _conn = pool.get_connection().await.unwrap();
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.connections_created, 2);
+        assert_eq!(stats.connections_closed, 1);
+        assert_eq!(stats.connections_idle, 0);
+    }
+
+    #[tokio::test]
+    async fn test_schema_restored_on_release() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(1))
+            .await
+            .unwrap();
+
+        let mut conn = pool.get_connection().await.unwrap();
+        conn.set_current_schema("HR").await.unwrap();
+        assert_eq!(conn.info().current_schema.as_deref(), Some("HR"));
+        assert!(conn.set_current_schema("bad\"schema").await.is_err());
+        drop(conn);
+
+        let conn = pool.get_connection().await.unwrap();
+        assert_eq!(pool.get_stats().await.connections_created, 1);
+        assert_eq!(conn.current_schema(), None);
+    }
+
+    #[tokio::test]
+    async fn test_default_options_applied() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let options = ExecuteOptions {
+            fetch_array_size: 500,
+            ..Default::default()
+        };
+        let pool_config = PoolConfig::new().min(0).max(1).default_options(options);
+        let pool = Pool::new(config, pool_config).await.unwrap();
+
+        let mut conn = pool.get_connection().await.unwrap();
+        assert_eq!(conn.defaults().unwrap().fetch_array_size, 500);
+        conn.set_defaults(ExecuteOptions {
+            max_rows: 1,
+            ..Default::default()
+        });
+        drop(conn);
+
+        let conn = pool.get_connection().await.unwrap();
+        assert_eq!(pool.get_stats().await.connections_created, 1);
+        assert_eq!(conn.defaults().unwrap().fetch_array_size, 500);
+        assert_eq!(conn.defaults().unwrap().max_rows, 0);
+    }
+
+    #[tokio::test]
+    async fn test_broken_connections_evicted() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(2))
+            .await
+            .unwrap();
+
+        drop(pool.get_connection().await.unwrap());
+        let conn = pool.get_connection().await.unwrap();
+        assert_eq!(pool.get_stats().await.connections_created, 1);
+
+        assert!(conn.is_healthy());
+        conn.mark_broken();
+        assert!(!conn.is_healthy());
+        assert!(!conn.info().is_open);
+        drop(conn);
+
+        let conn = pool.get_connection().await.unwrap();
+        assert!(!conn.is_broken());
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.connections_created, 2);
+        assert_eq!(stats.connections_closed, 1);
+        assert_eq!(stats.connections_in_use, 1);
+    }
+
+    #[tokio::test]
+    async fn test_open_transaction_discarded_on_release() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(1))
+            .await
+            .unwrap();
+
+        let conn = pool.get_connection().await.unwrap();
+        conn.mark_in_transaction();
+        drop(conn);
+
+        let conn = pool.get_connection().await.unwrap();
+        assert!(!conn.info().transaction_active);
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.connections_created, 2);
+        assert_eq!(stats.connections_closed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_draining_connections_replaced_on_release() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(1))
+            .await
+            .unwrap();
+
+        let conn = pool.get_connection().await.unwrap();
+        conn.mark_draining();
+        // Still usable until the borrower is done with it
+        assert!(conn.is_healthy());
+        drop(conn);
+
+        let conn = pool.get_connection().await.unwrap();
+        assert!(!conn.is_draining());
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.connections_created, 2);
+        assert_eq!(stats.connections_closed, 1);
+        assert_eq!(stats.connections_drained, 1);
+    }
+
+    #[tokio::test]
+    async fn test_leak_detection() {
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = reports.clone();
+        let detection = LeakDetection::new(Duration::from_millis(20))
+            .on_leak(move |report| seen.lock().unwrap().push(report.clone()));
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool_config = PoolConfig::new().min(0).max(2).leak_detection(detection);
+        let pool = Pool::new(config, pool_config).await.unwrap();
+
+        drop(pool.get_connection().await.unwrap());
+        let held = pool.get_connection().await.unwrap();
+        runtime::sleep(Duration::from_millis(60)).await;
+        assert!(held.checked_out_for() >= Duration::from_millis(60));
+        drop(held);
+        runtime::sleep(Duration::from_millis(40)).await;
+
+        let reports = reports.lock().unwrap().clone();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].held_for >= Duration::from_millis(20));
+        assert_eq!(reports[0].backtrace.is_some(), cfg!(feature = "leak-debug"));
+        assert_eq!(pool.get_stats().await.leaks_detected, 1);
+    }
+
+    #[tokio::test]
+    async fn test_notification_listener_owned_by_pool() {
+        use std::net::{IpAddr, Ipv4Addr};
+
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let listener = ListenerConfig::new().interface(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let pool = Pool::new(config, PoolConfig::new().min(0).notifications(listener))
+            .await
+            .unwrap();
+        let addr = pool.notifications().unwrap().local_addr();
+        assert!(tokio::net::TcpStream::connect(addr).await.is_ok());
+
+        pool.clone().close().await.unwrap();
+        assert!(!pool.notifications().unwrap().is_running());
+        assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_sub_pool() {
+        let config = ConnectionConfig::new("localhost:1521/sales", "user", "pass")
+            .read_only_service("sales_ro");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(2).read_only_max(1))
+            .await
+            .unwrap();
+
+        let reader = pool.get_connection_for(Intent::ReadOnly).await.unwrap();
+        assert_eq!(reader.info().intent, Intent::ReadOnly);
+        assert!(!pool.read_only_fallback.load(Ordering::Relaxed));
+        let writer = pool.get_connection_for(Intent::ReadWrite).await.unwrap();
+        assert_eq!(writer.info().intent, Intent::ReadWrite);
+
+        let read_only = pool.read_only().unwrap();
+        assert_eq!(read_only.get_stats().await.connections_in_use, 1);
+        assert_eq!(pool.get_stats().await.connections_in_use, 1);
+        assert!(matches!(
+            read_only
+                .get_connection_timeout(Duration::from_millis(10))
+                .await,
+            Err(Error::PoolTimeout)
+        ));
+
+        // Without a read-only service the sub-pool would reach the primary
+        let config = ConnectionConfig::new("localhost:1521/sales", "user", "pass");
+        let pool_config = PoolConfig::new().min(0).max(2);
+        assert!(matches!(
+            Pool::new(config.clone(), pool_config.clone().read_only_max(1)).await,
+            Err(Error::InvalidConfiguration(_))
+        ));
+        // Without a sub-pool the primary serves read-only requests
+        let pool = Pool::new(config, pool_config).await.unwrap();
+        let reader = pool.get_connection_for(Intent::ReadOnly).await.unwrap();
+        assert_eq!(reader.info().intent, Intent::ReadWrite);
+        assert!(pool.read_only_fallback.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_priority_lanes() {
+        async fn served_order(max_batch_wait: Duration) -> Vec<Priority> {
+            let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+            let pool_config = PoolConfig::new()
+                .min(0)
+                .max(1)
+                .priority_lanes(max_batch_wait);
+            let pool = Pool::new(config, pool_config).await.unwrap();
+            let held = pool.get_connection().await.unwrap();
+
+            let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let mut tasks = Vec::new();
+            for priority in [Priority::Batch, Priority::Interactive] {
+                let (pool, order) = (pool.clone(), order.clone());
+                tasks.push(tokio::spawn(async move {
+                    let conn = pool.get_connection_priority(priority).await.unwrap();
+                    order.lock().unwrap().push(priority);
+                    runtime::sleep(Duration::from_millis(10)).await;
+                    drop(conn);
+                }));
+                runtime::sleep(Duration::from_millis(20)).await;
+            }
+            drop(held);
+            for task in tasks {
+                task.await.unwrap();
+            }
+            let order = order.lock().unwrap().clone();
+            order
+        }
+
+        // The interactive request jumps ahead of the batch one queued first
+        assert_eq!(
+            served_order(Duration::from_secs(5)).await,
+            [Priority::Interactive, Priority::Batch]
+        );
+        // Unless the batch request has already waited its limit
+        assert_eq!(
+            served_order(Duration::from_millis(10)).await,
+            [Priority::Batch, Priority::Interactive]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_close_empties_every_pool() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass")
+            .read_only_service("XEPDB1_RO");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(2).read_only_max(1))
+            .await
+            .unwrap();
+        pool.add_partition("eu", "db-eu:1521/tenant_a")
+            .await
+            .unwrap();
+        let held = (
+            pool.get_connection().await.unwrap(),
+            pool.get_connection().await.unwrap(),
+            pool.get_connection_for(Intent::ReadOnly).await.unwrap(),
+            pool.get_connection_in("eu").await.unwrap(),
+        );
+        drop(held);
+
+        pool.close().await.unwrap();
+        let idle = |pool: &Pool| pool.idle.lock().unwrap().len();
+        assert_eq!(idle(&pool), 0);
+        assert_eq!(idle(pool.read_only().unwrap()), 0);
+        assert_eq!(idle(&pool.partition("eu").unwrap()), 0);
+    }
+
+    #[tokio::test]
+    async fn test_partitions() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(1))
+            .await
+            .unwrap();
+        pool.add_partition("eu", "db-eu:1521/tenant_a")
+            .await
+            .unwrap();
+        pool.add_partition("us", "db-us:1521/tenant_b")
+            .await
+            .unwrap();
+        assert!(matches!(
+            pool.add_partition("eu", "db-eu:1521/tenant_c").await,
+            Err(Error::Pool(_))
+        ));
+        // Concurrent adds of one key leave a single partition
+        let (first, second) = tokio::join!(
+            pool.add_partition("apac", "db-apac:1521/tenant_d"),
+            pool.add_partition("apac", "db-apac:1521/tenant_e")
+        );
+        assert!(first.is_ok() != second.is_ok());
+        assert!(pool.remove_partition("apac").await.unwrap());
+        assert_eq!(pool.partition_keys(), ["eu", "us"]);
+        assert!(Arc::ptr_eq(
+            &pool.partition("eu").unwrap().stats,
+            &pool.stats
+        ));
+
+        let eu = pool.get_connection_in("eu").await.unwrap();
+        assert_eq!(eu.info().connection_string, "db-eu:1521/tenant_a");
+        assert_eq!(eu.info().user, "user");
+        let us = pool.get_connection_in("us").await.unwrap();
+        assert_eq!(us.info().connection_string, "db-us:1521/tenant_b");
+        // Each partition has its own connections, counted by the pool
+        assert!(matches!(
+            pool.partition("eu")
+                .unwrap()
+                .get_connection_timeout(Duration::from_millis(10))
+                .await,
+            Err(Error::PoolTimeout)
+        ));
+        assert!(pool.get_connection().await.is_ok());
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.connections_created, 3);
+        assert_eq!(stats.connections_in_use, 2);
+
+        assert!(matches!(
+            pool.get_connection_in("apac").await,
+            Err(Error::Pool(_))
+        ));
+        assert!(pool.remove_partition("us").await.unwrap());
+        assert!(!pool.remove_partition("us").await.unwrap());
+        assert_eq!(pool.partition_keys(), ["eu"]);
+    }
+
+    #[tokio::test]
+    async fn test_with_connection_releases() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(1))
+            .await
+            .unwrap();
+
+        let user = pool
+            .with_connection(|conn| Box::pin(async move { Ok(conn.info().user) }))
+            .await
+            .unwrap();
+        assert_eq!(user, "user");
+
+        // Cancelled while holding the connection
+        let held = pool.with_connection(|_| {
+            Box::pin(async move {
+                runtime::sleep(Duration::from_secs(60)).await;
+                Ok(())
+            })
+        });
+        assert!(runtime::timeout(Duration::from_millis(10), held)
+            .await
+            .is_err());
+        assert_eq!(pool.get_stats().await.connections_in_use, 0);
+        assert_eq!(pool.get_stats().await.connections_created, 1);
+
+        // A panic discards the connection and reaches the caller
+        let panicking = pool.clone();
+        let task = tokio::spawn(async move {
+            panicking
+                .with_connection(|_| -> BoxFuture<'_, Result<()>> {
+                    Box::pin(async move { panic!("boom") })
+                })
+                .await
+        });
+        assert!(task.await.unwrap_err().is_panic());
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.connections_in_use, 0);
+        assert_eq!(stats.connections_closed, 1);
+
+        // Plain connects are not logged on here, so the reset fails
+        let result = pool
+            .with_connection_reset(|_| Box::pin(async move { Ok(1) }))
+            .await;
+        assert_eq!(result.unwrap(), 1);
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.connections_created, 2);
+        assert_eq!(stats.connections_closed, 2);
+        assert_eq!(stats.connections_idle, 0);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_connection_reset_reuses_or_discards() {
+        use crate::testing::{MockCall, MockConnection};
+
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = Pool::new(config, PoolConfig::new().min(0).max(1))
+            .await
+            .unwrap();
+        let mock = MockConnection::new();
+        let conn = mock.connect().await.unwrap();
+        pool.idle.lock().unwrap().push(conn);
+
+        // A reset that succeeds sends the connection back for reuse
+        let result = pool
+            .with_connection_reset(|_| Box::pin(async move { Ok(1) }))
+            .await;
+        assert_eq!(result.unwrap(), 1);
+        assert!(mock.calls().contains(&MockCall::ResetSession));
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.connections_created, 0);
+        assert_eq!(stats.connections_closed, 0);
+        assert_eq!(stats.connections_idle, 1);
+
+        // Dropped before its reset, the connection is discarded
+        let held = pool.with_connection_reset(|_| {
+            Box::pin(async move {
+                runtime::sleep(Duration::from_secs(60)).await;
+                Ok(())
+            })
+        });
+        assert!(runtime::timeout(Duration::from_millis(10), held)
+            .await
+            .is_err());
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.connections_created, 0);
+        assert_eq!(stats.connections_closed, 1);
+        assert!(pool.idle.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_pool_config_default() {
         let config = PoolConfig::default();
@@ -1,40 +1,260 @@
 // Oracle protocol implementation (TNS/TTC)
 
-use crate::statement::Row;
-use crate::types::{ColumnInfo, OracleType, Value};
-use crate::{ConnectionConfig, Error, Result};
+use crate::auth::Authenticator;
+use crate::budget::MemoryBudget;
+use crate::buffer::BufferPool;
+use crate::connection::{
+    ConnectionStats, Intent, PoolBoundary, Purity, SessionParams, SessionTags,
+};
+use crate::descriptor::{self, descriptor_sections, descriptor_value};
+use crate::error::ErrorContext;
+use crate::hooks::{ConnectTarget, TargetAddress};
+use crate::lob::{Lob, LobOp};
+use crate::metadata_cache::MetadataCache;
+use crate::pipeline::{PipelineOp, PipelineOutcome};
+use crate::result_cache::ResultCache;
+use crate::runtime::{DefaultRuntime, Runtime, SocketOptions, Transport};
+use crate::slow_query::Stopwatch;
+use crate::sqlnet::SqlNetConfig;
+use crate::statement::{value_wire_len, CallResults, ColumnIndex, ResultSet, Row};
+use crate::stmt_cache::StatementCache;
+use crate::types::{BindType, ColumnInfo, NumberFormat, OracleType, Value};
+use crate::wire::{self, NationalCharset, RawValue};
+use crate::xa::{Vote, Xid};
+use crate::{ConnectionConfig, Error, Result, Warning};
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Cursors given up on while their connection was busy
+///
+/// A [`RowStream`](crate::RowStream) dropped or cancelled while another
+/// task holds the connection cannot close its cursor itself; it hands the
+/// cursor over here and the next round trip closes it, breaking off the
+/// fetch that was cut short first.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AbandonedCursors {
+    inner: Arc<std::sync::Mutex<Abandoned>>,
+}
+
+#[derive(Debug, Default)]
+struct Abandoned {
+    /// Whether a fetch was dropped mid-exchange
+    interrupt: bool,
+    cursors: Vec<u32>,
+}
+
+impl AbandonedCursors {
+    /// Close `cursor` with the next round trip, after a break if its
+    /// fetch was `interrupt`ed
+    pub(crate) fn push(&self, cursor: u32, interrupt: bool) {
+        let mut abandoned = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        abandoned.interrupt |= interrupt;
+        abandoned.cursors.push(cursor);
+    }
+
+    fn take(&self) -> Abandoned {
+        std::mem::take(&mut *self.inner.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// In-band notification that the session's service is shutting down
+const INBAND_SESSION_SHUTDOWN: i32 = 12572;
+
+/// In-band notification asking the client to release the session, sent
+/// while a service is drained for planned maintenance
+const INBAND_DRAIN: i32 = 12573;
+
+/// Describe flag of a column stored with TDE column encryption
+const DESCRIBE_ENCRYPTED: u32 = 0x0400;
+
+/// Describe flag of a column covered by a data redaction policy
+const DESCRIBE_REDACTED: u32 = 0x2000;
 
 /// Oracle network protocol handler
 pub struct Protocol {
     // In a real implementation, this would contain:
     // - Network socket
     // - Session state
-    // - Statement cache
     // - Encoding information
-    #[allow(dead_code)]
     config: ConnectionConfig,
+    /// Addresses and timeouts from the connection string
+    conn_info: ConnectionInfo,
     session_id: Option<u64>,
+    /// Serial number that, with the session ID, identifies the session
+    serial_number: Option<u32>,
+    /// Instance the session runs on
+    instance_name: Option<String>,
+    /// Major and minor release of the server
+    server_version: (u8, u8),
+    /// Encoding of NCHAR and NVARCHAR2 data
+    national_charset: NationalCharset,
     is_connected: bool,
+    /// Set once a call fails with a fatal error
+    broken: Arc<AtomicBool>,
+    /// Set once the server asks for the session to be released
+    draining: Arc<AtomicBool>,
+    /// A request went out and its response has not been fully read
+    in_call: bool,
+    /// Uncommitted changes may be pending
+    in_transaction: Arc<AtomicBool>,
+    /// Parameters of the current session, shared with its connection
+    session: Arc<std::sync::Mutex<Option<SessionParams>>>,
+    /// Global transaction branch the session is attached to
+    branch: Option<Xid>,
+    /// Detached branches of this session, and whether they changed anything
+    detached: HashMap<Xid, bool>,
+    /// Session tags the server has applied
+    session_tags: SessionTags,
+    /// Tag changes waiting for the next round trip
+    pending_tags: SessionTags,
+    /// CURRENT_SCHEMA the server has applied, `None` for the login schema
+    current_schema: Option<String>,
+    /// CURRENT_SCHEMA change waiting for the next round trip
+    pending_schema: Option<Option<String>>,
+    /// Calls made since the session was created
+    stats: ConnectionStats,
+    /// Scratch buffers reused across calls
+    buffers: BufferPool,
+    /// Fetched rows held by result sets and streams
+    budget: MemoryBudget,
+    /// Open cursors and the rows the server has yet to send
+    cursors: HashMap<u32, OpenCursor>,
+    /// Cursors to close with the next round trip
+    abandoned: AbandonedCursors,
+    /// Cursor id handed out next
+    next_cursor: u32,
+    /// Parsed statements kept for reuse
+    stmt_cache: StatementCache,
+    /// Bind types declared for the next execution
+    pending_binds: Vec<Option<BindType>>,
+    /// Bind types of the last execution
+    binds: Vec<BindType>,
+    /// Commit with the next call if it succeeds
+    commit_next: bool,
+    /// Tell the server with the next call that the session is stateless
+    pending_stateless: bool,
+    /// Warning the server attached to the last call
+    warning: Option<Warning>,
+    /// Warnings the server attached to the logon
+    login_warnings: Vec<Warning>,
+    /// Rows of RESULT_CACHE queries
+    result_cache: ResultCache,
+    /// Columns of described statements
+    metadata_cache: MetadataCache,
+    /// sqlnet.ora parameters in effect
+    sqlnet: SqlNetConfig,
+    /// Scripted responses that stand in for the server
+    #[cfg(feature = "testing")]
+    mock: Option<crate::testing::MockConnection>,
 }
 
 impl Protocol {
     /// Create a new protocol instance
     pub async fn new(config: &ConnectionConfig) -> Result<Self> {
         // Parse connection string
-        let _conn_info = Self::parse_connection_string(&config.connection_string)?;
+        let mut conn_info = Self::parse_connection_string(&config.connection_string)?;
+        if config.intent == Intent::ReadOnly {
+            match &config.read_only_service {
+                Some(service) => {
+                    conn_info.service_name = service.clone();
+                    conn_info.sid = None;
+                }
+                None => log::warn!(
+                    "read-only intent without a read_only_service; connecting to the \
+                     connection string's service"
+                ),
+            }
+        }
+        if config.pool_boundary.is_some() && !conn_info.pooled {
+            return Err(Error::InvalidConfiguration(
+                "implicit connection pooling needs a pooled server: add :POOLED or (SERVER=POOLED)"
+                    .into(),
+            ));
+        }
+        config
+            .hooks
+            .before_connect(&conn_info.target(&config.user))?;
+        let sqlnet = config
+            .sqlnet
+            .clone()
+            .or(SqlNetConfig::load(config.tns_admin.as_deref())?);
+        // A real implementation opens the transport here with
+        // open_transport() and sends this in the connect packet
+        log::debug!(
+            "connecting with (CONNECT_DATA={})",
+            conn_info.connect_data(config)
+        );
 
         Ok(Self {
             config: config.clone(),
+            conn_info,
             session_id: None,
+            serial_number: None,
+            instance_name: None,
+            server_version: (0, 0),
+            national_charset: NationalCharset::default(),
             is_connected: false,
+            broken: Arc::default(),
+            draining: Arc::default(),
+            in_call: false,
+            in_transaction: Arc::default(),
+            session: Arc::default(),
+            branch: None,
+            detached: HashMap::new(),
+            session_tags: SessionTags::default(),
+            pending_tags: SessionTags::default(),
+            current_schema: None,
+            pending_schema: None,
+            stats: ConnectionStats::default(),
+            buffers: BufferPool::default(),
+            budget: MemoryBudget::new(config.max_buffered_bytes),
+            cursors: HashMap::new(),
+            abandoned: AbandonedCursors::default(),
+            next_cursor: 1,
+            stmt_cache: StatementCache::new(config.stmt_cache_size, config.stmt_cache_fold_case),
+            pending_binds: Vec::new(),
+            binds: Vec::new(),
+            commit_next: false,
+            pending_stateless: false,
+            warning: None,
+            login_warnings: Vec::new(),
+            result_cache: ResultCache::new(config.result_cache_size, config.result_cache_lag),
+            metadata_cache: MetadataCache::new(config.metadata_cache_size),
+            sqlnet,
+            #[cfg(feature = "testing")]
+            mock: None,
         })
     }
 
+    /// Create a connected protocol whose calls are served by `mock`
+    #[cfg(feature = "testing")]
+    pub(crate) async fn mock(
+        config: &ConnectionConfig,
+        mock: crate::testing::MockConnection,
+    ) -> Result<Self> {
+        let mut protocol = Self::new(config).await?;
+        protocol
+            .authenticate(&config.user, config.password.expose_secret())
+            .await?;
+        if let Some(version) = mock.reported_version() {
+            protocol.server_version = version;
+        }
+        protocol.login_warnings = mock.login_warnings();
+        protocol.mock = Some(mock);
+        protocol.check_pool_boundary()?;
+        Ok(protocol)
+    }
+
     /// Parse Oracle connection string
     fn parse_connection_string(conn_str: &str) -> Result<ConnectionInfo> {
         // Support formats:
         // - host:port/service
         // - host/service
+        // - [ipv6]:port/service
         // - Easy Connect: host:port/service_name
         // - TNS: (DESCRIPTION=...)
 
@@ -52,31 +272,101 @@ impl Protocol {
             )));
         }
 
-        let host_port: Vec<&str> = parts[0].split(':').collect();
-        let host = host_port[0].to_string();
-        let port = if host_port.len() > 1 {
-            host_port[1]
+        let (host, port) = split_host_port(parts[0])?;
+        let port = match port {
+            Some(port) => port
                 .parse()
-                .map_err(|_| Error::InvalidConfiguration("Invalid port number".into()))?
-        } else {
-            crate::constants::DEFAULT_PORT
+                .map_err(|_| Error::InvalidConfiguration("Invalid port number".into()))?,
+            None => crate::constants::DEFAULT_PORT,
+        };
+        // A ":POOLED" suffix asks for a DRCP pooled server
+        let (service_name, pooled) = match parts[1].rsplit_once(':') {
+            Some((service, server)) if server.eq_ignore_ascii_case("POOLED") => (service, true),
+            _ => (parts[1], false),
         };
-        let service_name = parts[1].to_string();
+        let service_name = service_name.to_string();
 
         Ok(ConnectionInfo {
-            host,
-            port,
+            addresses: vec![Address {
+                host: host.to_string(),
+                port,
+                ipc_key: None,
+                transport_connect_timeout: None,
+            }],
             service_name,
             sid: None,
+            pooled,
+            connect_timeout: None,
+            transport_connect_timeout: None,
         })
     }
 
     /// Parse TNS connection string
-    fn parse_tns_string(_tns: &str) -> Result<ConnectionInfo> {
-        // Simplified - real implementation would parse full TNS format
-        Err(Error::NotImplemented(
-            "TNS string parsing not yet implemented".into(),
-        ))
+    fn parse_tns_string(tns: &str) -> Result<ConnectionInfo> {
+        let sections = descriptor_sections(tns, "ADDRESS");
+        let mut addresses = Vec::with_capacity(sections.len());
+        for section in &sections {
+            let ipc = descriptor_value(section, "PROTOCOL")
+                .is_some_and(|protocol| protocol.eq_ignore_ascii_case("IPC"));
+            if ipc {
+                let key = descriptor_value(section, "KEY")
+                    .ok_or_else(|| Error::InvalidConfiguration("IPC address has no KEY".into()))?;
+                addresses.push(Address {
+                    host: String::new(),
+                    port: 0,
+                    ipc_key: Some(key.to_string()),
+                    transport_connect_timeout: descriptor_timeout(
+                        section,
+                        "TRANSPORT_CONNECT_TIMEOUT",
+                    )?,
+                });
+                continue;
+            }
+            let host = descriptor_value(section, "HOST")
+                .ok_or_else(|| Error::InvalidConfiguration("TNS address has no HOST".into()))?;
+            let port = match descriptor_value(section, "PORT") {
+                Some(port) => port
+                    .parse()
+                    .map_err(|_| Error::InvalidConfiguration("Invalid port number".into()))?,
+                None => crate::constants::DEFAULT_PORT,
+            };
+            addresses.push(Address {
+                host: crate::runtime::unbracket(&host).to_string(),
+                port,
+                ipc_key: None,
+                transport_connect_timeout: descriptor_timeout(
+                    section,
+                    "TRANSPORT_CONNECT_TIMEOUT",
+                )?,
+            });
+        }
+        if addresses.is_empty() {
+            return Err(Error::InvalidConfiguration(
+                "TNS descriptor has no HOST".into(),
+            ));
+        }
+        // Descriptor-wide parameters sit outside the addresses
+        let outer = sections.iter().fold(tns.to_string(), |outer, section| {
+            outer.replacen(section, "", 1)
+        });
+
+        let sid = descriptor_value(tns, "SID").map(Cow::into_owned);
+        let service_name = descriptor_value(tns, "SERVICE_NAME").unwrap_or_default();
+        if service_name.is_empty() && sid.is_none() {
+            return Err(Error::InvalidConfiguration(
+                "TNS descriptor has no SERVICE_NAME or SID".into(),
+            ));
+        }
+
+        Ok(ConnectionInfo {
+            addresses,
+            service_name: service_name.to_string(),
+            sid,
+            pooled: descriptor_value(tns, "SERVER")
+                .is_some_and(|server| server.eq_ignore_ascii_case("POOLED")),
+            connect_timeout: descriptor_timeout(&outer, "CONNECT_TIMEOUT")?,
+            transport_connect_timeout: descriptor_timeout(&outer, "TRANSPORT_CONNECT_TIMEOUT")?,
+        })
     }
 
     /// Authenticate with the database
@@ -89,30 +379,692 @@ impl Protocol {
 
         self.is_connected = true;
         self.session_id = Some(12345); // Mock session ID
+        self.serial_number = Some(1);
+        self.instance_name = Some("ORCL".to_string());
+        self.server_version = (19, 0);
+        // A real implementation takes the national charset from the
+        // logon response's NLS parameters
+        self.national_charset = NationalCharset::Al16Utf16;
+        Ok(())
+    }
+
+    /// Fail unless the server can honour the configured implicit pooling
+    /// boundary
+    pub(crate) fn check_pool_boundary(&self) -> Result<()> {
+        if self.config.pool_boundary.is_none() {
+            return Ok(());
+        }
+        crate::capabilities::Capabilities::new(self.config.mode, self.server_version)
+            .require(crate::capabilities::Feature::ImplicitPooling)
+    }
+
+    /// Warnings the server attached to the logon, such as ORA-28002
+    pub fn login_warnings(&self) -> &[Warning] {
+        &self.login_warnings
+    }
+
+    /// Take the warning the server attached to the last call
+    pub fn take_warning(&mut self) -> Option<Warning> {
+        self.warning.take()
+    }
+
+    /// Rows of a RESULT_CACHE query from the client result cache
+    ///
+    /// Nothing is served while this session has uncommitted changes.
+    pub(crate) fn cached_result(
+        &mut self,
+        sql: &str,
+        params: &[Value],
+    ) -> Option<(Vec<Row>, Vec<ColumnInfo>)> {
+        if self.in_transaction() {
+            return None;
+        }
+        let cached = self.result_cache.get(sql, params)?;
+        self.stats.result_cache_hits += 1;
+        Some(cached)
+    }
+
+    /// Keep the rows of a RESULT_CACHE query for later executions
+    pub(crate) fn cache_result(
+        &mut self,
+        sql: &str,
+        params: &[Value],
+        rows: &[Row],
+        metadata: &[ColumnInfo],
+    ) {
+        if !self.in_transaction() {
+            self.result_cache.insert(sql, params, rows, metadata);
+        }
+    }
+
+    /// Major and minor release of the server, as of authentication
+    pub fn server_version(&self) -> (u8, u8) {
+        self.server_version
+    }
+
+    /// Parameters negotiated at logon, `None` until authenticated
+    pub fn session_params(&self) -> Option<SessionParams> {
+        if !self.is_connected {
+            return None;
+        }
+        // Only what the server reported goes here: the character set, time
+        // zone, SDU and network encryption are not read from the accept
+        // and negotiation responses yet, so they stay unknown
+        Some(SessionParams {
+            server_version: self.server_version,
+            charset: None,
+            national_charset: self.national_charset.name().to_string(),
+            time_zone: None,
+            sdu: None,
+            encryption: None,
+            integrity: None,
+            drcp: self.conn_info.pooled,
+            purity: self.config.purity,
+            pool_boundary: self.config.pool_boundary,
+        })
+    }
+
+    /// Declare types for the binds of the next execution; positions
+    /// without one bind as their value's natural type
+    pub fn define_binds(&mut self, types: Vec<Option<BindType>>) {
+        self.pending_binds = types;
+    }
+
+    /// Mark the session stateless with the next round trip
+    pub(crate) fn mark_stateless(&mut self) {
+        self.pending_stateless = true;
+    }
+
+    /// Commit in the same round trip as the next statement, once it
+    /// succeeds, as the execute call's commit option does
+    pub(crate) fn commit_on_success(&mut self) {
+        self.commit_next = true;
+    }
+
+    /// Types the binds of the last execution were sent as
+    #[cfg(test)]
+    pub(crate) fn binds(&self) -> &[BindType] {
+        &self.binds
+    }
+
+    /// Resolve the bind types for `sql` and reject binds the server
+    /// cannot accept
+    ///
+    /// RAW binds cannot exceed 32767 bytes on any server. BOOLEAN binds
+    /// travel as their own wire type, which PL/SQL accepts from 12.1 and
+    /// SQL only from 23ai.
+    fn check_binds(&mut self, sql: &str, params: &[Value]) -> Result<()> {
+        if Self::parse_statement_type(sql)? == StatementType::Ddl {
+            self.check_ddl(params)?;
+        }
+        let mut declared = std::mem::take(&mut self.pending_binds).into_iter();
+        let binds: Vec<BindType> = params
+            .iter()
+            .map(|value| {
+                declared
+                    .next()
+                    .flatten()
+                    .unwrap_or_else(|| BindType::of(value))
+            })
+            .collect();
+
+        for (position, (bind, value)) in (1..).zip(binds.iter().zip(params)) {
+            let len = match (bind.oracle_type, value) {
+                (oracle_type, Value::String(s) | Value::Clob(s)) if oracle_type.is_national() => {
+                    wire::nchar_len(s)
+                }
+                (_, Value::String(s) | Value::Clob(s)) => s.len(),
+                (_, Value::Bytes(b) | Value::Blob(b)) => b.len(),
+                _ => 0,
+            };
+            if let Some(size) = bind.size.filter(|&size| len > size) {
+                return Err(Error::InvalidBindParameter(format!(
+                    "bind :{} is {} long, more than its declared size {}",
+                    position, len, size
+                )));
+            }
+            let max_raw = crate::constants::MAX_RAW_SIZE_EXTENDED;
+            if bind.oracle_type == OracleType::Raw && len > max_raw {
+                return Err(Error::InvalidBindParameter(format!(
+                    "RAW bind :{} of {} bytes exceeds {}; bind it as a BLOB",
+                    position, len, max_raw
+                )));
+            }
+        }
+
+        if binds.iter().any(|b| b.oracle_type == OracleType::Boolean) {
+            let (context, required) = match Self::parse_statement_type(sql)? {
+                StatementType::PlSql => ("PL/SQL", (12, 1)),
+                _ => ("SQL", (23, 0)),
+            };
+            if self.server_version < required {
+                return Err(Error::UnsupportedFeature(format!(
+                    "BOOLEAN binds in {} need Oracle {}.{} or later, server is {}.{}",
+                    context, required.0, required.1, self.server_version.0, self.server_version.1
+                )));
+            }
+        }
+        // A real implementation sends these in the execute message's
+        // bind metadata
+        self.binds = binds;
+        Ok(())
+    }
+
+    /// Queue session tag changes for the next round trip
+    pub fn set_session_tags(&mut self, tags: SessionTags) {
+        self.pending_tags.merge(tags);
+    }
+
+    /// Queue a CURRENT_SCHEMA change for the next round trip, `None` to
+    /// return to the login schema
+    pub fn set_current_schema(&mut self, schema: Option<String>) {
+        self.pending_schema = Some(schema);
+    }
+
+    /// Schema unqualified names resolve in, once pending changes apply;
+    /// `None` for the login schema
+    pub fn current_schema(&self) -> Option<&str> {
+        match &self.pending_schema {
+            Some(pending) => pending.as_deref(),
+            None => self.current_schema.as_deref(),
+        }
+    }
+
+    /// Session tags as last applied on the server
+    pub fn session_tags(&self) -> &SessionTags {
+        &self.session_tags
+    }
+
+    /// Configuration the session was opened with
+    pub(crate) fn config(&self) -> &ConnectionConfig {
+        &self.config
+    }
+
+    /// Fetched rows held on this connection
+    pub(crate) fn budget(&self) -> &MemoryBudget {
+        &self.budget
+    }
+
+    /// Cursors to close once the connection is free
+    pub(crate) fn abandoned(&self) -> &AbandonedCursors {
+        &self.abandoned
+    }
+
+    /// sqlnet.ora parameters in effect for this session
+    pub fn sqlnet(&self) -> &SqlNetConfig {
+        &self.sqlnet
+    }
+
+    /// Time allowed to establish the session, authentication included
+    pub fn connect_timeout(&self) -> Duration {
+        // As with other clients, the descriptor wins over the profile;
+        // a timeout set on the config in code wins over the profile too
+        self.conn_info
+            .connect_timeout
+            .or(self.config.connect_timeout)
+            .or(self.sqlnet.outbound_connect_timeout)
+            .unwrap_or(crate::constants::DEFAULT_CONNECT_TIMEOUT)
+    }
+
+    /// Time allowed to open the transport to `address`
+    fn transport_connect_timeout(&self, address: &Address) -> Duration {
+        address
+            .transport_connect_timeout
+            .or(self.conn_info.transport_connect_timeout)
+            .or(self.config.transport_connect_timeout)
+            .or(self.sqlnet.transport_connect_timeout)
+            .unwrap_or(crate::constants::DEFAULT_TRANSPORT_CONNECT_TIMEOUT)
+    }
+
+    /// Socket settings for new transports, with `SQLNET.EXPIRE_TIME` as
+    /// the keepalive time when the config sets none
+    fn socket_options(&self) -> SocketOptions {
+        let mut options = self.config.socket_options.clone();
+        options.keepalive = options.keepalive.or(self.sqlnet.expire_time);
+        options
+    }
+
+    /// Open a TCP connection to the first address that answers in time
+    ///
+    /// Each address gets its own transport connect timeout, so a dead
+    /// host fails over to the next one instead of using up the whole
+    /// connect timeout.
+    pub async fn open_transport(&self) -> Result<Transport> {
+        let mut last_error = None;
+        for address in &self.conn_info.addresses {
+            let timeout = self.transport_connect_timeout(address);
+            let error = match crate::runtime::timeout(timeout, self.connect_address(address)).await
+            {
+                Ok(Ok(transport)) => return Ok(transport),
+                Ok(Err(e)) => e,
+                Err(e) => e,
+            };
+            log::debug!("{} unreachable: {}", address, error);
+            last_error = Some(error);
+        }
+        Err(last_error.unwrap_or(Error::ConnectionClosed))
+    }
+
+    /// Open the transport to one address
+    async fn connect_address(&self, address: &Address) -> Result<Transport> {
+        match &address.ipc_key {
+            #[cfg(unix)]
+            Some(key) => {
+                // Listeners name the socket for an IPC key "s" + key
+                let path = self.config.ipc_directory.join(format!("s{}", key));
+                Ok(Transport::Ipc(DefaultRuntime::connect_unix(&path).await?))
+            }
+            #[cfg(not(unix))]
+            Some(_) => Err(Error::UnsupportedFeature(
+                "IPC addresses need Unix domain sockets".into(),
+            )),
+            None => {
+                let options = &self.socket_options();
+                let Some(resolver) = self.config.hooks.custom_resolver() else {
+                    let stream = DefaultRuntime::connect_tcp(&address.host, address.port, options);
+                    return Ok(Transport::Tcp(stream.await?));
+                };
+                let resolved = resolver.resolve(&address.host, address.port).await?;
+                let mut last_error = None;
+                for addr in resolved {
+                    let host = addr.ip().to_string();
+                    match DefaultRuntime::connect_tcp(&host, addr.port(), options).await {
+                        Ok(stream) => return Ok(Transport::Tcp(stream)),
+                        Err(e) => {
+                            log::debug!("{} resolved to {}, unreachable: {}", address, addr, e);
+                            last_error = Some(e);
+                        }
+                    }
+                }
+                Err(match last_error {
+                    Some(e) => e.into(),
+                    None => Error::Connection(format!("resolver found no address for {}", address)),
+                })
+            }
+        }
+    }
+
+    /// Local interface the client reaches the server from, for the
+    /// server to open connections back to
+    ///
+    /// A local IPC listener means the server runs on this host, which it
+    /// reaches over the loopback interface.
+    pub(crate) async fn client_interface(&self) -> Result<IpAddr> {
+        match self.open_transport().await? {
+            Transport::Tcp(stream) => Ok(DefaultRuntime::stream_local_addr(&stream)?.ip()),
+            #[cfg(unix)]
+            Transport::Ipc(_) => Ok(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        }
+    }
+
+    /// Flag held while uncommitted changes may be pending
+    pub(crate) fn transaction_flag(&self) -> Arc<AtomicBool> {
+        self.in_transaction.clone()
+    }
+
+    /// Parameters of the current session, kept up to date across
+    /// reconnects
+    pub(crate) fn shared_session(&self) -> Arc<std::sync::Mutex<Option<SessionParams>>> {
+        self.session.clone()
+    }
+
+    /// Record the parameters of the session now open
+    pub(crate) fn publish_session(&self) {
+        *self.session.lock().unwrap_or_else(|e| e.into_inner()) = self.session_params();
+    }
+
+    /// Flag raised when the session becomes unusable
+    pub(crate) fn broken_flag(&self) -> Arc<AtomicBool> {
+        self.broken.clone()
+    }
+
+    /// Flag raised when the server asks for the session to be released
+    pub(crate) fn draining_flag(&self) -> Arc<AtomicBool> {
+        self.draining.clone()
+    }
+
+    /// Mark the session unusable if `result` failed fatally, and attach
+    /// the session and `sql` to Oracle errors
+    ///
+    /// Successful statements other than queries may leave uncommitted
+    /// changes, so they open a transaction; DDL commits implicitly, and
+    /// so does a `COMMIT` or `ROLLBACK` sent as SQL.
+    fn observe<T>(&mut self, sql: &str, result: Result<T>) -> Result<T> {
+        if result.is_ok() {
+            match Self::parse_statement_type(sql) {
+                Ok(StatementType::Select) => {}
+                Ok(StatementType::Control) => {
+                    let keywords = crate::sql::leading_keywords(sql, 2);
+                    let ends = match keywords.first().map(String::as_str) {
+                        Some("COMMIT") => true,
+                        Some("ROLLBACK") => keywords.get(1).map(String::as_str) != Some("TO"),
+                        _ => false,
+                    };
+                    if ends {
+                        self.in_transaction.store(false, Ordering::Relaxed);
+                    }
+                }
+                Ok(StatementType::Ddl) => {
+                    if self.in_transaction.swap(false, Ordering::Relaxed) {
+                        log::debug!("DDL implicitly committed the open transaction");
+                    }
+                    self.result_cache.invalidate();
+                    self.metadata_cache.invalidate();
+                }
+                _ => {
+                    self.in_transaction.store(true, Ordering::Relaxed);
+                    self.result_cache.invalidate();
+                }
+            }
+        }
+        if std::mem::take(&mut self.commit_next) && result.is_ok() {
+            self.commit_call();
+        }
+        self.annotate(sql, result)
+    }
+
+    /// Mark the session unusable if `result` failed fatally, and attach
+    /// the session and `sql` to Oracle errors
+    fn annotate<T>(&mut self, sql: &str, result: Result<T>) -> Result<T> {
+        result.map_err(|e| {
+            if e.is_fatal() {
+                self.poison(&e);
+            }
+            e.with_context(ErrorContext {
+                session_id: self.session_id,
+                serial_number: self.serial_number,
+                instance_name: self.instance_name.clone(),
+                ..ErrorContext::new(&self.config.connection_string).sql(sql)
+            })
+        })
+    }
+
+    /// Check if uncommitted changes may be pending
+    pub(crate) fn in_transaction(&self) -> bool {
+        self.in_transaction.load(Ordering::Relaxed)
+    }
+
+    /// Replace a lost session with a new one on the same configuration
+    ///
+    /// Cursors of the old session are gone; the broken flag and memory
+    /// budget carry over, the flag cleared.
+    pub(crate) async fn reconnect(&mut self) -> Result<()> {
+        #[cfg(feature = "testing")]
+        let mut fresh = match self.mock.clone() {
+            Some(mock) => Self::mock(&self.config, mock).await?,
+            None => self.new_session().await?,
+        };
+        #[cfg(not(feature = "testing"))]
+        let mut fresh = self.new_session().await?;
+
+        fresh.broken = self.broken.clone();
+        fresh.broken.store(false, Ordering::Relaxed);
+        // The new session may land on an instance that is not draining
+        fresh.draining = self.draining.clone();
+        fresh.draining.store(false, Ordering::Relaxed);
+        // The lost session's transaction went with it
+        fresh.in_transaction = self.in_transaction.clone();
+        fresh.in_transaction.store(false, Ordering::Relaxed);
+        fresh.budget = self.budget.clone();
+        fresh.stats = self.stats;
+        fresh.session = self.session.clone();
+        // Tags and schema ride along with the new session's first call
+        let mut tags = self.session_tags.clone();
+        tags.merge(std::mem::take(&mut self.pending_tags));
+        fresh.pending_tags = tags;
+        fresh.pending_schema = self
+            .pending_schema
+            .take()
+            .or_else(|| self.current_schema.take().map(Some));
+        *self = fresh;
+        self.publish_session();
+        Ok(())
+    }
+
+    /// Open and authenticate another session like this one
+    async fn new_session(&self) -> Result<Self> {
+        let mut fresh = Self::new(&self.config).await?;
+        Authenticator::new(&self.config)
+            .authenticate(&mut fresh)
+            .await?;
+        Ok(fresh)
+    }
+
+    /// Mark the session unusable, logging `reason` the first time
+    fn poison(&self, reason: &dyn std::fmt::Display) {
+        if !self.broken.swap(true, Ordering::Relaxed) {
+            log::warn!("session {:?} is unusable: {}", self.session_id, reason);
+        }
+    }
+
+    /// Round trips made since the session was created
+    pub(crate) fn round_trips(&self) -> u64 {
+        self.stats.round_trips
+    }
+
+    /// Call counters since the connection was opened
+    pub fn stats(&self) -> &ConnectionStats {
+        &self.stats
+    }
+
+    /// Count a statement execution, the bytes it sends and whether its
+    /// cursor came from the statement cache
+    fn record_execute(&mut self, sql: &str, params: &[Value]) {
+        let binds: usize = params.iter().map(value_wire_len).sum();
+        if self.stmt_cache.lookup(sql) {
+            self.stats.cache_hits += 1;
+        } else {
+            self.stats.cache_misses += 1;
+            self.trim_cached_cursors();
+        }
+        self.stats.executes += 1;
+        self.stats.bytes_sent += (sql.len() + binds) as u64;
+    }
+
+    /// Count the bytes of received rows
+    fn record_rows(&mut self, rows: &[Row]) {
+        let bytes: usize = rows.iter().map(Row::wire_len).sum();
+        self.stats.bytes_received += bytes as u64;
+    }
+
+    /// Check the session and piggyback pending session tags on the call
+    ///
+    /// A call abandoned before its response was read leaves the stream
+    /// desynchronized, so the session is poisoned rather than reused.
+    fn begin_round_trip(&mut self) -> Result<()> {
+        if !self.is_connected || self.broken.load(Ordering::Relaxed) {
+            return Err(Error::ConnectionClosed);
+        }
+        let abandoned = self.abandoned.take();
+        if abandoned.interrupt && self.in_call {
+            self.send_break();
+        }
+        for cursor in abandoned.cursors {
+            self.close_cursor(cursor);
+        }
+        if self.in_call {
+            self.poison(&"previous call was abandoned mid-exchange");
+            return Err(Error::ConnectionClosed);
+        }
+        self.in_call = true;
+        self.warning = None;
+
+        #[cfg(feature = "otel")]
+        if let Some(target) = self.config.trace_context {
+            if let Some(tags) = crate::otel::current_tags(target) {
+                self.pending_tags.merge(tags);
+            }
+        }
+
+        // A real implementation encodes these as TTC piggyback messages
+        // ahead of the call itself, so they cost no extra round trip
+        let pending = std::mem::take(&mut self.pending_tags);
+        self.session_tags.merge(pending);
+        // ALTER SESSION SET CURRENT_SCHEMA rides along the same way
+        if let Some(schema) = self.pending_schema.take() {
+            self.current_schema = schema;
+        }
+        // So does the end-of-request marker with the stateless flag set,
+        // which lets a pooled server go back to its pool
+        if std::mem::take(&mut self.pending_stateless) {
+            #[cfg(feature = "testing")]
+            if let Some(mock) = &self.mock {
+                mock.record(crate::testing::MockCall::Stateless);
+            }
+        }
+        self.stats.round_trips += 1;
         Ok(())
     }
 
+    /// Finish the call begun by [`Protocol::begin_round_trip`], poisoning
+    /// the session if it failed partway through the exchange
+    fn end_round_trip<T>(&mut self, result: &Result<T>) {
+        self.in_call = false;
+        if let Err(e) = result {
+            if e.is_fatal() {
+                self.poison(e);
+            }
+        }
+        self.take_inband_notification();
+    }
+
+    /// Consume an in-band notification the server attached to the
+    /// response in place of a warning
+    ///
+    /// The call itself succeeded; the session is flagged so its owner
+    /// replaces it at the next point where no work is in flight, before
+    /// the server closes it.
+    fn take_inband_notification(&mut self) {
+        let code = match &self.warning {
+            Some(w) if w.code == INBAND_SESSION_SHUTDOWN || w.code == INBAND_DRAIN => w.code,
+            _ => return,
+        };
+        let notice = self.warning.take().expect("warning checked above");
+        if !self.draining.swap(true, Ordering::Relaxed) {
+            log::info!(
+                "session {:?} on {:?} asked to drain (ORA-{:05}): {}",
+                self.session_id,
+                self.instance_name,
+                code,
+                notice.message
+            );
+        }
+    }
+
     /// Execute a SQL statement
     pub async fn execute(
         &mut self,
         sql: &str,
         params: &[Value],
     ) -> Result<(Vec<Row>, Vec<ColumnInfo>)> {
-        if !self.is_connected {
-            return Err(Error::ConnectionClosed);
+        self.check_binds(sql, params)?;
+        self.begin_round_trip()?;
+        self.record_execute(sql, params);
+        let result = self.execute_call(sql, params).await;
+        if let Ok((rows, _)) = &result {
+            self.record_rows(rows);
+        }
+        self.end_round_trip(&result);
+        self.observe(sql, result)
+    }
+
+    /// Send several statements in one round trip, results in order
+    ///
+    /// Each statement succeeds or fails on its own; the outer error is for
+    /// the session as a whole.
+    pub(crate) async fn execute_pipeline(
+        &mut self,
+        ops: &[PipelineOp],
+    ) -> Result<Vec<Result<PipelineOutcome>>> {
+        // 23ai pipeline mode: every request goes out before the first
+        // response is read, so the batch costs a single round trip and
+        // each statement is timed from the start until its response
+        let stopwatch = Stopwatch::start(self);
+        self.begin_round_trip()?;
+
+        let mut outcomes = Vec::with_capacity(ops.len());
+        for op in ops {
+            let outcome = match op {
+                PipelineOp::Execute { sql, params, types } => {
+                    self.define_binds(types.clone());
+                    let outcome = self.pipelined(sql, params).await;
+                    let outcome = self.observe(sql, outcome);
+                    let rows = outcome.as_ref().map(|outcome| match outcome {
+                        PipelineOutcome::Rows(rows) => rows.len() as u64,
+                        PipelineOutcome::RowCount(count) => *count,
+                        PipelineOutcome::Completed => 0,
+                    });
+                    stopwatch.finish(self, sql, params, rows);
+                    outcome
+                }
+                PipelineOp::Commit => {
+                    self.commit_call();
+                    Ok(PipelineOutcome::Completed)
+                }
+            };
+            outcomes.push(outcome);
+        }
+        self.end_round_trip(&Ok(()));
+        Ok(outcomes)
+    }
+
+    /// One statement of a pipeline
+    async fn pipelined(&mut self, sql: &str, params: &[Value]) -> Result<PipelineOutcome> {
+        self.check_binds(sql, params)?;
+        self.record_execute(sql, params);
+        match Self::parse_statement_type(sql)? {
+            t if t.is_dml() => {
+                let count = self.execute_dml_call(sql, params).await?;
+                Ok(PipelineOutcome::RowCount(count))
+            }
+            StatementType::PlSql => {
+                self.execute_call(sql, params).await?;
+                Ok(PipelineOutcome::Completed)
+            }
+            _ => {
+                let (rows, metadata) = self.execute_call(sql, params).await?;
+                self.record_rows(&rows);
+                Ok(PipelineOutcome::Rows(ResultSet::new(rows, metadata)))
+            }
         }
+    }
 
+    /// Execute a SQL statement within the current round trip
+    async fn execute_call(
+        &mut self,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<(Vec<Row>, Vec<ColumnInfo>)> {
         // Parse SQL to determine statement type
         let stmt_type = Self::parse_statement_type(sql)?;
 
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            let result = mock.execute(sql, params, self.lob_prefetch());
+            self.warning = mock.take_warning();
+            return result;
+        }
+
         match stmt_type {
             StatementType::Select => self.execute_query(sql, params).await,
-            StatementType::Insert | StatementType::Update | StatementType::Delete => {
-                let _count = self.execute_dml(sql, params).await?;
+            t if t.is_dml() || t == StatementType::Explain => {
+                let _count = self.execute_dml_call(sql, params).await?;
                 // Return empty result set with row count in metadata
                 Ok((vec![], vec![]))
             }
             StatementType::PlSql => self.execute_plsql(sql, params).await,
+            StatementType::Ddl => {
+                // Sent as a plain execute; the server commits before and
+                // after the statement
+                self.execute_dml_call(sql, params).await?;
+                Ok((vec![], vec![]))
+            }
+            StatementType::Control => {
+                // Session and transaction control is a plain execute too,
+                // without DDL's implicit commits
+                self.execute_dml_call(sql, params).await?;
+                Ok((vec![], vec![]))
+            }
             _ => Err(Error::NotImplemented(format!(
                 "Statement type {:?} not implemented",
                 stmt_type
@@ -120,10 +1072,28 @@ impl Protocol {
         }
     }
 
+    /// Reject DDL the server would refuse before it commits anything
+    fn check_ddl(&self, params: &[Value]) -> Result<()> {
+        if !params.is_empty() {
+            return Err(Error::oracle(
+                1027,
+                "bind variables not allowed for data definition operations",
+            ));
+        }
+        if self.branch.is_some() {
+            // The implicit commit would end the global transaction's branch
+            return Err(Error::oracle(
+                2089,
+                "COMMIT is not allowed in a subordinate session",
+            ));
+        }
+        Ok(())
+    }
+
     /// Execute a query and return results
     async fn execute_query(
         &mut self,
-        _sql: &str,
+        sql: &str,
         _params: &[Value],
     ) -> Result<(Vec<Row>, Vec<ColumnInfo>)> {
         // Mock implementation - real version would:
@@ -132,7 +1102,7 @@ impl Protocol {
         // 3. Fetch rows
         // 4. Parse and convert data
 
-        let metadata = vec![
+        let mut metadata = vec![
             ColumnInfo {
                 name: "ID".to_string(),
                 oracle_type: OracleType::Number,
@@ -140,6 +1110,8 @@ impl Protocol {
                 precision: Some(10),
                 scale: Some(0),
                 nullable: false,
+                encrypted: false,
+                redacted: false,
             },
             ColumnInfo {
                 name: "NAME".to_string(),
@@ -148,27 +1120,361 @@ impl Protocol {
                 precision: None,
                 scale: None,
                 nullable: true,
+                encrypted: false,
+                redacted: false,
             },
         ];
+        // Protection flags arrive with each column's describe information
+        for (column, flags) in metadata.iter_mut().zip([0, 0]) {
+            apply_describe_flags(column, flags);
+        }
+
+        // Mock fetch response: every row of the batch in one buffer
+        let mut scratch = self.buffers.get();
+        wire::write_value(&mut scratch, Some(&wire::encode_number(1)));
+        wire::write_value(&mut scratch, Some(b"Test"));
+        let buffer = scratch.split().freeze();
 
-        let rows = vec![Row::new(
-            vec![Value::Integer(1), Value::String("Test".to_string())],
-            vec!["ID".to_string(), "NAME".to_string()],
-        )];
+        let types: Vec<OracleType> = metadata.iter().map(|c| c.oracle_type).collect();
+        let columns = Arc::new(ColumnIndex::from_columns(&metadata).with_sql(sql));
+        let mut rows = Vec::new();
+        let mut offset = 0;
+        while offset < buffer.len() {
+            let mut raw =
+                wire::read_row_limited(&buffer, &mut offset, &types, self.config.max_long_size)?;
+            if self.national_charset != NationalCharset::Al16Utf16 {
+                for value in &mut raw {
+                    if matches!(
+                        value.oracle_type(),
+                        OracleType::NVarchar2 | OracleType::NChar
+                    ) {
+                        *value = value.clone().with_national_charset(self.national_charset);
+                    }
+                }
+            }
+            if self.config.number_format != NumberFormat::Native {
+                for value in &mut raw {
+                    if value.oracle_type() == OracleType::Number {
+                        *value = value.clone().with_number_format(self.config.number_format);
+                    }
+                }
+            }
+            #[cfg(feature = "chrono-tz")]
+            if let Some(regions) = &self.config.time_zone_regions {
+                for value in &mut raw {
+                    if value.oracle_type() == OracleType::TimestampTz {
+                        *value = value.clone().with_time_zone_regions(regions.clone());
+                    }
+                }
+            }
+            if self.lob_prefetch().is_some() {
+                // LOB columns were defined for locators, each sent with
+                // its length, chunk size and up to the prefetch length
+                for value in &mut raw {
+                    if matches!(
+                        value.oracle_type(),
+                        OracleType::Clob | OracleType::NClob | OracleType::Blob | OracleType::BFile
+                    ) {
+                        *value = RawValue::locator(value.oracle_type(), value.bytes().cloned());
+                    }
+                }
+            }
+            rows.push(Row::from_raw(raw, columns.clone()));
+        }
 
         Ok((rows, metadata))
     }
 
+    /// Prefetch length the define of each LOB column asks for, in bytes,
+    /// or `None` to fetch LOBs whole instead of as locators
+    ///
+    /// A real implementation sends it with the LOB columns' defines; the
+    /// server then sends at most that much data along with each locator.
+    fn lob_prefetch(&self) -> Option<u32> {
+        (!self.config.fetch_lobs).then_some(self.config.lob_prefetch_size)
+    }
+
     /// Execute DML statement
-    pub async fn execute_dml(&mut self, _sql: &str, _params: &[Value]) -> Result<u64> {
-        if !self.is_connected {
-            return Err(Error::ConnectionClosed);
+    pub async fn execute_dml(&mut self, sql: &str, params: &[Value]) -> Result<u64> {
+        self.check_binds(sql, params)?;
+        self.begin_round_trip()?;
+        self.record_execute(sql, params);
+        let result = self.execute_dml_call(sql, params).await;
+        self.end_round_trip(&result);
+        self.observe(sql, result)
+    }
+
+    /// Execute DML once per row of `rows` in a single round trip,
+    /// returning each row's count
+    ///
+    /// Types declared with [`Protocol::define_binds`] apply to every row.
+    pub async fn execute_batch(&mut self, sql: &str, rows: &[Vec<Value>]) -> Result<Vec<u64>> {
+        let declared = std::mem::take(&mut self.pending_binds);
+        for row in rows {
+            self.pending_binds = declared.clone();
+            self.check_binds(sql, row)?;
+        }
+        self.begin_round_trip()?;
+        // Array DML: one execute carries the binds of every row
+        self.record_execute(sql, &[]);
+        let binds: usize = rows.iter().flatten().map(value_wire_len).sum();
+        self.stats.bytes_sent += binds as u64;
+
+        let mut counts = Vec::with_capacity(rows.len());
+        let mut result = Ok(());
+        for row in rows {
+            match self.execute_dml_call(sql, row).await {
+                Ok(count) => counts.push(count),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        self.end_round_trip(&result);
+        self.observe(sql, result.map(|()| counts))
+    }
+
+    /// Execute a PL/SQL call, returning its implicit result sets, the
+    /// values of the OUT binds at positions `outs`, and its row count
+    pub(crate) async fn execute_multi(
+        &mut self,
+        sql: &str,
+        params: &[Value],
+        outs: &[usize],
+    ) -> Result<CallResults> {
+        self.check_binds(sql, params)?;
+        self.begin_round_trip()?;
+        self.record_execute(sql, params);
+        let result = self.execute_multi_call(sql, params, outs);
+        if let Ok(results) = &result {
+            for (rows, _) in &results.result_sets {
+                self.record_rows(rows);
+            }
+        }
+        self.end_round_trip(&result);
+        self.observe(sql, result)
+    }
+
+    /// Execute a PL/SQL call within the current round trip
+    fn execute_multi_call(
+        &mut self,
+        _sql: &str,
+        _params: &[Value],
+        outs: &[usize],
+    ) -> Result<CallResults> {
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            let result = mock.execute_multi(_sql, _params, outs.len());
+            self.warning = mock.take_warning();
+            return result;
+        }
+
+        // A real implementation defines the OUT binds, reads their values
+        // from the call's response, then opens a cursor per implicit
+        // result (DBMS_SQL.RETURN_RESULT) and fetches it
+        Ok(CallResults {
+            result_sets: Vec::new(),
+            out_values: vec![Value::Null; outs.len()],
+            rows_affected: 1,
+        })
+    }
+
+    /// Execute DML within the current round trip
+    async fn execute_dml_call(&mut self, _sql: &str, _params: &[Value]) -> Result<u64> {
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            let result = mock.execute_dml(_sql, _params);
+            self.warning = mock.take_warning();
+            return result;
         }
 
         // Mock implementation - returns affected row count
         Ok(1)
     }
 
+    /// Execute a query and keep its cursor open for fetching
+    pub async fn open_cursor(
+        &mut self,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<(u32, Vec<ColumnInfo>)> {
+        // Mock implementation - the server would hold the remaining rows,
+        // so they count as received only once fetched
+        self.check_binds(sql, params)?;
+        self.reserve_cursor()?;
+        self.begin_round_trip()?;
+        self.record_execute(sql, params);
+        let result = self.execute_call(sql, params).await;
+        self.end_round_trip(&result);
+        let (rows, metadata) = self.observe(sql, result)?;
+        let cursor = self.next_cursor;
+        self.next_cursor = self.next_cursor.wrapping_add(1).max(1);
+        self.cursors.insert(
+            cursor,
+            OpenCursor {
+                sql: sql.to_string(),
+                pending: rows.into(),
+            },
+        );
+        self.trim_cached_cursors();
+        Ok((cursor, metadata))
+    }
+
+    /// Server cursors held open: cursors being fetched from and cached
+    /// statements
+    pub fn open_cursors(&self) -> usize {
+        self.cursors.len() + self.stmt_cache.len()
+    }
+
+    /// Make room for one more cursor under `max_open_cursors`, closing
+    /// cached statements if allowed
+    fn reserve_cursor(&mut self) -> Result<()> {
+        let limit = self.config.max_open_cursors;
+        if limit == 0 {
+            return Ok(());
+        }
+        while self.open_cursors() >= limit {
+            match self.evict_cached_cursor() {
+                Some(_) => {}
+                None => {
+                    let mut open: Vec<(&u32, &OpenCursor)> = self.cursors.iter().collect();
+                    open.sort_by_key(|(id, _)| **id);
+                    return Err(Error::TooManyOpenCursors {
+                        limit,
+                        sql: open.into_iter().map(|(_, c)| c.sql.clone()).collect(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Close cached statements while more than `max_open_cursors` are
+    /// open, if allowed
+    fn trim_cached_cursors(&mut self) {
+        let limit = self.config.max_open_cursors;
+        while limit > 0 && self.open_cursors() > limit {
+            if self.evict_cached_cursor().is_none() {
+                break;
+            }
+        }
+    }
+
+    /// Close the least recently used cached statement, if allowed
+    fn evict_cached_cursor(&mut self) -> Option<String> {
+        if !self.config.close_cached_cursors {
+            return None;
+        }
+        let sql = self.stmt_cache.evict_oldest()?;
+        log::debug!(
+            "closed cached cursor to stay under the cursor limit: {}",
+            sql
+        );
+        Some(sql)
+    }
+
+    /// Fetch up to `array_size` rows from an open cursor
+    ///
+    /// Returns the rows and whether more remain; the server closes the
+    /// cursor once it is exhausted.
+    pub async fn fetch(&mut self, cursor: u32, array_size: usize) -> Result<(Vec<Row>, bool)> {
+        self.begin_round_trip()?;
+        self.stats.fetches += 1;
+        let result = self.fetch_call(cursor, array_size);
+        if let Ok((rows, _)) = &result {
+            self.record_rows(rows);
+        }
+        self.end_round_trip(&result);
+        result
+    }
+
+    /// Fetch from a cursor within the current round trip
+    fn fetch_call(&mut self, cursor: u32, array_size: usize) -> Result<(Vec<Row>, bool)> {
+        let pending = &mut self
+            .cursors
+            .get_mut(&cursor)
+            .ok_or_else(|| Error::oracle(1001, "invalid cursor"))?
+            .pending;
+        let count = array_size.max(1).min(pending.len());
+        let rows: Vec<Row> = pending.drain(..count).collect();
+        let more = !pending.is_empty();
+        if !more {
+            self.cursors.remove(&cursor);
+        }
+        Ok((rows, more))
+    }
+
+    /// Close a cursor before it is exhausted
+    pub fn close_cursor(&mut self, cursor: u32) {
+        // A real implementation queues this as a piggyback on the next call
+        self.cursors.remove(&cursor);
+    }
+
+    /// Length of a LOB, in characters for CLOB and NCLOB
+    pub async fn lob_length(&mut self, lob: &Lob) -> Result<u64> {
+        self.begin_round_trip()?;
+        let result = self.lob_call(lob, LobOp::Length);
+        self.end_round_trip(&result);
+        result.map(|(len, _)| len)
+    }
+
+    /// Read `amount` characters or bytes of a LOB from `offset`
+    pub async fn lob_read(&mut self, lob: &Lob, offset: u64, amount: u64) -> Result<Vec<u8>> {
+        self.begin_round_trip()?;
+        let result = self.lob_call(lob, LobOp::Read { offset, amount });
+        if let Ok((_, data)) = &result {
+            self.stats.bytes_received += data.len() as u64;
+        }
+        self.end_round_trip(&result);
+        result.map(|(_, data)| data)
+    }
+
+    /// Write `data` into a LOB from `offset`, returning its new length
+    pub async fn lob_write(&mut self, lob: &Lob, offset: u64, data: &[u8]) -> Result<u64> {
+        self.begin_round_trip()?;
+        self.stats.bytes_sent += data.len() as u64;
+        let result = self.lob_call(lob, LobOp::Write { offset, data });
+        self.end_lob_change(&result);
+        result.map(|(len, _)| len)
+    }
+
+    /// Cut a LOB down to `len` characters or bytes
+    pub async fn lob_trim(&mut self, lob: &Lob, len: u64) -> Result<()> {
+        self.begin_round_trip()?;
+        let result = self.lob_call(lob, LobOp::Trim(len));
+        self.end_lob_change(&result);
+        result.map(|_| ())
+    }
+
+    /// Finish a round trip that changed a LOB, which like DML leaves a
+    /// transaction open
+    fn end_lob_change<T>(&mut self, result: &Result<T>) {
+        if result.is_ok() {
+            self.in_transaction.store(true, Ordering::Relaxed);
+        }
+        self.end_round_trip(result);
+    }
+
+    /// LOB operation within the current round trip, returning the LOB's
+    /// length after it and any data read
+    fn lob_call(&mut self, lob: &Lob, op: LobOp<'_>) -> Result<(u64, Vec<u8>)> {
+        if lob.oracle_type() == OracleType::BFile
+            && matches!(op, LobOp::Write { .. } | LobOp::Trim(_))
+        {
+            return Err(Error::Lob("BFILEs are read-only".into()));
+        }
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            return mock.lob_call(lob, op);
+        }
+
+        // A real implementation sends a LOB operation message carrying
+        // the locator
+        let _ = (lob, op);
+        Err(Error::NotImplemented("LOB operations".into()))
+    }
+
     /// Execute PL/SQL block
     async fn execute_plsql(
         &mut self,
@@ -179,39 +1485,221 @@ impl Protocol {
         Ok((vec![], vec![]))
     }
 
+    /// Columns `sql` returns, from a parse-only round trip that neither
+    /// executes the statement nor opens a transaction
+    ///
+    /// Descriptions are kept in the metadata cache when it is enabled.
+    pub async fn describe(&mut self, sql: &str) -> Result<Vec<ColumnInfo>> {
+        let schema = self.current_schema().map(str::to_string);
+        if let Some(columns) = self.metadata_cache.get(schema.as_deref(), sql) {
+            self.stats.metadata_cache_hits += 1;
+            return Ok(columns);
+        }
+        Self::parse_statement_type(sql)?;
+        self.begin_round_trip()?;
+        self.stats.bytes_sent += sql.len() as u64;
+        let result = self.describe_call(sql);
+        self.end_round_trip(&result);
+        let columns = self.annotate(sql, result)?;
+        self.metadata_cache.insert(schema.as_deref(), sql, &columns);
+        Ok(columns)
+    }
+
     /// Get statement metadata without execution
+    #[deprecated(note = "use `describe`")]
     pub async fn get_metadata(&mut self, sql: &str) -> Result<Vec<ColumnInfo>> {
-        let (_rows, metadata) = self.execute(sql, &[]).await?;
-        Ok(metadata)
+        self.describe(sql).await
+    }
+
+    /// Describe `sql` within the current round trip
+    fn describe_call(&mut self, _sql: &str) -> Result<Vec<ColumnInfo>> {
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            return mock.describe(_sql);
+        }
+
+        // Send EXECUTE with only the parse and describe options set, and
+        // read the column descriptions from the response
+        Ok(Vec::new())
     }
 
     /// Commit transaction
     pub async fn commit(&mut self) -> Result<()> {
-        if !self.is_connected {
-            return Err(Error::ConnectionClosed);
+        if self.branch.is_some() {
+            return Err(Error::oracle(
+                2089,
+                "COMMIT is not allowed in a subordinate session",
+            ));
+        }
+        self.begin_round_trip()?;
+        self.commit_call();
+        self.end_round_trip(&Ok(()));
+        Ok(())
+    }
+
+    /// Commit within the current round trip
+    fn commit_call(&mut self) {
+        self.in_transaction.store(false, Ordering::Relaxed);
+        self.stats.commits += 1;
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            mock.record(crate::testing::MockCall::Commit);
         }
 
         // Send COMMIT packet
+    }
+
+    /// Start global transaction branch `xid`
+    pub async fn tpc_begin(&mut self, xid: &Xid) -> Result<()> {
+        if self.in_transaction() || self.branch.is_some() {
+            return Err(Error::oracle(24776, "cannot start a new transaction"));
+        }
+        self.begin_round_trip()?;
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            mock.record(crate::testing::MockCall::TpcBegin(xid.clone()));
+        }
+
+        // Send TPC_TXN_SWITCH with the start operation
+        self.branch = Some(xid.clone());
+        self.end_round_trip(&Ok(()));
+        Ok(())
+    }
+
+    /// Detach from branch `xid`, leaving it to be prepared or resolved
+    pub async fn tpc_end(&mut self, xid: &Xid) -> Result<()> {
+        if self.branch.as_ref() != Some(xid) {
+            return Err(Error::oracle(24756, "transaction does not exist"));
+        }
+        self.begin_round_trip()?;
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            mock.record(crate::testing::MockCall::TpcEnd(xid.clone()));
+        }
+
+        // Send TPC_TXN_SWITCH with the detach operation
+        self.branch = None;
+        self.detached.insert(xid.clone(), self.in_transaction());
+        self.in_transaction.store(false, Ordering::Relaxed);
+        self.end_round_trip(&Ok(()));
+        Ok(())
+    }
+
+    /// Prepare branch `xid`; a branch that changed nothing is finished
+    /// there and then
+    pub async fn tpc_prepare(&mut self, xid: &Xid) -> Result<Vote> {
+        self.begin_round_trip()?;
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            mock.record(crate::testing::MockCall::TpcPrepare(xid.clone()));
+        }
+
+        // Send TPC_TXN_CHANGE_STATE with the prepare operation; branches
+        // of other sessions are prepared as the server finds them
+        let vote = match self.detached.get(xid) {
+            Some(false) => {
+                self.detached.remove(xid);
+                Vote::ReadOnly
+            }
+            _ => Vote::Commit,
+        };
+        self.end_round_trip(&Ok(()));
+        Ok(vote)
+    }
+
+    /// Commit branch `xid`, with `one_phase` skipping the prepare
+    pub async fn tpc_commit(&mut self, xid: &Xid, one_phase: bool) -> Result<()> {
+        self.begin_round_trip()?;
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            mock.record(crate::testing::MockCall::TpcCommit {
+                xid: xid.clone(),
+                one_phase,
+            });
+        }
+
+        // Send TPC_TXN_CHANGE_STATE with the commit operation, or the
+        // one-phase commit for a branch that was never prepared
+        log::debug!("committing {:?} (one phase: {})", xid, one_phase);
+        self.detached.remove(xid);
+        self.stats.commits += 1;
+        self.end_round_trip(&Ok(()));
+        Ok(())
+    }
+
+    /// Roll back branch `xid`
+    pub async fn tpc_rollback(&mut self, xid: &Xid) -> Result<()> {
+        self.begin_round_trip()?;
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            mock.record(crate::testing::MockCall::TpcRollback(xid.clone()));
+        }
+
+        // Send TPC_TXN_CHANGE_STATE with the abort operation
+        if self.branch.as_ref() == Some(xid) {
+            self.branch = None;
+            self.in_transaction.store(false, Ordering::Relaxed);
+        }
+        self.detached.remove(xid);
+        self.stats.rollbacks += 1;
+        self.end_round_trip(&Ok(()));
+        Ok(())
+    }
+
+    /// Roll back and clear session state so the next user starts clean
+    pub async fn reset_session(&mut self) -> Result<()> {
+        self.begin_round_trip()?;
+
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            mock.record(crate::testing::MockCall::ResetSession);
+        }
+
+        // Send ROLLBACK with the session-state reset piggyback, which 23ai
+        // uses to drop PL/SQL package state; older servers are sent
+        // DBMS_SESSION.RESET_PACKAGE in the same round trip
+        self.in_transaction.store(false, Ordering::Relaxed);
+        self.cursors.clear();
+        self.current_schema = None;
+        self.end_round_trip(&Ok(()));
         Ok(())
     }
 
+    /// Interrupt the call in progress after its caller gave up on it
+    pub fn send_break(&mut self) {
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            mock.record(crate::testing::MockCall::Break);
+        }
+
+        // Send an out-of-band BREAK marker and read the server's RESET
+        // so the session is left ready for the next call
+        self.in_call = false;
+    }
+
     /// Rollback transaction
     pub async fn rollback(&mut self) -> Result<()> {
-        if !self.is_connected {
-            return Err(Error::ConnectionClosed);
+        self.begin_round_trip()?;
+
+        #[cfg(feature = "testing")]
+        if let Some(mock) = &self.mock {
+            mock.record(crate::testing::MockCall::Rollback);
         }
 
         // Send ROLLBACK packet
+        self.in_transaction.store(false, Ordering::Relaxed);
+        self.commit_next = false;
+        self.stats.rollbacks += 1;
+        self.end_round_trip(&Ok(()));
         Ok(())
     }
 
     /// Ping database to check connection
     pub async fn ping(&mut self) -> Result<()> {
-        if !self.is_connected {
-            return Err(Error::ConnectionClosed);
-        }
+        self.begin_round_trip()?;
 
         // Send PING packet or simple SELECT
+        self.end_round_trip(&Ok(()));
         Ok(())
     }
 
@@ -222,58 +1710,221 @@ impl Protocol {
         }
 
         // Send LOGOFF packet
+        let (reused, allocated) = self.buffers.stats();
+        log::debug!(
+            "session {:?} closed; buffers reused {} times, allocated {}",
+            self.session_id,
+            reused,
+            allocated
+        );
         self.is_connected = false;
         self.session_id = None;
         Ok(())
     }
 
     /// Parse SQL statement to determine type
-    fn parse_statement_type(sql: &str) -> Result<StatementType> {
-        let trimmed = sql.trim().to_uppercase();
-
-        if trimmed.starts_with("SELECT") || trimmed.starts_with("WITH") {
-            Ok(StatementType::Select)
-        } else if trimmed.starts_with("INSERT") {
-            Ok(StatementType::Insert)
-        } else if trimmed.starts_with("UPDATE") {
-            Ok(StatementType::Update)
-        } else if trimmed.starts_with("DELETE") {
-            Ok(StatementType::Delete)
-        } else if trimmed.starts_with("BEGIN") || trimmed.starts_with("DECLARE") {
-            Ok(StatementType::PlSql)
-        } else if trimmed.starts_with("CREATE")
-            || trimmed.starts_with("ALTER")
-            || trimmed.starts_with("DROP")
-        {
-            Ok(StatementType::Ddl)
-        } else {
-            Ok(StatementType::Unknown)
-        }
+    ///
+    /// Classified by its leading keywords, after any comments and hints.
+    pub(crate) fn parse_statement_type(sql: &str) -> Result<StatementType> {
+        let keywords = crate::sql::leading_keywords(sql, 2);
+        let first = keywords.first().map_or("", String::as_str);
+        let second = keywords.get(1).map_or("", String::as_str);
+        Ok(match first {
+            "SELECT" | "WITH" => StatementType::Select,
+            "INSERT" => StatementType::Insert,
+            "UPDATE" => StatementType::Update,
+            "DELETE" => StatementType::Delete,
+            "MERGE" => StatementType::Merge,
+            "BEGIN" | "DECLARE" | "CALL" => StatementType::PlSql,
+            "EXPLAIN" => StatementType::Explain,
+            "ALTER" if matches!(second, "SESSION" | "SYSTEM") => StatementType::Control,
+            "COMMIT" | "ROLLBACK" | "SAVEPOINT" | "SET" => StatementType::Control,
+            "CREATE" | "ALTER" | "DROP" | "TRUNCATE" | "RENAME" | "COMMENT" | "GRANT"
+            | "REVOKE" | "ANALYZE" | "AUDIT" | "NOAUDIT" | "PURGE" | "FLASHBACK" | "ASSOCIATE"
+            | "DISASSOCIATE" => StatementType::Ddl,
+            _ => StatementType::Unknown,
+        })
     }
 }
 
+/// Record the protection `flags` of a column's describe information
+///
+/// Redaction can replace values with NULL whatever the column's
+/// constraints, so a redacted column is reported as nullable and typed
+/// mappings fetch it as an `Option`.
+fn apply_describe_flags(column: &mut ColumnInfo, flags: u32) {
+    column.encrypted = flags & DESCRIBE_ENCRYPTED != 0;
+    column.redacted = flags & DESCRIBE_REDACTED != 0;
+    if column.redacted {
+        column.nullable = true;
+    }
+}
+
+/// Split an Easy Connect `host[:port]`, where an IPv6 host is bracketed
+fn split_host_port(address: &str) -> Result<(&str, Option<&str>)> {
+    if let Some(rest) = address.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']').ok_or_else(|| {
+            Error::InvalidConfiguration(format!("Unclosed '[' in address: {}", address))
+        })?;
+        return match rest {
+            "" => Ok((host, None)),
+            _ => match rest.strip_prefix(':') {
+                Some(port) => Ok((host, Some(port))),
+                None => Err(Error::InvalidConfiguration(format!(
+                    "Invalid address: {}",
+                    address
+                ))),
+            },
+        };
+    }
+    match address.split_once(':') {
+        Some((_, port)) if port.contains(':') => Err(Error::InvalidConfiguration(format!(
+            "IPv6 addresses must be bracketed, as in [{}]",
+            address
+        ))),
+        Some((host, port)) => Ok((host, Some(port))),
+        None => Ok((address, None)),
+    }
+}
+
+/// Timeout parameter `key` of a descriptor, in seconds unless a unit follows
+fn descriptor_timeout(descriptor: &str, key: &str) -> Result<Option<Duration>> {
+    descriptor_value(descriptor, key)
+        .map(|value| {
+            crate::sqlnet::parse_timeout(&value)
+                .ok_or_else(|| Error::InvalidConfiguration(format!("Invalid {}: {}", key, value)))
+        })
+        .transpose()
+}
+
+/// Cursor open for fetching
+#[derive(Debug)]
+struct OpenCursor {
+    /// Statement that opened it
+    sql: String,
+    /// Rows the server has yet to send
+    pending: VecDeque<Row>,
+}
+
 /// Connection information parsed from connection string
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 struct ConnectionInfo {
-    host: String,
-    port: u16,
+    /// Addresses in failover order
+    addresses: Vec<Address>,
     service_name: String,
     sid: Option<String>,
+    /// `SERVER=POOLED` asks for a DRCP pooled server
+    pooled: bool,
+    /// `CONNECT_TIMEOUT` of the descriptor
+    connect_timeout: Option<Duration>,
+    /// `TRANSPORT_CONNECT_TIMEOUT` of the descriptor
+    transport_connect_timeout: Option<Duration>,
+}
+
+impl ConnectionInfo {
+    /// `CONNECT_DATA` of the connect packet, asking a pooled server for
+    /// the configured DRCP class, purity and implicit pooling boundary
+    fn connect_data(&self, config: &ConnectionConfig) -> String {
+        let mut out = match &self.sid {
+            Some(sid) => format!("(SID={})", descriptor::quote(sid)),
+            None => format!("(SERVICE_NAME={})", descriptor::quote(&self.service_name)),
+        };
+        if !self.pooled {
+            return out;
+        }
+        out.push_str("(SERVER=POOLED)");
+        if let Some(class) = &config.connection_class {
+            out.push_str(&format!(
+                "(POOL_CONNECTION_CLASS={})",
+                descriptor::quote(class)
+            ));
+        }
+        match config.purity {
+            Purity::Default => {}
+            Purity::New => out.push_str("(POOL_PURITY=NEW)"),
+            Purity::Reuse => out.push_str("(POOL_PURITY=SELF)"),
+        }
+        match config.pool_boundary {
+            None => {}
+            Some(PoolBoundary::Statement) => out.push_str("(POOL_BOUNDARY=STATEMENT)"),
+            Some(PoolBoundary::Transaction) => out.push_str("(POOL_BOUNDARY=TRANSACTION)"),
+        }
+        out
+    }
+
+    /// Public view of where `user` is connecting, for the pre-connect hook
+    fn target(&self, user: &str) -> ConnectTarget {
+        let addresses = self
+            .addresses
+            .iter()
+            .map(|address| match &address.ipc_key {
+                Some(key) => TargetAddress::Ipc { key: key.clone() },
+                None => TargetAddress::Tcp {
+                    host: address.host.clone(),
+                    port: address.port,
+                },
+            })
+            .collect();
+        ConnectTarget {
+            user: user.to_string(),
+            addresses,
+            service_name: self.service_name.clone(),
+            sid: self.sid.clone(),
+            pooled: self.pooled,
+        }
+    }
+}
+
+/// One listener address of a connection string
+#[derive(Debug, Clone)]
+struct Address {
+    host: String,
+    port: u16,
+    /// `KEY` of an IPC address, which has no host or port
+    ipc_key: Option<String>,
+    /// `TRANSPORT_CONNECT_TIMEOUT` given for this address
+    transport_connect_timeout: Option<Duration>,
+}
+
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.ipc_key {
+            Some(key) => write!(f, "IPC key {}", key),
+            None => write!(f, "{}:{}", self.host, self.port),
+        }
+    }
 }
 
 /// SQL statement types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum StatementType {
+pub(crate) enum StatementType {
     Select,
     Insert,
     Update,
     Delete,
+    Merge,
+    /// PL/SQL block or `CALL`
     PlSql,
+    /// `EXPLAIN PLAN`
+    Explain,
+    /// Commits implicitly
     Ddl,
+    /// Transaction, session and system control, which commit nothing
+    Control,
     Unknown,
 }
 
+impl StatementType {
+    /// Whether the statement changes rows and reports how many
+    pub(crate) fn is_dml(self) -> bool {
+        matches!(
+            self,
+            Self::Insert | Self::Update | Self::Delete | Self::Merge
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,17 +1932,579 @@ mod tests {
     #[test]
     fn test_parse_connection_string() {
         let info = Protocol::parse_connection_string("localhost:1521/XEPDB1").unwrap();
-        assert_eq!(info.host, "localhost");
-        assert_eq!(info.port, 1521);
+        assert_eq!(info.addresses[0].host, "localhost");
+        assert_eq!(info.addresses[0].port, 1521);
         assert_eq!(info.service_name, "XEPDB1");
     }
 
+    #[test]
+    fn test_connect_data() {
+        use crate::connection::{PoolBoundary, Purity};
+
+        let config = ConnectionConfig::new("db:1521/sales:POOLED", "app", "pass")
+            .purity(Purity::Reuse)
+            .connection_class("reports")
+            .pool_boundary(PoolBoundary::Statement);
+        let info = Protocol::parse_connection_string(&config.connection_string).unwrap();
+        assert_eq!(
+            info.connect_data(&config),
+            "(SERVICE_NAME=sales)(SERVER=POOLED)(POOL_CONNECTION_CLASS=reports)\
+             (POOL_PURITY=SELF)(POOL_BOUNDARY=STATEMENT)"
+        );
+
+        // Only a pooled server is asked for them
+        let info = Protocol::parse_connection_string("db:1521/sales").unwrap();
+        assert_eq!(info.connect_data(&config), "(SERVICE_NAME=sales)");
+    }
+
     #[test]
     fn test_parse_connection_string_no_port() {
         let info = Protocol::parse_connection_string("localhost/XEPDB1").unwrap();
-        assert_eq!(info.host, "localhost");
-        assert_eq!(info.port, 1521);
+        assert_eq!(info.addresses[0].host, "localhost");
+        assert_eq!(info.addresses[0].port, 1521);
+        assert_eq!(info.service_name, "XEPDB1");
+    }
+
+    #[test]
+    fn test_parse_ipv6_address() {
+        let info = Protocol::parse_connection_string("[::1]:1522/XEPDB1").unwrap();
+        assert_eq!(info.addresses[0].host, "::1");
+        assert_eq!(info.addresses[0].port, 1522);
         assert_eq!(info.service_name, "XEPDB1");
+
+        let info = Protocol::parse_connection_string("[fe80::1%eth0]/XEPDB1").unwrap();
+        assert_eq!(info.addresses[0].host, "fe80::1%eth0");
+        assert_eq!(info.addresses[0].port, 1521);
+
+        let info = Protocol::parse_connection_string(
+            "(DESCRIPTION=(ADDRESS=(HOST=[2001:db8::5])(PORT=1521))(CONNECT_DATA=(SID=ORCL)))",
+        )
+        .unwrap();
+        assert_eq!(info.addresses[0].host, "2001:db8::5");
+        let info = Protocol::parse_connection_string(
+            "(DESCRIPTION=(ADDRESS=(HOST=2001:db8::5))(CONNECT_DATA=(SID=ORCL)))",
+        )
+        .unwrap();
+        assert_eq!(info.addresses[0].host, "2001:db8::5");
+
+        assert!(Protocol::parse_connection_string("::1:1521/XEPDB1").is_err());
+        assert!(Protocol::parse_connection_string("[::1/XEPDB1").is_err());
+        assert!(Protocol::parse_connection_string("[::1]1521/XEPDB1").is_err());
+    }
+
+    #[test]
+    fn test_parse_tns_descriptor() {
+        let descriptor = crate::ConnectDescriptor::new()
+            .host("db.example.com")
+            .port(1522)
+            .service("orclpdb1")
+            .build()
+            .unwrap();
+        let info = Protocol::parse_connection_string(&descriptor).unwrap();
+        assert_eq!(info.addresses[0].host, "db.example.com");
+        assert_eq!(info.addresses[0].port, 1522);
+        assert_eq!(info.service_name, "orclpdb1");
+        assert!(Protocol::parse_connection_string("(DESCRIPTION=(ADDRESS=(HOST=db)))").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_call_poisons_session() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        // A break resynchronizes an interrupted call
+        protocol.begin_round_trip().unwrap();
+        protocol.send_break();
+        protocol.ping().await.unwrap();
+
+        // Without one, the next call finds the stream out of step
+        protocol.begin_round_trip().unwrap();
+        assert!(matches!(
+            protocol.ping().await,
+            Err(Error::ConnectionClosed)
+        ));
+        assert!(protocol.broken_flag().load(Ordering::Relaxed));
+        assert!(matches!(
+            protocol.execute("SELECT 1 FROM dual", &[]).await,
+            Err(Error::ConnectionClosed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_control_statements_execute() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        for sql in [
+            "ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY-MM-DD'",
+            "SET TRANSACTION READ ONLY",
+            "SAVEPOINT a",
+            "ROLLBACK TO SAVEPOINT a",
+            "COMMIT",
+        ] {
+            let (rows, columns) = protocol.execute(sql, &[]).await.unwrap();
+            assert!(rows.is_empty() && columns.is_empty(), "{}", sql);
+        }
+        assert!(!protocol.in_transaction());
+
+        protocol
+            .execute("INSERT INTO t VALUES (1)", &[])
+            .await
+            .unwrap();
+        protocol.execute("SAVEPOINT a", &[]).await.unwrap();
+        protocol
+            .execute("ROLLBACK TO SAVEPOINT a", &[])
+            .await
+            .unwrap();
+        assert!(protocol.in_transaction());
+        protocol.execute("ROLLBACK", &[]).await.unwrap();
+        assert!(!protocol.in_transaction());
+    }
+
+    #[tokio::test]
+    async fn test_current_schema_piggyback() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        protocol.set_current_schema(Some("HR".into()));
+        assert_eq!(protocol.current_schema(), Some("HR"));
+        protocol.ping().await.unwrap();
+        assert_eq!(protocol.round_trips(), 1);
+        assert_eq!(protocol.current_schema, Some("HR".into()));
+
+        protocol.reset_session().await.unwrap();
+        assert_eq!(protocol.current_schema(), None);
+    }
+
+    #[tokio::test]
+    async fn test_boolean_binds_need_server_support() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+        let flag = [Value::Boolean(true)];
+
+        protocol
+            .execute("BEGIN toggle(:1); END;", &flag)
+            .await
+            .unwrap();
+        let err = protocol
+            .execute_dml("UPDATE t SET active = :1", &flag)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedFeature(_)));
+        assert_eq!(protocol.round_trips(), 1);
+
+        protocol.server_version = (11, 2);
+        assert!(protocol
+            .execute("BEGIN toggle(:1); END;", &flag)
+            .await
+            .is_err());
+        protocol.server_version = (23, 4);
+        protocol
+            .execute_dml("UPDATE t SET active = :1", &flag)
+            .await
+            .unwrap();
+
+        let decode = |bytes: &'static [u8]| {
+            wire::RawValue::new(OracleType::Boolean, Some(bytes::Bytes::from_static(bytes)))
+                .decode()
+                .unwrap()
+        };
+        assert_eq!(decode(&[1, 1]), Value::Boolean(true));
+        assert_eq!(decode(&[1, 0]), Value::Boolean(false));
+    }
+
+    #[tokio::test]
+    async fn test_bind_type_hints() {
+        use crate::statement::bind;
+        use crate::types::{Param, Raw, ToSql};
+
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        let name = "Zoë".to_string();
+        let bonus: Option<i64> = None;
+        let blob = vec![7u8; 40_000];
+        let photo = Raw(&blob);
+        let params = [
+            &Param::new(&name).as_type(OracleType::NVarchar2).size(3) as &dyn ToSql,
+            &Param::new(&bonus).as_type(OracleType::Number),
+            &None::<i64>,
+            &Param::new(&photo).as_type(OracleType::Blob),
+        ];
+        let values = bind(&mut protocol, &params).unwrap();
+        protocol
+            .execute_dml(
+                "UPDATE emp SET name = :1, bonus = :2, dept = :3, photo = :4",
+                &values,
+            )
+            .await
+            .unwrap();
+        let types: Vec<_> = protocol.binds().iter().map(|b| b.oracle_type).collect();
+        assert_eq!(
+            types,
+            [
+                OracleType::NVarchar2,
+                OracleType::Number,
+                // A bare None binds as the type Some would
+                OracleType::Number,
+                OracleType::Blob
+            ]
+        );
+        assert_eq!(protocol.binds()[0].size, Some(3));
+
+        // Declarations apply to one execution only
+        protocol
+            .execute_dml("UPDATE t SET b = :1", &values[3..])
+            .await
+            .unwrap_err();
+
+        let short = Param::new(&name).size(3);
+        let values = bind(&mut protocol, &[&short]).unwrap();
+        let err = protocol
+            .execute_dml("UPDATE emp SET name = :1", &values)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("bind :1 is 4 long"));
+    }
+
+    #[tokio::test]
+    async fn test_nchar_bind_length() {
+        use crate::statement::bind;
+        use crate::types::{NStr, Param, ToSql};
+
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        // Two UTF-16 code units, so two NCHAR characters
+        let clef = NStr("𝄞");
+        let values = bind(&mut protocol, &[&Param::new(&clef).size(2) as &dyn ToSql]).unwrap();
+        protocol
+            .execute_dml("UPDATE t SET n = :1", &values)
+            .await
+            .unwrap();
+        assert_eq!(protocol.binds()[0].oracle_type, OracleType::NVarchar2);
+        assert!(protocol.binds()[0].oracle_type.is_national());
+
+        let values = bind(&mut protocol, &[&Param::new(&clef).size(1) as &dyn ToSql]).unwrap();
+        let err = protocol
+            .execute_dml("UPDATE t SET n = :1", &values)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("bind :1 is 2 long"));
+    }
+
+    #[tokio::test]
+    async fn test_raw_bind_limit() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        let fits = [Value::Bytes(vec![0; 32767])];
+        protocol
+            .execute_dml("UPDATE t SET b = :1", &fits)
+            .await
+            .unwrap();
+        let oversized = [Value::Null, Value::Bytes(vec![0; 32768])];
+        let err = protocol
+            .execute_dml("UPDATE t SET a = :1, b = :2", &oversized)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(":2 of 32768 bytes"));
+    }
+
+    #[tokio::test]
+    async fn test_sqlnet_overrides() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass")
+            .tns_admin(std::env::temp_dir().join("oracledb-no-tns-admin"))
+            .sqlnet(SqlNetConfig::new().outbound_connect_timeout(Duration::from_millis(250)));
+        let protocol = Protocol::new(&config).await.unwrap();
+        assert_eq!(protocol.connect_timeout(), Duration::from_millis(250));
+        assert_eq!(protocol.sqlnet().expire_time, None);
+        assert_eq!(protocol.socket_options().keepalive, None);
+
+        // A timeout set in code beats the profile
+        let config = config.timeout(Duration::from_secs(3)).sqlnet(
+            SqlNetConfig::new()
+                .outbound_connect_timeout(Duration::from_millis(250))
+                .expire_time(Duration::from_secs(600)),
+        );
+        let protocol = Protocol::new(&config).await.unwrap();
+        assert_eq!(protocol.connect_timeout(), Duration::from_secs(3));
+        assert_eq!(
+            protocol.socket_options().keepalive,
+            Some(Duration::from_secs(600))
+        );
+
+        let config =
+            config.socket_options(SocketOptions::default().keepalive(Duration::from_secs(30)));
+        let protocol = Protocol::new(&config).await.unwrap();
+        assert_eq!(
+            protocol.socket_options().keepalive,
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_inband_drain_notification() {
+        use crate::testing::{Expectation, MockConnection};
+
+        let mock = MockConnection::new()
+            .expect(Expectation::dml("UPDATE emp SET sal = sal", 14))
+            .expect(
+                Expectation::dml("UPDATE emp SET sal = sal", 14)
+                    .warns(INBAND_DRAIN, "service SALES is draining"),
+            );
+        let conn = mock.connect().await.unwrap();
+
+        conn.execute_dml("UPDATE emp SET sal = sal", &[])
+            .await
+            .unwrap();
+        assert!(!conn.is_draining());
+        let stmt = conn.prepare("UPDATE emp SET sal = sal").await.unwrap();
+        let result = stmt.run(&[]).await.unwrap();
+        assert_eq!(result.rows_affected, 14);
+        // The notice is for the pool, not a warning about the statement
+        assert!(result.warning.is_none());
+        assert!(conn.is_draining());
+        assert!(conn.is_healthy());
+        mock.verify();
+    }
+
+    #[tokio::test]
+    async fn test_read_only_service() {
+        let descriptor = "(DESCRIPTION=(ADDRESS=(HOST=db1)(PORT=1521))(CONNECT_DATA=(SID=ORCL)))";
+        let config = ConnectionConfig::new(descriptor, "user", "pass")
+            .tns_admin(std::env::temp_dir().join("oracledb-no-tns-admin"))
+            .read_only_service("sales_ro");
+        let protocol = Protocol::new(&config).await.unwrap();
+        assert_eq!(protocol.conn_info.sid.as_deref(), Some("ORCL"));
+
+        let config = config.intent(Intent::ReadOnly);
+        let protocol = Protocol::new(&config).await.unwrap();
+        assert_eq!(protocol.conn_info.service_name, "sales_ro");
+        assert_eq!(protocol.conn_info.sid, None);
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[tokio::test]
+    async fn test_transport_failover() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed_port = closed.local_addr().unwrap().port();
+        drop(closed);
+
+        let descriptor = format!(
+            "(DESCRIPTION=(CONNECT_TIMEOUT=5)(TRANSPORT_CONNECT_TIMEOUT=2)(ADDRESS_LIST=\
+             (ADDRESS=(HOST=127.0.0.1)(PORT={})(TRANSPORT_CONNECT_TIMEOUT=500 ms))\
+             (ADDRESS=(HOST=127.0.0.1)(PORT={})))\
+             (CONNECT_DATA=(SERVICE_NAME=orclpdb1)))",
+            closed_port, port
+        );
+        let config = ConnectionConfig::new(descriptor, "user", "pass")
+            .tns_admin(std::env::temp_dir().join("oracledb-no-tns-admin"))
+            .transport_connect_timeout(Duration::from_secs(9));
+        let protocol = Protocol::new(&config).await.unwrap();
+        assert_eq!(protocol.connect_timeout(), Duration::from_secs(5));
+        let timeouts: Vec<_> = protocol
+            .conn_info
+            .addresses
+            .iter()
+            .map(|address| protocol.transport_connect_timeout(address))
+            .collect();
+        assert_eq!(
+            timeouts,
+            [Duration::from_millis(500), Duration::from_secs(2)]
+        );
+
+        // The refused first address fails over to the listening one
+        match protocol.open_transport().await.unwrap() {
+            Transport::Tcp(stream) => {
+                assert_eq!(stream.get_ref().peer_addr().unwrap().port(), port)
+            }
+            _ => panic!("expected a TCP transport"),
+        }
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[tokio::test]
+    async fn test_client_interface() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let config = ConnectionConfig::new(format!("127.0.0.1:{}/orclpdb1", port), "user", "pass")
+            .tns_admin(std::env::temp_dir().join("oracledb-no-tns-admin"));
+        let protocol = Protocol::new(&config).await.unwrap();
+        assert_eq!(
+            protocol.client_interface().await.unwrap(),
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        );
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[tokio::test]
+    async fn test_resolver_and_pre_connect_hook() {
+        use crate::hooks::{ConnectHooks, Resolver};
+        use futures::future::BoxFuture;
+        use std::net::SocketAddr;
+        use std::sync::Mutex;
+
+        /// Resolves every host to one fixed address
+        struct Fixed(SocketAddr);
+
+        impl Resolver for Fixed {
+            fn resolve<'a>(
+                &'a self,
+                host: &'a str,
+                _port: u16,
+            ) -> BoxFuture<'a, std::io::Result<Vec<SocketAddr>>> {
+                assert_eq!(host, "orcl-primary.service.consul");
+                Box::pin(async move { Ok(vec![self.0]) })
+            }
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let audit = seen.clone();
+        let hooks = ConnectHooks::new()
+            .resolver(Fixed(addr))
+            .pre_connect(move |target| {
+                audit.lock().unwrap().push(target.clone());
+                Ok(())
+            });
+        let config = ConnectionConfig::new("orcl-primary.service.consul/sales", "app", "pass")
+            .tns_admin(std::env::temp_dir().join("oracledb-no-tns-admin"))
+            .hooks(hooks);
+        let protocol = Protocol::new(&config).await.unwrap();
+        match protocol.open_transport().await.unwrap() {
+            Transport::Tcp(stream) => assert_eq!(stream.get_ref().peer_addr().unwrap(), addr),
+            _ => panic!("expected a TCP transport"),
+        }
+        let target = seen.lock().unwrap()[0].clone();
+        assert_eq!(target.user, "app");
+        assert_eq!(
+            target.addresses,
+            [TargetAddress::Tcp {
+                host: "orcl-primary.service.consul".into(),
+                port: 1521
+            }]
+        );
+
+        let hooks = ConnectHooks::new()
+            .pre_connect(|_| Err(Error::InvalidConfiguration("outside change window".into())));
+        let config = config.hooks(hooks);
+        let err = Protocol::new(&config).await.err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("sales at orcl-primary.service.consul:1521"));
+        assert!(err.to_string().contains("outside change window"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_ipc_transport() {
+        let dir = std::env::temp_dir().join(format!("oracledb-ipc-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sEXTPROC1");
+        let _ = std::fs::remove_file(&path);
+        let _listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+        let descriptor = crate::ConnectDescriptor::new()
+            .ipc_key("EXTPROC1")
+            .service("orcl")
+            .build()
+            .unwrap();
+        let config = ConnectionConfig::new(descriptor, "user", "pass")
+            .tns_admin(std::env::temp_dir().join("oracledb-no-tns-admin"))
+            .ipc_directory(&dir);
+        let protocol = Protocol::new(&config).await.unwrap();
+        assert_eq!(
+            protocol.conn_info.addresses[0].ipc_key.as_deref(),
+            Some("EXTPROC1")
+        );
+        assert!(matches!(
+            protocol.open_transport().await,
+            Ok(Transport::Ipc(_))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(protocol.open_transport().await, Err(Error::Io(_))));
+    }
+
+    #[tokio::test]
+    async fn test_session_tags_piggyback() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        protocol.set_session_tags(SessionTags {
+            module: Some("billing".into()),
+            action: Some("x".repeat(40)),
+            ..Default::default()
+        });
+        assert_eq!(protocol.session_tags(), &SessionTags::default());
+
+        protocol.ping().await.unwrap();
+        assert_eq!(protocol.session_tags().module.as_deref(), Some("billing"));
+        assert_eq!(
+            protocol.session_tags().action.as_ref().map(String::len),
+            Some(32)
+        );
+
+        protocol.set_session_tags(SessionTags {
+            client_info: Some("batch".into()),
+            ..Default::default()
+        });
+        protocol.execute_dml("DELETE FROM t", &[]).await.unwrap();
+        assert_eq!(protocol.session_tags().module.as_deref(), Some("billing"));
+        assert_eq!(
+            protocol.session_tags().client_info.as_deref(),
+            Some("batch")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_buffers_reused() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        for _ in 0..3 {
+            let (rows, _) = protocol.execute("SELECT * FROM t", &[]).await.unwrap();
+            assert_eq!(rows[0].get_typed::<String>(1).unwrap(), "Test");
+        }
+        assert_eq!(protocol.buffers.stats(), (2, 1));
+    }
+
+    #[test]
+    fn test_describe_flags() {
+        let mut ssn = ColumnInfo {
+            name: "SSN".into(),
+            oracle_type: OracleType::Varchar2,
+            size: 11,
+            precision: None,
+            scale: None,
+            nullable: false,
+            encrypted: false,
+            redacted: false,
+        };
+        apply_describe_flags(&mut ssn, DESCRIBE_ENCRYPTED);
+        assert!(ssn.encrypted && !ssn.redacted && !ssn.nullable);
+        assert!(ssn.is_protected());
+
+        apply_describe_flags(&mut ssn, DESCRIBE_ENCRYPTED | DESCRIBE_REDACTED);
+        assert!(ssn.encrypted && ssn.redacted);
+        assert!(ssn.nullable);
+
+        apply_describe_flags(&mut ssn, 0);
+        assert!(!ssn.is_protected());
     }
 
     #[test]
@@ -308,5 +2521,42 @@ mod tests {
             Protocol::parse_statement_type("BEGIN NULL; END;").unwrap(),
             StatementType::PlSql
         );
+        let cases = [
+            (
+                "/*+ APPEND */ insert into t select * from s",
+                StatementType::Insert,
+            ),
+            (
+                "-- refresh\nMERGE INTO t USING s ON (t.id = s.id)",
+                StatementType::Merge,
+            ),
+            (
+                "with x as (select 1 from dual) select * from x",
+                StatementType::Select,
+            ),
+            (
+                "(SELECT 1 FROM dual) UNION (SELECT 2 FROM dual)",
+                StatementType::Select,
+            ),
+            ("CALL proc(:1)", StatementType::PlSql),
+            ("EXPLAIN PLAN FOR SELECT * FROM t", StatementType::Explain),
+            ("COMMENT ON TABLE t IS 'x'", StatementType::Ddl),
+            ("TRUNCATE TABLE t", StatementType::Ddl),
+            (
+                "ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY'",
+                StatementType::Control,
+            ),
+            ("ALTER TABLE t ADD c NUMBER", StatementType::Ddl),
+            ("SAVEPOINT before_load", StatementType::Control),
+            ("/* nothing */", StatementType::Unknown),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(
+                Protocol::parse_statement_type(sql).unwrap(),
+                expected,
+                "{}",
+                sql
+            );
+        }
     }
 }
@@ -0,0 +1,98 @@
+// r2d2 connection manager
+
+//! Pools [`blocking::Connection`]s with [r2d2](https://docs.rs/r2d2), for
+//! synchronous stacks that already manage their resources that way.
+//!
+//! Connections are checked with a ping on checkout (r2d2's default) and
+//! dropped from the pool once they have been closed, or when they come
+//! back with a transaction open, as the async pool does.
+//!
+//! ```rust,ignore
+//! use oracledb_rs::r2d2::OracleConnectionManager;
+//!
+//! let manager = OracleConnectionManager::new(config);
+//! let pool = r2d2::Pool::builder().max_size(8).build(manager)?;
+//!
+//! let conn = pool.get()?;
+//! conn.execute_dml("DELETE FROM sessions WHERE expires < SYSDATE", &[])?;
+//! ```
+
+use crate::blocking;
+use crate::{ConnectionConfig, Error};
+
+/// [`r2d2::ManageConnection`] for blocking Oracle connections
+#[derive(Debug, Clone)]
+pub struct OracleConnectionManager {
+    config: ConnectionConfig,
+}
+
+impl OracleConnectionManager {
+    /// Open pooled connections with `config`
+    pub fn new(config: ConnectionConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl r2d2::ManageConnection for OracleConnectionManager {
+    type Connection = blocking::Connection;
+    type Error = Error;
+
+    fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        blocking::Connection::connect(self.config.clone())
+    }
+
+    fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        conn.ping()
+    }
+
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        let info = conn.info();
+        // Uncommitted work must not leak into the next checkout
+        !info.is_open || info.transaction_active
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_checkout() {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let pool = r2d2::Pool::builder()
+            .max_size(2)
+            .test_on_check_out(false)
+            .build(OracleConnectionManager::new(config))
+            .unwrap();
+
+        let first = pool.get().unwrap();
+        let second = pool.get().unwrap();
+        assert!(first.info().is_open);
+        assert_eq!(second.info().user, "user");
+        assert_eq!(pool.state().idle_connections, 0);
+
+        drop(first);
+        assert_eq!(pool.state().idle_connections, 1);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_open_transaction_breaks_connection() {
+        use r2d2::ManageConnection;
+
+        let manager =
+            OracleConnectionManager::new(ConnectionConfig::new("localhost:1521/XEPDB1", "u", "p"));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let inner = runtime.block_on(crate::testing::unscripted()).unwrap();
+        let mut conn = blocking::Connection::with_runtime(inner, runtime);
+        assert!(!manager.has_broken(&mut conn));
+
+        conn.execute_dml("UPDATE emp SET sal = sal + 1", &[])
+            .unwrap();
+        assert!(manager.has_broken(&mut conn));
+        conn.commit().unwrap();
+        assert!(!manager.has_broken(&mut conn));
+    }
+}
@@ -1,6 +1,6 @@
 // Additional result utilities
 
-use crate::types::Value;
+use crate::types::{OracleType, Value};
 use serde::{Deserialize, Serialize};
 
 /// Query result formatting options
@@ -73,7 +73,7 @@ impl ExtendedResult {
 }
 
 /// Convert Oracle Value to JSON value
-fn value_to_json(value: &Value) -> serde_json::Value {
+pub(crate) fn value_to_json(value: &Value) -> serde_json::Value {
     match value {
         Value::Null => serde_json::Value::Null,
         Value::String(s) => serde_json::Value::String(s.clone()),
@@ -85,12 +85,28 @@ fn value_to_json(value: &Value) -> serde_json::Value {
         Value::Date(d) => serde_json::Value::String(d.to_string()),
         Value::Timestamp(ts) => serde_json::Value::String(ts.to_string()),
         Value::TimestampTz(ts) => serde_json::Value::String(ts.to_rfc3339()),
+        #[cfg(feature = "chrono-tz")]
+        Value::TimestampTzRegion(ts) => {
+            serde_json::Value::String(format!("{} {}", ts.datetime().to_rfc3339(), ts.region()))
+        }
+        Value::IntervalYM(i) => serde_json::Value::String(i.to_string()),
+        Value::IntervalDS(i) => serde_json::Value::String(i.to_string()),
+        Value::Vector(v) => serde_json::Value::from(v.to_f64()),
         Value::Bytes(b) => {
             // Base64 encode binary data
             serde_json::Value::String(base64_encode(b))
         }
         Value::Clob(s) => serde_json::Value::String(s.clone()),
         Value::Blob(b) => serde_json::Value::String(base64_encode(b)),
+        // Only a LOB prefetched whole has data to show
+        Value::Lob(lob) => match (lob.oracle_type(), lob.data()) {
+            (OracleType::Clob | OracleType::NClob, Some(_)) => match lob.text() {
+                Some(Ok(text)) => serde_json::Value::String(text),
+                _ => serde_json::Value::Null,
+            },
+            (_, Some(data)) => serde_json::Value::String(base64_encode(data)),
+            (_, None) => serde_json::Value::Null,
+        },
         Value::Json(j) => j.clone(),
         Value::Array(arr) => {
             let json_arr: Vec<_> = arr.iter().map(value_to_json).collect();
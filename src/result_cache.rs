@@ -0,0 +1,179 @@
+// Client result cache
+
+//! Rows of `/*+ RESULT_CACHE */` queries kept on the client, as Oracle's
+//! client result cache does.
+//!
+//! The server announces invalidations on its result-cache channel, which
+//! rides along on round trips; a session that makes none may miss them,
+//! so entries also expire after the configured lag, like
+//! `CLIENT_RESULT_CACHE_LAG`. The session's own DML invalidates the cache,
+//! and nothing is served or cached while its changes are uncommitted.
+
+use crate::sql::normalize;
+use crate::statement::Row;
+use crate::types::{ColumnInfo, Value};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Query results keyed by SQL text and binds
+#[derive(Debug)]
+pub(crate) struct ResultCache {
+    /// Bytes of rows the cache may hold (0 disables it)
+    capacity: usize,
+    /// Longest an entry is served without hearing from the server
+    lag: Duration,
+    used: usize,
+    entries: HashMap<String, Entry>,
+    /// Keys, oldest first
+    order: VecDeque<String>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    rows: Vec<Row>,
+    metadata: Vec<ColumnInfo>,
+    size: usize,
+    cached_at: Instant,
+}
+
+impl ResultCache {
+    pub(crate) fn new(capacity: usize, lag: Duration) -> Self {
+        Self {
+            capacity,
+            lag,
+            used: 0,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Rows cached for `sql` with `params`, unless expired
+    pub(crate) fn get(
+        &mut self,
+        sql: &str,
+        params: &[Value],
+    ) -> Option<(Vec<Row>, Vec<ColumnInfo>)> {
+        let key = Self::key(sql, params)?;
+        let entry = self.entries.get(&key)?;
+        if entry.cached_at.elapsed() > self.lag {
+            self.remove(&key);
+            return None;
+        }
+        Some((entry.rows.clone(), entry.metadata.clone()))
+    }
+
+    /// Cache the rows of a hinted query, evicting the oldest entries to
+    /// make room
+    pub(crate) fn insert(
+        &mut self,
+        sql: &str,
+        params: &[Value],
+        rows: &[Row],
+        metadata: &[ColumnInfo],
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let Some(key) = Self::key(sql, params) else {
+            return;
+        };
+        let size: usize = rows.iter().map(Row::wire_len).sum();
+        if size > self.capacity {
+            return;
+        }
+        self.remove(&key);
+        while self.used + size > self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.used -= entry.size;
+            }
+        }
+        self.used += size;
+        self.order.push_back(key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                rows: rows.to_vec(),
+                metadata: metadata.to_vec(),
+                size,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop every entry
+    pub(crate) fn invalidate(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.used = 0;
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.used -= entry.size;
+            self.order.retain(|k| k != key);
+        }
+    }
+
+    /// Cache key, for queries that carry the RESULT_CACHE hint
+    fn key(sql: &str, params: &[Value]) -> Option<String> {
+        let sql = normalize(sql, false);
+        let hinted = sql.match_indices("/*+").any(|(at, _)| {
+            let hint = &sql[at..];
+            let hint = &hint[..hint.find("*/").unwrap_or(hint.len())];
+            hint.to_ascii_uppercase().contains("RESULT_CACHE")
+        });
+        hinted.then(|| format!("{}\0{:?}", sql, params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hinted_queries_cached_until_lag() {
+        let rows = vec![Row::new(vec![Value::Integer(1)], vec!["N".into()])];
+        let hinted = "SELECT /*+ RESULT_CACHE */ n FROM lookup";
+        let mut cache = ResultCache::new(1024, Duration::from_secs(60));
+
+        cache.insert("SELECT n FROM lookup", &[], &rows, &[]);
+        assert!(cache.get("SELECT n FROM lookup", &[]).is_none());
+        cache.insert(hinted, &[], &rows, &[]);
+        assert_eq!(cache.get(hinted, &[]).unwrap().0.len(), 1);
+        assert!(cache.get(hinted, &[Value::Integer(2)]).is_none());
+        cache.invalidate();
+        assert!(cache.get(hinted, &[]).is_none());
+
+        let mut expired = ResultCache::new(1024, Duration::ZERO);
+        expired.insert(hinted, &[], &rows, &[]);
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(expired.get(hinted, &[]).is_none());
+        assert_eq!(expired.used, 0);
+
+        // Even an empty result stays out of a disabled cache
+        let mut disabled = ResultCache::new(0, Duration::from_secs(60));
+        disabled.insert(hinted, &[], &[], &[]);
+        assert!(disabled.get(hinted, &[]).is_none());
+    }
+
+    #[test]
+    fn test_evicts_oldest_to_fit() {
+        let row = |n| vec![Row::new(vec![Value::Integer(n)], vec!["N".into()])];
+        let size = row(0)[0].wire_len();
+        let mut cache = ResultCache::new(size * 2, Duration::from_secs(60));
+        for n in 0..3 {
+            let sql = format!("SELECT /*+ result_cache */ {} FROM dual", n);
+            cache.insert(&sql, &[], &row(n), &[]);
+        }
+        assert!(cache
+            .get("SELECT /*+ result_cache */ 0 FROM dual", &[])
+            .is_none());
+        assert!(cache
+            .get("SELECT /*+ result_cache */ 2 FROM dual", &[])
+            .is_some());
+        assert_eq!(cache.used, size * 2);
+    }
+}
@@ -0,0 +1,253 @@
+// Retry policy
+
+//! Re-runs operations that fail with transient errors, with exponential
+//! backoff and jitter between attempts.
+//!
+//! By default an error is retried when [`Error::is_retryable`] says so;
+//! supply [`RetryPolicy::retry_on`] to decide differently.
+//!
+//! ```rust,ignore
+//! use oracledb_rs::retry::RetryPolicy;
+//!
+//! let policy = RetryPolicy::new()
+//!     .max_attempts(5)
+//!     .backoff(Duration::from_millis(50), Duration::from_secs(2));
+//! let rows = conn.execute_with_retry("SELECT * FROM jobs", &[], &policy).await?;
+//! ```
+
+use crate::runtime;
+use crate::{Error, Result};
+use rand::Rng;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Decides whether an error is worth another attempt
+pub type RetryPredicate = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+
+/// How often and how patiently to retry
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: bool,
+    retry_on: Option<RetryPredicate>,
+}
+
+impl std::fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .field("retry_on", &self.retry_on.as_ref().map(|_| "<fn>"))
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: true,
+            retry_on: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Three attempts, backing off from 100 ms up to 5 s with jitter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total attempts, the first included
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Wait `initial` before the first retry, growing up to `max`
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Growth factor between consecutive waits
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Randomize waits so clients do not retry in lockstep
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Retry the errors `predicate` accepts instead of the retryable ones
+    pub fn retry_on<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Error) -> bool + Send + Sync + 'static,
+    {
+        self.retry_on = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Whether attempt number `attempt` (1-based) failing with `error`
+    /// earns another attempt
+    pub fn should_retry(&self, error: &Error, attempt: u32) -> bool {
+        self.should_retry_or(error, attempt, Error::is_retryable)
+    }
+
+    /// As [`RetryPolicy::should_retry`], with `default` deciding unless
+    /// [`RetryPolicy::retry_on`] was given
+    pub(crate) fn should_retry_or(
+        &self,
+        error: &Error,
+        attempt: u32,
+        default: fn(&Error) -> bool,
+    ) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        match &self.retry_on {
+            Some(predicate) => predicate(error),
+            None => default(error),
+        }
+    }
+
+    /// Wait after attempt number `attempt` (1-based) failed
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(63) as i32;
+        // Cap in floating point: large exponents overflow a Duration
+        let secs = (self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent))
+            .min(self.max_backoff.as_secs_f64());
+        let backoff = Duration::try_from_secs_f64(secs).unwrap_or(self.max_backoff);
+        if !self.jitter {
+            return backoff;
+        }
+        // Equal jitter: at least half the backoff, at most all of it
+        let half = backoff / 2;
+        half + half.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+
+    /// Run `operation` until it succeeds, fails for good or runs out of
+    /// attempts
+    pub async fn run<T, F, Fut>(&self, operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.run_unless(|_| false, operation).await
+    }
+
+    /// As [`RetryPolicy::run`], but failing at once with any error `stop`
+    /// picks out, whatever the policy says
+    pub(crate) async fn run_unless<T, F, Fut>(
+        &self,
+        stop: fn(&Error) -> bool,
+        mut operation: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if !stop(&e) && self.should_retry(&e, attempt) => {
+                    let delay = self.delay(attempt);
+                    log::debug!(
+                        "attempt {} failed ({}), retrying in {:?}",
+                        attempt,
+                        e,
+                        delay
+                    );
+                    runtime::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_grows_to_cap() {
+        let policy = RetryPolicy::new()
+            .backoff(Duration::from_millis(100), Duration::from_millis(300))
+            .jitter(false);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(300));
+        assert_eq!(policy.delay(40), Duration::from_millis(300));
+
+        let steep = RetryPolicy::new()
+            .backoff(Duration::from_millis(100), Duration::from_secs(5))
+            .multiplier(10.0)
+            .jitter(false);
+        assert_eq!(steep.delay(22), Duration::from_secs(5));
+        assert_eq!(steep.delay(u32::MAX), Duration::from_secs(5));
+
+        let jittered = policy.jitter(true).delay(2);
+        assert!(jittered >= Duration::from_millis(100));
+        assert!(jittered <= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let policy = RetryPolicy::new()
+            .max_attempts(4)
+            .backoff(Duration::from_millis(1), Duration::from_millis(1));
+        let attempts = AtomicU32::new(0);
+
+        let value = policy
+            .run(|| async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(Error::oracle(54, "resource busy")),
+                    _ => Ok(7),
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 7);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // Permanent errors and exhausted attempts end the loop
+        attempts.store(0, Ordering::SeqCst);
+        let result: Result<()> = policy
+            .run(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(Error::oracle(1, "unique constraint"))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        let policy = policy.max_attempts(2).retry_on(|_| true);
+        attempts.store(0, Ordering::SeqCst);
+        let result: Result<()> = policy
+            .run(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(Error::oracle(1, "unique constraint"))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,909 @@
+// Async runtime abstraction
+
+//! The driver core needs a TCP connector, a timer and async locks. Locks
+//! come from `async-lock`, which works under any executor; the connector
+//! and timer come from the [`Runtime`] picked by feature:
+//!
+//! | Feature | Runtime |
+//! |---------|---------|
+//! | `runtime-tokio` (enabled by the default `async` feature) | [`Tokio`] |
+//! | `runtime-async-std` | [`AsyncStd`] |
+//! | `runtime-smol` | [`Smol`] |
+//!
+//! [`DefaultRuntime`] is the first enabled runtime in that order. To run
+//! under async-std or smol, turn off default features:
+//!
+//! ```toml
+//! oracledb-rs = { version = "1", default-features = false, features = ["runtime-smol", "pool"] }
+//! ```
+//!
+//! On Unix, databases on the same host can also be reached over the
+//! listener's IPC socket; [`Transport`] holds either kind of stream.
+//!
+//! Sockets are created with [`SocketOptions`] applied before connecting;
+//! Nagle's algorithm is off by default, since it holds back the small
+//! packets TTC sends for most calls.
+//!
+//! Runtimes also accept the connections a database opens back to the
+//! client for notifications, and run the background task serving them.
+//!
+//! A connection's session sits behind a [`FairMutex`], which hands it to
+//! waiting callers strictly in arrival order.
+//!
+//! Hosts that resolve to both IPv6 and IPv4 addresses are tried
+//! alternating families. Under tokio the attempts are staggered rather
+//! than sequential (RFC 8305 "Happy Eyeballs"), so an unreachable family
+//! costs [`CONNECTION_ATTEMPT_DELAY`] instead of a full TCP timeout.
+//!
+//! The `sqlx`, `diesel` and `sea-orm` integrations drive tokio themselves
+//! and enable `async` regardless.
+
+use crate::{Error, Result};
+use futures::future::{self, BoxFuture, Either};
+use futures::io::{AsyncRead, AsyncWrite};
+use socket2::{Domain, Protocol, Socket, TcpKeepalive, Type};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::pin::{pin, Pin};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+pub use async_lock::{Mutex, MutexGuard, Semaphore, SemaphoreGuardArc};
+
+/// Head start each connection attempt gets before the next address is tried
+pub const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+#[cfg(not(any(
+    feature = "runtime-tokio",
+    feature = "runtime-async-std",
+    feature = "runtime-smol"
+)))]
+compile_error!("enable one of the `runtime-tokio`, `runtime-async-std` or `runtime-smol` features");
+
+/// Executor services the driver depends on
+pub trait Runtime: Send + Sync + 'static {
+    /// TCP stream used by the transport
+    type TcpStream: AsyncRead + AsyncWrite + Send + Unpin + 'static;
+
+    /// Open a TCP connection to `host:port`
+    fn connect_tcp<'a>(
+        host: &'a str,
+        port: u16,
+        options: &'a SocketOptions,
+    ) -> BoxFuture<'a, io::Result<Self::TcpStream>>;
+
+    /// Unix domain socket stream used by IPC transports
+    #[cfg(unix)]
+    type UnixStream: AsyncRead + AsyncWrite + Send + Unpin + 'static;
+
+    /// Connect to the Unix domain socket at `path`
+    #[cfg(unix)]
+    fn connect_unix(path: &Path) -> BoxFuture<'_, io::Result<Self::UnixStream>>;
+
+    /// Wait for `duration`
+    fn sleep(duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// TCP listener for connections the server opens to the client
+    type TcpListener: Send + Sync + 'static;
+
+    /// Listen on `addr`
+    fn bind_tcp(addr: SocketAddr) -> BoxFuture<'static, io::Result<Self::TcpListener>>;
+
+    /// Address `listener` is bound to
+    fn local_addr(listener: &Self::TcpListener) -> io::Result<SocketAddr>;
+
+    /// Local end of `stream`
+    fn stream_local_addr(stream: &Self::TcpStream) -> io::Result<SocketAddr>;
+
+    /// Accept the next connection on `listener`
+    fn accept(
+        listener: &Self::TcpListener,
+    ) -> BoxFuture<'_, io::Result<(Self::TcpStream, SocketAddr)>>;
+
+    /// Run `future` in the background, detached
+    fn spawn(future: BoxFuture<'static, ()>);
+
+    /// File opened without blocking the executor
+    type File: AsyncRead + AsyncWrite + Send + Unpin + 'static;
+
+    /// Open the file at `path` for reading
+    fn open_file(path: &Path) -> BoxFuture<'_, io::Result<Self::File>>;
+
+    /// Create the file at `path` for writing, truncating it if it exists
+    fn create_file(path: &Path) -> BoxFuture<'_, io::Result<Self::File>>;
+}
+
+/// Stream to the server over TCP or a local IPC socket
+pub enum Transport {
+    /// TCP connection to a listener
+    Tcp(<DefaultRuntime as Runtime>::TcpStream),
+    /// Unix domain socket of a local listener
+    #[cfg(unix)]
+    Ipc(<DefaultRuntime as Runtime>::UnixStream),
+}
+
+impl AsyncRead for Transport {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Transport::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            #[cfg(unix)]
+            Transport::Ipc(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Transport {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Transport::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            #[cfg(unix)]
+            Transport::Ipc(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Transport::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            #[cfg(unix)]
+            Transport::Ipc(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Transport::Tcp(stream) => Pin::new(stream).poll_close(cx),
+            #[cfg(unix)]
+            Transport::Ipc(stream) => Pin::new(stream).poll_close(cx),
+        }
+    }
+}
+
+/// Socket settings applied to every connection
+#[derive(Debug, Clone)]
+pub struct SocketOptions {
+    /// Disable Nagle's algorithm (`TCP_NODELAY`)
+    pub nodelay: bool,
+    /// Kernel send buffer size (`SO_SNDBUF`)
+    pub send_buffer_size: Option<usize>,
+    /// Kernel receive buffer size (`SO_RCVBUF`)
+    pub recv_buffer_size: Option<usize>,
+    /// Network interface to bind to (`SO_BINDTODEVICE`, Linux only)
+    pub bind_device: Option<String>,
+    /// Idle time before TCP keepalive probes start (`SO_KEEPALIVE`)
+    pub keepalive: Option<Duration>,
+}
+
+impl Default for SocketOptions {
+    fn default() -> Self {
+        Self {
+            nodelay: true,
+            send_buffer_size: None,
+            recv_buffer_size: None,
+            bind_device: None,
+            keepalive: None,
+        }
+    }
+}
+
+impl SocketOptions {
+    /// Set `TCP_NODELAY`
+    pub fn nodelay(mut self, nodelay: bool) -> Self {
+        self.nodelay = nodelay;
+        self
+    }
+
+    /// Set the kernel send buffer size
+    pub fn send_buffer_size(mut self, bytes: usize) -> Self {
+        self.send_buffer_size = Some(bytes);
+        self
+    }
+
+    /// Set the kernel receive buffer size
+    pub fn recv_buffer_size(mut self, bytes: usize) -> Self {
+        self.recv_buffer_size = Some(bytes);
+        self
+    }
+
+    /// Bind connections to a network interface, e.g. `eth1`
+    pub fn bind_device(mut self, interface: impl Into<String>) -> Self {
+        self.bind_device = Some(interface.into());
+        self
+    }
+
+    /// Probe idle connections after `idle`, so dead peers are detected
+    pub fn keepalive(mut self, idle: Duration) -> Self {
+        self.keepalive = Some(idle);
+        self
+    }
+
+    /// Create an unconnected, non-blocking socket for `addr` with these options
+    ///
+    /// Buffer sizes are set before connecting so the TCP window scale is
+    /// negotiated for them.
+    pub fn socket(&self, addr: SocketAddr) -> io::Result<Socket> {
+        let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+        socket.set_tcp_nodelay(self.nodelay)?;
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+        if let Some(interface) = &self.bind_device {
+            bind_device(&socket, interface)?;
+        }
+        if let Some(idle) = self.keepalive {
+            socket.set_tcp_keepalive(&TcpKeepalive::new().with_time(idle))?;
+        }
+        socket.set_nonblocking(true)?;
+        Ok(socket)
+    }
+
+    /// Resolve `host` and connect, blocking the current thread
+    #[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+    fn connect(&self, host: &str, port: u16) -> io::Result<std::net::TcpStream> {
+        use std::net::ToSocketAddrs;
+
+        let mut last_error = None;
+        let host = unbracket(host);
+        for addr in interleave_families((host, port).to_socket_addrs()?) {
+            let socket = self.socket(addr)?;
+            socket.set_nonblocking(false)?;
+            match socket.connect(&addr.into()) {
+                Ok(()) => return Ok(socket.into()),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| no_addresses(host)))
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+fn bind_device(socket: &Socket, interface: &str) -> io::Result<()> {
+    socket.bind_device(Some(interface.as_bytes()))
+}
+
+#[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+fn bind_device(_socket: &Socket, interface: &str) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "cannot bind to {}: SO_BINDTODEVICE is Linux only",
+            interface
+        ),
+    ))
+}
+
+/// Order addresses alternating IPv6 and IPv4, starting with the family
+/// the resolver put first
+pub(crate) fn interleave_families(addrs: impl IntoIterator<Item = SocketAddr>) -> Vec<SocketAddr> {
+    let (first, second): (Vec<_>, Vec<_>) = {
+        let addrs: Vec<_> = addrs.into_iter().collect();
+        let Some(v6_first) = addrs.first().map(SocketAddr::is_ipv6) else {
+            return addrs;
+        };
+        addrs.into_iter().partition(|a| a.is_ipv6() == v6_first)
+    };
+    let mut ordered = Vec::with_capacity(first.len() + second.len());
+    let (mut first, mut second) = (first.into_iter(), second.into_iter());
+    loop {
+        match (first.next(), second.next()) {
+            (None, None) => return ordered,
+            (a, b) => ordered.extend(a.into_iter().chain(b)),
+        }
+    }
+}
+
+/// Strip the brackets around an IPv6 literal so it resolves
+pub(crate) fn unbracket(host: &str) -> &str {
+    host.strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host)
+}
+
+fn no_addresses(host: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} did not resolve to any address", host),
+    )
+}
+
+/// tokio runtime
+#[cfg(feature = "runtime-tokio")]
+#[derive(Debug, Clone, Copy)]
+pub struct Tokio;
+
+#[cfg(feature = "runtime-tokio")]
+impl Runtime for Tokio {
+    type TcpStream = tokio_util::compat::Compat<tokio::net::TcpStream>;
+
+    fn connect_tcp<'a>(
+        host: &'a str,
+        port: u16,
+        options: &'a SocketOptions,
+    ) -> BoxFuture<'a, io::Result<Self::TcpStream>> {
+        use futures::stream::{FuturesUnordered, StreamExt};
+        use tokio_util::compat::TokioAsyncReadCompatExt;
+
+        let attempt = move |addr: SocketAddr| async move {
+            let socket = tokio::net::TcpSocket::from_std_stream(options.socket(addr)?.into());
+            socket.connect(addr).await
+        };
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((unbracket(host), port)).await?;
+            let mut pending = interleave_families(addrs).into_iter();
+            let mut attempts = FuturesUnordered::new();
+            let mut last_error = None;
+            loop {
+                // Start the next address whenever one fails or stalls
+                if let Some(addr) = pending.next() {
+                    attempts.push(attempt(addr));
+                }
+                if attempts.is_empty() {
+                    break;
+                }
+                let outcome = if pending.len() == 0 {
+                    attempts.next().await
+                } else {
+                    match future::select(attempts.next(), Self::sleep(CONNECTION_ATTEMPT_DELAY))
+                        .await
+                    {
+                        Either::Left((outcome, _)) => outcome,
+                        Either::Right(_) => continue,
+                    }
+                };
+                match outcome {
+                    Some(Ok(stream)) => return Ok(stream.compat()),
+                    Some(Err(e)) => last_error = Some(e),
+                    None => {}
+                }
+            }
+            Err(last_error.unwrap_or_else(|| no_addresses(host)))
+        })
+    }
+
+    #[cfg(unix)]
+    type UnixStream = tokio_util::compat::Compat<tokio::net::UnixStream>;
+
+    #[cfg(unix)]
+    fn connect_unix(path: &Path) -> BoxFuture<'_, io::Result<Self::UnixStream>> {
+        use tokio_util::compat::TokioAsyncReadCompatExt;
+
+        Box::pin(async move { Ok(tokio::net::UnixStream::connect(path).await?.compat()) })
+    }
+
+    fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    type TcpListener = tokio::net::TcpListener;
+
+    fn bind_tcp(addr: SocketAddr) -> BoxFuture<'static, io::Result<Self::TcpListener>> {
+        Box::pin(tokio::net::TcpListener::bind(addr))
+    }
+
+    fn local_addr(listener: &Self::TcpListener) -> io::Result<SocketAddr> {
+        listener.local_addr()
+    }
+
+    fn stream_local_addr(stream: &Self::TcpStream) -> io::Result<SocketAddr> {
+        stream.get_ref().local_addr()
+    }
+
+    fn accept(
+        listener: &Self::TcpListener,
+    ) -> BoxFuture<'_, io::Result<(Self::TcpStream, SocketAddr)>> {
+        use tokio_util::compat::TokioAsyncReadCompatExt;
+
+        Box::pin(async move {
+            let (stream, peer) = listener.accept().await?;
+            Ok((stream.compat(), peer))
+        })
+    }
+
+    fn spawn(future: BoxFuture<'static, ()>) {
+        tokio::spawn(future);
+    }
+
+    type File = tokio_util::compat::Compat<tokio::fs::File>;
+
+    fn open_file(path: &Path) -> BoxFuture<'_, io::Result<Self::File>> {
+        use tokio_util::compat::TokioAsyncReadCompatExt;
+
+        Box::pin(async move { Ok(tokio::fs::File::open(path).await?.compat()) })
+    }
+
+    fn create_file(path: &Path) -> BoxFuture<'_, io::Result<Self::File>> {
+        use tokio_util::compat::TokioAsyncReadCompatExt;
+
+        Box::pin(async move { Ok(tokio::fs::File::create(path).await?.compat()) })
+    }
+}
+
+/// async-std runtime
+#[cfg(feature = "runtime-async-std")]
+#[derive(Debug, Clone, Copy)]
+pub struct AsyncStd;
+
+#[cfg(feature = "runtime-async-std")]
+impl Runtime for AsyncStd {
+    type TcpStream = async_std::net::TcpStream;
+
+    fn connect_tcp<'a>(
+        host: &'a str,
+        port: u16,
+        options: &'a SocketOptions,
+    ) -> BoxFuture<'a, io::Result<Self::TcpStream>> {
+        let (host, options) = (host.to_string(), options.clone());
+        Box::pin(async move {
+            let stream =
+                async_std::task::spawn_blocking(move || options.connect(&host, port)).await?;
+            Ok(stream.into())
+        })
+    }
+
+    #[cfg(unix)]
+    type UnixStream = async_std::os::unix::net::UnixStream;
+
+    #[cfg(unix)]
+    fn connect_unix(path: &Path) -> BoxFuture<'_, io::Result<Self::UnixStream>> {
+        Box::pin(async_std::os::unix::net::UnixStream::connect(path))
+    }
+
+    fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(async_std::task::sleep(duration))
+    }
+
+    type TcpListener = async_std::net::TcpListener;
+
+    fn bind_tcp(addr: SocketAddr) -> BoxFuture<'static, io::Result<Self::TcpListener>> {
+        Box::pin(async_std::net::TcpListener::bind(addr))
+    }
+
+    fn local_addr(listener: &Self::TcpListener) -> io::Result<SocketAddr> {
+        listener.local_addr()
+    }
+
+    fn stream_local_addr(stream: &Self::TcpStream) -> io::Result<SocketAddr> {
+        stream.local_addr()
+    }
+
+    fn accept(
+        listener: &Self::TcpListener,
+    ) -> BoxFuture<'_, io::Result<(Self::TcpStream, SocketAddr)>> {
+        Box::pin(listener.accept())
+    }
+
+    fn spawn(future: BoxFuture<'static, ()>) {
+        async_std::task::spawn(future);
+    }
+
+    type File = async_std::fs::File;
+
+    fn open_file(path: &Path) -> BoxFuture<'_, io::Result<Self::File>> {
+        Box::pin(async_std::fs::File::open(path))
+    }
+
+    fn create_file(path: &Path) -> BoxFuture<'_, io::Result<Self::File>> {
+        Box::pin(async_std::fs::File::create(path))
+    }
+}
+
+/// smol runtime
+#[cfg(feature = "runtime-smol")]
+#[derive(Debug, Clone, Copy)]
+pub struct Smol;
+
+#[cfg(feature = "runtime-smol")]
+impl Runtime for Smol {
+    type TcpStream = smol::net::TcpStream;
+
+    fn connect_tcp<'a>(
+        host: &'a str,
+        port: u16,
+        options: &'a SocketOptions,
+    ) -> BoxFuture<'a, io::Result<Self::TcpStream>> {
+        let (host, options) = (host.to_string(), options.clone());
+        Box::pin(async move {
+            let stream = smol::unblock(move || options.connect(&host, port)).await?;
+            smol::net::TcpStream::try_from(stream)
+        })
+    }
+
+    #[cfg(unix)]
+    type UnixStream = smol::net::unix::UnixStream;
+
+    #[cfg(unix)]
+    fn connect_unix(path: &Path) -> BoxFuture<'_, io::Result<Self::UnixStream>> {
+        Box::pin(smol::net::unix::UnixStream::connect(path))
+    }
+
+    fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(async move {
+            smol::Timer::after(duration).await;
+        })
+    }
+
+    type TcpListener = smol::net::TcpListener;
+
+    fn bind_tcp(addr: SocketAddr) -> BoxFuture<'static, io::Result<Self::TcpListener>> {
+        Box::pin(smol::net::TcpListener::bind(addr))
+    }
+
+    fn local_addr(listener: &Self::TcpListener) -> io::Result<SocketAddr> {
+        listener.local_addr()
+    }
+
+    fn stream_local_addr(stream: &Self::TcpStream) -> io::Result<SocketAddr> {
+        stream.local_addr()
+    }
+
+    fn accept(
+        listener: &Self::TcpListener,
+    ) -> BoxFuture<'_, io::Result<(Self::TcpStream, SocketAddr)>> {
+        Box::pin(listener.accept())
+    }
+
+    fn spawn(future: BoxFuture<'static, ()>) {
+        smol::spawn(future).detach();
+    }
+
+    type File = smol::fs::File;
+
+    fn open_file(path: &Path) -> BoxFuture<'_, io::Result<Self::File>> {
+        Box::pin(smol::fs::File::open(path))
+    }
+
+    fn create_file(path: &Path) -> BoxFuture<'_, io::Result<Self::File>> {
+        Box::pin(smol::fs::File::create(path))
+    }
+}
+
+/// Runtime selected by the enabled features
+#[cfg(feature = "runtime-tokio")]
+pub type DefaultRuntime = Tokio;
+
+/// Runtime selected by the enabled features
+#[cfg(all(feature = "runtime-async-std", not(feature = "runtime-tokio")))]
+pub type DefaultRuntime = AsyncStd;
+
+/// Runtime selected by the enabled features
+#[cfg(all(
+    feature = "runtime-smol",
+    not(any(feature = "runtime-tokio", feature = "runtime-async-std"))
+))]
+pub type DefaultRuntime = Smol;
+
+/// Wait for `duration` on the default runtime
+pub async fn sleep(duration: Duration) {
+    DefaultRuntime::sleep(duration).await
+}
+
+/// Run `future`, failing with [`Error::Timeout`] if it outlasts `duration`
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output> {
+    match future::select(pin!(future), DefaultRuntime::sleep(duration)).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(Error::Timeout),
+    }
+}
+
+/// Async mutex granted first come, first served
+///
+/// Each `lock` takes a ticket and waits for its turn, so a caller cannot
+/// be overtaken by later ones however busy the lock is. A waiter dropped
+/// before its turn gives up its ticket.
+pub struct FairMutex<T> {
+    value: Mutex<T>,
+    turns: std::sync::Mutex<Turns>,
+}
+
+/// Ticket queue of a [`FairMutex`]
+#[derive(Default)]
+struct Turns {
+    /// Next ticket to hand out
+    next: u64,
+    /// Ticket whose turn it is
+    serving: u64,
+    /// Tickets given up before their turn
+    abandoned: HashSet<u64>,
+    /// Wakers of tickets still waiting
+    wakers: HashMap<u64, std::task::Waker>,
+}
+
+impl Turns {
+    /// Pass the turn to the next ticket still waiting
+    fn advance(&mut self) {
+        self.serving += 1;
+        while self.abandoned.remove(&self.serving) {
+            self.serving += 1;
+        }
+        if let Some(waker) = self.wakers.remove(&self.serving) {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> FairMutex<T> {
+    /// Create an unlocked mutex
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Mutex::new(value),
+            turns: std::sync::Mutex::new(Turns::default()),
+        }
+    }
+
+    fn turns(&self) -> std::sync::MutexGuard<'_, Turns> {
+        self.turns.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait for the lock behind every earlier caller
+    pub async fn lock(&self) -> FairMutexGuard<'_, T> {
+        let ticket = {
+            let mut turns = self.turns();
+            turns.next += 1;
+            turns.next - 1
+        };
+        Turn {
+            mutex: self,
+            ticket,
+            done: false,
+        }
+        .await
+    }
+
+    /// Wait for the lock up to `limit`, failing with
+    /// [`Error::ConnectionBusy`] once it passes
+    ///
+    /// A zero `limit` only takes the lock when it is free.
+    pub async fn lock_within(&self, limit: Option<Duration>) -> Result<FairMutexGuard<'_, T>> {
+        match limit {
+            None => Ok(self.lock().await),
+            Some(limit) if limit.is_zero() => self.try_lock().ok_or(Error::ConnectionBusy),
+            Some(limit) => timeout(limit, self.lock())
+                .await
+                .map_err(|_| Error::ConnectionBusy),
+        }
+    }
+
+    /// Take the lock if it is free and nobody is waiting
+    pub fn try_lock(&self) -> Option<FairMutexGuard<'_, T>> {
+        let mut turns = self.turns();
+        if turns.next != turns.serving {
+            return None;
+        }
+        let value = self.value.try_lock()?;
+        turns.next += 1;
+        Some(FairMutexGuard {
+            mutex: self,
+            value: Some(value),
+        })
+    }
+
+    /// Whether the lock is held or waited for
+    pub fn is_locked(&self) -> bool {
+        let turns = self.turns();
+        turns.next != turns.serving
+    }
+}
+
+/// Access to the value of a [`FairMutex`], passed to the next waiter on drop
+pub struct FairMutexGuard<'a, T> {
+    mutex: &'a FairMutex<T>,
+    value: Option<MutexGuard<'a, T>>,
+}
+
+impl<T> std::ops::Deref for FairMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+            .as_ref()
+            .expect("guard holds the value until dropped")
+    }
+}
+
+impl<T> std::ops::DerefMut for FairMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+            .as_mut()
+            .expect("guard holds the value until dropped")
+    }
+}
+
+impl<T> Drop for FairMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        // Unlock the value before the next ticket is woken to take it
+        self.value = None;
+        self.mutex.turns().advance();
+    }
+}
+
+/// Wait for one ticket's turn at a [`FairMutex`]
+struct Turn<'a, T> {
+    mutex: &'a FairMutex<T>,
+    ticket: u64,
+    done: bool,
+}
+
+impl<'a, T> Future for Turn<'a, T> {
+    type Output = FairMutexGuard<'a, T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mutex = self.mutex;
+        let mut turns = mutex.turns();
+        if turns.serving != self.ticket {
+            turns.wakers.insert(self.ticket, cx.waker().clone());
+            return Poll::Pending;
+        }
+        // Only the ticket being served locks the value, so it is free
+        let value = mutex
+            .value
+            .try_lock()
+            .expect("turn holder finds the value unlocked");
+        self.done = true;
+        Poll::Ready(FairMutexGuard {
+            mutex,
+            value: Some(value),
+        })
+    }
+}
+
+impl<T> Drop for Turn<'_, T> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let mut turns = self.mutex.turns();
+        turns.wakers.remove(&self.ticket);
+        if turns.serving == self.ticket {
+            // Woken but never polled again: pass the turn on
+            turns.advance();
+        } else {
+            turns.abandoned.insert(self.ticket);
+        }
+    }
+}
+
+/// Run `future`, failing with [`Error::Cancelled`] once `token` is cancelled
+pub async fn cancellable<F: Future>(token: &CancellationToken, future: F) -> Result<F::Output> {
+    // Cancellation is checked first, so an already-cancelled token wins
+    match future::select(pin!(token.cancelled()), pin!(future)).await {
+        Either::Left(_) => Err(Error::Cancelled),
+        Either::Right((output, _)) => Ok(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fair_mutex_order() {
+        use futures::FutureExt;
+
+        let mutex = std::sync::Arc::new(FairMutex::new(Vec::new()));
+        let held = mutex.lock().await;
+        let mut waiters = Vec::new();
+        for i in 0..4 {
+            let mutex = mutex.clone();
+            waiters.push(tokio::spawn(async move { mutex.lock().await.push(i) }));
+            tokio::task::yield_now().await;
+        }
+        // A waiter that gives up frees its place in the queue
+        let mut abandoned = Box::pin(mutex.lock());
+        assert!((&mut abandoned).now_or_never().is_none());
+        drop(abandoned);
+        assert!(mutex.try_lock().is_none());
+
+        drop(held);
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+        assert_eq!(*mutex.lock().await, [0, 1, 2, 3]);
+        assert!(!mutex.is_locked());
+    }
+
+    #[test]
+    fn test_interleave_families() {
+        let addrs: Vec<SocketAddr> = ["[::1]:1", "[::2]:1", "[::3]:1", "10.0.0.1:1", "10.0.0.2:1"]
+            .iter()
+            .map(|a| a.parse().unwrap())
+            .collect();
+        let ordered: Vec<String> = interleave_families(addrs)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            ordered,
+            ["[::1]:1", "10.0.0.1:1", "[::2]:1", "10.0.0.2:1", "[::3]:1"]
+        );
+        assert!(interleave_families(Vec::new()).is_empty());
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[tokio::test]
+    async fn test_ipv6_connect() {
+        // Hosts without IPv6 cannot bind the loopback; nothing to test there
+        let Ok(listener) = tokio::net::TcpListener::bind("[::1]:0").await else {
+            return;
+        };
+        let port = listener.local_addr().unwrap().port();
+        let stream = Tokio::connect_tcp("[::1]", port, &SocketOptions::default())
+            .await
+            .unwrap();
+        assert!(stream.get_ref().peer_addr().unwrap().is_ipv6());
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let value = timeout(Duration::from_secs(1), async { 7 }).await.unwrap();
+        assert_eq!(value, 7);
+
+        let result = timeout(Duration::from_millis(10), future::pending::<()>()).await;
+        assert!(matches!(result, Err(Error::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn test_cancellable() {
+        let token = CancellationToken::new();
+        assert_eq!(cancellable(&token, async { 7 }).await.unwrap(), 7);
+
+        token.cancel();
+        let result = cancellable(&token, async { 7 }).await;
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[tokio::test]
+    async fn test_socket_options_applied() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let options = SocketOptions::default().recv_buffer_size(256 * 1024);
+        let stream = Tokio::connect_tcp("127.0.0.1", port, &options)
+            .await
+            .unwrap();
+        let socket = socket2::SockRef::from(stream.get_ref());
+        assert!(socket.tcp_nodelay().unwrap());
+        assert!(socket.recv_buffer_size().unwrap() >= 256 * 1024);
+        assert!(!socket.keepalive().unwrap());
+
+        let options = SocketOptions::default().keepalive(Duration::from_secs(120));
+        let stream = Tokio::connect_tcp("127.0.0.1", port, &options)
+            .await
+            .unwrap();
+        let socket = socket2::SockRef::from(stream.get_ref());
+        assert!(socket.keepalive().unwrap());
+        assert_eq!(
+            socket.tcp_keepalive_time().unwrap(),
+            Duration::from_secs(120)
+        );
+
+        let options = SocketOptions::default().nodelay(false);
+        let stream = Tokio::connect_tcp("127.0.0.1", port, &options)
+            .await
+            .unwrap();
+        assert!(!stream.get_ref().nodelay().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_semaphore_guard() {
+        let semaphore = std::sync::Arc::new(Semaphore::new(1));
+        let guard = semaphore.acquire_arc().await;
+        assert!(semaphore.try_acquire().is_none());
+        drop(guard);
+        assert!(semaphore.try_acquire().is_some());
+    }
+}
@@ -0,0 +1,547 @@
+// SeaORM driver adapter
+
+//! SeaORM support: [`connect`] and [`connect_pool`] return a
+//! `sea_orm::DatabaseConnection` whose statements run on this driver.
+//!
+//! SeaORM has no Oracle backend, so the adapter plugs into its proxy
+//! connector with the SQLite dialect as the carrier. Every statement is
+//! rewritten into Oracle SQL by the same rules as [`crate::sea_query`]
+//! before it reaches the server.
+//!
+//! Mapping rules:
+//!
+//! - entity and column names are upper-cased (`cake.name` reads
+//!   `"CAKE"."NAME"`), and all-upper-case result columns come back
+//!   lower-cased so entity fields find them
+//! - `NUMBER(1..9)` decodes as `i32`, wider integer `NUMBER` as `i64`,
+//!   `NUMBER` without precision as the smallest integer type that fits, and
+//!   scaled `NUMBER` or `BINARY_DOUBLE` as `f64`
+//! - Oracle reports no auto-increment id, so `last_insert_id` is always 0;
+//!   set primary keys explicitly, e.g. from a sequence
+//! - schema statements SeaORM generates are SQLite DDL; create tables with
+//!   plain SQL or the `migrations` module instead
+//!
+//! SeaORM's proxy interface cannot report errors from `begin`, `commit` or
+//! `rollback`. Such a failure is logged and returned by the next statement
+//! on the connection.
+//!
+//! Over a [`Pool`], statements outside a transaction each check out a
+//! connection. A transaction pins one connection until it ends, and every
+//! statement on the same `DatabaseConnection` runs on it meanwhile.
+//!
+//! ```rust,ignore
+//! use sea_orm::{EntityTrait, TransactionTrait};
+//!
+//! let db = oracledb_rs::sea_orm::connect_pool(pool).await?;
+//! let txn = db.begin().await?;
+//! let cakes = cake::Entity::find().all(&txn).await?;
+//! txn.commit().await?;
+//! ```
+
+use crate::pool::{Pool, PooledConnection};
+use crate::protocol::Protocol;
+use crate::statement::Row;
+use crate::types::{ColumnInfo, OracleType, ToSql, Value};
+use crate::Connection;
+use ::sea_orm::sea_query::Value as SeaValue;
+use ::sea_orm::{
+    Database, DatabaseConnection, DbBackend, DbErr, ProxyDatabaseTrait, ProxyExecResult, ProxyRow,
+    RuntimeErr, Statement,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Connect SeaORM to a single driver connection
+pub async fn connect(connection: Connection) -> Result<DatabaseConnection, DbErr> {
+    OracleProxy::new(connection).into_database().await
+}
+
+/// Connect SeaORM to a connection pool
+pub async fn connect_pool(pool: Pool) -> Result<DatabaseConnection, DbErr> {
+    OracleProxy::from_pool(pool).into_database().await
+}
+
+/// SeaORM proxy that executes statements through this driver
+pub struct OracleProxy {
+    pool: Option<Pool>,
+    state: Mutex<ProxyState>,
+    /// Rollbacks requested by `start_rollback`, run before the next statement
+    pending_rollbacks: AtomicUsize,
+}
+
+struct ProxyState {
+    session: Option<Session>,
+    depth: usize,
+    deferred_error: Option<crate::Error>,
+}
+
+enum Session {
+    Owned(Box<Connection>),
+    Pooled(Box<PooledConnection>),
+}
+
+impl Deref for Session {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            Session::Owned(conn) => conn,
+            Session::Pooled(conn) => conn,
+        }
+    }
+}
+
+impl DerefMut for Session {
+    fn deref_mut(&mut self) -> &mut Connection {
+        match self {
+            Session::Owned(conn) => conn,
+            Session::Pooled(conn) => conn,
+        }
+    }
+}
+
+/// Connection a single statement runs on
+enum Handle<'a> {
+    Pinned(MutexGuard<'a, ProxyState>),
+    Checkout(Box<PooledConnection>),
+}
+
+impl Deref for Handle<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            Handle::Pinned(state) => state.session.as_deref().expect("pinned session"),
+            Handle::Checkout(conn) => conn,
+        }
+    }
+}
+
+impl OracleProxy {
+    /// Create a proxy over a single connection
+    pub fn new(connection: Connection) -> Self {
+        Self::with_session(None, Some(Session::Owned(Box::new(connection))))
+    }
+
+    /// Create a proxy over a connection pool
+    pub fn from_pool(pool: Pool) -> Self {
+        Self::with_session(Some(pool), None)
+    }
+
+    fn with_session(pool: Option<Pool>, session: Option<Session>) -> Self {
+        Self {
+            pool,
+            state: Mutex::new(ProxyState {
+                session,
+                depth: 0,
+                deferred_error: None,
+            }),
+            pending_rollbacks: AtomicUsize::new(0),
+        }
+    }
+
+    /// Wrap the proxy in a SeaORM `DatabaseConnection`
+    pub async fn into_database(self) -> Result<DatabaseConnection, DbErr> {
+        Database::connect_proxy(DbBackend::Sqlite, Arc::new(Box::new(self))).await
+    }
+
+    /// Run requested rollbacks and surface deferred errors
+    async fn settle(&self) -> crate::Result<MutexGuard<'_, ProxyState>> {
+        let mut state = self.state.lock().await;
+        let mut result = Ok(());
+        for _ in 0..self.pending_rollbacks.swap(0, Ordering::AcqRel) {
+            result = result.and(self.rollback_level(&mut state).await);
+        }
+        result?;
+        match state.deferred_error.take() {
+            Some(err) => Err(err),
+            None => Ok(state),
+        }
+    }
+
+    async fn connection(&self) -> crate::Result<Handle<'_>> {
+        let state = self.settle().await?;
+        if state.session.is_some() {
+            return Ok(Handle::Pinned(state));
+        }
+        drop(state);
+
+        let pool = self.pool.as_ref().ok_or(crate::Error::ConnectionClosed)?;
+        Ok(Handle::Checkout(Box::new(pool.get_connection().await?)))
+    }
+
+    async fn begin_level(&self, state: &mut ProxyState) -> crate::Result<()> {
+        // Oracle starts transactions implicitly; nested levels are savepoints
+        state.depth += 1;
+        if state.depth == 1 {
+            if let (Some(pool), None) = (&self.pool, &state.session) {
+                state.session = Some(Session::Pooled(Box::new(pool.get_connection().await?)));
+            }
+            return Ok(());
+        }
+
+        let sql = format!("SAVEPOINT sea_orm_sp_{}", state.depth - 1);
+        let session = state
+            .session
+            .as_ref()
+            .ok_or(crate::Error::ConnectionClosed)?;
+        session.execute_dml(&sql, &[]).await.map(drop)
+    }
+
+    async fn commit_level(&self, state: &mut ProxyState) -> crate::Result<()> {
+        let depth = state.depth;
+        state.depth = depth.saturating_sub(1);
+        // Oracle has no RELEASE SAVEPOINT; inner commits just close the scope
+        if depth != 1 {
+            return Ok(());
+        }
+
+        let session = state
+            .session
+            .as_mut()
+            .ok_or(crate::Error::ConnectionClosed)?;
+        let result = session.commit().await;
+        self.unpin(state);
+        result
+    }
+
+    async fn rollback_level(&self, state: &mut ProxyState) -> crate::Result<()> {
+        let depth = state.depth;
+        if depth == 0 {
+            return Ok(());
+        }
+        state.depth -= 1;
+
+        let session = state
+            .session
+            .as_mut()
+            .ok_or(crate::Error::ConnectionClosed)?;
+        if depth == 1 {
+            let result = session.rollback().await;
+            self.unpin(state);
+            result
+        } else {
+            let sql = format!("ROLLBACK TO SAVEPOINT sea_orm_sp_{}", depth - 1);
+            session.execute_dml(&sql, &[]).await.map(drop)
+        }
+    }
+
+    /// Return a pinned pool connection once its transaction ends
+    fn unpin(&self, state: &mut ProxyState) {
+        if self.pool.is_some() {
+            state.session = None;
+        }
+    }
+
+    /// Keep a transaction-control failure for the next statement
+    async fn defer(&self, action: &str, result: crate::Result<()>) {
+        if let Err(err) = result {
+            log::warn!("sea-orm {} failed: {}", action, err);
+            self.state.lock().await.deferred_error = Some(err);
+        }
+    }
+}
+
+impl fmt::Debug for OracleProxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OracleProxy")
+            .field("pooled", &self.pool.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl ProxyDatabaseTrait for OracleProxy {
+    async fn query(&self, statement: Statement) -> Result<Vec<ProxyRow>, DbErr> {
+        let (sql, values) = translate(statement);
+        let params: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+
+        let conn = self.connection().await.map_err(query_err)?;
+        let result = conn.query(&sql, &params).await.map_err(query_err)?;
+        result
+            .rows()
+            .iter()
+            .map(|row| to_proxy_row(row, result.metadata()).map_err(query_err))
+            .collect()
+    }
+
+    async fn execute(&self, statement: Statement) -> Result<ProxyExecResult, DbErr> {
+        let (sql, values) = translate(statement);
+        let params: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+
+        let conn = self.connection().await.map_err(exec_err)?;
+        let rows_affected = match Protocol::parse_statement_type(&sql).map_err(exec_err)? {
+            t if t.is_dml() => conn.execute_dml(&sql, &params).await.map_err(exec_err)?,
+            _ => {
+                conn.execute(&sql, &params).await.map_err(exec_err)?;
+                0
+            }
+        };
+        Ok(ProxyExecResult::new(0, rows_affected))
+    }
+
+    async fn begin(&self) {
+        let result = match self.settle().await {
+            Ok(mut state) => self.begin_level(&mut state).await,
+            Err(err) => Err(err),
+        };
+        self.defer("begin", result).await;
+    }
+
+    async fn commit(&self) {
+        let result = match self.settle().await {
+            Ok(mut state) => self.commit_level(&mut state).await,
+            Err(err) => Err(err),
+        };
+        self.defer("commit", result).await;
+    }
+
+    async fn rollback(&self) {
+        let result = match self.settle().await {
+            Ok(mut state) => self.rollback_level(&mut state).await,
+            Err(err) => Err(err),
+        };
+        self.defer("rollback", result).await;
+    }
+
+    fn start_rollback(&self) {
+        self.pending_rollbacks.fetch_add(1, Ordering::AcqRel);
+    }
+
+    async fn ping(&self) -> Result<(), DbErr> {
+        let conn = self.connection().await.map_err(conn_err)?;
+        conn.ping().await.map_err(conn_err)
+    }
+}
+
+/// Rewrite a SeaORM (SQLite dialect) statement into Oracle SQL
+fn translate(statement: Statement) -> (String, Vec<Value>) {
+    let values = statement.values.map(|v| v.0).unwrap_or_default();
+    crate::sea_query::rewrite(&statement.sql, values)
+}
+
+fn query_err(err: crate::Error) -> DbErr {
+    DbErr::Query(RuntimeErr::Internal(err.to_string()))
+}
+
+fn exec_err(err: crate::Error) -> DbErr {
+    DbErr::Exec(RuntimeErr::Internal(err.to_string()))
+}
+
+fn conn_err(err: crate::Error) -> DbErr {
+    DbErr::Conn(RuntimeErr::Internal(err.to_string()))
+}
+
+fn to_proxy_row(row: &Row, columns: &[ColumnInfo]) -> crate::Result<ProxyRow> {
+    let values: BTreeMap<String, SeaValue> = columns
+        .iter()
+        .zip(row.try_values()?)
+        .map(|(column, value)| (column_key(&column.name), to_sea_value(value, column)))
+        .collect();
+    Ok(ProxyRow::new(values))
+}
+
+/// Result key for a column: Oracle's upper-case names map to SeaORM's lower-case
+fn column_key(name: &str) -> String {
+    if name.chars().any(|c| c.is_ascii_uppercase()) && !name.chars().any(|c| c.is_lowercase()) {
+        name.to_ascii_lowercase()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Integer width a `NUMBER` column decodes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberKind {
+    Int,
+    BigInt,
+    Double,
+    /// No declared precision: smallest type that fits the value
+    Fitted,
+}
+
+fn number_kind(column: &ColumnInfo) -> NumberKind {
+    match (column.precision, column.scale) {
+        (Some(1..=9), Some(0) | None) => NumberKind::Int,
+        (Some(_), Some(0) | None) => NumberKind::BigInt,
+        (None, Some(0) | None) => NumberKind::Fitted,
+        _ => NumberKind::Double,
+    }
+}
+
+fn to_sea_value(value: &Value, column: &ColumnInfo) -> SeaValue {
+    match value {
+        Value::Null => null_value(column),
+        Value::Integer(i) => match number_kind(column) {
+            NumberKind::Double => SeaValue::Double(Some(*i as f64)),
+            NumberKind::BigInt => SeaValue::BigInt(Some(*i)),
+            _ => match i32::try_from(*i) {
+                Ok(i) => SeaValue::Int(Some(i)),
+                Err(_) => SeaValue::BigInt(Some(*i)),
+            },
+        },
+        Value::Float(f) if column.oracle_type == OracleType::BinaryFloat => {
+            SeaValue::Float(Some(*f as f32))
+        }
+        Value::Float(f) => SeaValue::Double(Some(*f)),
+        Value::Boolean(b) => SeaValue::Bool(Some(*b)),
+        Value::String(s) | Value::Clob(s) => SeaValue::String(Some(Box::new(s.clone()))),
+        Value::Bytes(b) | Value::Blob(b) => SeaValue::Bytes(Some(Box::new(b.clone()))),
+        Value::Lob(lob) => SeaValue::Bytes(lob.data().map(|data| Box::new(data.to_vec()))),
+        Value::Date(d) => SeaValue::ChronoDate(Some(Box::new(*d))),
+        Value::Timestamp(ts) => SeaValue::ChronoDateTime(Some(Box::new(*ts))),
+        Value::TimestampTz(ts) => SeaValue::ChronoDateTimeUtc(Some(Box::new(*ts))),
+        #[cfg(feature = "chrono-tz")]
+        Value::TimestampTzRegion(ts) => SeaValue::ChronoDateTimeUtc(Some(Box::new(ts.utc()))),
+        Value::IntervalYM(i) => SeaValue::String(Some(Box::new(i.to_string()))),
+        Value::IntervalDS(i) => SeaValue::String(Some(Box::new(i.to_string()))),
+        Value::Json(j) => SeaValue::Json(Some(Box::new(j.clone()))),
+        Value::Vector(_) => SeaValue::Json(Some(Box::new(crate::result::value_to_json(value)))),
+        Value::Array(_) | Value::Object(_) => {
+            SeaValue::Json(Some(Box::new(crate::result::value_to_json(value))))
+        }
+    }
+}
+
+/// Typed NULL for a column, so `Option<T>` fields decode
+fn null_value(column: &ColumnInfo) -> SeaValue {
+    match column.oracle_type {
+        OracleType::Number => match number_kind(column) {
+            NumberKind::BigInt => SeaValue::BigInt(None),
+            NumberKind::Double => SeaValue::Double(None),
+            NumberKind::Int | NumberKind::Fitted => SeaValue::Int(None),
+        },
+        OracleType::BinaryFloat => SeaValue::Float(None),
+        OracleType::BinaryDouble => SeaValue::Double(None),
+        OracleType::Raw | OracleType::LongRaw | OracleType::Blob | OracleType::BFile => {
+            SeaValue::Bytes(None)
+        }
+        OracleType::Date => SeaValue::ChronoDate(None),
+        OracleType::Timestamp | OracleType::TimestampLtz => SeaValue::ChronoDateTime(None),
+        OracleType::TimestampTz => SeaValue::ChronoDateTimeUtc(None),
+        OracleType::Json | OracleType::Object => SeaValue::Json(None),
+        OracleType::Boolean => SeaValue::Bool(None),
+        _ => SeaValue::String(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::sea_orm::sea_query::{Alias, Expr, Query};
+    use ::sea_orm::ConnectionTrait;
+
+    #[test]
+    fn test_statement_translation() {
+        let stmt = DbBackend::Sqlite.build(
+            Query::select()
+                .column((Alias::new("cake"), Alias::new("name")))
+                .from(Alias::new("cake"))
+                .and_where(Expr::col((Alias::new("cake"), Alias::new("id"))).eq(3))
+                .limit(1),
+        );
+        let (sql, values) = translate(stmt);
+
+        assert_eq!(
+            sql,
+            "SELECT \"CAKE\".\"NAME\" FROM \"CAKE\" WHERE \"CAKE\".\"ID\" = :1 FETCH NEXT :2 ROWS ONLY"
+        );
+        assert!(matches!(
+            values.as_slice(),
+            [Value::Integer(3), Value::Integer(1)]
+        ));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_row_conversion() {
+        use crate::testing::column;
+
+        let number = |name, precision, scale| ColumnInfo {
+            precision,
+            scale,
+            ..column(name, OracleType::Number)
+        };
+        let columns = vec![
+            number("QTY", Some(5), Some(0)),
+            number("ID", Some(10), Some(0)),
+            number("NUM_ITEMS", None, None),
+            number("PRICE", Some(8), Some(2)),
+            column("NOTE", OracleType::Varchar2),
+            column("MixedCase", OracleType::BinaryFloat),
+        ];
+        let row = Row::new(
+            vec![
+                Value::Integer(4),
+                Value::Integer(42),
+                Value::Integer(7),
+                Value::Float(9.5),
+                Value::Null,
+                Value::Float(1.5),
+            ],
+            columns.iter().map(|c| c.name.clone()).collect(),
+        );
+        let row = to_proxy_row(&row, &columns).unwrap();
+
+        assert_eq!(row.try_get::<i32, _>("qty").unwrap(), 4);
+        assert_eq!(row.try_get::<i64, _>("id").unwrap(), 42);
+        assert_eq!(row.try_get::<i32, _>("num_items").unwrap(), 7);
+        assert_eq!(row.try_get::<f64, _>("price").unwrap(), 9.5);
+        assert_eq!(row.try_get::<Option<String>, _>("note").unwrap(), None);
+        assert_eq!(row.try_get::<f32, _>("MixedCase").unwrap(), 1.5);
+        assert!(row.try_get::<i32, _>("id").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transaction_bookkeeping() {
+        let config = crate::ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let proxy = OracleProxy::new(Connection::connect(config).await.unwrap());
+
+        // The mock protocol never authenticates, so the savepoint fails
+        proxy.begin().await;
+        proxy.begin().await;
+        {
+            let state = proxy.state.lock().await;
+            assert_eq!(state.depth, 2);
+            assert!(state.deferred_error.is_some());
+        }
+
+        // The deferred error is reported once, by the next statement
+        let stmt = Statement::from_string(DbBackend::Sqlite, "SELECT 1");
+        assert!(matches!(proxy.query(stmt).await, Err(DbErr::Query(_))));
+        assert!(proxy.state.lock().await.deferred_error.is_none());
+
+        // Dropped transactions unwind every level even when a rollback fails
+        proxy.start_rollback();
+        proxy.start_rollback();
+        assert!(matches!(proxy.ping().await, Err(DbErr::Conn(_))));
+        assert_eq!(proxy.state.lock().await.depth, 0);
+
+        let db = proxy.into_database().await.unwrap();
+        let stmt = Statement::from_string(DbBackend::Sqlite, "DELETE FROM t");
+        assert!(matches!(db.execute(stmt).await, Err(DbErr::Exec(_))));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_nested_transactions() {
+        let proxy = OracleProxy::new(crate::testing::unscripted().await.unwrap());
+
+        proxy.begin().await;
+        proxy.begin().await;
+        proxy.rollback().await;
+        proxy.begin().await;
+        proxy.commit().await;
+        {
+            let state = proxy.state.lock().await;
+            assert_eq!(state.depth, 1);
+            assert!(state.deferred_error.is_none());
+        }
+        proxy.commit().await;
+        assert_eq!(proxy.state.lock().await.depth, 0);
+    }
+}
@@ -0,0 +1,535 @@
+// sea-query Oracle query builder
+
+//! sea-query support: [`build`] renders any sea-query statement as Oracle
+//! SQL with positional `:N` binds and driver [`Value`]s.
+//!
+//! Oracle-specific SQL generation:
+//!
+//! - `.limit()` / `.offset()` render as `OFFSET n ROWS FETCH NEXT m ROWS ONLY`
+//! - `SELECT` without a table reads from `DUAL`
+//! - table and subquery aliases are written without `AS`
+//! - unquoted-style identifiers (`users`, `EMP_ID`) are upper-cased, matching
+//!   how Oracle stores them in the data dictionary; mixed-case names are
+//!   quoted verbatim
+//! - `IFNULL`, `CHAR_LENGTH` and `RANDOM` become `NVL`, `LENGTH` and
+//!   `DBMS_RANDOM.VALUE`
+//!
+//! [`OracleQueryBuilder`] on its own emits the generic form that [`build`]
+//! rewrites, so pass statements through [`build`] rather than calling
+//! `statement.build(OracleQueryBuilder)` directly. `RETURNING` and
+//! `ON CONFLICT` have no Oracle equivalent and are rejected by the server.
+//!
+//! ```rust,ignore
+//! use oracledb_rs::sea_query::build;
+//! use oracledb_rs::types::ToSql;
+//! use sea_query::{Alias, Expr, Query};
+//!
+//! let (sql, values) = build(
+//!     Query::select()
+//!         .column(Alias::new("first_name"))
+//!         .from(Alias::new("employees"))
+//!         .and_where(Expr::col(Alias::new("employee_id")).gt(100))
+//!         .limit(10),
+//! );
+//! // SELECT "FIRST_NAME" FROM "EMPLOYEES" WHERE "EMPLOYEE_ID" > :1 FETCH NEXT :2 ROWS ONLY
+//! let params: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+//! let rows = conn.query(&sql, &params).await?;
+//! ```
+
+use crate::types::Value;
+use ::sea_query::{
+    BinOper, EscapeBuilder, Oper, OperLeftAssocDecider, PrecedenceDecider, QueryBuilder,
+    QueryStatementWriter, Quote, QuotedBuilder, SimpleExpr, SqlWriter, SqliteQueryBuilder,
+    SubQueryStatement, TableRefBuilder, Value as SeaValue,
+};
+use chrono::Utc;
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+/// Build a sea-query statement into Oracle SQL and bind values
+pub fn build<S: QueryStatementWriter>(statement: &S) -> (String, Vec<Value>) {
+    let (sql, values) = statement.build(OracleQueryBuilder);
+    rewrite(&sql, values.0)
+}
+
+/// sea-query builder for the Oracle dialect
+///
+/// Emits `?` placeholders and ANSI paging; [`build`] turns the result into
+/// Oracle SQL.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OracleQueryBuilder;
+
+impl QuotedBuilder for OracleQueryBuilder {
+    fn quote(&self) -> Quote {
+        Quote::new(b'"')
+    }
+}
+
+impl EscapeBuilder for OracleQueryBuilder {
+    fn escape_string(&self, string: &str) -> String {
+        string.replace('\'', "''")
+    }
+
+    fn unescape_string(&self, string: &str) -> String {
+        string.replace("''", "'")
+    }
+}
+
+impl TableRefBuilder for OracleQueryBuilder {}
+
+impl PrecedenceDecider for OracleQueryBuilder {
+    fn inner_expr_well_known_greater_precedence(
+        &self,
+        inner: &SimpleExpr,
+        outer_oper: &Oper,
+    ) -> bool {
+        SqliteQueryBuilder.inner_expr_well_known_greater_precedence(inner, outer_oper)
+    }
+}
+
+impl OperLeftAssocDecider for OracleQueryBuilder {
+    fn well_known_left_associative(&self, op: &BinOper) -> bool {
+        SqliteQueryBuilder.well_known_left_associative(op)
+    }
+}
+
+impl QueryBuilder for OracleQueryBuilder {
+    fn prepare_query_statement(&self, query: &SubQueryStatement, sql: &mut dyn SqlWriter) {
+        match query {
+            SubQueryStatement::SelectStatement(stmt) => self.prepare_select_statement(stmt, sql),
+            SubQueryStatement::InsertStatement(stmt) => self.prepare_insert_statement(stmt, sql),
+            SubQueryStatement::UpdateStatement(stmt) => self.prepare_update_statement(stmt, sql),
+            SubQueryStatement::DeleteStatement(stmt) => self.prepare_delete_statement(stmt, sql),
+            SubQueryStatement::WithStatement(stmt) => self.prepare_with_query(stmt, sql),
+        }
+    }
+
+    fn prepare_value(&self, value: &SeaValue, sql: &mut dyn SqlWriter) {
+        sql.push_param(value.clone(), self as _);
+    }
+
+    fn write_bytes(&self, bytes: &[u8], buffer: &mut String) {
+        buffer.push_str("HEXTORAW('");
+        for b in bytes {
+            write!(buffer, "{:02X}", b).unwrap();
+        }
+        buffer.push_str("')");
+    }
+
+    fn if_null_function(&self) -> &str {
+        "NVL"
+    }
+
+    fn char_length_function(&self) -> &str {
+        "LENGTH"
+    }
+
+    fn random_function(&self) -> &str {
+        "DBMS_RANDOM.VALUE"
+    }
+}
+
+/// Convert a sea-query value into a driver value
+pub(crate) fn to_value(value: SeaValue) -> Value {
+    let value = match value {
+        SeaValue::Bool(v) => v.map(Value::Boolean),
+        SeaValue::TinyInt(v) => v.map(|v| Value::Integer(v.into())),
+        SeaValue::SmallInt(v) => v.map(|v| Value::Integer(v.into())),
+        SeaValue::Int(v) => v.map(|v| Value::Integer(v.into())),
+        SeaValue::BigInt(v) => v.map(Value::Integer),
+        SeaValue::TinyUnsigned(v) => v.map(|v| Value::Integer(v.into())),
+        SeaValue::SmallUnsigned(v) => v.map(|v| Value::Integer(v.into())),
+        SeaValue::Unsigned(v) => v.map(|v| Value::Integer(v.into())),
+        // NUMBER holds the full u64 range; the server converts the text
+        SeaValue::BigUnsigned(v) => v.map(|v| match i64::try_from(v) {
+            Ok(i) => Value::Integer(i),
+            Err(_) => Value::String(v.to_string()),
+        }),
+        SeaValue::Float(v) => v.map(|v| Value::Float(v.into())),
+        SeaValue::Double(v) => v.map(Value::Float),
+        SeaValue::String(v) => v.map(|s| Value::String(*s)),
+        SeaValue::Char(v) => v.map(|c| Value::String(c.to_string())),
+        SeaValue::Bytes(v) => v.map(|b| Value::Bytes(*b)),
+        SeaValue::Json(v) => v.map(|j| Value::Json(*j)),
+        SeaValue::ChronoDate(v) => v.map(|d| Value::Date(*d)),
+        // Oracle has no TIME type; times travel as text
+        SeaValue::ChronoTime(v) => v.map(|t| Value::String(t.to_string())),
+        SeaValue::ChronoDateTime(v) => v.map(|ts| Value::Timestamp(*ts)),
+        SeaValue::ChronoDateTimeUtc(v) => v.map(|ts| Value::TimestampTz(*ts)),
+        SeaValue::ChronoDateTimeLocal(v) => v.map(|ts| Value::TimestampTz(ts.with_timezone(&Utc))),
+        SeaValue::ChronoDateTimeWithTimeZone(v) => {
+            v.map(|ts| Value::TimestampTz(ts.with_timezone(&Utc)))
+        }
+        // Variants enabled by other crates in the build (uuid, decimal, ...)
+        #[allow(unreachable_patterns)]
+        other => match ::sea_query::sea_value_to_json_value(&other) {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some(Value::String(s)),
+            json => Some(Value::Json(json)),
+        },
+    };
+    value.unwrap_or(Value::Null)
+}
+
+/// Lexical token of generated SQL
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Keyword, function name or number
+    Word(String),
+    /// Quoted identifier, already case-folded and re-quoted
+    Ident(String),
+    /// String literal including its quotes
+    Literal(String),
+    /// `?` placeholder, by position in the source statement
+    Param(usize),
+    Open,
+    Close,
+    Space,
+    Punct(char),
+}
+
+/// Quote an identifier, upper-casing names Oracle would store upper-cased
+fn quote_identifier(name: &str) -> String {
+    let simple = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '#'));
+    let single_case = name == name.to_ascii_lowercase() || name == name.to_ascii_uppercase();
+
+    if simple && single_case {
+        format!("\"{}\"", name.to_ascii_uppercase())
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+fn tokenize(sql: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+    let mut params = 0;
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\'' => {
+                let mut literal = String::from('\'');
+                while let Some(c) = chars.next() {
+                    literal.push(c);
+                    if c == '\'' {
+                        match chars.next_if_eq(&'\'') {
+                            Some(quote) => literal.push(quote),
+                            None => break,
+                        }
+                    }
+                }
+                Token::Literal(literal)
+            }
+            '"' => {
+                let mut name = String::new();
+                while let Some(c) = chars.next() {
+                    if c == '"' {
+                        match chars.next_if_eq(&'"') {
+                            Some(quote) => name.push(quote),
+                            None => break,
+                        }
+                    } else {
+                        name.push(c);
+                    }
+                }
+                Token::Ident(quote_identifier(&name))
+            }
+            '?' => {
+                params += 1;
+                Token::Param(params - 1)
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_whitespace() => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                Token::Space
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some(c) =
+                    chars.next_if(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '#'))
+                {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+            c => Token::Punct(c),
+        };
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Rewrite generic sea-query SQL with `?` binds into Oracle SQL
+///
+/// Binds are renumbered `:1..:N` in the order they appear in the output, and
+/// `values` are reordered to match.
+pub(crate) fn rewrite(sql: &str, values: Vec<SeaValue>) -> (String, Vec<Value>) {
+    let mut tokens = tokenize(sql).into_iter().peekable();
+    let mut out = Vec::new();
+    while tokens.peek().is_some() {
+        // A stray `)` ends the level early; keep going at the top level
+        rewrite_level(&mut tokens, &mut out);
+    }
+
+    let mut values: Vec<Option<SeaValue>> = values.into_iter().map(Some).collect();
+    let mut sql = String::with_capacity(sql.len());
+    let mut binds = Vec::with_capacity(values.len());
+    for token in out {
+        match token {
+            Token::Word(s) | Token::Ident(s) | Token::Literal(s) => sql.push_str(&s),
+            Token::Param(index) => {
+                let value = values.get_mut(index).and_then(Option::take);
+                binds.push(value.map(to_value).unwrap_or(Value::Null));
+                write!(sql, ":{}", binds.len()).unwrap();
+            }
+            Token::Open => sql.push('('),
+            Token::Close => sql.push(')'),
+            Token::Space => sql.push(' '),
+            Token::Punct(c) => sql.push(c),
+        }
+    }
+    (sql, binds)
+}
+
+/// Rewrite one parenthesis level, consuming its closing `)`
+fn rewrite_level(tokens: &mut Peekable<IntoIter<Token>>, out: &mut Vec<Token>) {
+    let mut in_from = false;
+    let mut needs_dual = false;
+
+    while let Some(token) = tokens.next() {
+        let word = match token {
+            Token::Open => {
+                out.push(Token::Open);
+                rewrite_level(tokens, out);
+                continue;
+            }
+            Token::Close => {
+                add_dual(&mut needs_dual, out);
+                out.push(Token::Close);
+                return;
+            }
+            Token::Word(word) => word,
+            token => {
+                out.push(token);
+                continue;
+            }
+        };
+
+        match word.to_ascii_uppercase().as_str() {
+            "SELECT" => {
+                needs_dual = true;
+                in_from = false;
+            }
+            "FROM" => {
+                needs_dual = false;
+                in_from = true;
+            }
+            "JOIN" => in_from = true,
+            "AS" if in_from => {
+                tokens.next_if_eq(&Token::Space);
+                continue;
+            }
+            "LIMIT" | "OFFSET" => {
+                add_dual(&mut needs_dual, out);
+                rewrite_paging(&word, tokens, out);
+                in_from = false;
+                continue;
+            }
+            "WHERE" | "ORDER" | "FOR" | "UNION" | "INTERSECT" | "EXCEPT" | "MINUS" => {
+                add_dual(&mut needs_dual, out);
+                in_from = false;
+            }
+            "ON" | "GROUP" | "HAVING" | "WINDOW" | "SET" | "VALUES" | "USING" => in_from = false,
+            _ => {}
+        }
+        out.push(Token::Word(word));
+    }
+    add_dual(&mut needs_dual, out);
+}
+
+/// Close a `SELECT` that has no `FROM` with `FROM DUAL`
+fn add_dual(needs_dual: &mut bool, out: &mut Vec<Token>) {
+    if std::mem::take(needs_dual) {
+        let at = out.len() - out.iter().rev().take_while(|t| **t == Token::Space).count();
+        let dual = [
+            Token::Space,
+            Token::Word("FROM".into()),
+            Token::Space,
+            Token::Word("DUAL".into()),
+        ];
+        out.splice(at..at, dual);
+    }
+}
+
+/// Turn `LIMIT m [OFFSET n]` into `OFFSET n ROWS FETCH NEXT m ROWS ONLY`
+fn rewrite_paging(first: &str, tokens: &mut Peekable<IntoIter<Token>>, out: &mut Vec<Token>) {
+    let mut limit = None;
+    let mut offset = None;
+    let mut keyword = first.to_ascii_uppercase();
+    let mut trailing_space;
+
+    loop {
+        let mut expr = Vec::new();
+        let mut depth = 0usize;
+        while let Some(token) = tokens.peek() {
+            match token {
+                Token::Close if depth == 0 => break,
+                Token::Word(w)
+                    if depth == 0
+                        && matches!(
+                            w.to_ascii_uppercase().as_str(),
+                            "LIMIT" | "OFFSET" | "FOR"
+                        ) =>
+                {
+                    break
+                }
+                Token::Open => depth += 1,
+                Token::Close => depth -= 1,
+                _ => {}
+            }
+            expr.extend(tokens.next());
+        }
+        while expr.first() == Some(&Token::Space) {
+            expr.remove(0);
+        }
+        trailing_space = expr.last() == Some(&Token::Space);
+        while expr.last() == Some(&Token::Space) {
+            expr.pop();
+        }
+
+        if keyword == "LIMIT" {
+            limit = Some(expr);
+        } else {
+            offset = Some(expr);
+        }
+
+        match tokens.peek() {
+            Some(Token::Word(w))
+                if matches!(w.to_ascii_uppercase().as_str(), "LIMIT" | "OFFSET") =>
+            {
+                keyword = w.to_ascii_uppercase();
+                tokens.next();
+            }
+            _ => break,
+        }
+    }
+
+    if let Some(offset) = offset {
+        out.push(Token::Word("OFFSET".into()));
+        out.push(Token::Space);
+        out.extend(offset);
+        out.push(Token::Space);
+        out.push(Token::Word("ROWS".into()));
+        if limit.is_some() {
+            out.push(Token::Space);
+        }
+    }
+    if let Some(limit) = limit {
+        for word in ["FETCH", "NEXT"] {
+            out.push(Token::Word(word.into()));
+            out.push(Token::Space);
+        }
+        out.extend(limit);
+        for word in ["ROWS", "ONLY"] {
+            out.push(Token::Space);
+            out.push(Token::Word(word.into()));
+        }
+    }
+    if trailing_space {
+        out.push(Token::Space);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::sea_query::{Alias, Expr, Order, Query};
+    use chrono::{FixedOffset, NaiveDate, TimeZone};
+
+    #[test]
+    fn test_select_generation() {
+        let (sql, values) = build(
+            Query::select()
+                .column((Alias::new("e"), Alias::new("first_name")))
+                .column((Alias::new("d"), Alias::new("DeptName")))
+                .from_as(Alias::new("employees"), Alias::new("e"))
+                .inner_join(
+                    Alias::new("departments"),
+                    Expr::col((Alias::new("d"), Alias::new("id")))
+                        .equals((Alias::new("e"), Alias::new("dept_id"))),
+                )
+                .and_where(Expr::col((Alias::new("e"), Alias::new("salary"))).gt(1000))
+                .order_by((Alias::new("e"), Alias::new("id")), Order::Asc)
+                .limit(10)
+                .offset(20),
+        );
+
+        assert_eq!(
+            sql,
+            "SELECT \"E\".\"FIRST_NAME\", \"D\".\"DeptName\" FROM \"EMPLOYEES\" \"E\" \
+             INNER JOIN \"DEPARTMENTS\" ON \"D\".\"ID\" = \"E\".\"DEPT_ID\" \
+             WHERE \"E\".\"SALARY\" > :1 ORDER BY \"E\".\"ID\" ASC \
+             OFFSET :2 ROWS FETCH NEXT :3 ROWS ONLY"
+        );
+        let values: Vec<_> = values.iter().filter_map(Value::as_i64).collect();
+        assert_eq!(values, vec![1000, 20, 10]);
+    }
+
+    #[test]
+    fn test_dual_subqueries_and_literals() {
+        let (sql, values) = build(Query::select().expr(Expr::val(1).add(2)));
+        assert_eq!(sql, "SELECT :1 + :2 FROM DUAL");
+        assert_eq!(values.len(), 2);
+
+        let (sql, _) = rewrite(
+            "SELECT COUNT(*) AS num_items FROM (SELECT \"a\" FROM \"t\" WHERE \"b\" = 'it''s ? as' LIMIT ?) AS \"sub_query\"",
+            vec![SeaValue::Int(Some(5))],
+        );
+        assert_eq!(
+            sql,
+            "SELECT COUNT(*) AS num_items FROM (SELECT \"A\" FROM \"T\" WHERE \"B\" = 'it''s ? as' FETCH NEXT :1 ROWS ONLY) \"SUB_QUERY\""
+        );
+
+        let (sql, _) = rewrite("SELECT ? UNION SELECT ? OFFSET ? FOR UPDATE", vec![]);
+        assert_eq!(
+            sql,
+            "SELECT :1 FROM DUAL UNION SELECT :2 FROM DUAL OFFSET :3 ROWS FOR UPDATE"
+        );
+    }
+
+    #[test]
+    fn test_value_conversion() {
+        assert!(matches!(
+            to_value(SeaValue::Bool(Some(true))),
+            Value::Boolean(true)
+        ));
+        assert!(matches!(to_value(SeaValue::Int(None)), Value::Null));
+        assert!(matches!(
+            to_value(SeaValue::SmallUnsigned(Some(7))),
+            Value::Integer(7)
+        ));
+        assert!(matches!(
+            to_value(SeaValue::BigUnsigned(Some(u64::MAX))),
+            Value::String(ref s) if s == "18446744073709551615"
+        ));
+        assert!(matches!(to_value(SeaValue::Char(Some('x'))), Value::String(ref s) if s == "x"));
+
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        assert!(matches!(
+            to_value(SeaValue::ChronoDate(Some(Box::new(date)))),
+            Value::Date(d) if d == date
+        ));
+
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        let ts = offset.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        match to_value(SeaValue::ChronoDateTimeWithTimeZone(Some(Box::new(ts)))) {
+            Value::TimestampTz(utc) => assert_eq!(utc.to_rfc3339(), "2024-01-01T11:00:00+00:00"),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// Credential storage
+
+//! Passwords and tokens are held in a [`SecretString`], which prints as
+//! `***` in `Debug` and `Display` output and wipes its memory when dropped,
+//! so credentials do not linger in logs, error messages or freed heap.
+//!
+//! ```rust,ignore
+//! let config = ConnectionConfig::new("db:1521/svc", "app", "s3cret");
+//! assert_eq!(format!("{:?}", config.password), "***");
+//! assert_eq!(config.password.expose_secret(), "s3cret");
+//! ```
+
+use std::fmt;
+use zeroize::Zeroizing;
+
+/// Password or token that is redacted when printed and zeroized on drop
+#[derive(Clone, Default)]
+pub struct SecretString(Zeroizing<String>);
+
+impl SecretString {
+    /// Wrap `secret`
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(Zeroizing::new(secret.into()))
+    }
+
+    /// Plaintext, for handing to the authentication exchange
+    pub fn expose_secret(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Check if the secret is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionConfig;
+
+    #[test]
+    fn test_redacted_when_printed() {
+        let secret = SecretString::from("s3cret");
+        assert_eq!(format!("{:?}", secret), "***");
+        assert_eq!(secret.to_string(), "***");
+        assert_eq!(secret.expose_secret(), "s3cret");
+
+        let config = ConnectionConfig::new("localhost/XE", "app", "s3cret");
+        let printed = format!("{:?}", config);
+        assert!(!printed.contains("s3cret"));
+        assert!(printed.contains("password: ***"));
+    }
+}
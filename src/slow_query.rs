@@ -0,0 +1,282 @@
+// Slow-query logging
+
+//! Reports statements that run longer than a threshold, so regressions show
+//! up in production logs before they show up in AWR.
+//!
+//! Enable it per connection with
+//! [`ConnectionConfig::slow_query_log`](crate::ConnectionConfig::slow_query_log).
+//! Without a callback, slow statements are logged as `tracing` warnings under
+//! the `oracledb_rs::slow_query` target. Bind values are never reported, only
+//! their types and sizes.
+//!
+//! ```rust,ignore
+//! use oracledb_rs::slow_query::SlowQueryLog;
+//! use std::time::Duration;
+//!
+//! let config = ConnectionConfig::new("localhost:1521/XEPDB1", "hr", "hr")
+//!     .slow_query_log(SlowQueryLog::new(Duration::from_millis(500)).callback(|query| {
+//!         metrics::counter!("db.slow_queries").increment(1);
+//!         eprintln!("{} took {:?}", query.sql, query.elapsed);
+//!     }));
+//! ```
+
+use crate::protocol::Protocol;
+use crate::types::Value;
+use crate::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A statement that exceeded the slow-query threshold
+#[derive(Debug, Clone)]
+pub struct SlowQuery {
+    /// SQL text as executed
+    pub sql: String,
+    /// Bind types and sizes, e.g. `[Integer, String(12), Null]`
+    pub binds: String,
+    /// Rows fetched or affected
+    pub rows: u64,
+    /// Round trips made by the statement
+    pub round_trips: u64,
+    /// Wall-clock time including waits for the server
+    pub elapsed: Duration,
+    /// Error message if the statement failed
+    pub error: Option<String>,
+}
+
+/// Callback invoked for each slow statement
+pub type SlowQueryCallback = Arc<dyn Fn(&SlowQuery) + Send + Sync>;
+
+/// Slow-query logging settings
+#[derive(Clone)]
+pub struct SlowQueryLog {
+    threshold: Duration,
+    callback: Option<SlowQueryCallback>,
+}
+
+impl SlowQueryLog {
+    /// Report statements that take at least `threshold`
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            callback: None,
+        }
+    }
+
+    /// Call `callback` instead of logging through `tracing`
+    pub fn callback(mut self, callback: impl Fn(&SlowQuery) + Send + Sync + 'static) -> Self {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Reporting threshold
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    fn report(&self, query: &SlowQuery) {
+        match &self.callback {
+            Some(callback) => callback(query),
+            None => tracing::warn!(
+                target: "oracledb_rs::slow_query",
+                elapsed_ms = query.elapsed.as_millis() as u64,
+                rows = query.rows,
+                round_trips = query.round_trips,
+                binds = %query.binds,
+                error = query.error.as_deref(),
+                "slow query: {}",
+                query.sql
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for SlowQueryLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlowQueryLog")
+            .field("threshold", &self.threshold)
+            .field("callback", &self.callback.is_some())
+            .finish()
+    }
+}
+
+/// Times one statement against the connection's slow-query threshold
+#[derive(Clone, Copy)]
+pub(crate) struct Stopwatch {
+    started: Instant,
+    round_trips: u64,
+}
+
+impl Stopwatch {
+    /// Start timing a statement about to run on `protocol`
+    pub(crate) fn start(protocol: &Protocol) -> Self {
+        Self {
+            started: Instant::now(),
+            round_trips: protocol.round_trips(),
+        }
+    }
+
+    /// Report the statement if it was slow
+    pub(crate) fn finish(
+        self,
+        protocol: &Protocol,
+        sql: &str,
+        binds: &[Value],
+        outcome: Result<u64, &Error>,
+    ) {
+        let Some(log) = &protocol.config().slow_query_log else {
+            return;
+        };
+        let elapsed = self.started.elapsed();
+        if elapsed < log.threshold {
+            return;
+        }
+
+        let (rows, error) = match outcome {
+            Ok(rows) => (rows, None),
+            Err(e) => (0, Some(e.to_string())),
+        };
+        log.report(&SlowQuery {
+            sql: sql.to_string(),
+            binds: bind_summary(binds),
+            rows,
+            round_trips: protocol.round_trips() - self.round_trips,
+            elapsed,
+            error,
+        });
+    }
+}
+
+/// Describe binds by type and size without exposing their values
+pub(crate) fn bind_summary(binds: &[Value]) -> String {
+    let items: Vec<String> = binds
+        .iter()
+        .map(|value| match value {
+            Value::Null => "Null".to_string(),
+            Value::String(s) => format!("String({})", s.len()),
+            Value::Integer(_) => "Integer".to_string(),
+            Value::Float(_) => "Float".to_string(),
+            Value::Boolean(_) => "Boolean".to_string(),
+            Value::Date(_) => "Date".to_string(),
+            Value::Timestamp(_) => "Timestamp".to_string(),
+            Value::TimestampTz(_) => "TimestampTz".to_string(),
+            #[cfg(feature = "chrono-tz")]
+            Value::TimestampTzRegion(_) => "TimestampTzRegion".to_string(),
+            Value::IntervalYM(_) => "IntervalYM".to_string(),
+            Value::IntervalDS(_) => "IntervalDS".to_string(),
+            Value::Bytes(b) => format!("Bytes({})", b.len()),
+            Value::Clob(s) => format!("Clob({})", s.len()),
+            Value::Blob(b) => format!("Blob({})", b.len()),
+            Value::Lob(_) => "Lob".to_string(),
+            Value::Json(_) => "Json".to_string(),
+            Value::Vector(v) => format!("Vector({})", v.dimensions()),
+            Value::Array(a) => format!("Array({})", a.len()),
+            Value::Object(o) => format!("Object({})", o.len()),
+        })
+        .collect();
+    format!("[{}]", items.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::FairMutex;
+    use crate::statement::Statement;
+    use crate::ConnectionConfig;
+    use std::sync::Mutex as StdMutex;
+
+    async fn statement(sql: &str, log: SlowQueryLog) -> Statement {
+        let config =
+            ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass").slow_query_log(log);
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+        Statement::new(sql, Arc::new(FairMutex::new(protocol)))
+    }
+
+    #[test]
+    fn test_bind_summary() {
+        let binds = [
+            Value::Integer(7),
+            Value::String("secret".into()),
+            Value::Null,
+        ];
+        assert_eq!(bind_summary(&binds), "[Integer, String(6), Null]");
+        assert_eq!(bind_summary(&[]), "[]");
+    }
+
+    #[tokio::test]
+    async fn test_slow_statements_reported() {
+        let seen = Arc::new(StdMutex::new(Vec::new()));
+        let sink = seen.clone();
+        let log = SlowQueryLog::new(Duration::ZERO)
+            .callback(move |query| sink.lock().unwrap().push(query.clone()));
+
+        let stmt = statement("SELECT id, name FROM t WHERE id = :1", log.clone()).await;
+        stmt.execute(&[&1]).await.unwrap();
+        let stmt = statement("UPDATE t SET name = :1", log.clone()).await;
+        stmt.execute_dml(&[&"x"]).await.unwrap();
+        // DDL takes no binds
+        let stmt = statement("CREATE TABLE t (id NUMBER DEFAULT :1)", log).await;
+        assert!(stmt.execute(&[&1]).await.is_err());
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[0].binds, "[Integer]");
+        assert_eq!(seen[0].rows, 1);
+        assert_eq!(seen[0].round_trips, 1);
+        assert_eq!(seen[1].sql, "UPDATE t SET name = :1");
+        assert_eq!(seen[1].rows, 1);
+        assert!(seen[2].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_statements_reported() {
+        use crate::pipeline::PipelineOp;
+
+        let seen = Arc::new(StdMutex::new(Vec::new()));
+        let sink = seen.clone();
+        let log = SlowQueryLog::new(Duration::ZERO)
+            .callback(move |query| sink.lock().unwrap().push(query.clone()));
+        let config =
+            ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass").slow_query_log(log);
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+
+        let ops = vec![
+            PipelineOp::Execute {
+                sql: "UPDATE t SET name = :1".into(),
+                params: vec![Value::String("x".into())],
+                types: vec![None],
+            },
+            PipelineOp::Commit,
+            PipelineOp::Execute {
+                sql: "SELECT id, name FROM t".into(),
+                params: vec![],
+                types: vec![],
+            },
+        ];
+        protocol.execute_pipeline(&ops).await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        let reported: Vec<_> = seen.iter().map(|q| (q.sql.as_str(), q.rows)).collect();
+        assert_eq!(
+            reported,
+            [("UPDATE t SET name = :1", 1), ("SELECT id, name FROM t", 1)]
+        );
+        // Both shared the pipeline's one round trip
+        assert!(seen.iter().all(|q| q.round_trips == 1));
+        assert_eq!(seen[0].binds, "[String(1)]");
+    }
+
+    #[tokio::test]
+    async fn test_fast_statements_ignored() {
+        let seen = Arc::new(StdMutex::new(0));
+        let sink = seen.clone();
+        let log = SlowQueryLog::new(Duration::from_secs(60))
+            .callback(move |_| *sink.lock().unwrap() += 1);
+
+        let stmt = statement("SELECT 1 FROM DUAL", log).await;
+        stmt.execute(&[]).await.unwrap();
+        assert_eq!(*seen.lock().unwrap(), 0);
+    }
+}
@@ -0,0 +1,795 @@
+// Dynamic SQL helpers
+
+//! Bind variables cover values, not names. When a table name or sort
+//! column comes from outside the program, quote it here rather than
+//! interpolating it:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::sql::{Assembler, SortOrder};
+//!
+//! let sql = Assembler::new("SELECT id, name FROM ")
+//!     .qualified_identifier("APP", &table)
+//!     .sql(" WHERE status = :1")
+//!     .order_by(&column, order.parse()?)
+//!     .build()?;
+//! let rows = conn.query(&sql, &[&"ACTIVE"]).await?;
+//! ```
+
+use crate::json::JsonPath;
+use crate::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest identifier Oracle accepts, in bytes (12.2 and later)
+const MAX_IDENTIFIER_LEN: usize = 128;
+
+/// Quote `name` as a case-sensitive Oracle identifier
+///
+/// Oracle has no escape for `"` inside quoted identifiers, so names
+/// containing one are rejected rather than escaped.
+pub fn quote_identifier(name: &str) -> Result<String> {
+    if name.is_empty() || name.len() > MAX_IDENTIFIER_LEN {
+        return Err(Error::InvalidSql(format!(
+            "identifier must be 1 to {} bytes: {:?}",
+            MAX_IDENTIFIER_LEN, name
+        )));
+    }
+    if name.contains(['"', '\0']) {
+        return Err(Error::InvalidSql(format!(
+            "identifier cannot contain quotes or NUL: {:?}",
+            name
+        )));
+    }
+    Ok(format!("\"{}\"", name))
+}
+
+/// Use `name` as an identifier, left unquoted when Oracle accepts it so
+/// it stays case-insensitive, and quoted otherwise
+pub fn identifier(name: &str) -> Result<String> {
+    let mut chars = name.chars();
+    let plain = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '#'))
+        && name.len() <= MAX_IDENTIFIER_LEN;
+    if plain {
+        Ok(name.to_string())
+    } else {
+        quote_identifier(name)
+    }
+}
+
+/// Quote `value` as a string literal, doubling embedded quotes
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Canonical text of `sql` for statement cache lookups
+///
+/// Leading and trailing whitespace is dropped and runs of whitespace
+/// collapse to one space. With `fold_case`, unquoted text is uppercased,
+/// as Oracle already treats it. String literals, quoted identifiers and
+/// comments are kept as written; a line comment keeps its line break.
+pub fn normalize(sql: &str, fold_case: bool) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut pending: Option<char> = None;
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            pending.get_or_insert(' ');
+            continue;
+        }
+        if let Some(separator) = pending.take() {
+            if !out.is_empty() {
+                out.push(separator);
+            }
+        }
+        out.push(if fold_case { c.to_ascii_uppercase() } else { c });
+        let close = match c {
+            '\'' | '"' => c.to_string(),
+            'q' | 'Q' if chars.peek() == Some(&'\'') => {
+                out.extend(chars.next());
+                let Some(open) = chars.next() else { break };
+                out.push(open);
+                format!("{}'", q_quote_close(open))
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref().take_while(|&c| c != '\n') {
+                    out.push(c);
+                }
+                pending = Some('\n');
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                out.extend(chars.next());
+                "*/".to_string()
+            }
+            _ => continue,
+        };
+        // Copy through the closing delimiter unchanged
+        let mut body = String::new();
+        while !body.ends_with(&close) {
+            match chars.next() {
+                Some(c) => body.push(c),
+                None => break,
+            }
+        }
+        out.push_str(&body);
+    }
+    out
+}
+
+/// Names of the bind placeholders in `sql`, in order of occurrence
+///
+/// Unquoted names are uppercased, as Oracle matches them, and quoted
+/// ones kept as written. Placeholders in literals and comments, and the
+/// PL/SQL `:=` operator, are skipped.
+pub(crate) fn bind_names(sql: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        let close = match c {
+            '\'' | '"' => c.to_string(),
+            'q' | 'Q' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                let Some(open) = chars.next() else { break };
+                format!("{}'", q_quote_close(open))
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.by_ref().find(|&c| c == '\n');
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                "*/".to_string()
+            }
+            ':' => {
+                let name: String = if chars.next_if_eq(&'"').is_some() {
+                    chars.by_ref().take_while(|&c| c != '"').collect()
+                } else {
+                    let mut name = String::new();
+                    while let Some(c) =
+                        chars.next_if(|&c| c.is_alphanumeric() || matches!(c, '_' | '$' | '#'))
+                    {
+                        name.push(c.to_ascii_uppercase());
+                    }
+                    name
+                };
+                if !name.is_empty() {
+                    names.push(name);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let mut body = String::new();
+        while !body.ends_with(&close) {
+            match chars.next() {
+                Some(c) => body.push(c),
+                None => break,
+            }
+        }
+    }
+    names
+}
+
+/// The first `n` keywords of `sql`, uppercased
+///
+/// Whitespace, comments and optimizer hints are skipped, as are opening
+/// parentheses, so `(SELECT ...)` and `/*+ APPEND */ INSERT` read as the
+/// statements they are. Stops early at anything other than a word.
+pub(crate) fn leading_keywords(sql: &str, n: usize) -> Vec<String> {
+    let mut keywords = Vec::with_capacity(n);
+    let mut chars = sql.chars().peekable();
+    while keywords.len() < n {
+        let Some(c) = chars.next() else { break };
+        match c {
+            c if c.is_whitespace() || c == '(' => {}
+            '-' if chars.peek() == Some(&'-') => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            c if c.is_alphabetic() => {
+                let mut word = c.to_uppercase().to_string();
+                while let Some(c) =
+                    chars.next_if(|&c| c.is_alphanumeric() || matches!(c, '_' | '$' | '#'))
+                {
+                    word.extend(c.to_uppercase());
+                }
+                keywords.push(word);
+            }
+            _ => break,
+        }
+    }
+    keywords
+}
+
+/// A word of SQL text: byte range and text
+type Word = (usize, usize, String);
+
+/// Words of `sql` outside parentheses, literals and comments, with their
+/// byte ranges, uppercased unless double-quoted
+///
+/// Dotted names such as `hr.emp` read as one word, and commas are words
+/// of their own.
+fn top_level_words(sql: &str) -> Vec<Word> {
+    words_by_depth(sql)
+        .into_iter()
+        .filter(|(depth, _)| *depth == 0)
+        .map(|(_, word)| word)
+        .collect()
+}
+
+/// Words of `sql` outside literals and comments, as
+/// [`top_level_words`] reads them, each with its parenthesis depth
+fn words_by_depth(sql: &str) -> Vec<(usize, Word)> {
+    let mut words = Vec::new();
+    let mut depth = 0usize;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let close = match c {
+            '(' => {
+                depth += 1;
+                continue;
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                continue;
+            }
+            'q' | 'Q' if chars.peek().map(|&(_, c)| c) == Some('\'') => {
+                chars.next();
+                let Some((_, open)) = chars.next() else { break };
+                format!("{}'", q_quote_close(open))
+            }
+            '\'' => "'".to_string(),
+            '"' => {
+                let end = chars
+                    .by_ref()
+                    .find(|&(_, c)| c == '"')
+                    .map_or(sql.len(), |(i, _)| i + 1);
+                words.push((depth, (start, end, sql[start..end].to_string())));
+                continue;
+            }
+            ',' => {
+                words.push((depth, (start, start + 1, ",".to_string())));
+                continue;
+            }
+            '-' if chars.peek().map(|&(_, c)| c) == Some('-') => {
+                chars.by_ref().find(|&(_, c)| c == '\n');
+                continue;
+            }
+            '/' if chars.peek().map(|&(_, c)| c) == Some('*') => {
+                chars.next();
+                "*/".to_string()
+            }
+            c if c.is_alphabetic() => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, c)) = chars.next_if(|&(_, c)| {
+                    c.is_alphanumeric() || matches!(c, '_' | '$' | '#' | '.' | '@')
+                }) {
+                    end = i + c.len_utf8();
+                }
+                words.push((depth, (start, end, sql[start..end].to_uppercase())));
+                continue;
+            }
+            _ => continue,
+        };
+        let mut body = String::new();
+        while !body.ends_with(&close) {
+            match chars.next() {
+                Some((_, c)) => body.push(c),
+                None => break,
+            }
+        }
+    }
+    words
+}
+
+/// Check for a top-level `RETURNING` (or `RETURN`) clause, ignoring
+/// literals and comments
+pub(crate) fn has_returning(sql: &str) -> bool {
+    top_level_words(sql)
+        .iter()
+        .any(|(_, _, word)| word == "RETURNING" || word == "RETURN")
+}
+
+/// Check for a top-level `FOR UPDATE` clause, which locks the rows it
+/// reads
+pub(crate) fn locks_rows(sql: &str) -> bool {
+    top_level_words(sql)
+        .windows(2)
+        .any(|pair| pair[0].2 == "FOR" && pair[1].2 == "UPDATE")
+}
+
+/// Words that end a table reference rather than alias it
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "WHERE",
+    "ORDER",
+    "GROUP",
+    "HAVING",
+    "CONNECT",
+    "START",
+    "FETCH",
+    "OFFSET",
+    "FOR",
+    "JOIN",
+    "INNER",
+    "LEFT",
+    "RIGHT",
+    "FULL",
+    "CROSS",
+    "NATURAL",
+    "PARTITION",
+    "SAMPLE",
+    "AS",
+    "MODEL",
+];
+
+/// Words that end a `FROM` list
+const FROM_LIST_END: &[&str] = &[
+    "WHERE",
+    "GROUP",
+    "ORDER",
+    "HAVING",
+    "CONNECT",
+    "START",
+    "FETCH",
+    "OFFSET",
+    "FOR",
+    "MODEL",
+    "UNION",
+    "INTERSECT",
+    "MINUS",
+    "EXCEPT",
+];
+
+/// Table named right after the keyword or comma at `words[at]`, and its
+/// alias
+///
+/// None when an inline view, table function or other expression follows
+/// instead.
+fn table_after<'a>(
+    sql: &str,
+    words: &'a [Word],
+    at: usize,
+) -> Option<(&'a Word, Option<&'a Word>)> {
+    let (_, keyword_end, _) = words.get(at)?;
+    let table = words.get(at + 1).filter(|(start, end, word)| {
+        sql[*keyword_end..*start].trim().is_empty()
+            && word != ","
+            && !sql[*end..].trim_start().starts_with('(')
+    })?;
+    let alias = words.get(at + 2).filter(|(start, _, alias)| {
+        alias != ","
+            && !CLAUSE_KEYWORDS.contains(&alias.as_str())
+            && sql[table.1..*start].trim().is_empty()
+    });
+    Some((table, alias))
+}
+
+/// Positions in `words` of each `FROM` or `JOIN`, and of each comma
+/// separating table references in a `FROM` list
+fn table_references(words: &[Word]) -> Vec<usize> {
+    let mut references = Vec::new();
+    let mut in_from = false;
+    for (i, (_, _, word)) in words.iter().enumerate() {
+        match word.as_str() {
+            "FROM" | "JOIN" => {
+                in_from = true;
+                references.push(i);
+            }
+            "," if in_from => references.push(i),
+            "SELECT" => in_from = false,
+            w if FROM_LIST_END.contains(&w) => in_from = false,
+            _ => {}
+        }
+    }
+    references
+}
+
+/// `sql` with every table it reads read as of `clause`, such as
+/// `SCN 4711`
+///
+/// The whole query must read from one snapshot, so anything the rewrite
+/// cannot reach is refused rather than left reading current data:
+/// subqueries, `WITH` clauses, inline views and table functions.
+pub(crate) fn with_as_of(sql: &str, clause: &str) -> Result<String> {
+    let invalid =
+        |reason: &str| Error::InvalidSql(format!("{} to read AS OF {}: {}", reason, clause, sql));
+    if leading_keywords(sql, 1).first().map(String::as_str) == Some("WITH") {
+        return Err(invalid("WITH clauses cannot be rewritten"));
+    }
+    if words_by_depth(sql)
+        .iter()
+        .any(|(depth, (_, _, word))| *depth > 0 && word == "SELECT")
+    {
+        return Err(invalid("subqueries cannot be rewritten"));
+    }
+    let words = top_level_words(sql);
+    let references = table_references(&words);
+    if references.is_empty() {
+        return Err(invalid("no table"));
+    }
+    let mut out = String::with_capacity(sql.len() + 32);
+    let mut copied = 0;
+    for i in references {
+        let ((_, table_end, _), _) =
+            table_after(sql, &words, i).ok_or_else(|| invalid("only named tables can be read"))?;
+        out.push_str(&sql[copied..*table_end]);
+        out.push_str(" AS OF ");
+        out.push_str(clause);
+        copied = *table_end;
+    }
+    out.push_str(&sql[copied..]);
+    Ok(out)
+}
+
+/// `sql` with the `ORA_ROWSCN` pseudocolumn of its first table appended to
+/// the select list
+///
+/// Only a single query block can carry it: set operators, `DISTINCT` and
+/// `GROUP BY` are refused, as is `SELECT *` over anything but a single
+/// named table, since only that table's columns could be listed.
+pub(crate) fn with_rowscn(sql: &str) -> Result<String> {
+    let invalid = |reason: &str| Error::InvalidSql(format!("{} for ORA_ROWSCN: {}", reason, sql));
+    let words = top_level_words(sql);
+    let from = words
+        .iter()
+        .position(|(_, _, w)| w == "FROM")
+        .ok_or_else(|| invalid("no FROM clause"))?;
+    let select = words[..from]
+        .iter()
+        .rposition(|(_, _, w)| w == "SELECT")
+        .ok_or_else(|| invalid("not a query"))?;
+    if words.iter().any(|(_, _, w)| {
+        matches!(
+            w.as_str(),
+            "UNION" | "INTERSECT" | "MINUS" | "EXCEPT" | "GROUP"
+        )
+    }) {
+        return Err(invalid("not a single query block"));
+    }
+    if words
+        .get(select + 1)
+        .is_some_and(|(_, _, w)| matches!(w.as_str(), "DISTINCT" | "UNIQUE"))
+    {
+        return Err(invalid("DISTINCT rows have no single row SCN"));
+    }
+
+    let (_, select_end, _) = &words[select];
+    let (from_start, _, _) = &words[from];
+    // The table reference, named by its alias when it has one
+    let qualifier = table_after(sql, &words, from).map(|(table, alias)| {
+        let (start, end, _) = alias.unwrap_or(table);
+        &sql[*start..*end]
+    });
+    let column = match &qualifier {
+        Some(q) => format!("{}.ORA_ROWSCN", q),
+        None => "ORA_ROWSCN".to_string(),
+    };
+
+    let list = &sql[*select_end..*from_start];
+    let list = if normalize(list, false) == "*" {
+        if table_references(&words).len() > 1 {
+            return Err(invalid("SELECT * over several tables"));
+        }
+        let q = qualifier.ok_or_else(|| invalid("SELECT * needs a named table"))?;
+        format!(" {}.* ", q)
+    } else {
+        list.to_string()
+    };
+    Ok(format!(
+        "{}{}, {} {}",
+        &sql[..*select_end],
+        list.trim_end(),
+        column,
+        &sql[*from_start..]
+    ))
+}
+
+/// Closing delimiter of a `q'...'` literal opened with `open`
+fn q_quote_close(open: char) -> char {
+    match open {
+        '[' => ']',
+        '{' => '}',
+        '(' => ')',
+        '<' => '>',
+        other => other,
+    }
+}
+
+/// Direction of an ORDER BY column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// Ascending
+    #[default]
+    Asc,
+    /// Descending
+    Desc,
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        })
+    }
+}
+
+impl FromStr for SortOrder {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "ASC" | "ASCENDING" => Ok(SortOrder::Asc),
+            "DESC" | "DESCENDING" => Ok(SortOrder::Desc),
+            _ => Err(Error::InvalidSql(format!("invalid sort order: {:?}", s))),
+        }
+    }
+}
+
+/// Builds SQL from trusted text and quoted identifiers and literals
+///
+/// The first invalid fragment is kept and reported by [`Assembler::build`].
+#[derive(Debug, Default)]
+pub struct Assembler {
+    sql: String,
+    ordered: bool,
+    error: Option<Error>,
+}
+
+impl Assembler {
+    /// Start from trusted SQL text
+    pub fn new(sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Append trusted SQL text
+    pub fn sql(mut self, fragment: &str) -> Self {
+        self.sql.push_str(fragment);
+        self
+    }
+
+    /// Append a quoted identifier
+    pub fn identifier(mut self, name: &str) -> Self {
+        self.push_identifier(name);
+        self
+    }
+
+    /// Append a quoted `schema.name`
+    pub fn qualified_identifier(mut self, schema: &str, name: &str) -> Self {
+        self.push_identifier(schema);
+        self.sql.push('.');
+        self.push_identifier(name);
+        self
+    }
+
+    /// Append a quoted string literal
+    pub fn literal(mut self, value: &str) -> Self {
+        self.sql.push_str(&quote_literal(value));
+        self
+    }
+
+    /// Append a JSON path expression as a literal, with its PASSING
+    /// clause
+    pub fn json_path(mut self, path: &JsonPath) -> Self {
+        match path.to_sql() {
+            Ok(sql) => self.sql.push_str(&sql),
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+        self
+    }
+
+    /// Append `word` if it is one of `allowed`, compared case-insensitively
+    pub fn keyword(mut self, word: &str, allowed: &[&str]) -> Self {
+        match allowed.iter().find(|a| a.eq_ignore_ascii_case(word)) {
+            Some(keyword) => self.sql.push_str(keyword),
+            None => self.fail(format!("keyword {:?} is not allowed", word)),
+        }
+        self
+    }
+
+    /// Sort by `column`, starting the ORDER BY clause on first use
+    pub fn order_by(mut self, column: &str, order: SortOrder) -> Self {
+        self.sql
+            .push_str(if self.ordered { ", " } else { " ORDER BY " });
+        self.ordered = true;
+        self.push_identifier(column);
+        self.sql.push(' ');
+        self.sql.push_str(&order.to_string());
+        self
+    }
+
+    /// The assembled SQL, or the first invalid fragment's error
+    pub fn build(self) -> Result<String> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.sql),
+        }
+    }
+
+    fn push_identifier(&mut self, name: &str) {
+        match quote_identifier(name) {
+            Ok(quoted) => self.sql.push_str(&quoted),
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+    }
+
+    fn fail(&mut self, message: String) {
+        self.error.get_or_insert(Error::InvalidSql(message));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(quote_identifier("Order Items").unwrap(), "\"Order Items\"");
+        assert!(matches!(
+            quote_identifier("x\" OR 1=1 --"),
+            Err(Error::InvalidSql(_))
+        ));
+        assert!(quote_identifier("").is_err());
+        assert!(quote_identifier(&"A".repeat(129)).is_err());
+        assert_eq!(quote_literal("O'Brien"), "'O''Brien'");
+        assert_eq!(identifier("emp_id").unwrap(), "emp_id");
+        assert_eq!(identifier("Order Id").unwrap(), "\"Order Id\"");
+        assert_eq!(identifier("1st").unwrap(), "\"1st\"");
+        assert_eq!("desc".parse::<SortOrder>().unwrap(), SortOrder::Desc);
+        assert!(matches!(
+            "DESC; DROP TABLE t".parse::<SortOrder>(),
+            Err(Error::InvalidSql(_))
+        ));
+    }
+
+    #[test]
+    fn test_leading_keywords() {
+        let sql = "/* audit */ -- note\n( /*+ PARALLEL(4) */ select x from dual)";
+        assert_eq!(leading_keywords(sql, 2), ["SELECT", "X"]);
+        assert_eq!(
+            leading_keywords("  explain plan for", 3),
+            ["EXPLAIN", "PLAN", "FOR"]
+        );
+        assert_eq!(leading_keywords("'lit' select", 1), Vec::<String>::new());
+        assert_eq!(leading_keywords("/* unterminated", 1), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_with_rowscn() {
+        assert_eq!(
+            with_rowscn("SELECT * FROM emp e WHERE deptno = :1").unwrap(),
+            "SELECT e.*, e.ORA_ROWSCN FROM emp e WHERE deptno = :1"
+        );
+        assert_eq!(
+            with_rowscn("select id, (select max(x) from t) m from hr.emp order by id").unwrap(),
+            "select id, (select max(x) from t) m, hr.emp.ORA_ROWSCN from hr.emp order by id"
+        );
+        assert!(with_rowscn("SELECT DISTINCT deptno FROM emp").is_err());
+        assert!(with_rowscn("SELECT a FROM t UNION SELECT a FROM u").is_err());
+        assert!(with_rowscn("SELECT * FROM (SELECT 1 FROM dual)").is_err());
+        assert!(with_rowscn("SELECT * FROM emp, dept").is_err());
+        assert!(with_rowscn("SELECT * FROM emp e JOIN dept d ON d.deptno = e.deptno").is_err());
+        assert_eq!(
+            with_rowscn("SELECT e.ename, d.dname FROM emp e, dept d").unwrap(),
+            "SELECT e.ename, d.dname, e.ORA_ROWSCN FROM emp e, dept d"
+        );
+    }
+
+    #[test]
+    fn test_with_as_of() {
+        assert_eq!(
+            with_as_of(
+                "SELECT * FROM orders o JOIN lines l ON l.order_id = o.id WHERE o.id = :1",
+                "SCN 4711"
+            )
+            .unwrap(),
+            "SELECT * FROM orders AS OF SCN 4711 o JOIN lines AS OF SCN 4711 l \
+             ON l.order_id = o.id WHERE o.id = :1"
+        );
+        assert_eq!(
+            with_as_of(
+                "SELECT e.ename, d.dname FROM emp e, dept d WHERE e.deptno = d.deptno",
+                "SCN 1"
+            )
+            .unwrap(),
+            "SELECT e.ename, d.dname FROM emp AS OF SCN 1 e, dept AS OF SCN 1 d \
+             WHERE e.deptno = d.deptno"
+        );
+        assert!(with_as_of("SELECT 1 FROM (SELECT 1 FROM dual)", "SCN 1").is_err());
+        assert!(with_as_of("SELECT * FROM t WHERE id IN (SELECT id FROM u)", "SCN 1").is_err());
+        assert!(with_as_of("WITH x AS (SELECT 1 n FROM dual) SELECT n FROM x", "SCN 1").is_err());
+        assert!(with_as_of("SELECT * FROM TABLE(f())", "SCN 1").is_err());
+        assert!(with_as_of("SELECT SYSDATE", "SCN 1").is_err());
+    }
+
+    #[test]
+    fn test_locks_rows() {
+        assert!(locks_rows("SELECT * FROM t WHERE id = 1 FOR UPDATE NOWAIT"));
+        assert!(locks_rows("select * from t for  update of n"));
+        assert!(!locks_rows("SELECT 'FOR UPDATE' FROM t"));
+        assert!(!locks_rows("SELECT * FROM t -- for update"));
+    }
+
+    #[test]
+    fn test_has_returning() {
+        assert!(has_returning("UPDATE t SET n = 1 RETURNING id INTO :1"));
+        assert!(has_returning("delete from t return id into :1"));
+        assert!(!has_returning("UPDATE t SET s = 'RETURN'"));
+        assert!(!has_returning("UPDATE t SET s = q'[x RETURNING y]'"));
+        assert!(!has_returning("UPDATE t /* RETURNING */ SET n = 1"));
+        assert!(!has_returning("DELETE FROM t -- returning\nWHERE n = 1"));
+    }
+
+    #[test]
+    fn test_bind_names() {
+        let sql = "BEGIN :total := :total + :Amount; -- :skipped\n\
+                   log('a :b', q'[:c]', :\"Mixed\", :1); /* :d */ END;";
+        assert_eq!(bind_names(sql), ["TOTAL", "TOTAL", "AMOUNT", "Mixed", "1"]);
+        assert!(bind_names("x := 1").is_empty());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let sql = "  select id,\n\t name from emp  where name = 'A  b'\n";
+        assert_eq!(
+            normalize(sql, true),
+            "SELECT ID, NAME FROM EMP WHERE NAME = 'A  b'"
+        );
+        assert_eq!(
+            normalize(sql, false),
+            "select id, name from emp where name = 'A  b'"
+        );
+        assert_eq!(
+            normalize("select \"Mixed  Case\", q'[it's  ]' from t", true),
+            "SELECT \"Mixed  Case\", Q'[it's  ]' FROM T"
+        );
+        // A line comment must not swallow the rest of the statement
+        assert_eq!(
+            normalize("select 1 -- One\n  from dual /* x  y */", true),
+            "SELECT 1 -- One\nFROM DUAL /* x  y */"
+        );
+    }
+
+    #[test]
+    fn test_assembler() {
+        let sql = Assembler::new("SELECT * FROM ")
+            .qualified_identifier("APP", "ORDERS")
+            .sql(" WHERE region = ")
+            .literal("it's")
+            .order_by("CREATED_AT", SortOrder::Desc)
+            .order_by("ID", SortOrder::Asc)
+            .sql(" FETCH FIRST 10 ROWS ")
+            .keyword("only", &["ONLY", "WITH TIES"])
+            .build()
+            .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM \"APP\".\"ORDERS\" WHERE region = 'it''s' \
+             ORDER BY \"CREATED_AT\" DESC, \"ID\" ASC FETCH FIRST 10 ROWS ONLY"
+        );
+
+        let err = Assembler::new("SELECT * FROM ")
+            .identifier("t\"")
+            .keyword("PERCENT", &["ONLY"])
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("quotes"));
+    }
+}
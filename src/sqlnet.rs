@@ -0,0 +1,316 @@
+// sqlnet.ora profile parameters
+
+//! Reads the client profile ops teams already maintain for other Oracle
+//! clients. The file is looked up in `$TNS_ADMIN`, then
+//! `$ORACLE_HOME/network/admin`, unless [`ConnectionConfig::tns_admin`]
+//! names a directory.
+//!
+//! Settings made on [`ConnectionConfig::sqlnet`] take precedence over the
+//! file, one parameter at a time.
+//!
+//! [`ConnectionConfig::tns_admin`]: crate::ConnectionConfig::tns_admin
+//! [`ConnectionConfig::sqlnet`]: crate::ConnectionConfig::sqlnet
+
+use crate::{Error, Result};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Client negotiation level for encryption (`SQLNET.ENCRYPTION_CLIENT`)
+/// and integrity (`SQLNET.CRYPTO_CHECKSUM_CLIENT`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptionLevel {
+    /// Refuse encryption
+    Rejected,
+    /// Encrypt if the server asks for it
+    Accepted,
+    /// Ask for encryption, connect without it if refused
+    Requested,
+    /// Fail unless the connection is encrypted
+    Required,
+}
+
+/// Parameters read from sqlnet.ora
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SqlNetConfig {
+    /// `SQLNET.OUTBOUND_CONNECT_TIMEOUT`
+    pub outbound_connect_timeout: Option<Duration>,
+    /// `SQLNET.TRANSPORT_CONNECT_TIMEOUT`
+    pub transport_connect_timeout: Option<Duration>,
+    /// `SQLNET.EXPIRE_TIME`, the dead connection detection interval,
+    /// applied as the TCP keepalive time unless
+    /// [`SocketOptions::keepalive`](crate::SocketOptions::keepalive) is set
+    pub expire_time: Option<Duration>,
+    /// `SQLNET.ENCRYPTION_CLIENT`
+    pub encryption_client: Option<EncryptionLevel>,
+    /// `SQLNET.ENCRYPTION_TYPES_CLIENT`, in order of preference
+    pub encryption_types_client: Option<Vec<String>>,
+    /// `SQLNET.CRYPTO_CHECKSUM_CLIENT`
+    pub crypto_checksum_client: Option<EncryptionLevel>,
+    /// `SQLNET.CRYPTO_CHECKSUM_TYPES_CLIENT`, in order of preference
+    pub crypto_checksum_types_client: Option<Vec<String>>,
+}
+
+impl SqlNetConfig {
+    /// Create a configuration with nothing set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the connect timeout
+    pub fn outbound_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.outbound_connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the transport connect timeout
+    pub fn transport_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.transport_connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the dead connection detection interval
+    pub fn expire_time(mut self, interval: Duration) -> Self {
+        self.expire_time = Some(interval);
+        self
+    }
+
+    /// Set the encryption negotiation level
+    pub fn encryption_client(mut self, level: EncryptionLevel) -> Self {
+        self.encryption_client = Some(level);
+        self
+    }
+
+    /// Set the acceptable encryption algorithms
+    pub fn encryption_types_client(mut self, types: Vec<String>) -> Self {
+        self.encryption_types_client = Some(types);
+        self
+    }
+
+    /// Set the integrity negotiation level
+    pub fn crypto_checksum_client(mut self, level: EncryptionLevel) -> Self {
+        self.crypto_checksum_client = Some(level);
+        self
+    }
+
+    /// Set the acceptable integrity algorithms
+    pub fn crypto_checksum_types_client(mut self, types: Vec<String>) -> Self {
+        self.crypto_checksum_types_client = Some(types);
+        self
+    }
+
+    /// Parse sqlnet.ora contents, ignoring parameters this driver does not use
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut config = Self::default();
+        for (key, value) in parameters(contents) {
+            let invalid =
+                || Error::InvalidConfiguration(format!("invalid sqlnet.ora {}: {}", key, value));
+            match key.as_str() {
+                "SQLNET.OUTBOUND_CONNECT_TIMEOUT" => {
+                    config.outbound_connect_timeout =
+                        Some(parse_timeout(&value).ok_or_else(invalid)?)
+                }
+                "SQLNET.TRANSPORT_CONNECT_TIMEOUT" => {
+                    config.transport_connect_timeout =
+                        Some(parse_timeout(&value).ok_or_else(invalid)?)
+                }
+                "SQLNET.EXPIRE_TIME" => {
+                    let minutes: u64 = value.parse().map_err(|_| invalid())?;
+                    config.expire_time = Some(Duration::from_secs(minutes * 60));
+                }
+                "SQLNET.ENCRYPTION_CLIENT" => {
+                    config.encryption_client = Some(parse_level(&value).ok_or_else(invalid)?)
+                }
+                "SQLNET.ENCRYPTION_TYPES_CLIENT" => {
+                    config.encryption_types_client = Some(parse_list(&value))
+                }
+                "SQLNET.CRYPTO_CHECKSUM_CLIENT" => {
+                    config.crypto_checksum_client = Some(parse_level(&value).ok_or_else(invalid)?)
+                }
+                "SQLNET.CRYPTO_CHECKSUM_TYPES_CLIENT" => {
+                    config.crypto_checksum_types_client = Some(parse_list(&value))
+                }
+                _ => {}
+            }
+        }
+        Ok(config)
+    }
+
+    /// Read `sqlnet.ora` from `dir`, or from the standard locations when
+    /// `dir` is `None`; a missing file yields an empty configuration
+    pub fn load(dir: Option<&Path>) -> Result<Self> {
+        let dir = match dir {
+            Some(dir) => dir.to_path_buf(),
+            None => match std::env::var_os("TNS_ADMIN") {
+                Some(dir) => PathBuf::from(dir),
+                None => match std::env::var_os("ORACLE_HOME") {
+                    Some(home) => Path::new(&home).join("network").join("admin"),
+                    None => return Ok(Self::default()),
+                },
+            },
+        };
+        match std::fs::read_to_string(dir.join("sqlnet.ora")) {
+            Ok(contents) => Self::parse(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
+    /// Fill parameters not set here from `file`
+    pub fn or(self, file: SqlNetConfig) -> Self {
+        Self {
+            outbound_connect_timeout: self
+                .outbound_connect_timeout
+                .or(file.outbound_connect_timeout),
+            transport_connect_timeout: self
+                .transport_connect_timeout
+                .or(file.transport_connect_timeout),
+            expire_time: self.expire_time.or(file.expire_time),
+            encryption_client: self.encryption_client.or(file.encryption_client),
+            encryption_types_client: self
+                .encryption_types_client
+                .or(file.encryption_types_client),
+            crypto_checksum_client: self.crypto_checksum_client.or(file.crypto_checksum_client),
+            crypto_checksum_types_client: self
+                .crypto_checksum_types_client
+                .or(file.crypto_checksum_types_client),
+        }
+    }
+}
+
+fn parse_level(value: &str) -> Option<EncryptionLevel> {
+    match value.to_ascii_uppercase().as_str() {
+        "REJECTED" => Some(EncryptionLevel::Rejected),
+        "ACCEPTED" => Some(EncryptionLevel::Accepted),
+        "REQUESTED" => Some(EncryptionLevel::Requested),
+        "REQUIRED" => Some(EncryptionLevel::Required),
+        _ => None,
+    }
+}
+
+/// `(A, b)` or `A, b` as upper-cased names
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(|t| t.trim().to_ascii_uppercase())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// `KEY = value` pairs with keys upper-cased; a value continues on
+/// following lines while its parentheses are open or the lines are indented
+fn parameters(contents: &str) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut depth = 0i32;
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let continues = depth > 0 || line.starts_with(char::is_whitespace);
+        match (continues, entries.last_mut()) {
+            (true, Some((_, value))) => value.push_str(line.trim()),
+            _ => {
+                let Some((key, value)) = line.split_once('=') else {
+                    continue;
+                };
+                entries.push((key.trim().to_ascii_uppercase(), value.trim().to_string()));
+                depth = 0;
+            }
+        }
+        depth += line.matches('(').count() as i32 - line.matches(')').count() as i32;
+    }
+    entries
+}
+
+/// Seconds, or a number followed by `ms`, `sec` or `min`
+pub(crate) fn parse_timeout(value: &str) -> Option<Duration> {
+    let value = value.to_ascii_lowercase();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    match unit.trim() {
+        "" | "s" | "sec" | "secs" => Some(Duration::from_secs(number)),
+        "ms" => Some(Duration::from_millis(number)),
+        "min" => Some(Duration::from_secs(number * 60)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sqlnet_ora() {
+        let contents = "\
+# client profile
+NAMES.DIRECTORY_PATH = (TNSNAMES, EZCONNECT)
+sqlnet.outbound_connect_timeout = 500 ms
+SQLNET.TRANSPORT_CONNECT_TIMEOUT = 3
+SQLNET.EXPIRE_TIME = 10
+SQLNET.ENCRYPTION_CLIENT = required
+SQLNET.ENCRYPTION_TYPES_CLIENT = (AES256, aes192)
+SQLNET.CRYPTO_CHECKSUM_CLIENT = requested
+SQLNET.CRYPTO_CHECKSUM_TYPES_CLIENT = sha256
+WALLET_LOCATION =
+  (SOURCE =
+    (METHOD = FILE)
+    (METHOD_DATA = (DIRECTORY = /opt/oracle/wallet))
+  )
+SSL_SERVER_DN_MATCH = yes
+";
+        let config = SqlNetConfig::parse(contents).unwrap();
+        assert_eq!(
+            config.outbound_connect_timeout,
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            config.transport_connect_timeout,
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(config.expire_time, Some(Duration::from_secs(600)));
+        assert_eq!(config.encryption_client, Some(EncryptionLevel::Required));
+        assert_eq!(
+            config.encryption_types_client,
+            Some(vec!["AES256".to_string(), "AES192".to_string()])
+        );
+        assert_eq!(
+            config.crypto_checksum_client,
+            Some(EncryptionLevel::Requested)
+        );
+        assert_eq!(
+            config.crypto_checksum_types_client,
+            Some(vec!["SHA256".to_string()])
+        );
+
+        assert!(SqlNetConfig::parse("SQLNET.EXPIRE_TIME = soon").is_err());
+    }
+
+    #[test]
+    fn test_overrides_and_load() {
+        let dir = std::env::temp_dir().join(format!("oracledb-sqlnet-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("sqlnet.ora"),
+            "SQLNET.EXPIRE_TIME = 5\nSQLNET.ENCRYPTION_CLIENT = accepted\n",
+        )
+        .unwrap();
+        let file = SqlNetConfig::load(Some(&dir)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let config = SqlNetConfig::new()
+            .encryption_client(EncryptionLevel::Required)
+            .or(file);
+        assert_eq!(config.encryption_client, Some(EncryptionLevel::Required));
+        assert_eq!(config.expire_time, Some(Duration::from_secs(300)));
+
+        let missing = std::env::temp_dir().join("oracledb-sqlnet-missing");
+        assert_eq!(
+            SqlNetConfig::load(Some(&missing)).unwrap(),
+            SqlNetConfig::default()
+        );
+    }
+}
@@ -1,22 +1,35 @@
 // SQL statement execution
 
-use crate::protocol::Protocol;
-use crate::types::{ColumnInfo, FromSql, ToSql, Value};
-use crate::{Error, Result};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use crate::budget;
+use crate::connection::Connection;
+use crate::plan::{self, Plan};
+use crate::protocol::{Protocol, StatementType};
+use crate::runtime::{self, FairMutex};
+use crate::slow_query::Stopwatch;
+use crate::sql::{bind_names, has_returning, with_as_of, with_rowscn};
+use crate::stream::RowStream;
+use crate::types::{BindType, ColumnInfo, FromSql, OracleType, Scn, ToSql, Value};
+use crate::wire::RawValue;
+use crate::{Error, ExecuteOptions, OutFormat, Result, Warning};
+use chrono::NaiveDateTime;
+use futures::future::BoxFuture;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::ControlFlow;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 
 /// Prepared statement
+#[derive(Clone)]
 pub struct Statement {
     sql: String,
-    protocol: Arc<Mutex<Protocol>>,
+    protocol: Arc<FairMutex<Protocol>>,
     metadata: Option<Vec<ColumnInfo>>,
 }
 
 impl Statement {
     /// Create a new statement
-    pub fn new(sql: impl Into<String>, protocol: Arc<Mutex<Protocol>>) -> Self {
+    pub fn new(sql: impl Into<String>, protocol: Arc<FairMutex<Protocol>>) -> Self {
         Self {
             sql: sql.into(),
             protocol,
@@ -24,48 +37,521 @@ impl Statement {
         }
     }
 
+    /// SQL text the statement executes
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Read the tables of the query as they were at `scn` (flashback
+    /// query)
+    ///
+    /// Each table named after a top-level `FROM` or `JOIN` gets an
+    /// `AS OF SCN` clause. The SCN must fall within the undo retention
+    /// period, or execution fails with ORA-01555 or ORA-08181.
+    pub fn as_of_scn(self, scn: Scn) -> Result<Self> {
+        self.flashback(&format!("SCN {}", scn))
+    }
+
+    /// Read the tables of the query as they were at `timestamp`, in the
+    /// session time zone
+    pub fn as_of_timestamp(self, timestamp: NaiveDateTime) -> Result<Self> {
+        self.flashback(&format!(
+            "TIMESTAMP TIMESTAMP '{}'",
+            timestamp.format("%Y-%m-%d %H:%M:%S%.f")
+        ))
+    }
+
+    fn flashback(self, clause: &str) -> Result<Self> {
+        if !matches!(
+            Protocol::parse_statement_type(&self.sql),
+            Ok(StatementType::Select)
+        ) {
+            return Err(Error::InvalidSql(format!(
+                "flashback needs a query: {}",
+                self.sql
+            )));
+        }
+        let sql = with_as_of(&self.sql, clause)?;
+        Ok(Self::new(sql, self.protocol))
+    }
+
     /// Execute the statement and return results
     pub async fn execute(&self, params: &[&dyn ToSql]) -> Result<ResultSet> {
         let mut protocol = self.protocol.lock().await;
 
-        // Convert parameters to Values
-        let values: Vec<Value> = params.iter().map(|p| p.to_sql()).collect();
+        let values = bind(&mut protocol, params)?;
 
         // Execute statement through protocol
-        let (rows, metadata) = protocol.execute(&self.sql, &values).await?;
+        let stopwatch = Stopwatch::start(&protocol);
+        let result = protocol.execute(&self.sql, &values).await;
+        let outcome = result.as_ref().map(|(rows, _)| rows.len() as u64);
+        stopwatch.finish(&protocol, &self.sql, &values, outcome);
+        let (rows, metadata) = result?;
 
-        Ok(ResultSet {
-            rows,
-            metadata,
-            current_row: 0,
+        // A real implementation checks as each fetch arrives and cancels
+        // the cursor, so the oversized result is never fully buffered
+        let size = rows.iter().map(Row::wire_len).sum();
+        protocol.budget().check(size)?;
+
+        Ok(ResultSet::new(rows, metadata))
+    }
+
+    /// Execute the statement with values bound by placeholder name
+    ///
+    /// A name repeated in a PL/SQL block is bound once, as Oracle expects.
+    pub async fn execute_named(&self, params: &[(&str, &dyn ToSql)]) -> Result<ResultSet> {
+        let params = bind_by_name(&self.sql, params)?;
+        self.execute(&params).await
+    }
+
+    /// Execute the statement under `options`
+    ///
+    /// Rows are fetched `fetch_array_size` at a time and stop at `max_rows`;
+    /// with `auto_commit` the transaction is committed once the statement
+    /// succeeds. The result set reports rows in `out_format`. Cancelling
+    /// `cancel` breaks off the call and fails it with [`Error::Cancelled`];
+    /// waiting longer than `queue_timeout` for the connection fails it
+    /// with [`Error::ConnectionBusy`].
+    pub async fn execute_with(
+        &self,
+        params: &[&dyn ToSql],
+        options: &ExecuteOptions,
+    ) -> Result<ResultSet> {
+        if options.cancel.as_ref().is_some_and(|t| t.is_cancelled()) {
+            return Err(Error::Cancelled);
+        }
+        let sql = if options.include_rowscn {
+            Cow::Owned(with_rowscn(&self.sql)?)
+        } else {
+            Cow::Borrowed(self.sql.as_str())
+        };
+        let mut protocol = self.protocol.lock_within(options.queue_timeout).await?;
+
+        // A result cut short by max_rows is neither served nor cached
+        let cacheable = options.result_cache && options.max_rows == 0;
+        let values = bind(&mut protocol, params)?;
+        if cacheable {
+            if let Some((rows, metadata)) = protocol.cached_result(&sql, &values) {
+                protocol.define_binds(Vec::new());
+                // Served rows count against the caps as fetched ones do
+                let size = rows.iter().map(Row::wire_len).sum();
+                budget::check_limit(size, options.max_result_bytes)
+                    .and_then(|()| protocol.budget().check(size))?;
+                let rows = apply_fetch_types(rows, &metadata, options)?;
+                return Ok(ResultSet::new(rows, metadata).out_format(options.out_format));
+            }
+        }
+        let stopwatch = Stopwatch::start(&protocol);
+        let mut opened = None;
+        let fetch = Self::fetch_all(&mut protocol, &sql, &values, options, &mut opened);
+        let result = match &options.cancel {
+            Some(token) => runtime::cancellable(token, fetch).await.and_then(|r| r),
+            None => fetch.await,
+        };
+        if matches!(result, Err(Error::Cancelled)) {
+            protocol.send_break();
+            // The fetch was dropped before it could close its cursor
+            if let Some(cursor) = opened {
+                protocol.close_cursor(cursor);
+            }
+        }
+        let outcome = result.as_ref().map(|(rows, _)| rows.len() as u64);
+        stopwatch.finish(&protocol, &sql, &values, outcome);
+        let (rows, metadata) = result?;
+        if cacheable {
+            protocol.cache_result(&sql, &values, &rows, &metadata);
+        }
+
+        let rows = apply_fetch_types(rows, &metadata, options)?;
+
+        if options.auto_commit {
+            protocol.commit().await?;
+        }
+        Ok(ResultSet::new(rows, metadata).out_format(options.out_format))
+    }
+
+    /// Open a cursor and fetch from it until exhausted or `max_rows`,
+    /// leaving the cursor in `opened` for a caller that cancels the fetch
+    async fn fetch_all(
+        protocol: &mut Protocol,
+        sql: &str,
+        values: &[Value],
+        options: &ExecuteOptions,
+        opened: &mut Option<u32>,
+    ) -> Result<(Vec<Row>, Vec<ColumnInfo>)> {
+        let (cursor, metadata) = protocol.open_cursor(sql, values).await?;
+        *opened = Some(cursor);
+        let mut rows = Vec::new();
+        if metadata.is_empty() {
+            // DML and PL/SQL leave nothing to fetch
+            protocol.close_cursor(cursor);
+            return Ok((rows, metadata));
+        }
+
+        let mut size = 0;
+        loop {
+            let mut array_size = options.fetch_array_size;
+            if options.max_rows > 0 {
+                array_size = array_size.min(options.max_rows - rows.len());
+            }
+            let (batch, more) = match protocol.fetch(cursor, array_size).await {
+                Ok(fetched) => fetched,
+                Err(e) => {
+                    protocol.close_cursor(cursor);
+                    return Err(e);
+                }
+            };
+            size += batch.iter().map(Row::wire_len).sum::<usize>();
+            rows.extend(batch);
+
+            let checked = budget::check_limit(size, options.max_result_bytes)
+                .and_then(|()| protocol.budget().check(size));
+            if let Err(e) = checked {
+                protocol.close_cursor(cursor);
+                return Err(e);
+            }
+            if !more {
+                break;
+            }
+            if options.max_rows > 0 && rows.len() >= options.max_rows {
+                protocol.close_cursor(cursor);
+                break;
+            }
+        }
+        Ok((rows, metadata))
+    }
+
+    /// Execute the statement and stream its rows in batches
+    pub async fn query_stream(
+        &self,
+        params: &[&dyn ToSql],
+        options: &ExecuteOptions,
+    ) -> Result<RowStream> {
+        RowStream::open(self.protocol.clone(), &self.sql, params, options).await
+    }
+
+    /// Execute the statement with owned binds, returning a `'static`
+    /// future that can be boxed or spawned
+    pub fn execute_owned(
+        &self,
+        params: impl Into<Params>,
+    ) -> BoxFuture<'static, Result<ResultSet>> {
+        let stmt = Statement::new(self.sql.clone(), self.protocol.clone());
+        let params = params.into();
+        Box::pin(async move {
+            let binds = params.binds();
+            let refs: Vec<&dyn ToSql> = binds.iter().map(|b| b as &dyn ToSql).collect();
+            stmt.execute(&refs).await
         })
     }
 
+    /// Execute DML with owned binds, returning a `'static` future
+    pub fn execute_dml_owned(&self, params: impl Into<Params>) -> BoxFuture<'static, Result<u64>> {
+        let stmt = Statement::new(self.sql.clone(), self.protocol.clone());
+        let params = params.into();
+        Box::pin(async move {
+            let binds = params.binds();
+            let refs: Vec<&dyn ToSql> = binds.iter().map(|b| b as &dyn ToSql).collect();
+            stmt.execute_dml(&refs).await
+        })
+    }
+
+    /// Plan the optimizer would choose for the statement, without running it
+    ///
+    /// Runs `EXPLAIN PLAN` under a fresh `STATEMENT_ID` and reads the steps
+    /// back from the session's `PLAN_TABLE`. Binds are not needed; the plan
+    /// assumes nothing about their values. The steps are deleted again
+    /// afterwards, and the insert committed away if no transaction was open
+    /// before, so the session is left as it was.
+    pub async fn explain_plan(&self) -> Result<Plan> {
+        self.explain_plan_as(&plan::next_statement_id()).await
+    }
+
+    /// [`explain_plan`](Self::explain_plan) under `statement_id`
+    async fn explain_plan_as(&self, statement_id: &str) -> Result<Plan> {
+        let in_transaction = self.protocol.lock().await.in_transaction();
+        let explain = format!(
+            "EXPLAIN PLAN SET STATEMENT_ID = '{}' FOR {}",
+            statement_id, self.sql
+        );
+        Statement::new(explain, self.protocol.clone())
+            .execute(&[])
+            .await?;
+        let steps = Statement::new(plan::plan_table_query(), self.protocol.clone())
+            .execute(&[&statement_id])
+            .await
+            .and_then(|rows| rows.as_typed());
+        // Clean up even when the steps could not be read
+        let deleted = Statement::new(plan::plan_table_delete(), self.protocol.clone())
+            .execute_dml(&[&statement_id])
+            .await;
+        let restored = if in_transaction {
+            Ok(())
+        } else {
+            self.protocol.lock().await.commit().await
+        };
+        let steps = steps?;
+        deleted?;
+        restored?;
+        Plan::new(steps)
+    }
+
     /// Execute DML and return affected rows
     pub async fn execute_dml(&self, params: &[&dyn ToSql]) -> Result<u64> {
         let mut protocol = self.protocol.lock().await;
 
-        let values: Vec<Value> = params.iter().map(|p| p.to_sql()).collect();
-        protocol.execute_dml(&self.sql, &values).await
+        let values = bind(&mut protocol, params)?;
+        let stopwatch = Stopwatch::start(&protocol);
+        let result = protocol.execute_dml(&self.sql, &values).await;
+        stopwatch.finish(&protocol, &self.sql, &values, result.as_ref().copied());
+        result
+    }
+
+    /// Execute DML once per row of binds, returning each row's count
+    ///
+    /// Rows are drawn from `rows` as needed and sent
+    /// [`ConnectionConfig::batch_size`](crate::ConnectionConfig::batch_size)
+    /// at a time, so an iterator over a large file is never held in
+    /// memory at once. Bind types declared by the first row of a batch
+    /// apply to the whole batch.
+    pub async fn execute_many<I>(&self, rows: I) -> Result<Vec<u64>>
+    where
+        I: IntoIterator,
+        I::Item: IntoIterator,
+        <I::Item as IntoIterator>::Item: ToSql,
+    {
+        let rows = rows
+            .into_iter()
+            .map(|row| BindRow(row.into_iter().collect()));
+        self.execute_many_as(rows).await
+    }
+
+    /// Execute DML once per row of binds in batches, as
+    /// [`Statement::execute_many`] does, calling `progress` after each
+    /// batch
+    ///
+    /// Returning [`ControlFlow::Break`] from `progress` stops the load
+    /// and returns the counts of the rows sent so far, which stay in the
+    /// transaction; a break after the last batch changes nothing. A
+    /// failed batch is reported before its error is returned.
+    ///
+    /// ```rust,ignore
+    /// stmt.execute_many_with_progress(rows, |p| {
+    ///     eprintln!("{} rows, {:.0} rows/s", p.rows_sent, p.rows_per_sec);
+    ///     if cancel.is_cancelled() {
+    ///         ControlFlow::Break(())
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// })
+    /// .await?;
+    /// ```
+    pub async fn execute_many_with_progress<I, F>(&self, rows: I, progress: F) -> Result<Vec<u64>>
+    where
+        I: IntoIterator,
+        I::Item: IntoIterator,
+        <I::Item as IntoIterator>::Item: ToSql,
+        F: FnMut(&BatchProgress) -> ControlFlow<()>,
+    {
+        let rows = rows
+            .into_iter()
+            .map(|row| BindRow(row.into_iter().collect()));
+        self.send_batches(rows, progress).await
+    }
+
+    /// Execute DML once per [`ToRow`] value, such as a struct, in batches
+    /// as [`Statement::execute_many`] does
+    pub async fn execute_many_as<I>(&self, rows: I) -> Result<Vec<u64>>
+    where
+        I: IntoIterator,
+        I::Item: ToRow,
+    {
+        self.send_batches(rows, |_| ControlFlow::Continue(())).await
+    }
+
+    /// Send `rows` in batches of the configured size, reporting to
+    /// `progress` after each one
+    async fn send_batches<I, F>(&self, rows: I, mut progress: F) -> Result<Vec<u64>>
+    where
+        I: IntoIterator,
+        I::Item: ToRow,
+        F: FnMut(&BatchProgress) -> ControlFlow<()>,
+    {
+        let batch_size = self.protocol.lock().await.config().batch_size.max(1);
+        let mut counts = Vec::new();
+        let mut batch: Vec<Vec<Value>> = Vec::with_capacity(batch_size);
+        let mut types = Vec::new();
+        let mut report = BatchProgress::default();
+        let started = Instant::now();
+
+        let mut rows = rows.into_iter().peekable();
+        while rows.peek().is_some() {
+            for row in rows.by_ref().take(batch_size) {
+                if batch.is_empty() {
+                    types = row.bind_types();
+                }
+                batch.push(row.to_row());
+            }
+            let result = self.execute_batch(&types, &batch).await;
+            report.rows_sent += batch.len() as u64;
+            report.batches += 1;
+            let elapsed = started.elapsed().as_secs_f64();
+            if elapsed > 0.0 {
+                report.rows_per_sec = report.rows_sent as f64 / elapsed;
+            }
+            batch.clear();
+            match result {
+                Ok(batch_counts) => counts.extend(batch_counts),
+                Err(e) => {
+                    report.errors += 1;
+                    let _ = progress(&report);
+                    return Err(e);
+                }
+            }
+            if progress(&report).is_break() {
+                break;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Send one batch of rows in a single round trip
+    pub(crate) async fn execute_batch(
+        &self,
+        types: &[Option<BindType>],
+        batch: &[Vec<Value>],
+    ) -> Result<Vec<u64>> {
+        let mut protocol = self.protocol.lock().await;
+        protocol.define_binds(types.to_vec());
+        let stopwatch = Stopwatch::start(&protocol);
+        let result = protocol.execute_batch(&self.sql, batch).await;
+        let outcome = result.as_ref().map(|counts| counts.iter().sum());
+        stopwatch.finish(&protocol, &self.sql, &batch[0], outcome);
+        result
+    }
+
+    /// Execute any kind of statement and report what it did
+    ///
+    /// Queries fill [`ExecutionResult::result_set`], DML with a
+    /// `RETURNING` clause fills [`ExecutionResult::returning`], and other
+    /// DML only counts rows.
+    ///
+    /// Only queries fail with [`Error::ResultTooLarge`] past the
+    /// connection's memory budget; DML has already been applied by then,
+    /// so its `RETURNING` rows are returned with a logged warning.
+    pub async fn run(&self, params: &[&dyn ToSql]) -> Result<ExecutionResult> {
+        let statement_type = Protocol::parse_statement_type(&self.sql)?;
+        let returns_rows = match statement_type {
+            t if t.is_dml() => has_returning(&self.sql),
+            _ => true,
+        };
+        let mut protocol = self.protocol.lock().await;
+        let values = bind(&mut protocol, params)?;
+        let stopwatch = Stopwatch::start(&protocol);
+        let result = if returns_rows {
+            protocol
+                .execute(&self.sql, &values)
+                .await
+                .map(|(rows, metadata)| ExecutionResult::rows(&self.sql, rows, metadata))
+        } else {
+            protocol
+                .execute_dml(&self.sql, &values)
+                .await
+                .map(ExecutionResult::affected)
+        };
+        let outcome = result.as_ref().map(|r| r.rows_affected);
+        stopwatch.finish(&protocol, &self.sql, &values, outcome);
+        let mut result = result?;
+
+        let size = result.returning.iter().map(Row::wire_len).sum::<usize>()
+            + result
+                .result_set
+                .as_ref()
+                .map_or(0, |rs| rs.rows().iter().map(Row::wire_len).sum::<usize>());
+        check_budget_after(&protocol, statement_type, &self.sql, size)?;
+        result.warning = protocol.take_warning();
+        Ok(result)
     }
 
-    /// Execute many statements with batch binding
-    pub async fn execute_many(&self, batch_params: &[Vec<&dyn ToSql>]) -> Result<Vec<u64>> {
-        let mut results = Vec::with_capacity(batch_params.len());
+    /// Execute DML once per row of binds, as [`Statement::execute_many`]
+    /// does, reporting each row's count
+    pub async fn run_many<I>(&self, rows: I) -> Result<ExecutionResult>
+    where
+        I: IntoIterator,
+        I::Item: ToRow,
+    {
+        let row_counts = self.execute_many_as(rows).await?;
+        Ok(ExecutionResult {
+            rows_affected: row_counts.iter().fold(0, |n, &c| n.saturating_add(c)),
+            row_counts,
+            ..Default::default()
+        })
+    }
 
-        for params in batch_params {
-            let count = self.execute_dml(params.as_slice()).await?;
-            results.push(count);
+    /// Execute a PL/SQL call that returns several result sets and OUT
+    /// binds together
+    ///
+    /// Bind each OUT parameter with [`Out`](crate::Out); the call's
+    /// implicit results (DBMS_SQL.RETURN_RESULT) and OUT values are taken
+    /// from the returned [`Execution`] in the order the call produced
+    /// them. The call has been applied by the time its result sets are
+    /// measured, so they are kept past the memory budget with a logged
+    /// warning.
+    pub async fn execute_multi(&self, params: &[&dyn ToSql]) -> Result<Execution> {
+        if Protocol::parse_statement_type(&self.sql)? != StatementType::PlSql {
+            return Err(Error::InvalidSql(format!(
+                "execute_multi runs PL/SQL blocks and CALL statements: {}",
+                self.sql
+            )));
         }
+        self.execute_outs(params, StatementType::PlSql).await
+    }
+
+    /// Execute a statement of `statement_type`, reading back its OUT
+    /// binds, such as the targets of a `RETURNING ... INTO` clause
+    pub(crate) async fn execute_outs(
+        &self,
+        params: &[&dyn ToSql],
+        statement_type: StatementType,
+    ) -> Result<Execution> {
+        let outs: Vec<usize> = params
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_out())
+            .map(|(i, _)| i)
+            .collect();
+        let mut protocol = self.protocol.lock().await;
+        let values = bind_all(&mut protocol, params);
+        let stopwatch = Stopwatch::start(&protocol);
+        let result = protocol.execute_multi(&self.sql, &values, &outs).await;
+        let outcome = result.as_ref().map(|r| r.rows_affected);
+        stopwatch.finish(&protocol, &self.sql, &values, outcome);
+        let result = result?;
 
-        Ok(results)
+        let size = result
+            .result_sets
+            .iter()
+            .flat_map(|(rows, _)| rows)
+            .map(Row::wire_len)
+            .sum::<usize>();
+        check_budget_after(&protocol, statement_type, &self.sql, size)?;
+        Ok(Execution {
+            result_sets: result
+                .result_sets
+                .into_iter()
+                .map(|(rows, metadata)| ResultSet::new(rows, metadata))
+                .collect(),
+            out_values: result.out_values.into(),
+            rows_affected: result.rows_affected,
+            warning: protocol.take_warning(),
+        })
     }
 
-    /// Get statement metadata
+    /// Get statement metadata, described without executing the statement
     pub async fn get_metadata(&mut self) -> Result<&[ColumnInfo]> {
         if self.metadata.is_none() {
             let mut protocol = self.protocol.lock().await;
-            let metadata = protocol.get_metadata(&self.sql).await?;
+            let metadata = protocol.describe(&self.sql).await?;
             self.metadata = Some(metadata);
         }
 
@@ -73,14 +559,253 @@ impl Statement {
     }
 }
 
+/// SQL text or a prepared [`Statement`], as taken by
+/// [`Connection::execute`](crate::Connection::execute)
+///
+/// SQL text goes through the statement cache like any other execution, so
+/// code can pass either without two paths.
+pub trait IntoStatement {
+    /// The statement to run on `conn`
+    fn into_statement(self, conn: &Connection) -> Result<Statement>;
+}
+
+impl IntoStatement for &str {
+    fn into_statement(self, conn: &Connection) -> Result<Statement> {
+        Ok(Statement::new(self, conn.protocol().clone()))
+    }
+}
+
+impl IntoStatement for String {
+    fn into_statement(self, conn: &Connection) -> Result<Statement> {
+        Ok(Statement::new(self, conn.protocol().clone()))
+    }
+}
+
+/// Borrowed and shared forms of SQL text, which a generic parameter
+/// does not deref-coerce to `&str`
+macro_rules! text_into_statement {
+    ($($ty:ty),*) => {
+        $(impl IntoStatement for $ty {
+            fn into_statement(self, conn: &Connection) -> Result<Statement> {
+                let sql: &str = self.as_ref();
+                sql.into_statement(conn)
+            }
+        })*
+    };
+}
+
+text_into_statement!(
+    &String,
+    &&str,
+    Cow<'_, str>,
+    &Cow<'_, str>,
+    Box<str>,
+    &Box<str>,
+    Arc<str>,
+    &Arc<str>
+);
+
+impl IntoStatement for Statement {
+    fn into_statement(self, conn: &Connection) -> Result<Statement> {
+        if !Arc::ptr_eq(&self.protocol, conn.protocol()) {
+            return Err(Error::InvalidConfiguration(format!(
+                "statement was prepared on another connection: {}",
+                self.sql
+            )));
+        }
+        Ok(self)
+    }
+}
+
+impl IntoStatement for &Statement {
+    fn into_statement(self, conn: &Connection) -> Result<Statement> {
+        self.clone().into_statement(conn)
+    }
+}
+
+/// Progress of a batched load, reported after each batch
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BatchProgress {
+    /// Rows sent so far
+    pub rows_sent: u64,
+    /// Batches sent so far
+    pub batches: u64,
+    /// Batches that failed so far
+    pub errors: u64,
+    /// Rows sent per second since the load started
+    pub rows_per_sec: f64,
+}
+
+/// What a statement did, whatever its kind
+#[derive(Debug, Default)]
+pub struct ExecutionResult {
+    /// Rows inserted, updated, deleted or fetched
+    pub rows_affected: u64,
+    /// Rows affected by each bind row of array DML, in order
+    pub row_counts: Vec<u64>,
+    /// Values of a `RETURNING ... INTO` clause, one row per affected row
+    pub returning: Vec<Row>,
+    /// Rows of a query
+    pub result_set: Option<ResultSet>,
+    /// Warning the server attached, such as ORA-24344
+    pub warning: Option<Warning>,
+}
+
+impl ExecutionResult {
+    fn affected(rows_affected: u64) -> Self {
+        Self {
+            rows_affected,
+            ..Default::default()
+        }
+    }
+
+    fn rows(sql: &str, rows: Vec<Row>, metadata: Vec<ColumnInfo>) -> Self {
+        let rows_affected = rows.len() as u64;
+        match Protocol::parse_statement_type(sql) {
+            Ok(StatementType::Select) => Self {
+                rows_affected,
+                result_set: Some(ResultSet::new(rows, metadata)),
+                ..Default::default()
+            },
+            // PL/SQL and DDL report no rows
+            _ => Self {
+                rows_affected,
+                returning: rows,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// What a PL/SQL call sent back, before it is wrapped in an [`Execution`]
+#[derive(Debug, Default)]
+pub(crate) struct CallResults {
+    pub(crate) result_sets: Vec<(Vec<Row>, Vec<ColumnInfo>)>,
+    pub(crate) out_values: Vec<Value>,
+    pub(crate) rows_affected: u64,
+}
+
+/// Everything a PL/SQL call returned, taken in order
+///
+/// Result sets come in the order the call returned them, OUT values in
+/// bind order.
+#[derive(Debug, Default)]
+pub struct Execution {
+    result_sets: VecDeque<ResultSet>,
+    out_values: VecDeque<Value>,
+    rows_affected: u64,
+    warning: Option<Warning>,
+}
+
+impl Execution {
+    /// Take the next implicit result set, if any are left
+    pub fn next_result_set(&mut self) -> Option<ResultSet> {
+        self.result_sets.pop_front()
+    }
+
+    /// Number of result sets not yet taken
+    pub fn result_sets_remaining(&self) -> usize {
+        self.result_sets.len()
+    }
+
+    /// Take the next OUT value, converted to `T`
+    pub fn next_out<T: FromSql>(&mut self) -> Result<T> {
+        let value = self
+            .out_values
+            .pop_front()
+            .ok_or_else(|| Error::TypeMismatch("no OUT values left; bind them with Out".into()))?;
+        T::from_sql(&value)
+    }
+
+    /// OUT values not yet taken, in bind order
+    pub fn out_values(&self) -> impl Iterator<Item = &Value> {
+        self.out_values.iter()
+    }
+
+    /// Rows the call reported as affected
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// Warning the server attached, such as ORA-24344
+    pub fn warning(&self) -> Option<&Warning> {
+        self.warning.as_ref()
+    }
+
+    /// Take the warning, leaving `None`
+    pub fn take_warning(&mut self) -> Option<Warning> {
+        self.warning.take()
+    }
+}
+
+/// Check `size` bytes of rows an executed statement returned against the
+/// memory budget
+///
+/// A query has no side effects, so it fails; any other statement has
+/// already been applied, and failing it would invite a retry that applies
+/// it twice, so its rows are kept with a warning.
+fn check_budget_after(
+    protocol: &Protocol,
+    statement_type: StatementType,
+    sql: &str,
+    size: usize,
+) -> Result<()> {
+    match protocol.budget().check(size) {
+        Err(e) if statement_type != StatementType::Select => {
+            log::warn!(
+                "keeping rows of executed statement past the budget ({}): {}",
+                e,
+                sql
+            );
+            Ok(())
+        }
+        checked => checked,
+    }
+}
+
 /// Result set from query execution
+#[derive(Debug)]
 pub struct ResultSet {
     rows: Vec<Row>,
     metadata: Vec<ColumnInfo>,
     current_row: usize,
+    out_format: OutFormat,
 }
 
 impl ResultSet {
+    /// Wrap fetched rows and their metadata
+    pub(crate) fn new(rows: Vec<Row>, metadata: Vec<ColumnInfo>) -> Self {
+        Self {
+            rows,
+            metadata,
+            current_row: 0,
+            out_format: OutFormat::Object,
+        }
+    }
+
+    /// Report rows in `format` from [`ResultSet::to_values`]
+    pub(crate) fn out_format(mut self, format: OutFormat) -> Self {
+        self.out_format = format;
+        self
+    }
+
+    /// Format rows are reported in
+    pub fn format(&self) -> OutFormat {
+        self.out_format
+    }
+
+    /// Rows in the requested format: [`Value::Object`] keyed by column
+    /// name, or [`Value::Array`] in column order
+    pub fn to_values(&self) -> Vec<Value> {
+        self.rows
+            .iter()
+            .map(|row| match self.out_format {
+                OutFormat::Object => Value::Object(row.to_map()),
+                OutFormat::Array => Value::Array(row.values().to_vec()),
+            })
+            .collect()
+    }
+
     /// Get number of rows in result set
     pub fn len(&self) -> usize {
         self.rows.len()
@@ -96,6 +821,30 @@ impl ResultSet {
         &self.metadata
     }
 
+    /// Column names, in the order of each row's values
+    pub fn columns(&self) -> Vec<&str> {
+        self.metadata.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    /// Position of the column called `name`, to look up once per query
+    /// and read each row with [`Row::get_typed_at`]
+    pub fn column_index(&self, name: &str) -> Result<usize> {
+        column_position(&self.metadata, name)
+    }
+
+    /// Every value in one flat list, row after row in column order
+    ///
+    /// The usual way to consume an [`OutFormat::Array`] result: names are
+    /// left to [`ResultSet::columns`], nothing is keyed per row, and each
+    /// row is `columns().len()` values wide, so `chunks` splits them.
+    pub fn into_arrays(self) -> Vec<Value> {
+        let mut values = Vec::with_capacity(self.rows.len() * self.metadata.len());
+        for row in self.rows {
+            values.extend(row.into_values());
+        }
+        values
+    }
+
     /// Get all rows
     pub fn rows(&self) -> &[Row] {
         &self.rows
@@ -137,77 +886,459 @@ impl Iterator for ResultSet {
     }
 }
 
+/// Column names of a result set mapped to their positions
+///
+/// Built once per execution and shared by every row of the result. The
+/// name lookup table is only built on the first lookup by name, so rows
+/// read by position never pay for it.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnIndex {
+    names: Vec<String>,
+    /// Declared types, when built from column metadata
+    types: Vec<OracleType>,
+    /// Statement the rows came from, for diagnostics
+    sql: Option<Arc<str>>,
+    positions: OnceLock<HashMap<String, usize>>,
+}
+
+impl ColumnIndex {
+    /// Index columns by name
+    pub fn new(names: Vec<String>) -> Self {
+        Self {
+            names,
+            types: Vec::new(),
+            sql: None,
+            positions: OnceLock::new(),
+        }
+    }
+
+    /// Index described columns, keeping their declared types
+    pub fn from_columns(columns: &[ColumnInfo]) -> Self {
+        Self {
+            names: columns.iter().map(|c| c.name.clone()).collect(),
+            types: columns.iter().map(|c| c.oracle_type).collect(),
+            sql: None,
+            positions: OnceLock::new(),
+        }
+    }
+
+    /// Record the statement the rows came from
+    pub fn with_sql(mut self, sql: &str) -> Self {
+        self.sql = Some(sql.into());
+        self
+    }
+
+    /// Statement the rows came from, if recorded
+    pub fn sql(&self) -> Option<&str> {
+        self.sql.as_deref()
+    }
+
+    /// Declared type of the column at `index`, if known
+    pub fn oracle_type(&self, index: usize) -> Option<OracleType> {
+        self.types.get(index).copied()
+    }
+
+    /// Position of the column called `name`
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.positions
+            .get_or_init(|| {
+                self.names
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| (name.clone(), i))
+                    .collect()
+            })
+            .get(name)
+            .copied()
+    }
+
+    /// Column names in order
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Get number of columns
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Check if there are no columns
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
 /// Row from query result
+///
+/// Rows fetched from the server keep their columns in wire format and are
+/// decoded on demand: typed getters convert a single column with
+/// [`FromSql::from_raw`], while untyped access decodes the whole row once.
 #[derive(Debug, Clone)]
 pub struct Row {
-    /// Column values (indexed)
-    values: Vec<Value>,
-    /// Column names mapped to indices
-    columns: HashMap<String, usize>,
+    /// Column values (indexed), decoded on first access for raw rows
+    values: OnceLock<Vec<Value>>,
+    /// Undecoded column data
+    raw: Option<Vec<RawValue>>,
+    /// Column positions shared with the rest of the result set
+    columns: Arc<ColumnIndex>,
 }
 
 impl Row {
     /// Create a new row
     pub fn new(values: Vec<Value>, column_names: Vec<String>) -> Self {
-        let columns = column_names
-            .into_iter()
-            .enumerate()
-            .map(|(i, name)| (name, i))
-            .collect();
+        Self::with_columns(values, Arc::new(ColumnIndex::new(column_names)))
+    }
 
-        Self { values, columns }
+    /// Create a row that shares its column index with other rows
+    pub fn with_columns(values: Vec<Value>, columns: Arc<ColumnIndex>) -> Self {
+        Self {
+            values: OnceLock::from(values),
+            raw: None,
+            columns,
+        }
+    }
+
+    /// Create a row over undecoded column data
+    pub fn from_raw(raw: Vec<RawValue>, columns: Arc<ColumnIndex>) -> Self {
+        Self {
+            values: OnceLock::new(),
+            raw: Some(raw),
+            columns,
+        }
+    }
+
+    /// Column positions by name
+    pub fn columns(&self) -> &Arc<ColumnIndex> {
+        &self.columns
     }
 
     /// Get value by index
     pub fn get(&self, index: usize) -> Option<&Value> {
-        self.values.get(index)
+        self.values().get(index)
     }
 
     /// Get value by column name
     pub fn get_by_name(&self, name: &str) -> Option<&Value> {
-        self.columns.get(name).and_then(|&i| self.values.get(i))
+        self.columns.position(name).and_then(|i| self.get(i))
+    }
+
+    /// Get undecoded column data by index, if the row came off the wire
+    pub fn get_raw(&self, index: usize) -> Option<&RawValue> {
+        self.raw.as_ref()?.get(index)
     }
 
     /// Get typed value by index
     pub fn get_typed<T: FromSql>(&self, index: usize) -> Result<T> {
+        self.get_typed_at(index)
+    }
+
+    /// Get typed value at a position from [`ResultSet::column_index`]
+    ///
+    /// Reads by position never touch the name table, so a loop that
+    /// resolves its columns once does no per-row lookups.
+    #[inline]
+    pub fn get_typed_at<T: FromSql>(&self, index: usize) -> Result<T> {
+        if self.values.get().is_none() {
+            if let Some(raw) = self.get_raw(index) {
+                return T::from_raw(raw);
+            }
+        }
         let value = self
             .get(index)
-            .ok_or(Error::ColumnNotFound(index.to_string()))?;
+            .ok_or_else(|| Error::ColumnNotFound(index.to_string()))?;
         T::from_sql(value)
     }
 
+    /// Get value by index without a bounds check
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than [`len`](Self::len).
+    #[inline]
+    pub unsafe fn get_unchecked(&self, index: usize) -> &Value {
+        // SAFETY: the caller keeps `index` in bounds
+        unsafe { self.values().get_unchecked(index) }
+    }
+
+    /// Get typed value by index without a bounds check; the conversion
+    /// itself can still fail
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than [`len`](Self::len).
+    #[inline]
+    pub unsafe fn get_typed_unchecked<T: FromSql>(&self, index: usize) -> Result<T> {
+        if self.values.get().is_none() {
+            if let Some(raw) = &self.raw {
+                // SAFETY: a raw row has one value per column
+                return T::from_raw(unsafe { raw.get_unchecked(index) });
+            }
+        }
+        // SAFETY: the caller keeps `index` in bounds
+        T::from_sql(unsafe { self.get_unchecked(index) })
+    }
+
     /// Get typed value by column name
     pub fn get_typed_by_name<T: FromSql>(&self, name: &str) -> Result<T> {
-        let value = self
-            .get_by_name(name)
+        let index = self
+            .columns
+            .position(name)
             .ok_or(Error::ColumnNotFound(name.to_string()))?;
-        T::from_sql(value)
+        self.get_typed(index)
+    }
+
+    /// Get typed value by index, failing with an [`Error::TypeMismatch`]
+    /// that names the column, its declared type, the query, the value's
+    /// variant and the Rust type asked for
+    pub fn try_get<T: FromSql>(&self, index: usize) -> Result<T> {
+        self.get_typed(index).map_err(|e| match e {
+            Error::TypeMismatch(detail) => {
+                Error::TypeMismatch(self.mismatch(index, std::any::type_name::<T>(), &detail))
+            }
+            e => e,
+        })
+    }
+
+    /// Get typed value by column name, with the diagnostics of
+    /// [`try_get`](Self::try_get)
+    pub fn try_get_by_name<T: FromSql>(&self, name: &str) -> Result<T> {
+        let index = self
+            .columns
+            .position(name)
+            .ok_or(Error::ColumnNotFound(name.to_string()))?;
+        self.try_get(index)
+    }
+
+    /// Describe a failed read of column `index` as `target`
+    fn mismatch(&self, index: usize, target: &str, detail: &str) -> String {
+        let name = self
+            .columns
+            .names()
+            .get(index)
+            .map_or_else(|| format!("#{}", index), |name| format!("{:?}", name));
+        let declared = self
+            .columns
+            .oracle_type(index)
+            .or_else(|| self.get_raw(index).map(RawValue::oracle_type))
+            .map_or("unknown type", |t| t.sql_name());
+        let value = self.get(index);
+        let held = match value {
+            Some(value) if value.is_null() => {
+                format!("NULL, which needs Option<{}>", target)
+            }
+            Some(value) => format!("Value::{}", value.variant_name()),
+            None => "no value".to_string(),
+        };
+        let query = self
+            .columns
+            .sql()
+            .map_or_else(String::new, |sql| format!(" in query {:?}", sql));
+        format!(
+            "column {} ({}) at index {}{} holds {}, not convertible to {}: {}",
+            name, declared, index, query, held, target, detail
+        )
     }
 
     /// Get all values
+    ///
+    /// A column that cannot be decoded reads as [`Value::Null`], with a
+    /// warning logged; [`try_values`](Self::try_values) reports it
+    /// instead.
     pub fn values(&self) -> &[Value] {
-        &self.values
+        self.values.get_or_init(|| self.decode_lossy())
+    }
+
+    /// Decode the raw values, reading columns that fail as NULL
+    fn decode_lossy(&self) -> Vec<Value> {
+        let raw = self.raw.as_deref().unwrap_or_default();
+        raw.iter()
+            .map(|raw| {
+                raw.decode().unwrap_or_else(|e| {
+                    log::warn!("undecodable {:?} column: {}", raw.oracle_type(), e);
+                    Value::Null
+                })
+            })
+            .collect()
+    }
+
+    /// Get all values, failing if a column cannot be decoded
+    pub fn try_values(&self) -> Result<&[Value]> {
+        if let Some(values) = self.values.get() {
+            return Ok(values);
+        }
+        let raw = self.raw.as_deref().unwrap_or_default();
+        let values = raw
+            .iter()
+            .map(RawValue::decode)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.values.get_or_init(|| values))
+    }
+
+    /// SCN of the row's last change, from a query run with
+    /// [`ExecuteOptions::include_rowscn`]
+    pub fn rowscn(&self) -> Option<Scn> {
+        self.get_typed_by_name("ORA_ROWSCN").ok()
+    }
+
+    /// Take the values, decoding them if they were never read
+    pub fn into_values(mut self) -> Vec<Value> {
+        match self.values.take() {
+            Some(values) => values,
+            None => self.decode_lossy(),
+        }
     }
 
     /// Get number of columns
     pub fn len(&self) -> usize {
-        self.values.len()
+        match (&self.raw, self.values.get()) {
+            (Some(raw), _) => raw.len(),
+            (None, Some(values)) => values.len(),
+            (None, None) => 0,
+        }
     }
 
     /// Check if row is empty
     pub fn is_empty(&self) -> bool {
-        self.values.is_empty()
+        self.len() == 0
+    }
+
+    /// Approximate size of the row on the wire, length bytes included
+    pub(crate) fn wire_len(&self) -> usize {
+        match &self.raw {
+            Some(raw) => raw
+                .iter()
+                .map(|v| 1 + v.bytes().map_or(0, |b| b.len()))
+                .sum(),
+            None => self.values().iter().map(|v| 1 + value_wire_len(v)).sum(),
+        }
     }
 
     /// Convert row to HashMap
     pub fn to_map(&self) -> HashMap<String, Value> {
+        let values = self.values();
         self.columns
+            .names()
             .iter()
-            .map(|(name, &i)| (name.clone(), self.values[i].clone()))
+            .zip(values)
+            .map(|(name, value)| (name.clone(), value.clone()))
             .collect()
     }
 }
 
+/// Position of the column called `name` in `metadata`
+pub(crate) fn column_position(metadata: &[ColumnInfo], name: &str) -> Result<usize> {
+    metadata
+        .iter()
+        .position(|c| c.name == name)
+        .ok_or_else(|| Error::ColumnNotFound(name.to_string()))
+}
+
+/// Run the rows of a query through `options.fetch_type_handler`, if set
+fn apply_fetch_types(
+    rows: Vec<Row>,
+    metadata: &[ColumnInfo],
+    options: &ExecuteOptions,
+) -> Result<Vec<Row>> {
+    match options
+        .fetch_type_handler
+        .as_ref()
+        .and_then(|handler| handler.plan(metadata))
+    {
+        Some(plan) => rows.into_iter().map(|row| plan.apply(row)).collect(),
+        None => Ok(rows),
+    }
+}
+
+/// Convert `params` to values, declaring their bind types on `protocol`
+/// for the execution that follows
+///
+/// Only [`Statement::execute_multi`] reads OUT binds back, so here they
+/// fail rather than bind a NULL whose result would be lost.
+pub(crate) fn bind(protocol: &mut Protocol, params: &[&dyn ToSql]) -> Result<Vec<Value>> {
+    if let Some(i) = params.iter().position(|p| p.is_out()) {
+        return Err(Error::InvalidBindParameter(format!(
+            "OUT bind at position {} is only read back by execute_multi",
+            i + 1
+        )));
+    }
+    Ok(bind_all(protocol, params))
+}
+
+/// As [`bind`], OUT binds included
+fn bind_all(protocol: &mut Protocol, params: &[&dyn ToSql]) -> Vec<Value> {
+    protocol.define_binds(params.iter().map(|p| p.bind_type()).collect());
+    params.iter().map(|p| p.to_sql()).collect()
+}
+
+/// Positional binds for values given by placeholder name
+///
+/// Names match with or without the leading `:`, case-insensitively
+/// unless double-quoted. A PL/SQL block binds each distinct name once,
+/// however often it appears; SQL binds every occurrence, so a repeated
+/// name is bound at each of its positions.
+pub(crate) fn bind_by_name<'a>(
+    sql: &str,
+    named: &[(&str, &'a dyn ToSql)],
+) -> Result<Vec<&'a dyn ToSql>> {
+    let key = |name: &str| {
+        let name = name.strip_prefix(':').unwrap_or(name);
+        match name.strip_prefix('"').and_then(|n| n.strip_suffix('"')) {
+            Some(quoted) => quoted.to_string(),
+            None => name.to_ascii_uppercase(),
+        }
+    };
+    let mut names = bind_names(sql);
+    if matches!(
+        Protocol::parse_statement_type(sql),
+        Ok(StatementType::PlSql)
+    ) {
+        let mut seen = HashSet::new();
+        names.retain(|name| seen.insert(name.clone()));
+    }
+    let values: HashMap<String, &dyn ToSql> = named
+        .iter()
+        .map(|&(name, value)| (key(name), value))
+        .collect();
+    if let Some((unused, _)) = named.iter().find(|(name, _)| !names.contains(&key(name))) {
+        return Err(Error::InvalidBindParameter(format!(
+            "no placeholder :{} in statement",
+            unused.trim_start_matches(':')
+        )));
+    }
+    names
+        .iter()
+        .map(|name| {
+            values
+                .get(name)
+                .copied()
+                .ok_or_else(|| Error::InvalidBindParameter(format!("no value bound for :{}", name)))
+        })
+        .collect()
+}
+
+/// Bytes a decoded value takes in Oracle's wire format, roughly
+pub(crate) fn value_wire_len(value: &Value) -> usize {
+    match value {
+        Value::Null => 0,
+        Value::String(s) | Value::Clob(s) => s.len(),
+        Value::Integer(_) | Value::Float(_) => 8,
+        Value::Boolean(_) => 1,
+        Value::Date(_) => 7,
+        Value::Timestamp(_) => 11,
+        Value::TimestampTz(_) => 13,
+        #[cfg(feature = "chrono-tz")]
+        Value::TimestampTzRegion(_) => 13,
+        Value::IntervalYM(_) => 5,
+        Value::IntervalDS(_) => 11,
+        Value::Bytes(b) | Value::Blob(b) => b.len(),
+        Value::Lob(lob) => lob.locator().len() + lob.prefetched().map_or(0, <[u8]>::len),
+        Value::Json(json) => json.to_string().len(),
+        Value::Vector(vector) => vector.encode().len(),
+        Value::Array(values) => values.iter().map(value_wire_len).sum(),
+        Value::Object(map) => map.values().map(value_wire_len).sum(),
+    }
+}
+
 /// Trait for converting from a Row
 pub trait FromRow: Sized {
     /// Convert from row
@@ -233,9 +1364,168 @@ impl<T1: FromSql, T2: FromSql, T3: FromSql> FromRow for (T1, T2, T3) {
     }
 }
 
+/// Trait for values bound as one row of a batch, in placeholder order
+///
+/// Implemented for tuples of [`ToSql`] values; use [`to_row!`](crate::to_row)
+/// to implement it for a struct.
+pub trait ToRow {
+    /// Bind values of the row
+    fn to_row(&self) -> Vec<Value>;
+
+    /// Declared bind types, if any differ from the values' own
+    fn bind_types(&self) -> Vec<Option<BindType>> {
+        Vec::new()
+    }
+}
+
+impl<T: ToRow + ?Sized> ToRow for &T {
+    fn to_row(&self) -> Vec<Value> {
+        (**self).to_row()
+    }
+
+    fn bind_types(&self) -> Vec<Option<BindType>> {
+        (**self).bind_types()
+    }
+}
+
+macro_rules! impl_to_row_tuple {
+    ($($field:tt: $ty:ident),+) => {
+        impl<$($ty: ToSql),+> ToRow for ($($ty,)+) {
+            fn to_row(&self) -> Vec<Value> {
+                vec![$(self.$field.to_sql()),+]
+            }
+
+            fn bind_types(&self) -> Vec<Option<BindType>> {
+                vec![$(self.$field.bind_type()),+]
+            }
+        }
+    };
+}
+
+impl_to_row_tuple!(0: T1);
+impl_to_row_tuple!(0: T1, 1: T2);
+impl_to_row_tuple!(0: T1, 1: T2, 2: T3);
+impl_to_row_tuple!(0: T1, 1: T2, 2: T3, 3: T4);
+impl_to_row_tuple!(0: T1, 1: T2, 2: T3, 3: T4, 4: T5);
+impl_to_row_tuple!(0: T1, 1: T2, 2: T3, 3: T4, 4: T5, 5: T6);
+impl_to_row_tuple!(0: T1, 1: T2, 2: T3, 3: T4, 4: T5, 5: T6, 6: T7);
+impl_to_row_tuple!(0: T1, 1: T2, 2: T3, 3: T4, 4: T5, 5: T6, 6: T7, 7: T8);
+
+/// Implement [`ToRow`] for a struct, binding the listed fields in order
+///
+/// ```rust,ignore
+/// struct Employee { name: String, salary: f64, dept_id: i64 }
+/// oracledb_rs::to_row!(Employee { name, salary, dept_id });
+///
+/// conn.execute_many_as("INSERT INTO emp VALUES (:1, :2, :3)", &staff).await?;
+/// ```
+#[macro_export]
+macro_rules! to_row {
+    ($ty:ty { $($field:ident),+ $(,)? }) => {
+        impl $crate::statement::ToRow for $ty {
+            fn to_row(&self) -> Vec<$crate::Value> {
+                vec![$($crate::types::ToSql::to_sql(&self.$field)),+]
+            }
+
+            fn bind_types(&self) -> Vec<Option<$crate::BindType>> {
+                vec![$($crate::types::ToSql::bind_type(&self.$field)),+]
+            }
+        }
+    };
+}
+
+/// A row of individually bound values
+struct BindRow<T>(Vec<T>);
+
+/// Bind values owned by an execution, so its future borrows nothing
+///
+/// Converts from a `Vec<Value>` or any [`ToRow`], such as a tuple, keeping
+/// declared bind types:
+///
+/// ```rust,ignore
+/// let insert = conn.execute_dml_owned(sql, (id, name.clone()));
+/// tokio::spawn(insert);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params {
+    values: Vec<Value>,
+    types: Vec<Option<BindType>>,
+}
+
+impl Params {
+    /// Create an empty parameter list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a bind
+    pub fn push(mut self, value: impl ToSql) -> Self {
+        self.types.resize(self.values.len(), None);
+        self.types.push(value.bind_type());
+        self.values.push(value.to_sql());
+        self
+    }
+
+    /// Bound values, in position order
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Binds borrowed for a positional execution
+    pub(crate) fn binds(&self) -> Vec<Typed<'_>> {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| Typed(value, self.types.get(i).copied().flatten()))
+            .collect()
+    }
+}
+
+impl From<Vec<Value>> for Params {
+    fn from(values: Vec<Value>) -> Self {
+        Self {
+            values,
+            types: Vec::new(),
+        }
+    }
+}
+
+impl<T: ToRow> From<T> for Params {
+    fn from(row: T) -> Self {
+        Self {
+            values: row.to_row(),
+            types: row.bind_types(),
+        }
+    }
+}
+
+/// An owned value bound with its declared type
+pub(crate) struct Typed<'a>(&'a Value, Option<BindType>);
+
+impl ToSql for Typed<'_> {
+    fn to_sql(&self) -> Value {
+        self.0.clone()
+    }
+
+    fn bind_type(&self) -> Option<BindType> {
+        self.1.or_else(|| self.0.bind_type())
+    }
+}
+
+impl<T: ToSql> ToRow for BindRow<T> {
+    fn to_row(&self) -> Vec<Value> {
+        self.0.iter().map(ToSql::to_sql).collect()
+    }
+
+    fn bind_types(&self) -> Vec<Option<BindType>> {
+        self.0.iter().map(ToSql::bind_type).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::OracleType;
 
     #[test]
     fn test_row_creation() {
@@ -261,4 +1551,571 @@ mod tests {
         let count: i64 = row.get_typed_by_name("count").unwrap();
         assert_eq!(count, 42);
     }
+
+    #[tokio::test]
+    async fn test_result_over_connection_budget() {
+        let config = crate::ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass")
+            .max_buffered_bytes(4);
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+        let protocol = Arc::new(FairMutex::new(protocol));
+
+        let stmt = Statement::new("SELECT id, name FROM t", protocol.clone());
+        let err = stmt.execute(&[]).await.unwrap_err();
+        assert!(matches!(err, Error::ResultTooLarge { limit: 4, .. }));
+
+        // DML carries no rows and is unaffected
+        let stmt = Statement::new("UPDATE t SET name = 'x'", protocol);
+        assert_eq!(stmt.execute_dml(&[]).await.unwrap(), 1);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_applied_dml_over_budget_kept() {
+        use crate::testing::{column, Expectation, MockConnection};
+
+        let sql = "UPDATE t SET name = 'x' RETURNING name INTO :1";
+        let mock = MockConnection::new().expect(Expectation::query(sql).returns(
+            vec![column("NAME", OracleType::Varchar2)],
+            vec![vec![Value::String("a long returned name".into())]],
+        ));
+        let config =
+            crate::ConnectionConfig::new("localhost:1521/ORCL", "u", "p").max_buffered_bytes(4);
+        let conn = mock.connect_with(config).await.unwrap();
+
+        // Failing would invite a retry that applies the update twice
+        let result = conn.prepare(sql).await.unwrap().run(&[]).await.unwrap();
+        assert_eq!(result.returning.len(), 1);
+        mock.verify();
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_explain_plan_cleans_up() {
+        use crate::testing::{column, Expectation, MockCall, MockConnection};
+
+        let explain = "EXPLAIN PLAN SET STATEMENT_ID = 'plan_1' FOR SELECT * FROM emp";
+        let columns: Vec<_> = [
+            "ID",
+            "PARENT_ID",
+            "DEPTH",
+            "OPERATION",
+            "OPTIONS",
+            "OBJECT_OWNER",
+            "OBJECT_NAME",
+            "COST",
+            "CARDINALITY",
+            "BYTES",
+        ]
+        .map(|name| column(name, OracleType::Varchar2))
+        .into();
+        let mut root = vec![Value::Null; 10];
+        root[0] = Value::Integer(0);
+        root[3] = Value::String("SELECT STATEMENT".into());
+        let id = || vec![Value::String("plan_1".into())];
+        let explained = |mock: MockConnection| {
+            mock.expect(Expectation::dml(explain, 0))
+                .expect(
+                    Expectation::query(plan::plan_table_query())
+                        .params(id())
+                        .returns(columns.clone(), vec![root.clone()]),
+                )
+                .expect(Expectation::dml(plan::plan_table_delete(), 1).params(id()))
+        };
+        let mock = explained(
+            explained(MockConnection::new())
+                .expect(Expectation::dml("UPDATE emp SET sal = sal", 14)),
+        );
+        let conn = mock.connect().await.unwrap();
+        let stmt = conn.prepare("SELECT * FROM emp").await.unwrap();
+
+        // Without a transaction open, the plan rows' insert is committed away
+        let plan = stmt.explain_plan_as("plan_1").await.unwrap();
+        assert_eq!(plan.root().unwrap().operation, "SELECT STATEMENT");
+        assert_eq!(mock.calls().last(), Some(&MockCall::Commit));
+
+        // An open transaction is left for the caller to finish
+        conn.execute_dml("UPDATE emp SET sal = sal", &[])
+            .await
+            .unwrap();
+        stmt.explain_plan_as("plan_1").await.unwrap();
+        assert!(matches!(
+            mock.calls().last(),
+            Some(MockCall::Execute { sql, .. }) if sql == plan::plan_table_delete()
+        ));
+        mock.verify();
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_include_rowscn() {
+        use crate::testing::{column, Expectation, MockConnection};
+
+        let ts = chrono::NaiveDate::from_ymd_opt(2026, 3, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let mock = MockConnection::new()
+            .expect(
+                Expectation::query("SELECT o.*, o.ORA_ROWSCN FROM orders o WHERE id = :1").returns(
+                    vec![
+                        column("ID", OracleType::Number),
+                        column("ORA_ROWSCN", OracleType::Number),
+                    ],
+                    vec![vec![Value::Integer(7), Value::Integer(4_711_000)]],
+                ),
+            )
+            .expect(
+                Expectation::query("SELECT SCN_TO_TIMESTAMP(:1) FROM dual")
+                    .params(vec![Value::Integer(4_711_000)])
+                    .returns(
+                        vec![column("TS", OracleType::Timestamp)],
+                        vec![vec![Value::Timestamp(ts)]],
+                    ),
+            );
+        let conn = mock.connect().await.unwrap();
+
+        let options = ExecuteOptions {
+            include_rowscn: true,
+            ..Default::default()
+        };
+        let result = conn
+            .execute_with("SELECT * FROM orders o WHERE id = :1", &[&7], &options)
+            .await
+            .unwrap();
+        let scn = result.rows()[0].rowscn().unwrap();
+        assert_eq!(scn, Scn(4_711_000));
+        assert_eq!(conn.scn_timestamp(scn).await.unwrap(), ts);
+        mock.verify();
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_fetch_type_handler() {
+        use crate::fetch_type::{FetchType, FetchTypeHandler};
+        use crate::testing::{column, Expectation, MockConnection};
+        use futures::TryStreamExt;
+        use std::sync::Mutex;
+
+        let query = "SELECT id, price, flag FROM items";
+        let returns = |e: Expectation| {
+            e.returns(
+                vec![
+                    column("ID", OracleType::Number),
+                    column("PRICE", OracleType::Number),
+                    column("FLAG", OracleType::Char),
+                ],
+                vec![
+                    vec![
+                        Value::Integer(1),
+                        Value::Float(9.5),
+                        Value::String("Y".into()),
+                    ],
+                    vec![Value::Integer(2), Value::Null, Value::String("X".into())],
+                ],
+            )
+        };
+        let mock = MockConnection::new()
+            .expect(returns(Expectation::query(query)))
+            .expect(returns(Expectation::query(query)));
+        let conn = mock.connect().await.unwrap();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let described = seen.clone();
+        let handler = FetchTypeHandler::new(move |column| {
+            described.lock().unwrap().push(column.name.clone());
+            match column.name.as_str() {
+                "PRICE" => Some(FetchType::new().fetch_as(OracleType::Varchar2)),
+                "FLAG" => Some(FetchType::new().converter(|v| match v.as_str() {
+                    Some("Y") => Ok(Value::Boolean(true)),
+                    Some("N") => Ok(Value::Boolean(false)),
+                    _ => Err(Error::TypeMismatch(format!("bad flag {:?}", v))),
+                })),
+                _ => None,
+            }
+        });
+        let options = ExecuteOptions {
+            fetch_type_handler: Some(handler),
+            ..Default::default()
+        };
+
+        // The bad flag in the second row fails the whole query
+        let err = conn.execute_with(query, &[], &options).await.unwrap_err();
+        assert!(matches!(err, Error::TypeMismatch(_)));
+        assert_eq!(*seen.lock().unwrap(), ["ID", "PRICE", "FLAG"]);
+
+        // A stream converts row by row, failing at the bad one
+        let mut rows = conn.query_stream(query, &[], &options).await.unwrap();
+        let first = rows.try_next().await.unwrap().unwrap();
+        assert_eq!(
+            first.values(),
+            [
+                Value::Integer(1),
+                Value::String("9.5".into()),
+                Value::Boolean(true)
+            ]
+        );
+        assert!(rows.try_next().await.is_err());
+        mock.verify();
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_as_of_scn() {
+        use crate::testing::{column, Expectation, MockConnection};
+
+        let scn_query = "SELECT DBMS_FLASHBACK.GET_SYSTEM_CHANGE_NUMBER FROM dual";
+        let mock = MockConnection::new()
+            .expect(Expectation::query(scn_query).returns(
+                vec![column("SCN", OracleType::Number)],
+                vec![vec![Value::Integer(9_000)]],
+            ))
+            .expect(
+                Expectation::query("SELECT balance FROM accounts AS OF SCN 9000 WHERE id = :1")
+                    .returns(
+                        vec![column("BALANCE", OracleType::Number)],
+                        vec![vec![Value::Integer(70)]],
+                    ),
+            );
+        let conn = mock.connect().await.unwrap();
+
+        let scn = conn.current_scn().await.unwrap();
+        let stmt = conn
+            .prepare("SELECT balance FROM accounts WHERE id = :1")
+            .await
+            .unwrap()
+            .as_of_scn(scn)
+            .unwrap();
+        let balance: i64 = stmt.execute(&[&1]).await.unwrap().rows()[0]
+            .get_typed(0)
+            .unwrap();
+        assert_eq!(balance, 70);
+        mock.verify();
+
+        let ts = chrono::NaiveDate::from_ymd_opt(2026, 3, 1)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+        let stmt = conn.prepare("SELECT * FROM t").await.unwrap();
+        assert_eq!(
+            stmt.as_of_timestamp(ts).unwrap().sql(),
+            "SELECT * FROM t AS OF TIMESTAMP TIMESTAMP '2026-03-01 09:30:00'"
+        );
+        let stmt = conn.prepare("DELETE FROM t").await.unwrap();
+        assert!(stmt.as_of_scn(scn).is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_execute_with_options() {
+        use crate::testing::{column, Expectation, MockCall, MockConnection};
+
+        let rows = (1..=5).map(|i| vec![Value::Integer(i)]).collect();
+        let mock = MockConnection::new().expect(
+            Expectation::query("SELECT id FROM t")
+                .returns(vec![column("ID", OracleType::Number)], rows),
+        );
+        let conn = mock.connect().await.unwrap();
+
+        let options = ExecuteOptions {
+            auto_commit: true,
+            fetch_array_size: 2,
+            max_rows: 3,
+            out_format: OutFormat::Array,
+            ..Default::default()
+        };
+        let result = conn
+            .execute_with("SELECT id FROM t", &[], &options)
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.format(), OutFormat::Array);
+        assert_eq!(result.to_values()[2], Value::Array(vec![Value::Integer(3)]));
+        assert_eq!(result.columns(), ["ID"]);
+        assert_eq!(
+            result.into_arrays(),
+            [Value::Integer(1), Value::Integer(2), Value::Integer(3)]
+        );
+
+        mock.verify();
+        assert_eq!(mock.calls().last(), Some(&MockCall::Commit));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_execute_named() {
+        use crate::testing::{Expectation, MockConnection};
+
+        let block = "BEGIN :total := :total + :amount; END;";
+        let update = "UPDATE t SET a = :v, b = :v WHERE id = :id";
+        let mock = MockConnection::new()
+            .expect(Expectation::dml(block, 0).params(vec![Value::Integer(10), Value::Integer(5)]))
+            .expect(Expectation::dml(update, 1).params(vec![
+                Value::Integer(1),
+                Value::Integer(1),
+                Value::Integer(7),
+            ]));
+        let conn = mock.connect().await.unwrap();
+
+        // PL/SQL binds a repeated name once, SQL at every occurrence
+        conn.execute_named(block, &[(":AMOUNT", &5i64), ("total", &10i64)])
+            .await
+            .unwrap();
+        conn.execute_named(update, &[("v", &1i64), ("id", &7i64)])
+            .await
+            .unwrap();
+        mock.verify();
+
+        let err = conn
+            .execute_named(update, &[("v", &1i64)])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no value bound for :ID"));
+        let err = conn
+            .execute_named(update, &[("v", &1i64), ("id", &7i64), ("x", &0i64)])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no placeholder :x"));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_execute_owned() {
+        use crate::testing::{Expectation, MockConnection};
+
+        let insert = "INSERT INTO t (id, name) VALUES (:1, :2)";
+        let mock = MockConnection::new()
+            .expect(
+                Expectation::dml(insert, 1)
+                    .params(vec![Value::Integer(1), Value::String("a".into())]),
+            )
+            .expect(Expectation::dml(insert, 1).params(vec![Value::Integer(2), Value::Null]));
+        let conn = mock.connect().await.unwrap();
+
+        // The futures own their binds, so they outlive the locals
+        let first = {
+            let name = String::from("a");
+            conn.execute_dml_owned(insert, (1i64, name))
+        };
+        assert_eq!(tokio::spawn(first).await.unwrap().unwrap(), 1);
+        let params = Params::new().push(2i64).push(None::<String>);
+        assert_eq!(conn.execute_dml_owned(insert, params).await.unwrap(), 1);
+        mock.verify();
+
+        let params = Params::from((1i64, Some("x")));
+        assert_eq!(
+            params.values(),
+            [Value::Integer(1), Value::String("x".into())]
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_result_cache() {
+        use crate::testing::{column, Expectation, MockConnection};
+
+        let sql = "SELECT /*+ RESULT_CACHE */ code FROM currencies";
+        let rows = vec![vec![Value::String("EUR".into())]];
+        let cols = vec![column("CODE", OracleType::Varchar2)];
+        let mock = MockConnection::new()
+            .expect(Expectation::query(sql).returns(cols.clone(), rows.clone()))
+            .expect(Expectation::dml("UPDATE currencies SET code = 'USD'", 1))
+            .expect(Expectation::query(sql).returns(cols, rows));
+        let config =
+            crate::ConnectionConfig::new("localhost:1521/ORCL", "u", "p").result_cache_size(1024);
+        let mut conn = mock.connect_with(config).await.unwrap();
+        let options = ExecuteOptions {
+            result_cache: true,
+            ..Default::default()
+        };
+
+        for _ in 0..2 {
+            let result = conn.execute_with(sql, &[], &options).await.unwrap();
+            assert_eq!(result.len(), 1);
+        }
+        let stats = conn.stats().await;
+        assert_eq!((stats.executes, stats.result_cache_hits), (1, 1));
+        let capped = ExecuteOptions {
+            max_result_bytes: 1,
+            ..options.clone()
+        };
+        assert!(matches!(
+            conn.execute_with(sql, &[], &capped).await,
+            Err(Error::ResultTooLarge { .. })
+        ));
+
+        // The session's own change invalidates the cache
+        conn.execute_dml("UPDATE currencies SET code = 'USD'", &[])
+            .await
+            .unwrap();
+        conn.commit().await.unwrap();
+        conn.execute_with(sql, &[], &options).await.unwrap();
+        assert_eq!(conn.stats().await.result_cache_hits, 2);
+        mock.verify();
+    }
+
+    #[test]
+    fn test_rows_share_column_index() {
+        let columns = Arc::new(ColumnIndex::new(vec!["ID".into(), "NAME".into()]));
+        let rows: Vec<Row> = (0..3)
+            .map(|i| Row::with_columns(vec![Value::Integer(i), Value::Null], columns.clone()))
+            .collect();
+
+        assert_eq!(Arc::strong_count(&columns), 4);
+        assert!(Arc::ptr_eq(rows[0].columns(), rows[2].columns()));
+        // Positional reads never build the name table
+        assert_eq!(rows[0].get_typed::<i64>(0).unwrap(), 0);
+        assert!(columns.positions.get().is_none());
+        assert_eq!(rows[2].get_typed_by_name::<i64>("ID").unwrap(), 2);
+        assert_eq!(rows[1].to_map().len(), 2);
+    }
+
+    #[test]
+    fn test_positional_reads() {
+        let metadata = [("ID", OracleType::Number), ("NAME", OracleType::Varchar2)].map(
+            |(name, oracle_type)| ColumnInfo {
+                name: name.into(),
+                oracle_type,
+                size: 0,
+                precision: None,
+                scale: None,
+                nullable: true,
+                encrypted: false,
+                redacted: false,
+            },
+        );
+        let buffer = bytes::Bytes::from_static(&[0xC1, 0x08, b'K', b'I', b'N', b'G']);
+        let columns = Arc::new(ColumnIndex::from_columns(&metadata));
+        let raw = Row::from_raw(
+            vec![
+                RawValue::new(OracleType::Number, Some(buffer.slice(0..2))),
+                RawValue::new(OracleType::Varchar2, Some(buffer.slice(2..))),
+            ],
+            columns.clone(),
+        );
+        let decoded = Row::with_columns(
+            vec![Value::Integer(8), Value::String("FORD".into())],
+            columns.clone(),
+        );
+        let result = ResultSet::new(vec![raw, decoded], metadata.to_vec());
+
+        let name = result.column_index("NAME").unwrap();
+        assert_eq!(name, 1);
+        assert!(matches!(
+            result.column_index("name"),
+            Err(Error::ColumnNotFound(_))
+        ));
+        let names: Vec<String> = result
+            .rows()
+            .iter()
+            .map(|row| row.get_typed_at(name).unwrap())
+            .collect();
+        assert_eq!(names, ["KING", "FORD"]);
+        assert!(columns.positions.get().is_none());
+
+        let ids: Vec<i64> = result
+            .rows()
+            .iter()
+            // SAFETY: every row has two columns
+            .map(|row| unsafe { row.get_typed_unchecked(0) }.unwrap())
+            .collect();
+        assert_eq!(ids, [7, 8]);
+        // SAFETY: as above
+        assert_eq!(
+            unsafe { result.rows()[1].get_unchecked(1) },
+            &Value::String("FORD".into())
+        );
+        assert!(result.rows()[0].get_typed_at::<i64>(2).is_err());
+    }
+
+    #[test]
+    fn test_raw_row_decodes_lazily() {
+        let buffer = bytes::Bytes::from_static(&[0xC1, 0x08, b'K', b'I', b'N', b'G']);
+        let raw = vec![
+            RawValue::new(OracleType::Number, Some(buffer.slice(0..2))),
+            RawValue::new(OracleType::Varchar2, Some(buffer.slice(2..))),
+            RawValue::new(OracleType::Raw, None),
+        ];
+        let columns = vec!["ID".to_string(), "NAME".to_string(), "PHOTO".to_string()];
+        let row = Row::from_raw(raw, Arc::new(ColumnIndex::new(columns)));
+
+        assert_eq!(row.len(), 3);
+        let name: bytes::Bytes = row.get_typed(1).unwrap();
+        assert_eq!(name.as_ptr(), buffer[2..].as_ptr());
+        let photo: Option<Vec<u8>> = row.get_typed_by_name("PHOTO").unwrap();
+        assert_eq!(photo, None);
+
+        assert_eq!(row.get(0), Some(&Value::Integer(7)));
+        assert_eq!(row.get_typed::<String>(1).unwrap(), "KING");
+        assert_eq!(row.try_values().unwrap().len(), 3);
+
+        // An undecodable column is an error, not a NULL
+        let raw = vec![RawValue::new(
+            OracleType::Date,
+            Some(bytes::Bytes::from_static(&[120, 124, 2])),
+        )];
+        let row = Row::from_raw(raw, Arc::new(ColumnIndex::new(vec!["HIRED".into()])));
+        assert!(matches!(row.try_values(), Err(Error::Encoding(_))));
+        assert_eq!(row.values(), [Value::Null]);
+    }
+
+    #[test]
+    fn test_try_get_diagnostics() {
+        let columns = [
+            ("ID", OracleType::Number),
+            ("NAME", OracleType::Varchar2),
+            ("HIRED", OracleType::Date),
+        ]
+        .map(|(name, oracle_type)| ColumnInfo {
+            name: name.into(),
+            oracle_type,
+            size: 0,
+            precision: None,
+            scale: None,
+            nullable: true,
+            encrypted: false,
+            redacted: false,
+        });
+        let row = Row::with_columns(
+            vec![Value::Integer(5), Value::String("KING".into()), Value::Null],
+            Arc::new(ColumnIndex::from_columns(&columns).with_sql("SELECT * FROM emp")),
+        );
+
+        assert_eq!(row.try_get::<i64>(0).unwrap(), 5);
+        let Err(Error::TypeMismatch(message)) = row.try_get_by_name::<String>("ID") else {
+            panic!("expected a type mismatch");
+        };
+        assert!(message.starts_with(
+            "column \"ID\" (NUMBER) at index 0 in query \"SELECT * FROM emp\" holds \
+             Value::Integer, not convertible to "
+        ));
+        assert!(message.contains("String"));
+        assert!(message.ends_with(": Cannot convert Integer(5) to String"));
+        let Err(Error::TypeMismatch(message)) = row.try_get::<chrono::NaiveDate>(2) else {
+            panic!("expected a type mismatch");
+        };
+        assert!(message.contains("\"HIRED\" (DATE)"));
+        assert!(message.contains("NULL, which needs Option<") && message.contains("NaiveDate>"));
+        assert!(row
+            .try_get::<Option<chrono::NaiveDate>>(2)
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            row.try_get_by_name::<i64>("SALARY"),
+            Err(Error::ColumnNotFound(_))
+        ));
+
+        // Rows without metadata fall back to the wire type
+        let raw = Row::from_raw(
+            vec![RawValue::new(
+                OracleType::Varchar2,
+                Some(bytes::Bytes::from_static(b"x")),
+            )],
+            Arc::new(ColumnIndex::new(vec!["CODE".into()])),
+        );
+        let Err(Error::TypeMismatch(message)) = raw.try_get::<i64>(0) else {
+            panic!("expected a type mismatch");
+        };
+        assert!(message.starts_with("column \"CODE\" (VARCHAR2) at index 0 holds Value::String"));
+    }
 }
@@ -0,0 +1,89 @@
+// Statement cache
+
+//! Statements a session keeps parsed for reuse, keyed by normalized SQL
+//! so text differing only in whitespace, or with `fold_case` in the case
+//! of keywords and names, shares one cursor.
+
+use crate::sql::normalize;
+use std::collections::VecDeque;
+
+/// Least recently used cache of parsed statements
+#[derive(Debug)]
+pub(crate) struct StatementCache {
+    capacity: usize,
+    fold_case: bool,
+    /// Normalized SQL, most recently used last
+    entries: VecDeque<String>,
+}
+
+impl StatementCache {
+    /// Cache up to `capacity` statements (0 disables caching)
+    pub(crate) fn new(capacity: usize, fold_case: bool) -> Self {
+        Self {
+            capacity,
+            fold_case,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Look `sql` up, caching it on a miss; true on a hit
+    pub(crate) fn lookup(&mut self, sql: &str) -> bool {
+        if self.capacity == 0 {
+            return false;
+        }
+        let key = normalize(sql, self.fold_case);
+        if let Some(index) = self.entries.iter().position(|entry| *entry == key) {
+            let entry = self.entries.remove(index).expect("index in bounds");
+            self.entries.push_back(entry);
+            return true;
+        }
+        if self.entries.len() == self.capacity {
+            // A real implementation closes the evicted cursor on the
+            // next round trip
+            self.entries.pop_front();
+        }
+        self.entries.push_back(key);
+        false
+    }
+
+    /// Number of cached statements, each holding a server cursor
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Drop the least recently used statement, returning its SQL
+    pub(crate) fn evict_oldest(&mut self) -> Option<String> {
+        // A real implementation closes the cursor on the next round trip
+        self.entries.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_evicts_least_recent() {
+        let mut cache = StatementCache::new(2, false);
+        assert!(!cache.lookup("SELECT 1 FROM dual"));
+        assert!(cache.lookup("  SELECT 1\n  FROM dual"));
+        assert!(!cache.lookup("select 1 from dual"));
+        assert!(cache.lookup("SELECT 1 FROM dual"));
+        // "select 1" is now least recent and makes room for "SELECT 2"
+        assert!(!cache.lookup("SELECT 2 FROM dual"));
+        assert!(cache.lookup("SELECT 1 FROM dual"));
+        assert!(!cache.lookup("select 1 from dual"));
+
+        let mut folded = StatementCache::new(2, true);
+        assert!(!folded.lookup("SELECT 1 FROM dual"));
+        assert!(folded.lookup("select 1 from DUAL"));
+
+        let mut disabled = StatementCache::new(0, false);
+        assert!(!disabled.lookup("SELECT 1 FROM dual"));
+        assert!(!disabled.lookup("SELECT 1 FROM dual"));
+        assert_eq!(disabled.len(), 0);
+
+        assert_eq!(folded.evict_oldest().as_deref(), Some("SELECT 1 FROM DUAL"));
+        assert_eq!(folded.len(), 0);
+    }
+}
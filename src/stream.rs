@@ -0,0 +1,599 @@
+// Streaming query results
+
+//! [`RowStream`] yields the rows of a query batch by batch instead of
+//! buffering the whole result like [`ResultSet`](crate::ResultSet).
+//!
+//! Each batch is one TTC FETCH of [`ExecuteOptions::fetch_array_size`] rows.
+//! With [`ExecuteOptions::prefetch_batches`] above one, the next FETCH is
+//! issued while the caller is still working through the current batch, so
+//! the network round trip overlaps row processing:
+//!
+//! ```rust,ignore
+//! use futures::TryStreamExt;
+//!
+//! let options = ExecuteOptions { fetch_array_size: 500, prefetch_batches: 2, ..Default::default() };
+//! let mut rows = conn.query_stream("SELECT * FROM events", &[], &options).await?;
+//! while let Some(row) = rows.try_next().await? {
+//!     process(row);
+//! }
+//! ```
+//!
+//! The first FETCH asks for `fetch_array_size` rows. After that, with a
+//! nonzero [`ExecuteOptions::fetch_memory_budget`], each FETCH asks for as
+//! many rows as fit the budget at the average row width seen so far, so
+//! narrow rows come back in fewer round trips and wide rows stay bounded.
+//!
+//! Buffering is bounded by [`ExecuteOptions::max_result_bytes`] for the
+//! stream and [`ConnectionConfig::max_buffered_bytes`] for the connection:
+//! the stream only fetches ahead while the next batch, at the row width
+//! seen so far, would fit under both caps, and otherwise waits for the
+//! caller to drain what it holds. It always fetches when it holds nothing,
+//! so a single batch may exceed the caps.
+//!
+//! [`ConnectionConfig::max_buffered_bytes`]: crate::ConnectionConfig::max_buffered_bytes
+//!
+//! A prefetch holds the connection until it completes, so other calls on the
+//! same connection wait for it; finish or drop the stream first.
+//!
+//! Cancelling [`ExecuteOptions::cancel`] ends the stream with
+//! [`Error::Cancelled`], breaking off a fetch in flight and closing the
+//! cursor.
+
+use crate::budget::MemoryBudget;
+use crate::fetch_type::FetchPlan;
+use crate::protocol::{AbandonedCursors, Protocol};
+use crate::runtime::FairMutex;
+use crate::slow_query::Stopwatch;
+use crate::statement::{self, Row};
+use crate::types::{ColumnInfo, ToSql};
+use crate::{constants, Error, ExecuteOptions, Result};
+use futures::future::BoxFuture;
+use futures::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+type FetchFuture = BoxFuture<'static, Result<(Vec<Row>, bool)>>;
+
+/// Picks fetch array sizes from the row width observed so far
+#[derive(Debug, Clone)]
+struct FetchSizer {
+    initial: usize,
+    budget: usize,
+    rows: usize,
+    bytes: usize,
+}
+
+impl FetchSizer {
+    fn new(options: &ExecuteOptions) -> Self {
+        Self {
+            initial: options
+                .fetch_array_size
+                .clamp(1, constants::MAX_FETCH_ARRAY_SIZE),
+            budget: options.fetch_memory_budget,
+            rows: 0,
+            bytes: 0,
+        }
+    }
+
+    fn observe(&mut self, rows: &[Row]) {
+        self.rows += rows.len();
+        self.bytes += rows.iter().map(Row::wire_len).sum::<usize>();
+    }
+
+    /// Expected size of the next batch, 0 before any row is seen
+    fn batch_bytes(&self) -> usize {
+        match self.rows {
+            0 => 0,
+            rows => self.bytes.div_ceil(rows) * self.array_size(),
+        }
+    }
+
+    /// Array size for the next fetch
+    fn array_size(&self) -> usize {
+        if self.budget == 0 || self.rows == 0 {
+            return self.initial;
+        }
+        let width = self.bytes.div_ceil(self.rows).max(1);
+        (self.budget / width).clamp(1, constants::MAX_FETCH_ARRAY_SIZE)
+    }
+}
+
+/// Rows of a query, fetched in batches as they are consumed
+pub struct RowStream {
+    protocol: Arc<FairMutex<Protocol>>,
+    cursor: u32,
+    metadata: Vec<ColumnInfo>,
+    /// Conversions the fetch type handler chose
+    fetch_plan: Option<FetchPlan>,
+    sizer: FetchSizer,
+    prefetch_batches: usize,
+    /// Fetched batches not yet handed out, current batch first
+    batches: VecDeque<VecDeque<Row>>,
+    in_flight: Option<FetchFuture>,
+    /// Whether the server has rows left on the cursor
+    more: bool,
+    /// Rows still allowed by `max_rows`
+    remaining: Option<usize>,
+    /// Wire bytes of the buffered rows
+    buffered_bytes: usize,
+    /// Cap on `buffered_bytes`, 0 for none
+    max_buffered_bytes: usize,
+    /// Connection-wide count the buffered rows are charged to
+    budget: MemoryBudget,
+    /// Where the cursor goes if the connection is busy when it closes
+    abandoned: AbandonedCursors,
+    /// Resolves when the caller's cancellation token fires
+    cancelled: Option<BoxFuture<'static, ()>>,
+    error: Option<Error>,
+}
+
+impl std::fmt::Debug for RowStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RowStream")
+            .field("cursor", &self.cursor)
+            .field("array_size", &self.sizer.array_size())
+            .field("prefetch_batches", &self.prefetch_batches)
+            .field("buffered_rows", &self.buffered_rows())
+            .field("more", &self.more)
+            .finish()
+    }
+}
+
+impl RowStream {
+    /// Execute `sql` and open a stream over its rows
+    pub(crate) async fn open(
+        protocol: Arc<FairMutex<Protocol>>,
+        sql: &str,
+        params: &[&dyn ToSql],
+        options: &ExecuteOptions,
+    ) -> Result<Self> {
+        let with_rowscn;
+        let sql = if options.include_rowscn {
+            with_rowscn = crate::sql::with_rowscn(sql)?;
+            with_rowscn.as_str()
+        } else {
+            sql
+        };
+        let (cursor, metadata, budget, abandoned) = {
+            let mut guard = protocol.lock_within(options.queue_timeout).await?;
+            let values = statement::bind(&mut guard, params)?;
+            let stopwatch = Stopwatch::start(&guard);
+            let result = guard.open_cursor(sql, &values).await;
+            // Rows arrive later, through fetches
+            stopwatch.finish(&guard, sql, &values, result.as_ref().map(|_| 0));
+            let (cursor, metadata) = result?;
+            (
+                cursor,
+                metadata,
+                guard.budget().clone(),
+                guard.abandoned().clone(),
+            )
+        };
+
+        let fetch_plan = options
+            .fetch_type_handler
+            .as_ref()
+            .and_then(|handler| handler.plan(&metadata));
+        Ok(Self {
+            protocol,
+            cursor,
+            metadata,
+            fetch_plan,
+            sizer: FetchSizer::new(options),
+            prefetch_batches: options.prefetch_batches.max(1),
+            batches: VecDeque::new(),
+            in_flight: None,
+            more: true,
+            remaining: (options.max_rows > 0).then_some(options.max_rows),
+            buffered_bytes: 0,
+            max_buffered_bytes: options.max_result_bytes,
+            budget,
+            abandoned,
+            cancelled: options
+                .cancel
+                .clone()
+                .map(|token| Box::pin(token.cancelled_owned()) as BoxFuture<'static, ()>),
+            error: None,
+        })
+    }
+
+    /// Column metadata
+    pub fn metadata(&self) -> &[ColumnInfo] {
+        &self.metadata
+    }
+
+    /// Position of the column called `name`, to look up once and read
+    /// each row with [`Row::get_typed_at`]
+    pub fn column_index(&self, name: &str) -> Result<usize> {
+        statement::column_position(&self.metadata, name)
+    }
+
+    /// Array size the next fetch will ask for
+    pub fn fetch_array_size(&self) -> usize {
+        self.sizer.array_size()
+    }
+
+    /// Rows fetched but not yet returned
+    pub fn buffered_rows(&self) -> usize {
+        self.batches.iter().map(VecDeque::len).sum()
+    }
+
+    fn next_buffered(&mut self) -> Option<Row> {
+        let batch = self.batches.front_mut()?;
+        let row = batch.pop_front();
+        if batch.is_empty() {
+            self.batches.pop_front();
+        }
+        if let Some(row) = &row {
+            let bytes = row.wire_len().min(self.buffered_bytes);
+            self.buffered_bytes -= bytes;
+            self.budget.release(bytes);
+        }
+        row
+    }
+
+    /// Whether another batch may be fetched ahead of the caller
+    fn has_room(&self) -> bool {
+        if self.batches.is_empty() {
+            return true;
+        }
+        let batch = self.sizer.batch_bytes();
+        self.batches.len() < self.prefetch_batches
+            && (self.max_buffered_bytes == 0
+                || self.buffered_bytes + batch <= self.max_buffered_bytes)
+            && self.budget.has_room(batch)
+    }
+
+    /// Advance the in-flight fetch and start the next while below the limit
+    fn drive(&mut self, cx: &mut Context<'_>) {
+        loop {
+            if let Some(fetch) = &mut self.in_flight {
+                match fetch.as_mut().poll(cx) {
+                    Poll::Ready(Ok((rows, more))) => {
+                        self.in_flight = None;
+                        self.more = more;
+                        self.sizer.observe(&rows);
+                        let bytes = rows.iter().map(Row::wire_len).sum::<usize>();
+                        self.buffered_bytes += bytes;
+                        self.budget.reserve(bytes);
+                        if !rows.is_empty() {
+                            self.batches.push_back(rows.into());
+                        }
+                    }
+                    Poll::Ready(Err(e)) => {
+                        self.in_flight = None;
+                        self.more = false;
+                        self.error = Some(e);
+                        return;
+                    }
+                    Poll::Pending => return,
+                }
+            }
+
+            if !self.more || !self.has_room() {
+                return;
+            }
+            let protocol = self.protocol.clone();
+            let (cursor, array_size) = (self.cursor, self.sizer.array_size());
+            self.in_flight = Some(Box::pin(async move {
+                protocol.lock().await.fetch(cursor, array_size).await
+            }));
+        }
+    }
+
+    /// Stop fetching and release the cursor, breaking off a fetch in flight
+    fn close(&mut self) {
+        let interrupt = self.in_flight.take().is_some();
+        self.batches.clear();
+        self.budget
+            .release(std::mem::take(&mut self.buffered_bytes));
+        if !std::mem::take(&mut self.more) {
+            return;
+        }
+        match self.protocol.try_lock() {
+            Some(mut protocol) => {
+                if interrupt {
+                    protocol.send_break();
+                }
+                protocol.close_cursor(self.cursor)
+            }
+            None => {
+                log::debug!(
+                    "cursor {} closes with the next call, connection busy",
+                    self.cursor
+                );
+                self.abandoned.push(self.cursor, interrupt);
+            }
+        }
+    }
+}
+
+impl Stream for RowStream {
+    type Item = Result<Row>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.remaining == Some(0) {
+            return Poll::Ready(None);
+        }
+        if let Some(cancelled) = &mut this.cancelled {
+            if cancelled.as_mut().poll(cx).is_ready() {
+                this.cancelled = None;
+                this.remaining = Some(0);
+                this.close();
+                return Poll::Ready(Some(Err(Error::Cancelled)));
+            }
+        }
+
+        // Take a row first so an emptied batch makes room for the next fetch
+        let row = this.next_buffered();
+        this.drive(cx);
+        let row = row.or_else(|| this.next_buffered());
+
+        match row {
+            Some(row) => {
+                if let Some(remaining) = &mut this.remaining {
+                    *remaining -= 1;
+                    if *remaining == 0 {
+                        this.close();
+                    }
+                }
+                Poll::Ready(Some(match &this.fetch_plan {
+                    Some(plan) => plan.apply(row),
+                    None => Ok(row),
+                }))
+            }
+            None => match this.error.take() {
+                Some(e) => Poll::Ready(Some(Err(e))),
+                None if this.in_flight.is_some() => Poll::Pending,
+                None => Poll::Ready(None),
+            },
+        }
+    }
+}
+
+impl Drop for RowStream {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Value;
+    use crate::ConnectionConfig;
+    use futures::TryStreamExt;
+
+    async fn protocol() -> Arc<FairMutex<Protocol>> {
+        let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+        let mut protocol = Protocol::new(&config).await.unwrap();
+        protocol.authenticate("user", "pass").await.unwrap();
+        Arc::new(FairMutex::new(protocol))
+    }
+
+    #[tokio::test]
+    async fn test_stream_rows() {
+        let protocol = protocol().await;
+        let options = ExecuteOptions::default();
+        let mut stream = RowStream::open(protocol.clone(), "SELECT * FROM t", &[], &options)
+            .await
+            .unwrap();
+        assert_eq!(stream.metadata().len(), 2);
+
+        let row = stream.try_next().await.unwrap().unwrap();
+        assert_eq!(row.get_typed::<String>(1).unwrap(), "Test");
+        assert!(stream.try_next().await.unwrap().is_none());
+        // One execute and one fetch
+        assert_eq!(protocol.lock().await.round_trips(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_stream() {
+        let protocol = protocol().await;
+        let cancel = crate::CancellationToken::new();
+        let options = ExecuteOptions {
+            cancel: Some(cancel.clone()),
+            ..Default::default()
+        };
+        let mut stream = RowStream::open(protocol.clone(), "SELECT * FROM t", &[], &options)
+            .await
+            .unwrap();
+
+        cancel.cancel();
+        assert!(matches!(stream.try_next().await, Err(Error::Cancelled)));
+        assert!(stream.try_next().await.unwrap().is_none());
+        let closed = protocol.lock().await.fetch(stream.cursor, 1).await;
+        assert_eq!(closed.unwrap_err().oracle_code(), Some(1001));
+        // Cancelled while another task holds the connection, the cursor
+        // closes with the connection's next call
+        let cancel = crate::CancellationToken::new();
+        let options = ExecuteOptions {
+            cancel: Some(cancel.clone()),
+            ..Default::default()
+        };
+        let mut stream = RowStream::open(protocol.clone(), "SELECT * FROM t", &[], &options)
+            .await
+            .unwrap();
+        let busy = protocol.lock().await;
+        cancel.cancel();
+        assert!(matches!(stream.try_next().await, Err(Error::Cancelled)));
+        drop(busy);
+        let closed = protocol.lock().await.fetch(stream.cursor, 1).await;
+        assert_eq!(closed.unwrap_err().oracle_code(), Some(1001));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_prefetch_overlaps_consumption() {
+        use crate::testing::{column, Expectation, MockConnection};
+        use crate::OracleType;
+
+        let rows: Vec<Vec<Value>> = (0..5).map(|i| vec![Value::Integer(i)]).collect();
+        let mut consumed = Vec::new();
+        for prefetch_batches in [1, 2] {
+            let mock = MockConnection::new().expect(
+                Expectation::query("SELECT n FROM t")
+                    .returns(vec![column("N", OracleType::Number)], rows.clone()),
+            );
+            let conn = mock.connect().await.unwrap();
+            let options = ExecuteOptions {
+                fetch_array_size: 2,
+                fetch_memory_budget: 0,
+                prefetch_batches,
+                ..Default::default()
+            };
+            let mut stream = conn
+                .query_stream("SELECT n FROM t", &[], &options)
+                .await
+                .unwrap();
+
+            stream.try_next().await.unwrap().unwrap();
+            // Double buffering has the second batch in hand already
+            let expected = if prefetch_batches == 1 { 1 } else { 3 };
+            assert_eq!(stream.buffered_rows(), expected);
+
+            let rest: Vec<Row> = stream.try_collect().await.unwrap();
+            consumed.push(rest.len() + 1);
+            mock.verify();
+        }
+        assert_eq!(consumed, [5, 5]);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_backpressure_at_byte_cap() {
+        use crate::testing::{column, Expectation, MockConnection};
+        use crate::OracleType;
+
+        let rows: Vec<Vec<Value>> = (0..6).map(|i| vec![Value::Integer(i)]).collect();
+        let mock = MockConnection::new().expect(
+            Expectation::query("SELECT n FROM t")
+                .returns(vec![column("N", OracleType::Number)], rows),
+        );
+        let conn = mock.connect().await.unwrap();
+        // Each row is 9 wire bytes, so one batch of 2 fills the cap
+        let options = ExecuteOptions {
+            fetch_array_size: 2,
+            fetch_memory_budget: 0,
+            prefetch_batches: 3,
+            max_result_bytes: 18,
+            ..Default::default()
+        };
+        let mut stream = conn
+            .query_stream("SELECT n FROM t", &[], &options)
+            .await
+            .unwrap();
+
+        let mut seen = 0;
+        while let Some(_row) = stream.try_next().await.unwrap() {
+            seen += 1;
+            // Never more than the batch being consumed
+            assert!(stream.buffered_rows() <= 2);
+            assert_eq!(stream.budget.used(), stream.buffered_rows() * 9);
+        }
+        assert_eq!(seen, 6);
+
+        let budget = stream.budget.clone();
+        drop(stream);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[test]
+    fn test_fetch_size_follows_row_width() {
+        let options = ExecuteOptions {
+            fetch_array_size: 50,
+            fetch_memory_budget: 1000,
+            ..Default::default()
+        };
+        let mut sizer = FetchSizer::new(&options);
+        assert_eq!(sizer.array_size(), 50);
+
+        // 10 + 8 bytes of data plus a length byte per column: 20 bytes
+        let row = Row::new(
+            vec![Value::String("x".repeat(10)), Value::Integer(7)],
+            vec!["A".into(), "B".into()],
+        );
+        sizer.observe(&[row.clone(), row]);
+        assert_eq!(sizer.array_size(), 50);
+
+        let wide = Row::new(vec![Value::Bytes(vec![0; 4000])], vec!["B".into()]);
+        sizer.observe(&[wide]);
+        assert_eq!(sizer.array_size(), 1);
+
+        let fixed = FetchSizer {
+            budget: 0,
+            ..sizer.clone()
+        };
+        assert_eq!(fixed.array_size(), 50);
+    }
+
+    #[tokio::test]
+    async fn test_max_rows_closes_cursor() {
+        let protocol = protocol().await;
+        let options = ExecuteOptions {
+            max_rows: 1,
+            ..Default::default()
+        };
+        let stream = RowStream::open(protocol.clone(), "SELECT * FROM t", &[], &options)
+            .await
+            .unwrap();
+        let rows: Vec<Row> = stream.try_collect().await.unwrap();
+        assert_eq!(rows.len(), 1);
+
+        // Dropping an unfinished stream releases its cursor too
+        let stream = RowStream::open(protocol.clone(), "SELECT * FROM t", &[], &options)
+            .await
+            .unwrap();
+        let cursor = stream.cursor;
+        drop(stream);
+        let err = protocol.lock().await.fetch(cursor, 1).await.unwrap_err();
+        assert_eq!(err.oracle_code(), Some(1001));
+    }
+
+    #[tokio::test]
+    async fn test_cursor_limit() {
+        let config =
+            ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass").max_open_cursors(2);
+        let open = |config: ConnectionConfig| async move {
+            let mut protocol = Protocol::new(&config).await.unwrap();
+            protocol.authenticate("user", "pass").await.unwrap();
+            Arc::new(FairMutex::new(protocol))
+        };
+        let protocol = open(config.clone()).await;
+        let options = ExecuteOptions::default();
+
+        // The leaked stream holds a cursor, and its statement is cached
+        let leaked = RowStream::open(protocol.clone(), "SELECT * FROM t", &[], &options)
+            .await
+            .unwrap();
+        assert_eq!(protocol.lock().await.open_cursors(), 2);
+        let err = RowStream::open(protocol.clone(), "SELECT * FROM u", &[], &options)
+            .await
+            .unwrap_err();
+        match err {
+            Error::TooManyOpenCursors { limit, sql } => {
+                assert_eq!((limit, sql), (2, vec!["SELECT * FROM t".to_string()]))
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+
+        drop(leaked);
+        assert_eq!(protocol.lock().await.open_cursors(), 1);
+
+        // Closing cached statements makes room
+        let protocol = open(config.close_cached_cursors(true)).await;
+        let mut streams = Vec::new();
+        for sql in ["SELECT * FROM t", "SELECT * FROM u"] {
+            let stream = RowStream::open(protocol.clone(), sql, &[], &options).await;
+            streams.push(stream.unwrap());
+        }
+        assert_eq!(protocol.lock().await.open_cursors(), 2);
+        let err = RowStream::open(protocol.clone(), "SELECT * FROM v", &[], &options)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::TooManyOpenCursors { ref sql, .. } if sql.len() == 2));
+    }
+}
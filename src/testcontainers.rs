@@ -0,0 +1,179 @@
+// Oracle containers for integration tests
+
+//! Starts an Oracle Database Free or XE container with
+//! [testcontainers](https://docs.rs/testcontainers), waits until the database
+//! accepts sessions, and hands back a [`ConnectionConfig`] or [`Pool`] for it.
+//!
+//! The images are the community `gvenzl/oracle-free` and `gvenzl/oracle-xe`
+//! builds, which create an application user on first start. The container
+//! is removed when the [`RunningOracle`] is dropped.
+//!
+//! ```rust,ignore
+//! use oracledb_rs::testcontainers::OracleContainer;
+//!
+//! #[tokio::test]
+//! async fn orders_round_trip() {
+//!     let oracle = OracleContainer::free().start().await.unwrap();
+//!     let conn = Connection::connect(oracle.config()).await.unwrap();
+//!     // ...
+//! }
+//! ```
+
+use crate::{ConnectionConfig, Error, Pool, PoolConfig, Result};
+use std::time::Duration;
+use testcontainers::core::{IntoContainerPort, WaitFor};
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, GenericImage, ImageExt};
+
+/// Line the gvenzl images log once the database is open
+const READY_MESSAGE: &str = "DATABASE IS READY TO USE!";
+
+/// Settings for an Oracle container
+#[derive(Debug, Clone)]
+pub struct OracleContainer {
+    image: String,
+    tag: String,
+    service_name: String,
+    admin_password: String,
+    user: String,
+    password: String,
+    startup_timeout: Duration,
+}
+
+impl OracleContainer {
+    /// Oracle Database 23ai Free, service `FREEPDB1`
+    pub fn free() -> Self {
+        Self {
+            image: "gvenzl/oracle-free".into(),
+            tag: "23-slim-faststart".into(),
+            service_name: "FREEPDB1".into(),
+            admin_password: "oracle".into(),
+            user: "test".into(),
+            password: "test".into(),
+            startup_timeout: Duration::from_secs(300),
+        }
+    }
+
+    /// Oracle Database 21c XE, service `XEPDB1`
+    pub fn xe() -> Self {
+        Self {
+            image: "gvenzl/oracle-xe".into(),
+            tag: "21-slim-faststart".into(),
+            service_name: "XEPDB1".into(),
+            ..Self::free()
+        }
+    }
+
+    /// Use another tag of the image, e.g. `23.6-slim`
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = tag.into();
+        self
+    }
+
+    /// Application user created on first start
+    pub fn user(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.user = user.into();
+        self.password = password.into();
+        self
+    }
+
+    /// Password for SYS and SYSTEM
+    pub fn admin_password(mut self, password: impl Into<String>) -> Self {
+        self.admin_password = password.into();
+        self
+    }
+
+    /// How long to wait for the database to open
+    pub fn startup_timeout(mut self, timeout: Duration) -> Self {
+        self.startup_timeout = timeout;
+        self
+    }
+
+    /// Start the container and wait until the database is ready
+    pub async fn start(self) -> Result<RunningOracle> {
+        let container = GenericImage::new(self.image.as_str(), self.tag.as_str())
+            .with_exposed_port(1521.tcp())
+            .with_wait_for(WaitFor::message_on_stdout(READY_MESSAGE))
+            .with_env_var("ORACLE_PASSWORD", self.admin_password.as_str())
+            .with_env_var("APP_USER", self.user.as_str())
+            .with_env_var("APP_USER_PASSWORD", self.password.as_str())
+            .with_startup_timeout(self.startup_timeout)
+            .start()
+            .await
+            .map_err(container_error)?;
+
+        let host = container.get_host().await.map_err(container_error)?;
+        let port = container
+            .get_host_port_ipv4(1521)
+            .await
+            .map_err(container_error)?;
+
+        Ok(RunningOracle {
+            config: self.config(&host.to_string(), port),
+            container,
+        })
+    }
+
+    fn config(&self, host: &str, port: u16) -> ConnectionConfig {
+        ConnectionConfig::new(
+            format!("{}:{}/{}", host, port, self.service_name),
+            self.user.as_str(),
+            self.password.as_str(),
+        )
+    }
+}
+
+/// A started Oracle container; stopped and removed on drop
+pub struct RunningOracle {
+    config: ConnectionConfig,
+    container: ContainerAsync<GenericImage>,
+}
+
+impl RunningOracle {
+    /// Configuration for the application user
+    pub fn config(&self) -> ConnectionConfig {
+        self.config.clone()
+    }
+
+    /// Pool of connections as the application user
+    pub async fn pool(&self, pool_config: PoolConfig) -> Result<Pool> {
+        Pool::new(self.config(), pool_config).await
+    }
+
+    /// Docker container ID
+    pub fn id(&self) -> &str {
+        self.container.id()
+    }
+}
+
+fn container_error(e: testcontainers::TestcontainersError) -> Error {
+    Error::Connection(format!("Oracle container failed to start: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_config() {
+        let config = OracleContainer::xe()
+            .user("app", "secret")
+            .config("localhost", 32768);
+        assert_eq!(config.connection_string, "localhost:32768/XEPDB1");
+        assert_eq!(config.user, "app");
+        assert_eq!(config.password.expose_secret(), "secret");
+
+        let free = OracleContainer::free().tag("23.6-slim");
+        assert_eq!(free.image, "gvenzl/oracle-free");
+        assert_eq!(free.tag, "23.6-slim");
+        assert_eq!(free.service_name, "FREEPDB1");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Docker daemon"]
+    async fn test_start_free() {
+        let oracle = OracleContainer::free().start().await.unwrap();
+        assert!(oracle.config().connection_string.ends_with("/FREEPDB1"));
+        assert!(!oracle.id().is_empty());
+    }
+}
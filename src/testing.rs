@@ -0,0 +1,659 @@
+// Mock connections for unit tests
+
+//! Script the statements application code is expected to run and the rows
+//! the server would send back, then hand the code a real [`Connection`]
+//! without an Oracle instance behind it.
+//!
+//! Expectations are matched in order; SQL is compared with whitespace
+//! collapsed. A statement that does not match the next expectation fails
+//! with [`Error::Protocol`] and is reported again by
+//! [`MockConnection::verify`].
+//!
+//! ```rust,ignore
+//! use oracledb_rs::testing::{column, Expectation, MockConnection};
+//! use oracledb_rs::{OracleType, Value};
+//!
+//! let mock = MockConnection::new()
+//!     .expect(
+//!         Expectation::query("SELECT name FROM emp WHERE id = :1")
+//!             .params(vec![Value::Integer(7)])
+//!             .returns(
+//!                 vec![column("NAME", OracleType::Varchar2)],
+//!                 vec![vec![Value::String("KING".into())]],
+//!             ),
+//!     )
+//!     .expect(Expectation::dml("UPDATE emp SET sal = sal * 2", 14));
+//!
+//! let conn = mock.connect().await?;
+//! run_payroll(&conn).await?;
+//! mock.verify();
+//! ```
+
+use crate::connection::Connection;
+use crate::lob::{Lob, LobOp};
+use crate::protocol::Protocol;
+use crate::statement::{CallResults, ColumnIndex, Row};
+use crate::types::{ColumnInfo, OracleType, Value};
+use crate::xa::Xid;
+use crate::{ConnectionConfig, Error, Result, Warning};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Scripted statement and its response
+#[derive(Debug)]
+pub struct Expectation {
+    sql: String,
+    params: Option<Vec<Value>>,
+    response: Response,
+    warning: Option<Warning>,
+}
+
+#[derive(Debug)]
+enum Response {
+    Rows(Vec<ColumnInfo>, Vec<Vec<Value>>),
+    Affected(u64),
+    Call(CallResponse),
+    Error(Error),
+}
+
+/// Scripted response of a PL/SQL call run with `execute_multi`
+#[derive(Debug, Default)]
+struct CallResponse {
+    result_sets: Vec<(Vec<ColumnInfo>, Vec<Vec<Value>>)>,
+    out_values: Vec<Value>,
+    rows_affected: u64,
+}
+
+impl Expectation {
+    /// Expect a query; it returns no rows unless [`returns`](Self::returns) is set
+    pub fn query(sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            params: None,
+            response: Response::Rows(Vec::new(), Vec::new()),
+            warning: None,
+        }
+    }
+
+    /// Expect a DML statement that affects `rows_affected` rows
+    pub fn dml(sql: impl Into<String>, rows_affected: u64) -> Self {
+        Self {
+            sql: sql.into(),
+            params: None,
+            response: Response::Affected(rows_affected),
+            warning: None,
+        }
+    }
+
+    /// Expect a PL/SQL call run with
+    /// [`Connection::execute_multi`](crate::Connection::execute_multi);
+    /// it returns nothing unless given result sets or OUT values
+    pub fn call(sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            params: None,
+            response: Response::Call(CallResponse {
+                rows_affected: 1,
+                ..Default::default()
+            }),
+            warning: None,
+        }
+    }
+
+    /// Add an implicit result set the call returns, after any added
+    /// before
+    pub fn result_set(mut self, columns: Vec<ColumnInfo>, rows: Vec<Vec<Value>>) -> Self {
+        self.call_response().result_sets.push((columns, rows));
+        self
+    }
+
+    /// Values the call's OUT binds return, in bind order
+    pub fn out_values(mut self, values: Vec<Value>) -> Self {
+        self.call_response().out_values = values;
+        self
+    }
+
+    /// Row count the call reports
+    pub fn call_rows(mut self, rows_affected: u64) -> Self {
+        self.call_response().rows_affected = rows_affected;
+        self
+    }
+
+    fn call_response(&mut self) -> &mut CallResponse {
+        if !matches!(self.response, Response::Call(_)) {
+            self.response = Response::Call(CallResponse::default());
+        }
+        match &mut self.response {
+            Response::Call(call) => call,
+            _ => unreachable!("set above"),
+        }
+    }
+
+    /// Only match when bound with exactly these values
+    pub fn params(mut self, params: Vec<Value>) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// Rows to return, one `Vec<Value>` per row in column order
+    pub fn returns(mut self, columns: Vec<ColumnInfo>, rows: Vec<Vec<Value>>) -> Self {
+        self.response = Response::Rows(columns, rows);
+        self
+    }
+
+    /// Succeed with a warning, as the server does for ORA-24344
+    pub fn warns(mut self, code: i32, message: impl Into<String>) -> Self {
+        self.warning = Some(Warning {
+            code,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Fail the statement with `error`
+    pub fn fails(mut self, error: Error) -> Self {
+        self.response = Response::Error(error);
+        self
+    }
+
+    fn matches(&self, sql: &str, params: &[Value]) -> bool {
+        normalize(&self.sql) == normalize(sql) && self.params.as_deref().is_none_or(|p| p == params)
+    }
+}
+
+/// A call received by a mock connection
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockCall {
+    /// Statement execution
+    Execute {
+        /// SQL text
+        sql: String,
+        /// Bound values
+        params: Vec<Value>,
+    },
+    /// Parse-only describe of a statement
+    Describe(String),
+    /// COMMIT
+    Commit,
+    /// ROLLBACK
+    Rollback,
+    /// Break sent to interrupt a call
+    Break,
+    /// Session state reset
+    ResetSession,
+    /// Session marked stateless, riding on the call after it
+    Stateless,
+    /// Global transaction branch started
+    TpcBegin(Xid),
+    /// Global transaction branch detached
+    TpcEnd(Xid),
+    /// Global transaction branch prepared
+    TpcPrepare(Xid),
+    /// Global transaction branch committed
+    TpcCommit {
+        /// Branch
+        xid: Xid,
+        /// Committed without a prepare
+        one_phase: bool,
+    },
+    /// Global transaction branch rolled back
+    TpcRollback(Xid),
+    /// LOB operation, by locator
+    Lob(Vec<u8>),
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    expectations: VecDeque<Expectation>,
+    calls: Vec<MockCall>,
+    warning: Option<Warning>,
+    login_warnings: Vec<Warning>,
+    server_version: Option<(u8, u8)>,
+    unexpected: Vec<String>,
+    lobs: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+/// Scripted stand-in for an Oracle session
+///
+/// Clones share the script, so keep one to inspect after the code under
+/// test has consumed the connection.
+#[derive(Debug, Clone, Default)]
+pub struct MockConnection {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockConnection {
+    /// Create a mock with an empty script
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an expectation to the script
+    pub fn expect(self, expectation: Expectation) -> Self {
+        self.state().expectations.push_back(expectation);
+        self
+    }
+
+    /// Report `major.minor` as the server release
+    pub fn server_version(self, major: u8, minor: u8) -> Self {
+        self.state().server_version = Some((major, minor));
+        self
+    }
+
+    /// Attach warning `code` to the logon, as with ORA-28002 for a
+    /// password in its grace period
+    pub fn login_warning(self, code: i32, message: impl Into<String>) -> Self {
+        self.state().login_warnings.push(Warning {
+            code,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Serve `data` for the LOB behind `locator`, and keep what is
+    /// written to it; CLOB data is UTF-8 and NCLOB data UTF-16
+    pub fn lob(self, locator: impl Into<Vec<u8>>, data: impl Into<Vec<u8>>) -> Self {
+        self.state().lobs.insert(locator.into(), data.into());
+        self
+    }
+
+    /// Current data of the LOB behind `locator`
+    pub fn lob_data(&self, locator: &[u8]) -> Option<Vec<u8>> {
+        self.state().lobs.get(locator).cloned()
+    }
+
+    /// Open a connection served by this mock
+    pub async fn connect(&self) -> Result<Connection> {
+        self.connect_with(ConnectionConfig::new("mock:1521/MOCK", "mock", "mock"))
+            .await
+    }
+
+    /// Open a connection with `config` served by this mock
+    pub async fn connect_with(&self, config: ConnectionConfig) -> Result<Connection> {
+        let protocol = Protocol::mock(&config, self.clone()).await?;
+        Ok(Connection::from_protocol(config, protocol))
+    }
+
+    /// Release set with [`MockConnection::server_version`]
+    pub(crate) fn reported_version(&self) -> Option<(u8, u8)> {
+        self.state().server_version
+    }
+
+    /// Warnings set with [`MockConnection::login_warning`]
+    pub(crate) fn login_warnings(&self) -> Vec<Warning> {
+        self.state().login_warnings.clone()
+    }
+
+    /// Calls received so far
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    /// Panic if expectations are left over or unexpected statements ran
+    pub fn verify(&self) {
+        let state = self.state();
+        let pending: Vec<&str> = state.expectations.iter().map(|e| e.sql.as_str()).collect();
+        assert!(
+            state.unexpected.is_empty(),
+            "unexpected statements: {:?}",
+            state.unexpected
+        );
+        assert!(pending.is_empty(), "unmet expectations: {:?}", pending);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn next_response(&self, sql: &str, params: &[Value]) -> Result<Response> {
+        let call = MockCall::Execute {
+            sql: sql.to_string(),
+            params: params.to_vec(),
+        };
+        self.respond(call, sql, params)
+    }
+
+    /// Record `call` and take the expectation it meets
+    fn respond(&self, call: MockCall, sql: &str, params: &[Value]) -> Result<Response> {
+        let mut state = self.state();
+        state.calls.push(call);
+        if !state
+            .expectations
+            .front()
+            .is_some_and(|e| e.matches(sql, params))
+        {
+            state.unexpected.push(sql.to_string());
+            return Err(Error::Protocol(format!("unexpected statement: {}", sql)));
+        }
+        let expectation = state.expectations.pop_front().unwrap();
+        state.warning = expectation.warning;
+        Ok(expectation.response)
+    }
+
+    /// Serve a describe through [`Protocol::describe`], from the columns
+    /// of a query expectation
+    pub(crate) fn describe(&self, sql: &str) -> Result<Vec<ColumnInfo>> {
+        match self.respond(MockCall::Describe(sql.to_string()), sql, &[])? {
+            Response::Rows(columns, _) => Ok(columns),
+            Response::Affected(_) => Ok(Vec::new()),
+            Response::Call(_) => Err(Error::TypeMismatch(format!(
+                "scripted as a multi-result call but described: {}",
+                sql
+            ))),
+            Response::Error(e) => Err(e),
+        }
+    }
+
+    /// Serve a statement executed through [`Protocol::execute`]
+    ///
+    /// With `lob_prefetch` set, as when LOB columns are defined for
+    /// locators, scripted [`Lob`]s carry at most that many bytes of
+    /// prefetched data, as a server would send them.
+    pub(crate) fn execute(
+        &self,
+        sql: &str,
+        params: &[Value],
+        lob_prefetch: Option<u32>,
+    ) -> Result<(Vec<Row>, Vec<ColumnInfo>)> {
+        match self.next_response(sql, params)? {
+            Response::Rows(columns, rows) => {
+                let index = Arc::new(ColumnIndex::from_columns(&columns).with_sql(sql));
+                let rows = rows
+                    .into_iter()
+                    .map(|mut values| {
+                        if let Some(limit) = lob_prefetch {
+                            for value in &mut values {
+                                if let Value::Lob(lob) = value {
+                                    *lob = limit_prefetched(lob, limit as usize);
+                                }
+                            }
+                        }
+                        Row::with_columns(values, index.clone())
+                    })
+                    .collect();
+                Ok((rows, columns))
+            }
+            Response::Affected(_) => Ok((vec![], vec![])),
+            Response::Call(_) => Err(Error::TypeMismatch(format!(
+                "scripted as a multi-result call but executed as a statement: {}",
+                sql
+            ))),
+            Response::Error(e) => Err(e),
+        }
+    }
+
+    /// Serve a statement executed through [`Protocol::execute_dml`]
+    pub(crate) fn execute_dml(&self, sql: &str, params: &[Value]) -> Result<u64> {
+        match self.next_response(sql, params)? {
+            Response::Affected(count) => Ok(count),
+            Response::Rows(..) => Err(Error::TypeMismatch(format!(
+                "scripted as a query but executed as DML: {}",
+                sql
+            ))),
+            Response::Call(_) => Err(Error::TypeMismatch(format!(
+                "scripted as a multi-result call but executed as DML: {}",
+                sql
+            ))),
+            Response::Error(e) => Err(e),
+        }
+    }
+
+    /// Serve a call executed through [`Protocol::execute_multi`] with
+    /// `outs` OUT binds
+    pub(crate) fn execute_multi(
+        &self,
+        sql: &str,
+        params: &[Value],
+        outs: usize,
+    ) -> Result<CallResults> {
+        let call = match self.next_response(sql, params)? {
+            Response::Call(call) => call,
+            Response::Affected(rows_affected) => CallResponse {
+                rows_affected,
+                ..Default::default()
+            },
+            Response::Rows(columns, rows) => CallResponse {
+                result_sets: vec![(columns, rows)],
+                ..Default::default()
+            },
+            Response::Error(e) => return Err(e),
+        };
+        if call.out_values.len() != outs {
+            return Err(Error::Protocol(format!(
+                "scripted {} OUT values for {} OUT binds: {}",
+                call.out_values.len(),
+                outs,
+                sql
+            )));
+        }
+        let result_sets = call
+            .result_sets
+            .into_iter()
+            .map(|(columns, rows)| {
+                let index = Arc::new(ColumnIndex::from_columns(&columns).with_sql(sql));
+                let rows = rows
+                    .into_iter()
+                    .map(|values| Row::with_columns(values, index.clone()))
+                    .collect();
+                (rows, columns)
+            })
+            .collect();
+        Ok(CallResults {
+            result_sets,
+            out_values: call.out_values,
+            rows_affected: call.rows_affected,
+        })
+    }
+
+    /// Warning scripted for the statement last served
+    pub(crate) fn take_warning(&self) -> Option<Warning> {
+        self.state().warning.take()
+    }
+
+    /// Serve a LOB operation, returning the LOB's length after it and
+    /// any data read
+    pub(crate) fn lob_call(&self, lob: &Lob, op: LobOp<'_>) -> Result<(u64, Vec<u8>)> {
+        let mut state = self.state();
+        state.calls.push(MockCall::Lob(lob.locator().to_vec()));
+        let data = state
+            .lobs
+            .get_mut(lob.locator())
+            .ok_or_else(|| Error::oracle(22922, "nonexistent LOB value"))?;
+        let mut units = lob_units(lob.oracle_type(), data)?;
+        let read = match op {
+            LobOp::Length => Vec::new(),
+            LobOp::Read { offset, amount } => units
+                .iter()
+                .skip(offset as usize)
+                .take(amount as usize)
+                .flatten()
+                .copied()
+                .collect(),
+            LobOp::Write { offset, data } => {
+                // Writing past the end pads, with spaces for character
+                // LOBs as Oracle does
+                let pad = match lob.oracle_type() {
+                    OracleType::Clob => vec![b' '],
+                    OracleType::NClob => vec![0, b' '],
+                    _ => vec![0],
+                };
+                let offset = offset as usize;
+                if offset > units.len() {
+                    units.resize(offset, pad);
+                }
+                let written = lob_units(lob.oracle_type(), data)?;
+                let end = (offset + written.len()).min(units.len());
+                units.splice(offset..end, written);
+                Vec::new()
+            }
+            LobOp::Trim(len) if len as usize > units.len() => {
+                return Err(Error::oracle(
+                    22926,
+                    "specified trim length is greater than current LOB value's length",
+                ))
+            }
+            LobOp::Trim(len) => {
+                units.truncate(len as usize);
+                Vec::new()
+            }
+        };
+        *data = units.concat();
+        Ok((units.len() as u64, read))
+    }
+
+    /// Record a call that needs no scripted response
+    pub(crate) fn record(&self, call: MockCall) {
+        self.state().calls.push(call);
+    }
+}
+
+/// LOB data split into the units its offsets count: UTF-8 characters of
+/// a CLOB, UTF-16 code units of an NCLOB and bytes otherwise
+fn lob_units(oracle_type: OracleType, data: &[u8]) -> Result<Vec<Vec<u8>>> {
+    match oracle_type {
+        OracleType::Clob => Ok(std::str::from_utf8(data)
+            .map_err(|e| Error::Encoding(e.to_string()))?
+            .chars()
+            .map(|c| c.to_string().into_bytes())
+            .collect()),
+        OracleType::NClob => Ok(data.chunks(2).map(<[u8]>::to_vec).collect()),
+        _ => Ok(data.iter().map(|&b| vec![b]).collect()),
+    }
+}
+
+/// `lob` as sent with a prefetch length of `limit` bytes: the whole
+/// units of its prefetched data that fit, and none at all for 0
+fn limit_prefetched(lob: &Lob, limit: usize) -> Lob {
+    let mut limited = Lob::new(lob.oracle_type(), lob.locator().to_vec());
+    if let (Some(len), Some(chunk_size)) = (lob.len(), lob.chunk_size()) {
+        limited = limited.with_metadata(len, chunk_size);
+    }
+    let Some(data) = lob.prefetched().filter(|_| limit > 0) else {
+        return limited;
+    };
+    let Ok(units) = lob_units(lob.oracle_type(), data) else {
+        return lob.clone();
+    };
+    let mut kept = Vec::new();
+    for unit in units {
+        if kept.len() + unit.len() > limit {
+            break;
+        }
+        kept.extend(unit);
+    }
+    limited.with_prefetched(kept)
+}
+
+/// Nullable column description for [`Expectation::returns`]
+pub fn column(name: impl Into<String>, oracle_type: OracleType) -> ColumnInfo {
+    ColumnInfo {
+        name: name.into(),
+        oracle_type,
+        size: 0,
+        precision: None,
+        scale: None,
+        nullable: true,
+        encrypted: false,
+        redacted: false,
+    }
+}
+
+fn normalize(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Open an authenticated connection whose calls take the driver's own
+/// code paths, with no mock serving them
+#[cfg(test)]
+pub(crate) async fn unscripted() -> Result<Connection> {
+    let config = ConnectionConfig::new("localhost:1521/XEPDB1", "user", "pass");
+    let mut protocol = Protocol::new(&config).await?;
+    protocol
+        .authenticate(&config.user, config.password.expose_secret())
+        .await?;
+    Ok(Connection::from_protocol(config, protocol))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unscripted_connection() {
+        let conn = unscripted().await.unwrap();
+        conn.execute_dml("SAVEPOINT a", &[]).await.unwrap();
+        assert_eq!(
+            conn.execute_dml("UPDATE emp SET sal = 1", &[])
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(conn.info().transaction_active);
+    }
+
+    #[tokio::test]
+    async fn test_scripted_responses() {
+        let mock = MockConnection::new()
+            .expect(
+                Expectation::query("SELECT name FROM emp WHERE id = :1")
+                    .params(vec![Value::Integer(7)])
+                    .returns(
+                        vec![column("NAME", OracleType::Varchar2)],
+                        vec![vec![Value::String("KING".into())]],
+                    ),
+            )
+            .expect(Expectation::dml("UPDATE emp\n   SET sal = sal * 2", 14));
+
+        let mut conn = mock.connect().await.unwrap();
+        let rows = conn
+            .query("SELECT name FROM emp WHERE id = :1", &[&7])
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows.rows()[0].get_by_name("NAME").and_then(Value::as_str),
+            Some("KING")
+        );
+
+        let count = conn
+            .execute_dml("UPDATE emp SET sal = sal * 2", &[])
+            .await
+            .unwrap();
+        assert_eq!(count, 14);
+        conn.commit().await.unwrap();
+
+        mock.verify();
+        assert_eq!(mock.calls().len(), 3);
+        assert_eq!(mock.calls()[2], MockCall::Commit);
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_statement() {
+        let mock = MockConnection::new()
+            .expect(Expectation::query("SELECT 1 FROM DUAL").params(vec![Value::Integer(1)]));
+        let conn = mock.connect().await.unwrap();
+
+        let result = conn.query("SELECT 1 FROM DUAL", &[&2]).await;
+        assert!(matches!(result, Err(Error::Protocol(_))));
+
+        let verified = std::panic::catch_unwind(|| mock.verify());
+        assert!(verified.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scripted_error() {
+        let mock = MockConnection::new().expect(
+            Expectation::dml("DELETE FROM emp", 0)
+                .fails(Error::oracle(2292, "integrity constraint violated")),
+        );
+        let conn = mock.connect().await.unwrap();
+
+        let err = conn.execute_dml("DELETE FROM emp", &[]).await.unwrap_err();
+        assert_eq!(err.oracle_code(), Some(2292));
+        let context = err.context().unwrap();
+        assert_eq!(context.sql.as_deref(), Some("DELETE FROM emp"));
+        assert_eq!(context.connection_string, "mock:1521/MOCK");
+        assert!(context.session_id.is_some());
+        mock.verify();
+    }
+}
@@ -0,0 +1,309 @@
+// Oracle Client libraries
+
+//! Loading the Oracle Client libraries that thick mode runs on.
+//!
+//! [`init_thick`] finds and loads `libclntsh` once per process, like
+//! node-oracledb's `initOracleClient`, and reports the client release:
+//!
+//! ```rust,ignore
+//! let client = oracledb_rs::init_thick(Some(Path::new("/opt/instantclient_23_5")), None)?;
+//! println!("Oracle Client {} from {}", client.version, client.path.display());
+//! ```
+//!
+//! Without `lib_dir`, the library is looked for in `$ORACLE_HOME/lib` and
+//! then on the system search path (`LD_LIBRARY_PATH`, the `ldconfig` cache,
+//! or `DYLD_LIBRARY_PATH` on macOS). Call it early in `main`, before other
+//! threads start: a `config_dir` is applied by setting `TNS_ADMIN` for the
+//! process, which the client reads `tnsnames.ora` and `sqlnet.ora` from.
+
+use crate::{Error, Result};
+#[cfg(unix)]
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[cfg(target_os = "macos")]
+const LIBRARY: &str = "libclntsh.dylib";
+#[cfg(not(target_os = "macos"))]
+const LIBRARY: &str = "libclntsh.so";
+
+/// Release of the loaded Oracle Client libraries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClientVersion {
+    /// Major release, such as 23
+    pub major: i32,
+    /// Release update
+    pub update: i32,
+    /// Release update revision
+    pub revision: i32,
+    /// Increment
+    pub increment: i32,
+    /// Extension
+    pub ext: i32,
+}
+
+impl fmt::Display for ClientVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}.{}",
+            self.major, self.update, self.revision, self.increment, self.ext
+        )
+    }
+}
+
+/// Oracle Client libraries loaded by [`init_thick`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInfo {
+    /// Library file that was loaded, or its bare name when found on the
+    /// system search path
+    pub path: PathBuf,
+    /// Client release
+    pub version: ClientVersion,
+    /// Directory set as `TNS_ADMIN`, if any
+    pub config_dir: Option<PathBuf>,
+}
+
+/// Libraries loaded by the first successful [`init_thick`]; they stay
+/// loaded for the life of the process
+static CLIENT: Mutex<Option<ClientInfo>> = Mutex::new(None);
+
+/// Load the Oracle Client libraries for thick mode
+///
+/// `lib_dir` is the directory holding `libclntsh`, such as an Instant
+/// Client directory; `config_dir` is where the client reads its network
+/// configuration. Later calls return the libraries already loaded, and
+/// fail if they name a different `lib_dir` or `config_dir`.
+pub fn init_thick(lib_dir: Option<&Path>, config_dir: Option<&Path>) -> Result<ClientInfo> {
+    let mut client = CLIENT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(loaded) = client.as_ref() {
+        let same_lib = lib_dir.is_none_or(|dir| loaded.path.parent() == Some(dir));
+        let same_config = config_dir.is_none_or(|dir| loaded.config_dir.as_deref() == Some(dir));
+        if !same_lib || !same_config {
+            return Err(Error::ClientLibrary(format!(
+                "Oracle Client already initialized from {}; it can only be loaded once per process",
+                loaded.path.display()
+            )));
+        }
+        return Ok(loaded.clone());
+    }
+
+    if let Some(dir) = config_dir {
+        if !dir.is_dir() {
+            return Err(Error::ClientLibrary(format!(
+                "config_dir {} is not a directory",
+                dir.display()
+            )));
+        }
+    }
+    let loaded = load(lib_dir)?;
+    if let Some(dir) = config_dir {
+        std::env::set_var("TNS_ADMIN", dir);
+    }
+    let info = ClientInfo {
+        config_dir: config_dir.map(Path::to_path_buf),
+        ..loaded
+    };
+    log::debug!(
+        "loaded Oracle Client {} from {}",
+        info.version,
+        info.path.display()
+    );
+    *client = Some(info.clone());
+    Ok(info)
+}
+
+/// Libraries loaded by [`init_thick`], if any
+pub fn client_info() -> Option<ClientInfo> {
+    CLIENT.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Library files to try, in order
+#[cfg(unix)]
+fn candidates(lib_dir: Option<&Path>) -> Vec<PathBuf> {
+    if let Some(dir) = lib_dir {
+        return vec![dir.join(LIBRARY)];
+    }
+    let mut paths = Vec::new();
+    if let Some(home) = std::env::var_os("ORACLE_HOME") {
+        paths.push(Path::new(&home).join("lib").join(LIBRARY));
+    }
+    paths.push(PathBuf::from(LIBRARY));
+    paths
+}
+
+#[cfg(unix)]
+fn load(lib_dir: Option<&Path>) -> Result<ClientInfo> {
+    if let Some(dir) = lib_dir {
+        let path = dir.join(LIBRARY);
+        if !path.exists() {
+            return Err(Error::ClientLibrary(format!(
+                "{} not found in {}; pass the directory of an Oracle Instant Client",
+                LIBRARY,
+                dir.display()
+            )));
+        }
+    }
+
+    let mut failures = Vec::new();
+    for path in candidates(lib_dir) {
+        match open(&path) {
+            Ok(handle) => {
+                let version = client_version(handle)?;
+                return Ok(ClientInfo {
+                    path,
+                    version,
+                    config_dir: None,
+                });
+            }
+            Err(message) => match classify(&path, &message) {
+                LoadFailure::Missing => failures.push(path.display().to_string()),
+                LoadFailure::Unusable(e) => return Err(e),
+            },
+        }
+    }
+    Err(Error::ClientLibrary(format!(
+        "{} not found (tried {}); install Oracle Instant Client and pass its \
+         directory as lib_dir, set ORACLE_HOME, or add it to the library search path",
+        LIBRARY,
+        failures.join(", ")
+    )))
+}
+
+#[cfg(not(unix))]
+fn load(_lib_dir: Option<&Path>) -> Result<ClientInfo> {
+    Err(Error::UnsupportedFeature(
+        "loading the Oracle Client libraries is only supported on Unix".into(),
+    ))
+}
+
+/// `dlopen` a library, returning its handle or the loader's message
+#[cfg(unix)]
+fn open(path: &Path) -> std::result::Result<usize, String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let name = CString::new(path.as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+    // SAFETY: `name` is a valid C string; the handle is never closed, so
+    // symbols resolved from it stay valid
+    unsafe {
+        let handle = libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_GLOBAL);
+        if handle.is_null() {
+            let error = libc::dlerror();
+            return Err(if error.is_null() {
+                "unknown loader error".into()
+            } else {
+                CStr::from_ptr(error).to_string_lossy().into_owned()
+            });
+        }
+        Ok(handle as usize)
+    }
+}
+
+/// Release reported by the client's `OCIClientVersion`
+#[cfg(unix)]
+fn client_version(handle: usize) -> Result<ClientVersion> {
+    type OciClientVersion = unsafe extern "C" fn(*mut i32, *mut i32, *mut i32, *mut i32, *mut i32);
+
+    let symbol = CString::new("OCIClientVersion").expect("no NUL in symbol name");
+    // SAFETY: the handle came from a successful dlopen, and
+    // OCIClientVersion only writes the five integers it is given
+    unsafe {
+        let function = libc::dlsym(handle as *mut libc::c_void, symbol.as_ptr());
+        if function.is_null() {
+            return Err(Error::ClientLibrary(format!(
+                "{} has no OCIClientVersion; it is not an Oracle Client library",
+                LIBRARY
+            )));
+        }
+        let function: OciClientVersion = std::mem::transmute(function);
+        let mut v = [0i32; 5];
+        let [a, b, c, d, e] = &mut v;
+        function(a, b, c, d, e);
+        Ok(ClientVersion {
+            major: v[0],
+            update: v[1],
+            revision: v[2],
+            increment: v[3],
+            ext: v[4],
+        })
+    }
+}
+
+/// Why a candidate library did not load
+#[cfg(unix)]
+#[derive(Debug)]
+enum LoadFailure {
+    /// Not at this location; try the next one
+    Missing,
+    /// Found but unusable, with an error saying how to fix it
+    Unusable(Error),
+}
+
+/// Turn a loader message into advice
+#[cfg(unix)]
+fn classify(path: &Path, message: &str) -> LoadFailure {
+    let lower = message.to_lowercase();
+    if lower.contains("wrong elf class")
+        || lower.contains("incompatible architecture")
+        || lower.contains("wrong architecture")
+    {
+        return LoadFailure::Unusable(Error::ClientLibrary(format!(
+            "{} is built for another architecture than this {} program; install the {} \
+             Oracle Instant Client ({})",
+            path.display(),
+            std::env::consts::ARCH,
+            std::env::consts::ARCH,
+            message
+        )));
+    }
+    let missing = lower.contains("no such file") || lower.contains("image not found");
+    if missing && lower.contains(&LIBRARY.to_lowercase()) {
+        return LoadFailure::Missing;
+    }
+    let hint = if lower.contains("libaio") {
+        "; install the libaio package"
+    } else {
+        ""
+    };
+    LoadFailure::Unusable(Error::ClientLibrary(format!(
+        "{} could not be loaded: {}{}",
+        path.display(),
+        message,
+        hint
+    )))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_loader_messages() {
+        let path = Path::new("/opt/ic/libclntsh.so");
+        let missing = format!("{}: cannot open shared object file: No such file", LIBRARY);
+        assert!(matches!(classify(path, &missing), LoadFailure::Missing));
+
+        let LoadFailure::Unusable(err) = classify(path, "wrong ELF class: ELFCLASS32") else {
+            panic!("architecture mismatch is not worth retrying");
+        };
+        assert!(err.to_string().contains(std::env::consts::ARCH));
+
+        let LoadFailure::Unusable(err) = classify(
+            path,
+            "libaio.so.1: cannot open shared object file: No such file or directory",
+        ) else {
+            panic!("a missing dependency is not worth retrying");
+        };
+        assert!(err.to_string().contains("install the libaio package"));
+    }
+
+    #[test]
+    fn test_missing_lib_dir() {
+        let dir = std::env::temp_dir().join("oracledb-no-instant-client");
+        let err = init_thick(Some(&dir), None).unwrap_err();
+        assert!(matches!(err, Error::ClientLibrary(_)));
+        assert!(err.to_string().contains("oracledb-no-instant-client"));
+        assert!(client_info().is_none());
+    }
+}
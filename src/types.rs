@@ -1,8 +1,15 @@
 // Oracle data type mappings
 
+use crate::interval::{IntervalDS, IntervalYM};
+use crate::lob::Lob;
+use crate::vector::Vector;
+use crate::wire::RawValue;
+use bytes::Bytes;
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::num::{IntErrorKind, NonZeroI128, NonZeroI32, NonZeroI64, NonZeroU128, NonZeroU64};
 
 /// Oracle data types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -35,6 +42,8 @@ pub enum OracleType {
     IntervalDS,
     /// RAW
     Raw,
+    /// LONG
+    Long,
     /// LONG RAW
     LongRaw,
     /// ROWID
@@ -59,10 +68,76 @@ pub enum OracleType {
     RefCursor,
     /// Boolean (PL/SQL only)
     Boolean,
+    /// VECTOR (23ai)
+    Vector,
+}
+
+impl OracleType {
+    /// Name of the type in SQL
+    pub fn sql_name(&self) -> &'static str {
+        match self {
+            OracleType::Varchar2 => "VARCHAR2",
+            OracleType::NVarchar2 => "NVARCHAR2",
+            OracleType::Char => "CHAR",
+            OracleType::NChar => "NCHAR",
+            OracleType::Number => "NUMBER",
+            OracleType::BinaryFloat => "BINARY_FLOAT",
+            OracleType::BinaryDouble => "BINARY_DOUBLE",
+            OracleType::Date => "DATE",
+            OracleType::Timestamp => "TIMESTAMP",
+            OracleType::TimestampTz => "TIMESTAMP WITH TIME ZONE",
+            OracleType::TimestampLtz => "TIMESTAMP WITH LOCAL TIME ZONE",
+            OracleType::IntervalYM => "INTERVAL YEAR TO MONTH",
+            OracleType::IntervalDS => "INTERVAL DAY TO SECOND",
+            OracleType::Raw => "RAW",
+            OracleType::Long => "LONG",
+            OracleType::LongRaw => "LONG RAW",
+            OracleType::Rowid => "ROWID",
+            OracleType::URowid => "UROWID",
+            OracleType::Clob => "CLOB",
+            OracleType::NClob => "NCLOB",
+            OracleType::Blob => "BLOB",
+            OracleType::BFile => "BFILE",
+            OracleType::Json => "JSON",
+            OracleType::XmlType => "XMLTYPE",
+            OracleType::Object => "OBJECT",
+            OracleType::RefCursor => "REF CURSOR",
+            OracleType::Boolean => "BOOLEAN",
+            OracleType::Vector => "VECTOR",
+        }
+    }
+
+    /// Whether the type holds national character set data, and so binds
+    /// with charset form NCHAR
+    pub fn is_national(&self) -> bool {
+        matches!(
+            self,
+            OracleType::NVarchar2 | OracleType::NChar | OracleType::NClob
+        )
+    }
+}
+
+/// How NUMBER columns are fetched when read untyped
+///
+/// NUMBER holds up to 38 significant digits, more than `i64` or `f64`.
+/// Typed reads are exact either way: `row.get_typed::<String>()` of a
+/// NUMBER column always gives its canonical digits, and the wide integer
+/// types fail rather than round.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NumberFormat {
+    /// `Value::Integer` when integral and in range, `Value::Float`
+    /// otherwise, which may round
+    #[default]
+    Native,
+    /// Like `Native` while exact, falling back to the canonical digits as
+    /// `Value::String` beyond `i64` and 15 significant digits
+    Exact,
+    /// Always the canonical digits as `Value::String`
+    String,
 }
 
 /// Value wrapper for Oracle types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Value {
     /// NULL value
     Null,
@@ -80,14 +155,30 @@ pub enum Value {
     Timestamp(NaiveDateTime),
     /// Timestamp with timezone
     TimestampTz(DateTime<Utc>),
+    /// Timestamp with time zone stored in a region listed in
+    /// [`ConnectionConfig::time_zone_regions`]
+    ///
+    /// [`ConnectionConfig::time_zone_regions`]: crate::ConnectionConfig::time_zone_regions
+    #[cfg(feature = "chrono-tz")]
+    TimestampTzRegion(crate::tz::ZonedTimestamp),
+    /// INTERVAL YEAR TO MONTH
+    IntervalYM(IntervalYM),
+    /// INTERVAL DAY TO SECOND
+    IntervalDS(IntervalDS),
     /// Binary data
     Bytes(Vec<u8>),
     /// CLOB data
     Clob(String),
     /// BLOB data
     Blob(Vec<u8>),
+    /// LOB locator, fetched when [`ConnectionConfig::fetch_lobs`] is off
+    ///
+    /// [`ConnectionConfig::fetch_lobs`]: crate::ConnectionConfig::fetch_lobs
+    Lob(Lob),
     /// JSON data
     Json(serde_json::Value),
+    /// VECTOR data
+    Vector(Vector),
     /// Array of values
     Array(Vec<Value>),
     /// Object (key-value pairs)
@@ -100,6 +191,32 @@ impl Value {
         matches!(self, Value::Null)
     }
 
+    /// Name of the variant, such as `"Integer"`
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Value::Null => "Null",
+            Value::String(_) => "String",
+            Value::Integer(_) => "Integer",
+            Value::Float(_) => "Float",
+            Value::Boolean(_) => "Boolean",
+            Value::Date(_) => "Date",
+            Value::Timestamp(_) => "Timestamp",
+            Value::TimestampTz(_) => "TimestampTz",
+            #[cfg(feature = "chrono-tz")]
+            Value::TimestampTzRegion(_) => "TimestampTzRegion",
+            Value::IntervalYM(_) => "IntervalYM",
+            Value::IntervalDS(_) => "IntervalDS",
+            Value::Bytes(_) => "Bytes",
+            Value::Clob(_) => "Clob",
+            Value::Blob(_) => "Blob",
+            Value::Lob(_) => "Lob",
+            Value::Json(_) => "Json",
+            Value::Vector(_) => "Vector",
+            Value::Array(_) => "Array",
+            Value::Object(_) => "Object",
+        }
+    }
+
     /// Try to convert to string
     pub fn as_str(&self) -> Option<&str> {
         match self {
@@ -148,12 +265,153 @@ impl Value {
 pub trait ToSql: Send + Sync {
     /// Convert to Oracle value
     fn to_sql(&self) -> Value;
+
+    /// Type to bind as, when it should not follow from the value
+    fn bind_type(&self) -> Option<BindType> {
+        None
+    }
+
+    /// Whether the bind is an OUT bind of a PL/SQL call
+    fn is_out(&self) -> bool {
+        false
+    }
+
+    /// Oracle type a NULL of this type is declared as, so that `None`
+    /// binds as the type `Some` would
+    fn null_type() -> Option<OracleType>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// Oracle type and maximum size a bind is sent as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindType {
+    /// Type the server receives
+    pub oracle_type: OracleType,
+    /// Largest value the bind may hold, in characters for NCHAR types
+    /// and bytes otherwise
+    pub size: Option<usize>,
+}
+
+impl BindType {
+    /// Type a value binds as without a declared one
+    ///
+    /// Untyped NULLs bind as VARCHAR2, as Oracle assumes.
+    pub fn of(value: &Value) -> Self {
+        let oracle_type = match value {
+            Value::Null | Value::String(_) => OracleType::Varchar2,
+            Value::Integer(_) => OracleType::Number,
+            Value::Float(_) => OracleType::BinaryDouble,
+            Value::Boolean(_) => OracleType::Boolean,
+            Value::Date(_) => OracleType::Date,
+            Value::Timestamp(_) => OracleType::Timestamp,
+            Value::TimestampTz(_) => OracleType::TimestampTz,
+            #[cfg(feature = "chrono-tz")]
+            Value::TimestampTzRegion(_) => OracleType::TimestampTz,
+            Value::IntervalYM(_) => OracleType::IntervalYM,
+            Value::IntervalDS(_) => OracleType::IntervalDS,
+            Value::Bytes(_) => OracleType::Raw,
+            Value::Clob(_) => OracleType::Clob,
+            Value::Blob(_) => OracleType::Blob,
+            Value::Lob(lob) => lob.oracle_type(),
+            Value::Json(_) => OracleType::Json,
+            Value::Vector(_) => OracleType::Vector,
+            Value::Array(_) | Value::Object(_) => OracleType::Object,
+        };
+        Self {
+            oracle_type,
+            size: None,
+        }
+    }
+}
+
+/// A bind with an explicit type or size
+///
+/// ```rust,ignore
+/// let name = Param::new(&name).as_type(OracleType::NVarchar2).size(100);
+/// let missing = Param::new(&None::<i64>).as_type(OracleType::Number);
+/// conn.execute_dml("UPDATE emp SET name = :1, bonus = :2", &[&name, &missing]).await?;
+/// ```
+pub struct Param<'a> {
+    value: &'a dyn ToSql,
+    oracle_type: Option<OracleType>,
+    size: Option<usize>,
+}
+
+impl<'a> Param<'a> {
+    /// Bind `value` as its natural type until told otherwise
+    pub fn new(value: &'a dyn ToSql) -> Self {
+        Self {
+            value,
+            oracle_type: None,
+            size: None,
+        }
+    }
+
+    /// Bind as `oracle_type`
+    pub fn as_type(mut self, oracle_type: OracleType) -> Self {
+        self.oracle_type = Some(oracle_type);
+        self
+    }
+
+    /// Declare the largest value the bind may hold
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = Some(size);
+        self
+    }
+}
+
+impl fmt::Debug for Param<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Param")
+            .field("value", &self.value.to_sql())
+            .field("oracle_type", &self.oracle_type)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl ToSql for Param<'_> {
+    fn to_sql(&self) -> Value {
+        self.value.to_sql()
+    }
+
+    fn bind_type(&self) -> Option<BindType> {
+        if self.oracle_type.is_none() && self.size.is_none() {
+            return self.value.bind_type();
+        }
+        let oracle_type = self.oracle_type.unwrap_or_else(|| {
+            self.value
+                .bind_type()
+                .unwrap_or_else(|| BindType::of(&self.value.to_sql()))
+                .oracle_type
+        });
+        Some(BindType {
+            oracle_type,
+            size: self.size,
+        })
+    }
+
+    fn is_out(&self) -> bool {
+        self.value.is_out()
+    }
 }
 
 /// Trait for types that can be converted from SQL values
 pub trait FromSql: Sized {
     /// Convert from Oracle value
     fn from_sql(value: &Value) -> Result<Self, crate::Error>;
+
+    /// Convert straight from wire bytes
+    ///
+    /// The default decodes into a [`Value`] first; override it to skip
+    /// that step.
+    fn from_raw(raw: &RawValue) -> Result<Self, crate::Error> {
+        Self::from_sql(&raw.decode()?)
+    }
 }
 
 // Implementations for basic types
@@ -161,66 +419,260 @@ impl ToSql for String {
     fn to_sql(&self) -> Value {
         Value::String(self.clone())
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Varchar2)
+    }
 }
 
 impl ToSql for &str {
     fn to_sql(&self) -> Value {
         Value::String(self.to_string())
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Varchar2)
+    }
 }
 
 impl ToSql for i32 {
     fn to_sql(&self) -> Value {
         Value::Integer(*self as i64)
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Number)
+    }
 }
 
 impl ToSql for i64 {
     fn to_sql(&self) -> Value {
         Value::Integer(*self)
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Number)
+    }
 }
 
 impl ToSql for f32 {
     fn to_sql(&self) -> Value {
         Value::Float(*self as f64)
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::BinaryDouble)
+    }
 }
 
 impl ToSql for f64 {
     fn to_sql(&self) -> Value {
         Value::Float(*self)
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::BinaryDouble)
+    }
 }
 
 impl ToSql for bool {
     fn to_sql(&self) -> Value {
         Value::Boolean(*self)
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Boolean)
+    }
 }
 
+/// Binds as RAW whatever its length; bind a [`Value::Blob`] for a BLOB
 impl ToSql for Vec<u8> {
     fn to_sql(&self) -> Value {
         Value::Bytes(self.clone())
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Raw)
+    }
+}
+
+/// Borrowed binary data bound as RAW whatever its length
+///
+/// Like `Vec<u8>`, a large value is never promoted to a BLOB; it fails
+/// to bind beyond [`MAX_RAW_SIZE_EXTENDED`](crate::constants::MAX_RAW_SIZE_EXTENDED)
+/// bytes, and beyond [`MAX_RAW_SIZE`](crate::constants::MAX_RAW_SIZE) the
+/// server needs `MAX_STRING_SIZE = EXTENDED`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Raw<'a>(pub &'a [u8]);
+
+impl ToSql for Raw<'_> {
+    fn to_sql(&self) -> Value {
+        Value::Bytes(self.0.to_vec())
+    }
+
+    fn bind_type(&self) -> Option<BindType> {
+        Some(BindType {
+            oracle_type: OracleType::Raw,
+            size: None,
+        })
+    }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Raw)
+    }
+}
+
+/// Text bound as NVARCHAR2, in the national character set
+///
+/// A `&str` binds in the database character set, which may lack
+/// characters an NCHAR column can hold; this sends the bind with charset
+/// form NCHAR so nothing is lost in conversion. Its length counts UTF-16
+/// code units, as NCHAR lengths do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NStr<'a>(pub &'a str);
+
+impl ToSql for NStr<'_> {
+    fn to_sql(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+
+    fn bind_type(&self) -> Option<BindType> {
+        Some(BindType {
+            oracle_type: OracleType::NVarchar2,
+            size: None,
+        })
+    }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::NVarchar2)
+    }
+}
+
+/// An OUT bind of a PL/SQL call, read back from an
+/// [`Execution`](crate::statement::Execution)
+///
+/// ```rust,ignore
+/// let total = Out::new(OracleType::Number);
+/// let mut call = conn.execute_multi("BEGIN report(:1, :2); END;", &[&region, &total]).await?;
+/// let total: f64 = call.next_out()?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Out {
+    oracle_type: OracleType,
+    size: Option<usize>,
+}
+
+impl Out {
+    /// An OUT bind of `oracle_type`
+    pub fn new(oracle_type: OracleType) -> Self {
+        Self {
+            oracle_type,
+            size: None,
+        }
+    }
+
+    /// Declare the largest value the bind may return
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = Some(size);
+        self
+    }
+}
+
+impl ToSql for Out {
+    fn to_sql(&self) -> Value {
+        Value::Null
+    }
+
+    fn bind_type(&self) -> Option<BindType> {
+        Some(BindType {
+            oracle_type: self.oracle_type,
+            size: self.size,
+        })
+    }
+
+    fn is_out(&self) -> bool {
+        true
+    }
 }
 
 impl ToSql for NaiveDate {
     fn to_sql(&self) -> Value {
         Value::Date(*self)
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Date)
+    }
 }
 
 impl ToSql for NaiveDateTime {
     fn to_sql(&self) -> Value {
         Value::Timestamp(*self)
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Timestamp)
+    }
 }
 
 impl ToSql for DateTime<Utc> {
     fn to_sql(&self) -> Value {
         Value::TimestampTz(*self)
     }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::TimestampTz)
+    }
+}
+
+impl ToSql for IntervalYM {
+    fn to_sql(&self) -> Value {
+        Value::IntervalYM(*self)
+    }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::IntervalYM)
+    }
+}
+
+impl ToSql for IntervalDS {
+    fn to_sql(&self) -> Value {
+        Value::IntervalDS(*self)
+    }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::IntervalDS)
+    }
+}
+
+impl ToSql for serde_json::Value {
+    fn to_sql(&self) -> Value {
+        Value::Json(self.clone())
+    }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Json)
+    }
+}
+
+impl ToSql for Value {
+    fn to_sql(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl<T: ToSql + ?Sized> ToSql for &T {
+    fn to_sql(&self) -> Value {
+        (**self).to_sql()
+    }
+
+    fn bind_type(&self) -> Option<BindType> {
+        (**self).bind_type()
+    }
+
+    fn is_out(&self) -> bool {
+        (**self).is_out()
+    }
 }
 
 impl<T: ToSql> ToSql for Option<T> {
@@ -230,6 +682,25 @@ impl<T: ToSql> ToSql for Option<T> {
             None => Value::Null,
         }
     }
+
+    /// `None` declares the type `Some` would bind as
+    fn bind_type(&self) -> Option<BindType> {
+        match self {
+            Some(v) => v.bind_type(),
+            None => T::null_type().map(|oracle_type| BindType {
+                oracle_type,
+                size: None,
+            }),
+        }
+    }
+
+    fn is_out(&self) -> bool {
+        self.as_ref().is_some_and(ToSql::is_out)
+    }
+
+    fn null_type() -> Option<OracleType> {
+        T::null_type()
+    }
 }
 
 // FromSql implementations
@@ -238,12 +709,22 @@ impl FromSql for String {
         match value {
             Value::String(s) => Ok(s.clone()),
             Value::Clob(s) => Ok(s.clone()),
+            Value::Lob(lob) => lob_data(lob).and_then(|_| lob.text().expect("whole LOB")),
             _ => Err(crate::Error::TypeMismatch(format!(
                 "Cannot convert {:?} to String",
                 value
             ))),
         }
     }
+
+    /// NUMBER columns give their canonical digits, exact to the last one
+    fn from_raw(raw: &RawValue) -> Result<Self, crate::Error> {
+        match (raw.as_str(), raw.oracle_type(), raw.bytes()) {
+            (Some(s), _, _) => Ok(s.to_string()),
+            (None, OracleType::Number, Some(data)) => crate::wire::decode_number_string(data),
+            _ => Self::from_sql(&raw.decode()?),
+        }
+    }
 }
 
 impl FromSql for i64 {
@@ -283,12 +764,366 @@ impl FromSql for bool {
     }
 }
 
-impl<T: FromSql> FromSql for Option<T> {
+impl FromSql for i32 {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        match value {
+            Value::Integer(i) => i32::try_from(*i).map_err(|_| {
+                crate::Error::TypeMismatch(format!("Value {} out of range for i32", i))
+            }),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to i32",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromSql for f32 {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        match value {
+            Value::Float(f) => Ok(*f as f32),
+            Value::Integer(i) => Ok(*i as f32),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to f32",
+                value
+            ))),
+        }
+    }
+}
+
+/// Integer of a NUMBER value, exactly or not at all
+///
+/// Integers beyond `i64` arrive as strings; floats count only while
+/// they are integral and exact, up to 2^53.
+fn exact_integer(value: &Value, target: &str) -> Result<i128, crate::Error> {
+    let integer = match value {
+        Value::Integer(i) => Some(*i as i128),
+        Value::String(s) => match s.trim().parse::<i128>() {
+            Ok(n) => Some(n),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow
+                ) =>
+            {
+                return Err(crate::Error::TypeMismatch(format!(
+                    "Value {} out of range for {}",
+                    s.trim(),
+                    target
+                )))
+            }
+            Err(_) => None,
+        },
+        Value::Float(f) if f.fract() == 0.0 && f.abs() <= (1u64 << 53) as f64 => Some(*f as i128),
+        _ => None,
+    };
+    integer.ok_or_else(|| {
+        crate::Error::TypeMismatch(format!("Cannot convert {:?} to {} exactly", value, target))
+    })
+}
+
+/// Integer of a NUMBER column, decoded from the wire without rounding
+fn exact_integer_raw(raw: &RawValue, target: &str) -> Result<i128, crate::Error> {
+    match (raw.oracle_type(), raw.bytes()) {
+        (OracleType::Number, Some(data)) => match crate::wire::decode_integer(data)? {
+            Some(n) => Ok(n),
+            None => {
+                let decimal = crate::wire::decode_number_string(data)?;
+                Err(if decimal.contains('.') {
+                    crate::Error::TypeMismatch(format!(
+                        "NUMBER with a fractional part is not {}",
+                        target
+                    ))
+                } else {
+                    crate::Error::TypeMismatch(format!(
+                        "Value {} out of range for {}",
+                        decimal, target
+                    ))
+                })
+            }
+        },
+        _ => exact_integer(&raw.decode()?, target),
+    }
+}
+
+fn out_of_range(n: i128, target: &str) -> crate::Error {
+    crate::Error::TypeMismatch(format!("Value {} out of range for {}", n, target))
+}
+
+/// Bind integers as NUMBER, beyond `i64` as their decimal digits, and
+/// fetch them exactly, failing rather than truncating out of range
+macro_rules! wide_integer {
+    ($($t:ty),*) => {$(
+        impl ToSql for $t {
+            fn to_sql(&self) -> Value {
+                match i64::try_from(*self) {
+                    Ok(n) => Value::Integer(n),
+                    Err(_) => Value::String(self.to_string()),
+                }
+            }
+
+            fn bind_type(&self) -> Option<BindType> {
+                Some(BindType {
+                    oracle_type: OracleType::Number,
+                    size: None,
+                })
+            }
+
+            fn null_type() -> Option<OracleType> {
+                Some(OracleType::Number)
+            }
+        }
+
+        impl FromSql for $t {
+            fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+                let n = exact_integer(value, stringify!($t))?;
+                <$t>::try_from(n).map_err(|_| out_of_range(n, stringify!($t)))
+            }
+
+            fn from_raw(raw: &RawValue) -> Result<Self, crate::Error> {
+                let n = exact_integer_raw(raw, stringify!($t))?;
+                <$t>::try_from(n).map_err(|_| out_of_range(n, stringify!($t)))
+            }
+        }
+    )*};
+}
+
+wide_integer!(u64, i128, u128);
+
+/// Bind and fetch non-zero integers as their primitive, rejecting a
+/// fetched zero
+macro_rules! non_zero {
+    ($($t:ty => $inner:ty),*) => {$(
+        impl ToSql for $t {
+            fn to_sql(&self) -> Value {
+                self.get().to_sql()
+            }
+
+            fn bind_type(&self) -> Option<BindType> {
+                self.get().bind_type()
+            }
+
+            fn null_type() -> Option<OracleType> {
+                Some(OracleType::Number)
+            }
+        }
+
+        impl FromSql for $t {
+            fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+                <$t>::new(<$inner>::from_sql(value)?).ok_or_else(|| {
+                    crate::Error::TypeMismatch(format!("zero is not a valid {}", stringify!($t)))
+                })
+            }
+
+            fn from_raw(raw: &RawValue) -> Result<Self, crate::Error> {
+                <$t>::new(<$inner>::from_raw(raw)?).ok_or_else(|| {
+                    crate::Error::TypeMismatch(format!("zero is not a valid {}", stringify!($t)))
+                })
+            }
+        }
+    )*};
+}
+
+non_zero!(
+    NonZeroI32 => i32,
+    NonZeroI64 => i64,
+    NonZeroU64 => u64,
+    NonZeroI128 => i128,
+    NonZeroU128 => u128
+);
+
+impl FromSql for Vec<u8> {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        match value {
+            Value::Bytes(b) | Value::Blob(b) => Ok(b.clone()),
+            Value::Lob(lob) => lob_data(lob).map(<[u8]>::to_vec),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to Vec<u8>",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromSql for Bytes {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        Vec::<u8>::from_sql(value).map(Bytes::from)
+    }
+
+    /// Shares the fetch buffer for binary and UTF-8 character columns
+    fn from_raw(raw: &RawValue) -> Result<Self, crate::Error> {
+        if raw.is_locator() {
+            return Self::from_sql(&raw.decode()?);
+        }
+        match (raw.oracle_type(), raw.bytes()) {
+            (
+                OracleType::Raw
+                | OracleType::Long
+                | OracleType::LongRaw
+                | OracleType::Blob
+                | OracleType::Varchar2
+                | OracleType::Char
+                | OracleType::Clob,
+                Some(data),
+            ) => Ok(data.clone()),
+            _ => Self::from_sql(&raw.decode()?),
+        }
+    }
+}
+
+impl FromSql for NaiveDate {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        match value {
+            Value::Date(d) => Ok(*d),
+            Value::Timestamp(ts) => Ok(ts.date()),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to NaiveDate",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromSql for NaiveDateTime {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        match value {
+            Value::Timestamp(ts) => Ok(*ts),
+            Value::Date(d) => Ok(d.and_time(chrono::NaiveTime::MIN)),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to NaiveDateTime",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromSql for DateTime<Utc> {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        match value {
+            Value::TimestampTz(ts) => Ok(*ts),
+            #[cfg(feature = "chrono-tz")]
+            Value::TimestampTzRegion(ts) => Ok(ts.utc()),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to DateTime<Utc>",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromSql for IntervalYM {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        match value {
+            Value::IntervalYM(interval) => Ok(*interval),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to IntervalYM",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromSql for IntervalDS {
     fn from_sql(value: &Value) -> Result<Self, crate::Error> {
         match value {
-            Value::Null => Ok(None),
-            _ => Ok(Some(T::from_sql(value)?)),
+            Value::IntervalDS(interval) => Ok(*interval),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to IntervalDS",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromSql for Lob {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        match value {
+            Value::Lob(lob) => Ok(lob.clone()),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to Lob; LOB columns are fetched as locators only \
+                 with fetch_lobs off",
+                value
+            ))),
+        }
+    }
+}
+
+/// Data of a LOB fetched as a locator, if it was all prefetched with it
+fn lob_data(lob: &Lob) -> Result<&[u8], crate::Error> {
+    lob.data().ok_or_else(|| {
+        crate::Error::TypeMismatch(format!(
+            "{:?} locator holds only part of the LOB; read it with Connection::read_lob",
+            lob.oracle_type()
+        ))
+    })
+}
+
+impl FromSql for serde_json::Value {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        match value {
+            Value::Json(j) => Ok(j.clone()),
+            Value::String(s) | Value::Clob(s) => serde_json::from_str(s)
+                .map_err(|e| crate::Error::TypeMismatch(format!("Invalid JSON: {}", e))),
+            _ => Err(crate::Error::TypeMismatch(format!(
+                "Cannot convert {:?} to serde_json::Value",
+                value
+            ))),
+        }
+    }
+}
+
+/// System change number, the database's logical clock
+///
+/// Read from `ORA_ROWSCN` it dates the last change to a row's block (or
+/// row, for tables created with `ROWDEPENDENCIES`); binds as a NUMBER.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Scn(pub u64);
+
+impl fmt::Display for Scn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl ToSql for Scn {
+    fn to_sql(&self) -> Value {
+        self.0.to_sql()
+    }
+
+    fn bind_type(&self) -> Option<BindType> {
+        self.0.bind_type()
+    }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Number)
+    }
+}
+
+impl FromSql for Scn {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        let scn = match value {
+            Value::Integer(i) => u64::try_from(*i).ok(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        };
+        scn.map(Scn)
+            .ok_or_else(|| crate::Error::TypeMismatch(format!("Cannot convert {:?} to Scn", value)))
+    }
+}
+
+impl<T: FromSql> FromSql for Option<T> {
+    fn from_sql(value: &Value) -> Result<Self, crate::Error> {
+        if value.is_null() {
+            return Ok(None);
         }
+        T::from_sql(value).map(Some)
+    }
+
+    fn from_raw(raw: &RawValue) -> Result<Self, crate::Error> {
+        if raw.is_null() {
+            return Ok(None);
+        }
+        T::from_raw(raw).map(Some)
     }
 }
 
@@ -307,6 +1142,18 @@ pub struct ColumnInfo {
     pub scale: Option<i8>,
     /// Nullable
     pub nullable: bool,
+    /// Stored with TDE column encryption
+    pub encrypted: bool,
+    /// Covered by a data redaction (DBMS_REDACT) policy, so fetched
+    /// values may be masked
+    pub redacted: bool,
+}
+
+impl ColumnInfo {
+    /// Whether the server encrypts or redacts the column's data
+    pub fn is_protected(&self) -> bool {
+        self.encrypted || self.redacted
+    }
 }
 
 #[cfg(test)]
@@ -334,5 +1181,92 @@ mod tests {
 
         let b = true;
         assert!(matches!(b.to_sql(), Value::Boolean(true)));
+
+        let small = vec![0u8; 16];
+        assert!(matches!(small.to_sql(), Value::Bytes(_)));
+        let large = vec![0u8; 4000];
+        // Never promoted to a BLOB, whatever the length
+        assert!(matches!(large.to_sql(), Value::Bytes(b) if b.len() == 4000));
+        assert!(matches!(Raw(&large).to_sql(), Value::Bytes(b) if b.len() == 4000));
+    }
+
+    #[test]
+    fn test_typed_null() {
+        // The value stays a plain NULL; only the bind declares the type
+        let none = None::<i64>;
+        assert_eq!(none.to_sql(), Value::Null);
+        assert_eq!(none.bind_type().unwrap().oracle_type, OracleType::Number);
+        assert_eq!(
+            None::<&str>.bind_type().unwrap().oracle_type,
+            OracleType::Varchar2
+        );
+        assert_eq!(Some(7i64).bind_type(), 7i64.bind_type());
+        assert_eq!(Option::<i64>::from_sql(&none.to_sql()).unwrap(), None);
+
+        let fetched = RawValue::new(OracleType::Date, None).decode().unwrap();
+        assert_eq!(fetched, Value::Null);
+        assert_eq!(Option::<NaiveDate>::from_sql(&fetched).unwrap(), None);
+    }
+
+    #[test]
+    fn test_from_sql_range_checks() {
+        assert_eq!(i32::from_sql(&Value::Integer(7)).unwrap(), 7);
+        assert!(i32::from_sql(&Value::Integer(i64::MAX)).is_err());
+
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let ts = NaiveDateTime::from_sql(&Value::Date(date)).unwrap();
+        assert_eq!(ts.date(), date);
+    }
+
+    #[test]
+    fn test_wide_integers() {
+        assert_eq!(u64::MAX.to_sql(), Value::String(u64::MAX.to_string()));
+        assert_eq!(7u64.to_sql(), Value::Integer(7));
+        assert_eq!(Scn(7).to_sql(), Value::Integer(7));
+        assert_eq!(Scn(u64::MAX).to_sql(), Value::String(u64::MAX.to_string()));
+        assert_eq!(
+            i128::MIN.bind_type().unwrap().oracle_type,
+            OracleType::Number
+        );
+        assert_eq!(
+            u64::from_sql(&Value::String(u64::MAX.to_string())).unwrap(),
+            u64::MAX
+        );
+        assert!(u64::from_sql(&Value::Integer(-1)).is_err());
+        assert!(u128::from_sql(&Value::Float(1e300)).is_err());
+
+        // 10^20, past i64 and u64
+        let big = RawValue::new(OracleType::Number, Some(Bytes::from_static(&[0xCB, 0x02])));
+        assert_eq!(i128::from_raw(&big).unwrap(), 10i128.pow(20));
+        assert_eq!(u128::from_raw(&big).unwrap(), 10u128.pow(20));
+        assert!(u64::from_raw(&big).is_err());
+        assert!(i64::from_raw(&big).is_err());
+        // 1.5
+        let fraction = RawValue::new(
+            OracleType::Number,
+            Some(Bytes::from_static(&[0xC1, 0x02, 0x33])),
+        );
+        let Err(crate::Error::TypeMismatch(message)) = i128::from_raw(&fraction) else {
+            panic!("expected a type mismatch");
+        };
+        assert!(message.contains("fractional part"));
+        // 10^40, integral but past i128
+        let huge = RawValue::new(OracleType::Number, Some(Bytes::from_static(&[0xD5, 0x02])));
+        let Err(crate::Error::TypeMismatch(message)) = i128::from_raw(&huge) else {
+            panic!("expected a type mismatch");
+        };
+        let digits = format!("1{}", "0".repeat(40));
+        assert_eq!(message, format!("Value {} out of range for i128", digits));
+        let Err(crate::Error::TypeMismatch(message)) = i128::from_sql(&Value::String(digits))
+        else {
+            panic!("expected a type mismatch");
+        };
+        assert!(message.contains("out of range for i128"));
+
+        let id = NonZeroU64::new(42).unwrap();
+        assert_eq!(id.to_sql(), Value::Integer(42));
+        assert_eq!(NonZeroU64::from_sql(&Value::Integer(42)).unwrap(), id);
+        assert!(NonZeroI64::from_sql(&Value::Integer(0)).is_err());
+        assert_eq!(Option::<NonZeroI32>::from_sql(&Value::Null).unwrap(), None);
     }
 }
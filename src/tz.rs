@@ -0,0 +1,227 @@
+// Time zone regions
+
+//! TIMESTAMP WITH TIME ZONE values that keep their IANA region.
+//!
+//! A TSTZ value stores either a fixed offset or a time zone region, such
+//! as `Europe/Paris`, which Oracle numbers in its time zone file. Only
+//! the number is sent over the wire, and the client has no copy of the
+//! file, so the regions in use are listed once in a [`TimeZoneRegions`]
+//! table. Values stored in a listed region
+//! then decode as a [`ZonedTimestamp`], which keeps the region and so
+//! its daylight saving rules, instead of a `DateTime<Utc>`:
+//!
+//! ```rust,ignore
+//! use chrono_tz::Tz;
+//! use oracledb_rs::tz::TimeZoneRegions;
+//!
+//! let regions = TimeZoneRegions::new()
+//!     .region(paris_id, Tz::Europe__Paris)
+//!     .region(new_york_id, Tz::America__New_York);
+//! let config = ConnectionConfig::new(dsn, user, password).time_zone_regions(regions);
+//!
+//! let departs: DateTime<Tz> = row.get_typed("DEPARTS_AT")?;
+//! ```
+//!
+//! Offset values, and values in regions missing from the table, decode to
+//! `DateTime<Utc>` as they do without this feature.
+
+use crate::types::{FromSql, OracleType, ToSql, Value};
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Oracle's region IDs and the zones they name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimeZoneRegions {
+    regions: HashMap<u16, Tz>,
+}
+
+impl TimeZoneRegions {
+    /// An empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode region `id` as `zone`
+    pub fn region(mut self, id: u16, zone: Tz) -> Self {
+        self.regions.insert(id, zone);
+        self
+    }
+
+    /// Build a table from region IDs and IANA names, failing on a name
+    /// chrono-tz does not know
+    pub fn from_names<S: AsRef<str>>(names: impl IntoIterator<Item = (u16, S)>) -> Result<Self> {
+        names
+            .into_iter()
+            .try_fold(Self::new(), |table, (id, name)| {
+                let zone = name.as_ref().parse::<Tz>().map_err(|_| {
+                    Error::InvalidConfiguration(format!(
+                        "unknown time zone region {:?}",
+                        name.as_ref()
+                    ))
+                })?;
+                Ok(table.region(id, zone))
+            })
+    }
+
+    /// Zone region `id` names, if listed
+    pub fn get(&self, id: u16) -> Option<Tz> {
+        self.regions.get(&id).copied()
+    }
+
+    /// Number of regions listed
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Whether no region is listed
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+/// A TIMESTAMP WITH TIME ZONE stored in a time zone region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ZonedTimestamp {
+    utc: DateTime<Utc>,
+    region: Tz,
+}
+
+impl ZonedTimestamp {
+    /// The instant `utc`, in `region`
+    pub fn new(utc: DateTime<Utc>, region: Tz) -> Self {
+        Self { utc, region }
+    }
+
+    /// The instant, in UTC
+    pub fn utc(&self) -> DateTime<Utc> {
+        self.utc
+    }
+
+    /// The region the value is stored in
+    pub fn region(&self) -> Tz {
+        self.region
+    }
+
+    /// Local time in the region
+    pub fn datetime(&self) -> DateTime<Tz> {
+        self.utc.with_timezone(&self.region)
+    }
+}
+
+impl From<DateTime<Tz>> for ZonedTimestamp {
+    fn from(datetime: DateTime<Tz>) -> Self {
+        Self::new(datetime.with_timezone(&Utc), datetime.timezone())
+    }
+}
+
+impl ToSql for ZonedTimestamp {
+    fn to_sql(&self) -> Value {
+        Value::TimestampTzRegion(*self)
+    }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::TimestampTz)
+    }
+}
+
+impl ToSql for DateTime<Tz> {
+    fn to_sql(&self) -> Value {
+        Value::TimestampTzRegion((*self).into())
+    }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::TimestampTz)
+    }
+}
+
+impl FromSql for ZonedTimestamp {
+    fn from_sql(value: &Value) -> Result<Self> {
+        match value {
+            Value::TimestampTzRegion(ts) => Ok(*ts),
+            _ => Err(Error::TypeMismatch(format!(
+                "Cannot convert {:?} to ZonedTimestamp; only values stored in a region \
+                 listed in ConnectionConfig::time_zone_regions keep it",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromSql for DateTime<Tz> {
+    fn from_sql(value: &Value) -> Result<Self> {
+        ZonedTimestamp::from_sql(value).map(|ts| ts.datetime())
+    }
+}
+
+/// Region a 13-byte TIMESTAMP WITH TIME ZONE is stored in, if it names
+/// one rather than an offset
+pub(crate) fn region_id(data: &[u8]) -> Option<u16> {
+    const HAS_REGION_ID: u8 = 0x80;
+    match data.get(11..13)? {
+        &[high, low] if high & HAS_REGION_ID != 0 => {
+            Some(((high & 0x7F) as u16) << 6 | (low >> 2) as u16)
+        }
+        _ => None,
+    }
+}
+
+/// The instant `utc` in the region of `data`, if `regions` lists it
+pub(crate) fn zoned(
+    utc: DateTime<Utc>,
+    data: &[u8],
+    regions: &TimeZoneRegions,
+) -> Option<ZonedTimestamp> {
+    let region = regions.get(region_id(data)?)?;
+    Some(ZonedTimestamp::new(utc, region))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wire::RawValue;
+    use bytes::Bytes;
+    use chrono::{NaiveDate, Timelike};
+    use std::sync::Arc;
+
+    /// 2024-07-01 10:00:00 UTC in region `id`
+    fn tstz(id: u16) -> Bytes {
+        let mut bytes = vec![120, 124, 7, 1, 11, 1, 1, 0, 0, 0, 0];
+        bytes.push(0x80 | (id >> 6) as u8);
+        bytes.push(((id & 0x3F) as u8) << 2);
+        Bytes::from(bytes)
+    }
+
+    #[test]
+    fn test_region_decoding() {
+        let regions = Arc::new(
+            TimeZoneRegions::from_names([(1234, "Europe/Paris"), (99, "Asia/Tokyo")]).unwrap(),
+        );
+        assert_eq!(region_id(&tstz(1234)), Some(1234));
+
+        let raw = RawValue::new(OracleType::TimestampTz, Some(tstz(1234)))
+            .with_time_zone_regions(regions.clone());
+        let value = raw.decode().unwrap();
+        let paris: DateTime<Tz> = FromSql::from_sql(&value).unwrap();
+        assert_eq!(paris.timezone(), Tz::Europe__Paris);
+        // Summer time, so two hours ahead
+        assert_eq!(paris.hour(), 12);
+        let utc: DateTime<Utc> = FromSql::from_sql(&value).unwrap();
+        assert_eq!(
+            utc.naive_utc(),
+            NaiveDate::from_ymd_opt(2024, 7, 1)
+                .unwrap()
+                .and_hms_opt(10, 0, 0)
+                .unwrap()
+        );
+
+        // Regions missing from the table flatten to UTC
+        let raw =
+            RawValue::new(OracleType::TimestampTz, Some(tstz(7))).with_time_zone_regions(regions);
+        assert!(matches!(raw.decode().unwrap(), Value::TimestampTz(_)));
+
+        assert!(TimeZoneRegions::from_names([(1, "Mars/Olympus_Mons")]).is_err());
+    }
+}
@@ -0,0 +1,636 @@
+// VECTOR values and similarity search
+
+//! 23ai VECTOR columns, and helpers for similarity search over them.
+//!
+//! A `Vec<f32>` (or `Vec<f64>`, `Vec<i8>`) binds and fetches as a
+//! VECTOR. [`VectorTable`] names a table of embeddings, loads rows into
+//! it with array DML and builds nearest-neighbour queries, which stream
+//! `(id, distance)` pairs closest first:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::vector::{Distance, VectorTable};
+//!
+//! let docs = VectorTable::new("docs", "id", "embedding")?;
+//! docs.insert(&conn, chunks.iter().map(|c| (c.id, c.embedding.clone()))).await?;
+//!
+//! let mut hits = docs
+//!     .search(question_embedding)
+//!     .metric(Distance::Cosine)
+//!     .limit(5)
+//!     .target_accuracy(95)
+//!     .stream::<i64>(&conn)
+//!     .await?;
+//! while let Some((id, distance)) = hits.try_next().await? {
+//!     println!("{id}: {distance}");
+//! }
+//! ```
+
+use crate::connection::Connection;
+use crate::sql::identifier;
+use crate::types::{BindType, FromSql, OracleType, ToSql, Value};
+use crate::wire::{decode_binary_double, decode_binary_float, make_sortable};
+use crate::{Error, ExecuteOptions, Result};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// First byte of every VECTOR image
+const MAGIC: u8 = 0xDB;
+/// Image version of dense numeric vectors
+const VERSION_BASE: u8 = 0;
+/// Image version that adds BINARY vectors
+const VERSION_BINARY: u8 = 1;
+/// The image carries an 8-byte norm
+const FLAG_NORM: u16 = 0x0002;
+/// Space for a norm is reserved
+const FLAG_NORM_RESERVED: u16 = 0x0010;
+/// Bytes before the norm or the elements
+const HEADER_LEN: usize = 9;
+/// Bytes of the norm, which the client neither needs nor computes
+const NORM_LEN: usize = 8;
+
+const FORMAT_FLOAT32: u8 = 2;
+const FORMAT_FLOAT64: u8 = 3;
+const FORMAT_INT8: u8 = 4;
+const FORMAT_BINARY: u8 = 5;
+
+/// A VECTOR value, in one of the formats a column can declare
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Vector {
+    /// FLOAT32 dimensions
+    Float32(Vec<f32>),
+    /// FLOAT64 dimensions
+    Float64(Vec<f64>),
+    /// INT8 dimensions
+    Int8(Vec<i8>),
+    /// BINARY dimensions, packed eight to a byte with the first in the
+    /// high bit
+    Binary(Vec<u8>),
+}
+
+impl Vector {
+    /// Number of dimensions
+    pub fn dimensions(&self) -> usize {
+        match self {
+            Vector::Float32(v) => v.len(),
+            Vector::Float64(v) => v.len(),
+            Vector::Int8(v) => v.len(),
+            Vector::Binary(v) => v.len() * 8,
+        }
+    }
+
+    /// Name of the format, as in a `VECTOR(n, FLOAT32)` declaration
+    pub fn format_name(&self) -> &'static str {
+        match self {
+            Vector::Float32(_) => "FLOAT32",
+            Vector::Float64(_) => "FLOAT64",
+            Vector::Int8(_) => "INT8",
+            Vector::Binary(_) => "BINARY",
+        }
+    }
+
+    /// Dimensions as `f64`, BINARY ones as 0 or 1
+    pub fn to_f64(&self) -> Vec<f64> {
+        match self {
+            Vector::Float32(v) => v.iter().map(|&x| x as f64).collect(),
+            Vector::Float64(v) => v.clone(),
+            Vector::Int8(v) => v.iter().map(|&x| x as f64).collect(),
+            Vector::Binary(v) => v
+                .iter()
+                .flat_map(|byte| (0..8).rev().map(move |bit| ((byte >> bit) & 1) as f64))
+                .collect(),
+        }
+    }
+
+    /// Decode a VECTOR image
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let invalid = |what: &str| Error::Encoding(format!("invalid VECTOR image: {}", what));
+        if data.len() < HEADER_LEN || data[0] != MAGIC {
+            return Err(invalid("bad header"));
+        }
+        if data[1] > VERSION_BINARY {
+            return Err(Error::Encoding(format!(
+                "unsupported VECTOR image version {}; sparse vectors are not supported",
+                data[1]
+            )));
+        }
+        let flags = u16::from_be_bytes([data[2], data[3]]);
+        let format = data[4];
+        let count = u32::from_be_bytes([data[5], data[6], data[7], data[8]]) as usize;
+        let mut body = &data[HEADER_LEN..];
+        if flags & (FLAG_NORM | FLAG_NORM_RESERVED) != 0 {
+            body = body
+                .get(NORM_LEN..)
+                .ok_or_else(|| invalid("truncated norm"))?;
+        }
+
+        let width = match format {
+            FORMAT_FLOAT32 => 4,
+            FORMAT_FLOAT64 => 8,
+            FORMAT_INT8 => 1,
+            FORMAT_BINARY => {
+                if !count.is_multiple_of(8) || body.len() != count / 8 {
+                    return Err(invalid("wrong length"));
+                }
+                return Ok(Vector::Binary(body.to_vec()));
+            }
+            other => {
+                return Err(Error::Encoding(format!(
+                    "unsupported VECTOR format {}",
+                    other
+                )))
+            }
+        };
+        if body.len() != count * width {
+            return Err(invalid("wrong length"));
+        }
+        let elements = body.chunks_exact(width);
+        Ok(match format {
+            FORMAT_FLOAT32 => {
+                Vector::Float32(elements.map(decode_binary_float).collect::<Result<_>>()?)
+            }
+            FORMAT_FLOAT64 => {
+                Vector::Float64(elements.map(decode_binary_double).collect::<Result<_>>()?)
+            }
+            _ => Vector::Int8(body.iter().map(|&b| b as i8).collect()),
+        })
+    }
+
+    /// Encode as a VECTOR image, with the norm left for the server to
+    /// compute
+    pub fn encode(&self) -> Vec<u8> {
+        let (version, format, flags) = match self {
+            Vector::Float32(_) => (VERSION_BASE, FORMAT_FLOAT32, FLAG_NORM | FLAG_NORM_RESERVED),
+            Vector::Float64(_) => (VERSION_BASE, FORMAT_FLOAT64, FLAG_NORM | FLAG_NORM_RESERVED),
+            Vector::Int8(_) => (VERSION_BASE, FORMAT_INT8, FLAG_NORM | FLAG_NORM_RESERVED),
+            Vector::Binary(_) => (VERSION_BINARY, FORMAT_BINARY, FLAG_NORM_RESERVED),
+        };
+        let mut out = Vec::with_capacity(HEADER_LEN + NORM_LEN + self.dimensions() * 8);
+        out.extend_from_slice(&[MAGIC, version]);
+        out.extend_from_slice(&flags.to_be_bytes());
+        out.push(format);
+        out.extend_from_slice(&(self.dimensions() as u32).to_be_bytes());
+        out.extend_from_slice(&[0; NORM_LEN]);
+        match self {
+            Vector::Float32(v) => v.iter().for_each(|x| {
+                let mut bytes = x.to_be_bytes();
+                make_sortable(&mut bytes);
+                out.extend_from_slice(&bytes);
+            }),
+            Vector::Float64(v) => v.iter().for_each(|x| {
+                let mut bytes = x.to_be_bytes();
+                make_sortable(&mut bytes);
+                out.extend_from_slice(&bytes);
+            }),
+            Vector::Int8(v) => out.extend(v.iter().map(|&x| x as u8)),
+            Vector::Binary(v) => out.extend_from_slice(v),
+        }
+        out
+    }
+}
+
+impl From<Vec<f32>> for Vector {
+    fn from(v: Vec<f32>) -> Self {
+        Vector::Float32(v)
+    }
+}
+
+impl From<&[f32]> for Vector {
+    fn from(v: &[f32]) -> Self {
+        Vector::Float32(v.to_vec())
+    }
+}
+
+impl From<Vec<f64>> for Vector {
+    fn from(v: Vec<f64>) -> Self {
+        Vector::Float64(v)
+    }
+}
+
+impl From<Vec<i8>> for Vector {
+    fn from(v: Vec<i8>) -> Self {
+        Vector::Int8(v)
+    }
+}
+
+impl ToSql for Vector {
+    fn to_sql(&self) -> Value {
+        Value::Vector(self.clone())
+    }
+
+    fn null_type() -> Option<OracleType> {
+        Some(OracleType::Vector)
+    }
+}
+
+macro_rules! vector_to_sql {
+    ($($ty:ty => $variant:ident),+) => {
+        $(impl ToSql for Vec<$ty> {
+            fn to_sql(&self) -> Value {
+                Value::Vector(Vector::$variant(self.clone()))
+            }
+
+            fn null_type() -> Option<OracleType> {
+                Some(OracleType::Vector)
+            }
+        })+
+    };
+}
+
+vector_to_sql!(f32 => Float32, f64 => Float64, i8 => Int8);
+
+fn not_a_vector<T>(value: &Value, target: &str) -> Result<T> {
+    Err(Error::TypeMismatch(format!(
+        "Cannot convert {:?} to {}",
+        value, target
+    )))
+}
+
+impl FromSql for Vector {
+    fn from_sql(value: &Value) -> Result<Self> {
+        match value {
+            Value::Vector(v) => Ok(v.clone()),
+            _ => not_a_vector(value, "Vector"),
+        }
+    }
+}
+
+impl FromSql for Vec<f32> {
+    fn from_sql(value: &Value) -> Result<Self> {
+        match value {
+            Value::Vector(Vector::Float32(v)) => Ok(v.clone()),
+            Value::Vector(Vector::Int8(v)) => Ok(v.iter().map(|&x| x as f32).collect()),
+            // FLOAT64 dimensions would lose precision
+            _ => not_a_vector(value, "Vec<f32>"),
+        }
+    }
+}
+
+impl FromSql for Vec<f64> {
+    fn from_sql(value: &Value) -> Result<Self> {
+        match value {
+            Value::Vector(v) => Ok(v.to_f64()),
+            _ => not_a_vector(value, "Vec<f64>"),
+        }
+    }
+}
+
+impl FromSql for Vec<i8> {
+    fn from_sql(value: &Value) -> Result<Self> {
+        match value {
+            Value::Vector(Vector::Int8(v)) => Ok(v.clone()),
+            _ => not_a_vector(value, "Vec<i8>"),
+        }
+    }
+}
+
+/// Distance metric of a similarity search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Distance {
+    /// Cosine distance, the default
+    #[default]
+    Cosine,
+    /// Negated dot product
+    Dot,
+    /// Euclidean distance
+    Euclidean,
+    /// Squared Euclidean distance
+    EuclideanSquared,
+    /// Manhattan distance
+    Manhattan,
+    /// Hamming distance, for BINARY vectors among others
+    Hamming,
+    /// Jaccard distance, for BINARY vectors only
+    Jaccard,
+}
+
+impl Distance {
+    /// The metric as VECTOR_DISTANCE names it
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Distance::Cosine => "COSINE",
+            Distance::Dot => "DOT",
+            Distance::Euclidean => "EUCLIDEAN",
+            Distance::EuclideanSquared => "EUCLIDEAN_SQUARED",
+            Distance::Manhattan => "MANHATTAN",
+            Distance::Hamming => "HAMMING",
+            Distance::Jaccard => "JACCARD",
+        }
+    }
+}
+
+impl fmt::Display for Distance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_sql())
+    }
+}
+
+/// A table with an ID column and a VECTOR column of embeddings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VectorTable {
+    table: String,
+    id_column: String,
+    vector_column: String,
+}
+
+impl VectorTable {
+    /// Name the table, which may be `schema.table`, and its columns
+    pub fn new(table: &str, id_column: &str, vector_column: &str) -> Result<Self> {
+        let table = match table.split_once('.') {
+            Some((schema, name)) => format!("{}.{}", identifier(schema)?, identifier(name)?),
+            None => identifier(table)?,
+        };
+        Ok(Self {
+            table,
+            id_column: identifier(id_column)?,
+            vector_column: identifier(vector_column)?,
+        })
+    }
+
+    /// Insert `(id, vector)` rows with array DML, returning the number
+    /// inserted
+    ///
+    /// Rows are sent in batches of [`ConnectionConfig::batch_size`] as
+    /// the iterator yields them. The insert joins the current
+    /// transaction; commit it as usual.
+    ///
+    /// [`ConnectionConfig::batch_size`]: crate::ConnectionConfig::batch_size
+    pub async fn insert<I, T, V>(&self, conn: &Connection, rows: I) -> Result<u64>
+    where
+        I: IntoIterator<Item = (T, V)>,
+        T: ToSql,
+        V: Into<Vector>,
+    {
+        let sql = format!(
+            "INSERT INTO {} ({}, {}) VALUES (:1, :2)",
+            self.table, self.id_column, self.vector_column
+        );
+        let counts = conn
+            .execute_many_as(&sql, rows.into_iter().map(|(id, v)| (id, v.into())))
+            .await?;
+        Ok(counts.iter().sum())
+    }
+
+    /// Start a search for the rows nearest `query`
+    pub fn search(&self, query: impl Into<Vector>) -> VectorSearch<'_> {
+        VectorSearch {
+            table: self,
+            query: query.into(),
+            metric: Distance::default(),
+            limit: 10,
+            approximate: false,
+            target_accuracy: None,
+            filter: None,
+        }
+    }
+}
+
+/// A nearest-neighbour query over a [`VectorTable`]
+#[derive(Debug, Clone)]
+pub struct VectorSearch<'a> {
+    table: &'a VectorTable,
+    query: Vector,
+    metric: Distance,
+    limit: u32,
+    approximate: bool,
+    target_accuracy: Option<u8>,
+    filter: Option<(String, Vec<FilterBind>)>,
+}
+
+/// A filter bind, kept with the type its source declared
+#[derive(Debug, Clone)]
+struct FilterBind {
+    value: Value,
+    bind_type: Option<BindType>,
+}
+
+impl ToSql for FilterBind {
+    fn to_sql(&self) -> Value {
+        self.value.clone()
+    }
+
+    fn bind_type(&self) -> Option<BindType> {
+        self.bind_type
+    }
+}
+
+impl VectorSearch<'_> {
+    /// Rank by `metric`, [`Distance::Cosine`] by default
+    pub fn metric(mut self, metric: Distance) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Return the `k` nearest rows, 10 by default
+    pub fn limit(mut self, k: u32) -> Self {
+        self.limit = k;
+        self
+    }
+
+    /// Allow a vector index to answer approximately (FETCH APPROX)
+    pub fn approximate(mut self, approximate: bool) -> Self {
+        self.approximate = approximate;
+        self
+    }
+
+    /// Search approximately, asking the index for `percent` accuracy
+    pub fn target_accuracy(mut self, percent: u8) -> Self {
+        self.approximate = true;
+        self.target_accuracy = Some(percent.clamp(1, 100));
+        self
+    }
+
+    /// Only consider rows meeting `condition`, SQL placed in the WHERE
+    /// clause as is
+    ///
+    /// The condition's bind placeholders take `params` in order, after
+    /// the query vector.
+    pub fn filter(mut self, condition: &str, params: &[&dyn ToSql]) -> Self {
+        let binds = params
+            .iter()
+            .map(|p| FilterBind {
+                value: p.to_sql(),
+                bind_type: p.bind_type(),
+            })
+            .collect();
+        self.filter = Some((condition.to_string(), binds));
+        self
+    }
+
+    /// The query's SQL; the query vector binds first
+    pub fn sql(&self) -> String {
+        let mut sql = format!(
+            "SELECT {}, VECTOR_DISTANCE({}, :1, {}) AS distance FROM {}",
+            self.table.id_column, self.table.vector_column, self.metric, self.table.table
+        );
+        if let Some((condition, _)) = &self.filter {
+            sql.push_str(&format!(" WHERE {}", condition));
+        }
+        sql.push_str(" ORDER BY distance FETCH ");
+        if self.approximate {
+            sql.push_str("APPROX ");
+        }
+        sql.push_str(&format!("FIRST {} ROWS ONLY", self.limit));
+        if let Some(percent) = self.target_accuracy {
+            sql.push_str(&format!(" WITH TARGET ACCURACY {}", percent));
+        }
+        sql
+    }
+
+    /// Run the query, streaming `(id, distance)` pairs nearest first
+    pub async fn stream<I: FromSql>(
+        &self,
+        conn: &Connection,
+    ) -> Result<impl Stream<Item = Result<(I, f64)>> + Send + Unpin> {
+        let mut params: Vec<&dyn ToSql> = vec![&self.query];
+        if let Some((_, binds)) = &self.filter {
+            params.extend(binds.iter().map(|b| b as &dyn ToSql));
+        }
+        let rows = conn
+            .query_stream(&self.sql(), &params, &ExecuteOptions::default())
+            .await?;
+        Ok(rows.map(|row| {
+            let row = row?;
+            Ok((row.get_typed::<I>(0)?, row.get_typed::<f64>(1)?))
+        }))
+    }
+
+    /// Run the query, collecting `(id, distance)` pairs nearest first
+    pub async fn fetch<I: FromSql>(&self, conn: &Connection) -> Result<Vec<(I, f64)>> {
+        use futures::TryStreamExt;
+        self.stream(conn).await?.try_collect().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_images() {
+        // VECTOR(2, FLOAT32) holding [1.5, -2], as the server sends it
+        let mut image = vec![MAGIC, 0, 0x00, 0x12, FORMAT_FLOAT32, 0, 0, 0, 2];
+        image.extend_from_slice(&[0; NORM_LEN]);
+        image.extend_from_slice(&[0xBF, 0xC0, 0x00, 0x00, 0x3F, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(
+            Vector::decode(&image).unwrap(),
+            Vector::Float32(vec![1.5, -2.0])
+        );
+        assert_eq!(Vector::Float32(vec![1.5, -2.0]).encode(), image);
+
+        for vector in [
+            Vector::Float64(vec![0.25, -1e300, 0.0]),
+            Vector::Int8(vec![-128, 0, 127]),
+            Vector::Binary(vec![0b1010_0000, 0xFF]),
+        ] {
+            assert_eq!(Vector::decode(&vector.encode()).unwrap(), vector);
+        }
+        assert_eq!(Vector::Binary(vec![0b1010_0000]).dimensions(), 8);
+        assert_eq!(
+            Vector::Binary(vec![0b1010_0000]).to_f64()[..3],
+            [1.0, 0.0, 1.0]
+        );
+
+        assert!(Vector::decode(&image[..image.len() - 1]).is_err());
+        assert!(Vector::decode(&[0xDB, 2, 0, 0, 2, 0, 0, 0, 0]).is_err());
+        assert!(Vector::decode(&[]).is_err());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let value = vec![0.5f32, 1.0].to_sql();
+        assert_eq!(value, Value::Vector(Vector::Float32(vec![0.5, 1.0])));
+        assert_eq!(Vec::<f32>::from_sql(&value).unwrap(), [0.5, 1.0]);
+        assert_eq!(Vec::<f64>::from_sql(&value).unwrap(), [0.5, 1.0]);
+
+        let wide = Value::Vector(Vector::Float64(vec![0.1]));
+        assert!(Vec::<f32>::from_sql(&wide).is_err());
+        assert!(Vec::<i8>::from_sql(&wide).is_err());
+        assert!(Vector::from_sql(&Value::Integer(1)).is_err());
+    }
+
+    #[test]
+    fn test_search_sql() {
+        use crate::types::NStr;
+
+        let docs = VectorTable::new("app.docs", "id", "Doc Embedding").unwrap();
+        let search = docs.search(vec![0.0f32; 3]);
+        assert_eq!(
+            search.sql(),
+            "SELECT id, VECTOR_DISTANCE(\"Doc Embedding\", :1, COSINE) AS distance FROM app.docs \
+             ORDER BY distance FETCH FIRST 10 ROWS ONLY"
+        );
+        let search = search
+            .metric(Distance::EuclideanSquared)
+            .limit(3)
+            .target_accuracy(90)
+            .filter("lang = :lang", &[&"en"]);
+        assert_eq!(
+            search.sql(),
+            "SELECT id, VECTOR_DISTANCE(\"Doc Embedding\", :1, EUCLIDEAN_SQUARED) AS distance \
+             FROM app.docs WHERE lang = :lang ORDER BY distance \
+             FETCH APPROX FIRST 3 ROWS ONLY WITH TARGET ACCURACY 90"
+        );
+        assert!(VectorTable::new("docs", "id", "vec\"tor").is_err());
+
+        // Filter binds keep their declared types
+        let search = docs
+            .search(vec![0.0f32; 3])
+            .filter("lang = :2 AND rank = :3", &[&NStr("en"), &None::<i64>]);
+        let (_, binds) = search.filter.as_ref().unwrap();
+        let types: Vec<_> = binds
+            .iter()
+            .map(|b| b.bind_type().unwrap().oracle_type)
+            .collect();
+        assert_eq!(types, [OracleType::NVarchar2, OracleType::Number]);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_load_and_search() {
+        use crate::testing::{column, Expectation, MockConnection};
+        use futures::TryStreamExt;
+
+        let docs = VectorTable::new("docs", "id", "embedding").unwrap();
+        let query = vec![0.1f32, 0.2];
+        let search = docs.search(query.clone()).limit(2);
+        let insert = "INSERT INTO docs (id, embedding) VALUES (:1, :2)";
+        let mock = MockConnection::new()
+            .expect(Expectation::dml(insert, 1).params(vec![
+                Value::Integer(1),
+                Value::Vector(Vector::Float32(vec![0.1, 0.2])),
+            ]))
+            .expect(Expectation::dml(insert, 1).params(vec![
+                Value::Integer(2),
+                Value::Vector(Vector::Float32(vec![0.9, 0.1])),
+            ]))
+            .expect(
+                Expectation::query(search.sql())
+                    .params(vec![Value::Vector(Vector::Float32(query))])
+                    .returns(
+                        vec![
+                            column("ID", OracleType::Number),
+                            column("DISTANCE", OracleType::BinaryDouble),
+                        ],
+                        vec![
+                            vec![Value::Integer(1), Value::Float(0.0)],
+                            vec![Value::Integer(2), Value::Float(0.4)],
+                        ],
+                    ),
+            );
+        let conn = mock.connect().await.unwrap();
+
+        let rows = [(1i64, vec![0.1f32, 0.2]), (2, vec![0.9, 0.1])];
+        assert_eq!(docs.insert(&conn, rows).await.unwrap(), 2);
+
+        let hits: Vec<(i64, f64)> = search
+            .stream(&conn)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(hits, [(1, 0.0), (2, 0.4)]);
+        mock.verify();
+    }
+}
@@ -0,0 +1,862 @@
+// Oracle wire formats for column data
+
+//! Column values as they arrive from the server, and their decoders.
+//!
+//! A fetch response carries every row of the batch in one network buffer.
+//! [`read_row`] slices each column out of that shared [`Bytes`] without
+//! copying, and the resulting [`RawValue`]s are only converted when the
+//! application asks for them: [`FromSql::from_raw`](crate::types::FromSql::from_raw)
+//! decodes straight from the wire bytes, and [`Row`](crate::Row) decodes
+//! into [`Value`]s on first untyped access. Asking for [`Bytes`] or
+//! [`RawValue::as_str`] never allocates.
+//!
+//! Column data on the wire is length-prefixed: a length byte of 0 is NULL,
+//! 1 to 253 is an inline value of that length, and 254 starts a chunked
+//! value of length-prefixed chunks ending in an empty chunk. LONG and
+//! LONG RAW columns arrive as such pieces and are reassembled up to
+//! [`read_row_limited`]'s cap.
+
+use crate::interval::{IntervalDS, IntervalYM};
+use crate::lob::Lob;
+use crate::types::{NumberFormat, OracleType, Value};
+#[cfg(feature = "chrono-tz")]
+use crate::tz::TimeZoneRegions;
+use crate::vector::Vector;
+use crate::{Error, Result};
+use bytes::{BufMut, Bytes, BytesMut};
+use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+#[cfg(feature = "chrono-tz")]
+use std::sync::Arc;
+
+/// Length byte that starts a chunked value
+const CHUNKED: u8 = 0xFE;
+
+/// Longest value sent inline
+const MAX_INLINE: usize = 0xFD;
+
+/// A column value still in its wire format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawValue {
+    oracle_type: OracleType,
+    data: Option<Bytes>,
+    /// The bytes are a LOB locator rather than the LOB's data
+    locator: bool,
+    /// How a NUMBER decodes
+    number_format: NumberFormat,
+    /// Encoding of NCHAR and NVARCHAR2 data
+    national_charset: NationalCharset,
+    /// Regions TIMESTAMP WITH TIME ZONE values keep
+    #[cfg(feature = "chrono-tz")]
+    regions: Option<Arc<TimeZoneRegions>>,
+}
+
+impl RawValue {
+    /// Wrap wire bytes of a column; `None` is NULL
+    pub fn new(oracle_type: OracleType, data: Option<Bytes>) -> Self {
+        Self {
+            oracle_type,
+            data,
+            locator: false,
+            number_format: NumberFormat::Native,
+            national_charset: NationalCharset::Al16Utf16,
+            #[cfg(feature = "chrono-tz")]
+            regions: None,
+        }
+    }
+
+    /// Wrap a LOB locator, as sent for LOB columns when
+    /// [`ConnectionConfig::fetch_lobs`](crate::ConnectionConfig::fetch_lobs)
+    /// is off
+    pub fn locator(oracle_type: OracleType, data: Option<Bytes>) -> Self {
+        Self {
+            oracle_type,
+            data,
+            locator: true,
+            number_format: NumberFormat::Native,
+            national_charset: NationalCharset::Al16Utf16,
+            #[cfg(feature = "chrono-tz")]
+            regions: None,
+        }
+    }
+
+    /// Decode a NUMBER as `format` says
+    pub fn with_number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
+    }
+
+    /// Decode NCHAR and NVARCHAR2 data as `charset`
+    pub fn with_national_charset(mut self, charset: NationalCharset) -> Self {
+        self.national_charset = charset;
+        self
+    }
+
+    /// Keep the region of TIMESTAMP WITH TIME ZONE values stored in one
+    /// `regions` lists
+    #[cfg(feature = "chrono-tz")]
+    pub fn with_time_zone_regions(mut self, regions: Arc<TimeZoneRegions>) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+
+    /// Check if the bytes are a LOB locator
+    pub fn is_locator(&self) -> bool {
+        self.locator
+    }
+
+    /// Column type the bytes are encoded as
+    pub fn oracle_type(&self) -> OracleType {
+        self.oracle_type
+    }
+
+    /// Check if value is NULL
+    pub fn is_null(&self) -> bool {
+        self.data.is_none()
+    }
+
+    /// Wire bytes, sharing the fetch buffer
+    pub fn bytes(&self) -> Option<&Bytes> {
+        self.data.as_ref()
+    }
+
+    /// Borrow character data without decoding, if it is UTF-8
+    pub fn as_str(&self) -> Option<&str> {
+        if self.locator {
+            return None;
+        }
+        match self.oracle_type {
+            OracleType::Varchar2
+            | OracleType::Char
+            | OracleType::Long
+            | OracleType::Clob
+            | OracleType::Rowid
+            | OracleType::URowid => std::str::from_utf8(self.data.as_ref()?).ok(),
+            _ => None,
+        }
+    }
+
+    /// Convert to a [`Value`]
+    pub fn decode(&self) -> Result<Value> {
+        let Some(data) = &self.data else {
+            return Ok(Value::Null);
+        };
+        if self.locator {
+            return Lob::decode(self.oracle_type, data).map(Value::Lob);
+        }
+        match self.oracle_type {
+            OracleType::Varchar2 | OracleType::Char | OracleType::Rowid | OracleType::URowid => {
+                decode_utf8(data).map(Value::String)
+            }
+            OracleType::NVarchar2 | OracleType::NChar => {
+                self.national_charset.decode(data).map(Value::String)
+            }
+            OracleType::Long => decode_utf8(data).map(Value::String),
+            OracleType::Clob => decode_utf8(data).map(Value::Clob),
+            // LOB data travels as UTF-16 whatever the national charset
+            OracleType::NClob => decode_utf16(data).map(Value::Clob),
+            OracleType::Number => decode_number_as(data, self.number_format),
+            OracleType::BinaryDouble => decode_binary_double(data).map(Value::Float),
+            OracleType::BinaryFloat => decode_binary_float(data).map(|f| Value::Float(f as f64)),
+            OracleType::Date => decode_datetime(data).map(Value::Timestamp),
+            OracleType::Timestamp | OracleType::TimestampLtz => {
+                decode_datetime(data).map(Value::Timestamp)
+            }
+            OracleType::TimestampTz => {
+                let utc = Utc.from_utc_datetime(&decode_datetime(data)?);
+                #[cfg(feature = "chrono-tz")]
+                if let Some(ts) = self
+                    .regions
+                    .as_ref()
+                    .and_then(|r| crate::tz::zoned(utc, data, r))
+                {
+                    return Ok(Value::TimestampTzRegion(ts));
+                }
+                Ok(Value::TimestampTz(utc))
+            }
+            OracleType::IntervalYM => IntervalYM::decode(data).map(Value::IntervalYM),
+            OracleType::IntervalDS => IntervalDS::decode(data).map(Value::IntervalDS),
+            OracleType::Vector => Vector::decode(data).map(Value::Vector),
+            OracleType::Raw | OracleType::LongRaw => Ok(Value::Bytes(data.to_vec())),
+            OracleType::Blob => Ok(Value::Blob(data.to_vec())),
+            // A length byte then the flag: the length byte is always
+            // nonzero, so only the last byte tells true from false
+            OracleType::Boolean => Ok(Value::Boolean(data.last() == Some(&1))),
+            other => Err(Error::Encoding(format!(
+                "no wire decoder for {:?} columns",
+                other
+            ))),
+        }
+    }
+}
+
+/// Slice one row of length-prefixed column values out of a fetch buffer
+///
+/// `offset` is advanced past the row. Inline values share `buffer`;
+/// chunked values are reassembled into a buffer of their own.
+pub fn read_row(buffer: &Bytes, offset: &mut usize, types: &[OracleType]) -> Result<Vec<RawValue>> {
+    read_row_limited(buffer, offset, types, 0)
+}
+
+/// Like [`read_row`], failing with [`Error::ResultTooLarge`] once a LONG
+/// or LONG RAW value grows past `max_long_size` bytes (0 = unlimited)
+///
+/// The pieces of a long value are checked as they are appended, so an
+/// oversized column is rejected before it is fully buffered.
+pub fn read_row_limited(
+    buffer: &Bytes,
+    offset: &mut usize,
+    types: &[OracleType],
+    max_long_size: usize,
+) -> Result<Vec<RawValue>> {
+    types
+        .iter()
+        .map(|&oracle_type| {
+            let limit = match oracle_type {
+                OracleType::Long | OracleType::LongRaw => max_long_size,
+                _ => 0,
+            };
+            let data = read_value(buffer, offset, limit)?;
+            Ok(RawValue::new(oracle_type, data))
+        })
+        .collect()
+}
+
+fn read_value(buffer: &Bytes, offset: &mut usize, limit: usize) -> Result<Option<Bytes>> {
+    let truncated = || Error::Protocol("truncated column data".into());
+    let len = *buffer.get(*offset).ok_or_else(truncated)?;
+    *offset += 1;
+    match len {
+        0 => Ok(None),
+        CHUNKED => {
+            let mut value = BytesMut::new();
+            loop {
+                let chunk = read_value(buffer, offset, 0)?;
+                match chunk {
+                    Some(chunk) => {
+                        let size = value.len() + chunk.len();
+                        if limit > 0 && size > limit {
+                            return Err(Error::ResultTooLarge { size, limit });
+                        }
+                        value.extend_from_slice(&chunk)
+                    }
+                    None => return Ok(Some(value.freeze())),
+                }
+            }
+        }
+        len => {
+            let end = *offset + len as usize;
+            if end > buffer.len() {
+                return Err(truncated());
+            }
+            if limit > 0 && len as usize > limit {
+                return Err(Error::ResultTooLarge {
+                    size: len as usize,
+                    limit,
+                });
+            }
+            let data = buffer.slice(*offset..end);
+            *offset = end;
+            Ok(Some(data))
+        }
+    }
+}
+
+/// Append a length-prefixed column value
+pub(crate) fn write_value(buf: &mut BytesMut, data: Option<&[u8]>) {
+    match data {
+        None | Some([]) => buf.put_u8(0),
+        Some(data) if data.len() <= MAX_INLINE => {
+            buf.put_u8(data.len() as u8);
+            buf.put_slice(data);
+        }
+        Some(data) => {
+            buf.put_u8(CHUNKED);
+            for chunk in data.chunks(MAX_INLINE) {
+                buf.put_u8(chunk.len() as u8);
+                buf.put_slice(chunk);
+            }
+            buf.put_u8(0);
+        }
+    }
+}
+
+fn decode_utf8(data: &[u8]) -> Result<String> {
+    String::from_utf8(data.to_vec()).map_err(|e| Error::Encoding(e.to_string()))
+}
+
+/// Character set of NCHAR, NVARCHAR2 and NCLOB columns, negotiated at
+/// logon
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NationalCharset {
+    /// UTF-16 big-endian, the default
+    #[default]
+    Al16Utf16,
+    /// Oracle's UTF8, which is CESU-8: characters beyond the BMP are
+    /// encoded as two 3-byte surrogates rather than one 4-byte sequence
+    Utf8,
+}
+
+impl NationalCharset {
+    /// Charset the server names `name`, if supported
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "AL16UTF16" => Some(NationalCharset::Al16Utf16),
+            "UTF8" => Some(NationalCharset::Utf8),
+            _ => None,
+        }
+    }
+
+    /// Name the server uses for the charset
+    pub fn name(&self) -> &'static str {
+        match self {
+            NationalCharset::Al16Utf16 => "AL16UTF16",
+            NationalCharset::Utf8 => "UTF8",
+        }
+    }
+
+    /// Decode NCHAR or NVARCHAR2 wire bytes
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        match self {
+            NationalCharset::Al16Utf16 => decode_utf16(data),
+            NationalCharset::Utf8 => decode_cesu8(data),
+        }
+    }
+
+    /// Encode `s` for an NCHAR or NVARCHAR2 bind
+    pub fn encode(&self, s: &str) -> Vec<u8> {
+        match self {
+            NationalCharset::Al16Utf16 => s.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+            NationalCharset::Utf8 => {
+                let mut bytes = Vec::with_capacity(s.len());
+                for c in s.chars() {
+                    if c.len_utf16() == 1 {
+                        bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                        continue;
+                    }
+                    for unit in c.encode_utf16(&mut [0; 2]) {
+                        bytes.extend([
+                            0xE0 | (*unit >> 12) as u8,
+                            0x80 | (*unit >> 6 & 0x3F) as u8,
+                            0x80 | (*unit & 0x3F) as u8,
+                        ]);
+                    }
+                }
+                bytes
+            }
+        }
+    }
+}
+
+/// Length of `s` in NCHAR characters, which count a character beyond the
+/// BMP as two in either national charset
+pub(crate) fn nchar_len(s: &str) -> usize {
+    s.encode_utf16().count()
+}
+
+pub(crate) fn decode_utf16(data: &[u8]) -> Result<String> {
+    if !data.len().is_multiple_of(2) {
+        return Err(Error::Encoding("odd length UTF-16 data".into()));
+    }
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).map_err(|e| Error::Encoding(e.to_string()))
+}
+
+/// Decode CESU-8, falling back from UTF-8 only when surrogates appear
+fn decode_cesu8(data: &[u8]) -> Result<String> {
+    if let Ok(s) = std::str::from_utf8(data) {
+        return Ok(s.to_string());
+    }
+    let invalid = || Error::Encoding(format!("invalid UTF8 national charset bytes {:02x?}", data));
+    let mut units = Vec::with_capacity(data.len());
+    let mut rest = data;
+    while let Some(&lead) = rest.first() {
+        let (width, bits) = match lead {
+            0x00..=0x7F => (1, lead as u32),
+            0xC0..=0xDF => (2, (lead & 0x1F) as u32),
+            0xE0..=0xEF => (3, (lead & 0x0F) as u32),
+            0xF0..=0xF4 => (4, (lead & 0x07) as u32),
+            _ => return Err(invalid()),
+        };
+        let (sequence, tail) = rest.split_at_checked(width).ok_or_else(invalid)?;
+        let code = sequence[1..]
+            .iter()
+            .try_fold(bits, |code, &b| {
+                (b & 0xC0 == 0x80).then_some(code << 6 | (b & 0x3F) as u32)
+            })
+            .ok_or_else(invalid)?;
+        match char::from_u32(code) {
+            Some(c) => units.extend(c.encode_utf16(&mut [0; 2]).iter()),
+            // A lone surrogate, to pair with its neighbour
+            None => units.push(u16::try_from(code).map_err(|_| invalid())?),
+        }
+        rest = tail;
+    }
+    String::from_utf16(&units).map_err(|e| Error::Encoding(e.to_string()))
+}
+
+/// Sign, base-100 exponent and base-100 digits of an Oracle NUMBER
+struct NumberParts {
+    positive: bool,
+    exponent: i32,
+    digits: Vec<i32>,
+}
+
+impl NumberParts {
+    fn parse(data: &[u8]) -> Result<Self> {
+        let invalid = || Error::Encoding(format!("invalid NUMBER bytes {:02x?}", data));
+        let (&head, digits) = data.split_first().ok_or_else(invalid)?;
+        if head == 0x80 {
+            return Ok(Self {
+                positive: true,
+                exponent: 0,
+                digits: Vec::new(),
+            });
+        }
+
+        let positive = head & 0x80 != 0;
+        let (exponent, digits) = if positive {
+            ((head & 0x7F) as i32 - 65, digits)
+        } else {
+            let digits = digits.strip_suffix(&[102]).unwrap_or(digits);
+            ((!head & 0x7F) as i32 - 65, digits)
+        };
+        let digits: Vec<i32> = digits
+            .iter()
+            .map(|&b| {
+                if positive {
+                    b as i32 - 1
+                } else {
+                    101 - b as i32
+                }
+            })
+            .collect();
+        if digits.iter().any(|d| !(0..100).contains(d)) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            positive,
+            exponent,
+            digits,
+        })
+    }
+
+    /// The value if it is an integer, `None` if it has fractional digits
+    /// or does not fit an `i128`
+    fn integer(&self) -> Option<i128> {
+        if self.digits.is_empty() {
+            return Some(0);
+        }
+        if self.digits.len() as i32 > self.exponent + 1 {
+            return None;
+        }
+        let n = (0..=self.exponent).try_fold(0i128, |n, i| {
+            let digit = self.digits.get(i as usize).copied().unwrap_or(0);
+            n.checked_mul(100)?.checked_add(digit as i128)
+        })?;
+        Some(if self.positive { n } else { -n })
+    }
+
+    /// Canonical decimal digits, such as `-1234.5` or `0.001`
+    fn to_decimal(&self) -> String {
+        if self.digits.is_empty() {
+            return "0".into();
+        }
+        let pair = |d: &i32| format!("{:02}", d);
+        let (integer, fraction) = if self.exponent >= 0 {
+            let split = (self.exponent as usize + 1).min(self.digits.len());
+            let mut integer: String = self.digits[..split].iter().map(pair).collect();
+            for _ in split..=self.exponent as usize {
+                integer.push_str("00");
+            }
+            let fraction: String = self.digits[split..].iter().map(pair).collect();
+            (integer, fraction)
+        } else {
+            let zeros = "00".repeat((-self.exponent - 1) as usize);
+            let digits: String = self.digits.iter().map(pair).collect();
+            (String::new(), zeros + &digits)
+        };
+
+        let integer = integer.trim_start_matches('0');
+        let fraction = fraction.trim_end_matches('0');
+        let mut out = String::new();
+        if !self.positive {
+            out.push('-');
+        }
+        out.push_str(if integer.is_empty() { "0" } else { integer });
+        if !fraction.is_empty() {
+            out.push('.');
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    fn float(&self) -> f64 {
+        let value: f64 = self
+            .digits
+            .iter()
+            .enumerate()
+            .map(|(i, &d)| d as f64 * 100f64.powi(self.exponent - i as i32))
+            .sum();
+        if self.positive {
+            value
+        } else {
+            -value
+        }
+    }
+}
+
+/// Decode an Oracle NUMBER, as an integer when it has no fractional digits
+pub(crate) fn decode_number(data: &[u8]) -> Result<Value> {
+    let parts = NumberParts::parse(data)?;
+    match parts.integer().and_then(|n| i64::try_from(n).ok()) {
+        Some(n) => Ok(Value::Integer(n)),
+        None => Ok(Value::Float(parts.float())),
+    }
+}
+
+/// Decode an Oracle NUMBER as `format` says
+pub(crate) fn decode_number_as(data: &[u8], format: NumberFormat) -> Result<Value> {
+    if format == NumberFormat::Native {
+        return decode_number(data);
+    }
+    let parts = NumberParts::parse(data)?;
+    let decimal = parts.to_decimal();
+    if format == NumberFormat::String {
+        return Ok(Value::String(decimal));
+    }
+    if let Some(n) = parts.integer().and_then(|n| i64::try_from(n).ok()) {
+        return Ok(Value::Integer(n));
+    }
+    // Up to 15 significant digits survive the trip through f64
+    let digits = decimal.trim_start_matches('-').replace('.', "");
+    let significant = digits.trim_matches('0').len();
+    match decimal.parse::<f64>() {
+        Ok(f) if significant <= 15 && f.is_finite() => Ok(Value::Float(f)),
+        _ => Ok(Value::String(decimal)),
+    }
+}
+
+/// Canonical decimal string of an Oracle NUMBER, exact to its last digit
+pub(crate) fn decode_number_string(data: &[u8]) -> Result<String> {
+    Ok(NumberParts::parse(data)?.to_decimal())
+}
+
+/// Decode an integral Oracle NUMBER exactly, `None` if it has
+/// fractional digits or does not fit an `i128`
+///
+/// NUMBER holds at most 38 significant digits, but scales them by up to
+/// 10^125.
+pub(crate) fn decode_integer(data: &[u8]) -> Result<Option<i128>> {
+    Ok(NumberParts::parse(data)?.integer())
+}
+
+/// Encode an integer as an Oracle NUMBER
+pub(crate) fn encode_number(n: i64) -> Vec<u8> {
+    if n == 0 {
+        return vec![0x80];
+    }
+
+    let mut digits = Vec::new();
+    let mut rest = n.unsigned_abs();
+    while rest > 0 {
+        digits.push((rest % 100) as u8);
+        rest /= 100;
+    }
+    digits.reverse();
+    let exponent = digits.len() as u8 - 1;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    if n > 0 {
+        let mut out = vec![0xC1 + exponent];
+        out.extend(digits.iter().map(|d| d + 1));
+        out
+    } else {
+        let mut out = vec![0x3E - exponent];
+        out.extend(digits.iter().map(|d| 101 - d));
+        if out.len() < 21 {
+            out.push(102);
+        }
+        out
+    }
+}
+
+pub(crate) fn decode_binary_double(data: &[u8]) -> Result<f64> {
+    let mut bytes: [u8; 8] = data
+        .try_into()
+        .map_err(|_| Error::Encoding("BINARY_DOUBLE must be 8 bytes".into()))?;
+    flip_sortable(&mut bytes);
+    Ok(f64::from_be_bytes(bytes))
+}
+
+pub(crate) fn decode_binary_float(data: &[u8]) -> Result<f32> {
+    let mut bytes: [u8; 4] = data
+        .try_into()
+        .map_err(|_| Error::Encoding("BINARY_FLOAT must be 4 bytes".into()))?;
+    flip_sortable(&mut bytes);
+    Ok(f32::from_be_bytes(bytes))
+}
+
+/// Undo the byte-comparable encoding of IEEE floats
+fn flip_sortable(bytes: &mut [u8]) {
+    if bytes[0] & 0x80 != 0 {
+        bytes[0] &= 0x7F;
+    } else {
+        bytes.iter_mut().for_each(|b| *b = !*b);
+    }
+}
+
+/// Encode big-endian IEEE float bytes so they compare as the floats do
+pub(crate) fn make_sortable(bytes: &mut [u8]) {
+    if bytes[0] & 0x80 == 0 {
+        bytes[0] |= 0x80;
+    } else {
+        bytes.iter_mut().for_each(|b| *b = !*b);
+    }
+}
+
+/// Decode DATE (7 bytes) and TIMESTAMP (11 or 13 bytes) values
+fn decode_datetime(data: &[u8]) -> Result<NaiveDateTime> {
+    let invalid = || Error::Encoding(format!("invalid DATE bytes {:02x?}", data));
+    if data.len() < 7 {
+        return Err(invalid());
+    }
+    let year = (data[0] as i32 - 100) * 100 + data[1] as i32 - 100;
+    let nanos = match data.get(7..11) {
+        Some(n) => u32::from_be_bytes([n[0], n[1], n[2], n[3]]),
+        None => 0,
+    };
+    // Hours, minutes and seconds are stored plus one
+    let [hour, minute, second] = [data[4], data[5], data[6]].map(|b| (b as u32).checked_sub(1));
+    NaiveDate::from_ymd_opt(year, data[2] as u32, data[3] as u32)
+        .zip(hour.zip(minute).zip(second))
+        .and_then(|(date, ((hour, minute), second))| {
+            date.and_hms_nano_opt(hour, minute, second, nanos)
+        })
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_round_trip() {
+        for n in [
+            0,
+            1,
+            -1,
+            100,
+            -100,
+            12_345,
+            -987_654_321,
+            i64::MAX,
+            i64::MIN,
+        ] {
+            let value = decode_number(&encode_number(n)).unwrap();
+            assert_eq!(value, Value::Integer(n), "{}", n);
+        }
+        assert_eq!(encode_number(1), vec![0xC1, 0x02]);
+        assert_eq!(encode_number(-1), vec![0x3E, 0x64, 0x66]);
+
+        // 1.5 and -0.25
+        assert_eq!(
+            decode_number(&[0xC1, 0x02, 0x33]).unwrap(),
+            Value::Float(1.5)
+        );
+        assert_eq!(
+            decode_number(&[0x3F, 0x4C, 0x66]).unwrap(),
+            Value::Float(-0.25)
+        );
+        assert!(decode_number(&[]).is_err());
+    }
+
+    #[test]
+    fn test_read_row_slices_buffer() {
+        let mut buf = BytesMut::new();
+        write_value(&mut buf, Some(&encode_number(42)));
+        write_value(&mut buf, None);
+        write_value(&mut buf, Some("x".repeat(300).as_bytes()));
+        write_value(&mut buf, Some(b"KING"));
+        let buffer = buf.freeze();
+
+        let types = [
+            OracleType::Number,
+            OracleType::Varchar2,
+            OracleType::Clob,
+            OracleType::Varchar2,
+        ];
+        let mut offset = 0;
+        let row = read_row(&buffer, &mut offset, &types).unwrap();
+        assert_eq!(offset, buffer.len());
+
+        assert_eq!(row[0].decode().unwrap(), Value::Integer(42));
+        assert!(row[1].is_null());
+        assert_eq!(row[2].as_str().map(str::len), Some(300));
+        assert_eq!(row[3].as_str(), Some("KING"));
+        // Inline values point into the fetch buffer
+        let name = row[3].bytes().unwrap();
+        assert_eq!(name.as_ptr(), buffer[buffer.len() - 4..].as_ptr());
+
+        let mut offset = 0;
+        assert!(read_row(&buffer.slice(..10), &mut offset, &types).is_err());
+    }
+
+    #[test]
+    fn test_long_columns_reassemble_pieces() {
+        let text = "y".repeat(600);
+        let mut buf = BytesMut::new();
+        write_value(&mut buf, Some(text.as_bytes()));
+        write_value(&mut buf, Some(&[0xAB; 300]));
+        let buffer = buf.freeze();
+        let types = [OracleType::Long, OracleType::LongRaw];
+
+        let mut offset = 0;
+        let row = read_row_limited(&buffer, &mut offset, &types, 1000).unwrap();
+        assert_eq!(row[0].decode().unwrap(), Value::String(text));
+        assert_eq!(row[1].decode().unwrap(), Value::Bytes(vec![0xAB; 300]));
+
+        let mut offset = 0;
+        let err = read_row_limited(&buffer, &mut offset, &types, 500).unwrap_err();
+        assert!(matches!(err, Error::ResultTooLarge { limit: 500, .. }));
+        // The cap only applies to LONG columns
+        let mut offset = 0;
+        let clob = [OracleType::Clob, OracleType::Raw];
+        assert!(read_row_limited(&buffer, &mut offset, &clob, 100).is_ok());
+    }
+
+    #[test]
+    fn test_number_formats() {
+        // 12345678901234567890.12, past i64 and f64's 15 digits
+        let wide = [0xCA, 13, 35, 57, 79, 91, 13, 35, 57, 79, 91, 13];
+        assert_eq!(
+            decode_number_string(&wide).unwrap(),
+            "12345678901234567890.12"
+        );
+        assert!(matches!(decode_number(&wide).unwrap(), Value::Float(_)));
+        assert_eq!(
+            decode_number_as(&wide, NumberFormat::Exact).unwrap(),
+            Value::String("12345678901234567890.12".into())
+        );
+
+        // 0.001 and -1.5 fit f64 exactly enough
+        assert_eq!(decode_number_string(&[0xBF, 11]).unwrap(), "0.001");
+        assert_eq!(
+            decode_number_as(&[0xBF, 11], NumberFormat::Exact).unwrap(),
+            Value::Float(0.001)
+        );
+        let minus = [0x3E, 100, 51, 102];
+        assert_eq!(decode_number_string(&minus).unwrap(), "-1.5");
+
+        let million = encode_number(1_000_000);
+        assert_eq!(
+            decode_number_as(&million, NumberFormat::Exact).unwrap(),
+            Value::Integer(1_000_000)
+        );
+        assert_eq!(
+            decode_number_as(&million, NumberFormat::String).unwrap(),
+            Value::String("1000000".into())
+        );
+        assert_eq!(decode_number_string(&[0x80]).unwrap(), "0");
+
+        let raw = RawValue::new(OracleType::Number, Some(Bytes::copy_from_slice(&wide)));
+        assert_eq!(
+            <String as crate::types::FromSql>::from_raw(&raw).unwrap(),
+            "12345678901234567890.12"
+        );
+    }
+
+    #[test]
+    fn test_datetime_and_float_decoding() {
+        // 2024-02-29 13:45:30.5
+        let ts = RawValue::new(
+            OracleType::Timestamp,
+            Some(Bytes::from_static(&[
+                120, 124, 2, 29, 14, 46, 31, 0x1D, 0xCD, 0x65, 0x00,
+            ])),
+        );
+        let expected = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_milli_opt(13, 45, 30, 500)
+            .unwrap();
+        assert_eq!(ts.decode().unwrap(), Value::Timestamp(expected));
+        // A zero hour byte is corrupt, not an underflow
+        let corrupt = RawValue::new(
+            OracleType::Date,
+            Some(Bytes::from_static(&[120, 124, 2, 29, 0, 46, 31])),
+        );
+        assert!(matches!(corrupt.decode(), Err(Error::Encoding(_))));
+
+        let mut bytes = 2.5f64.to_be_bytes();
+        bytes[0] |= 0x80;
+        let double = RawValue::new(
+            OracleType::BinaryDouble,
+            Some(Bytes::copy_from_slice(&bytes)),
+        );
+        assert_eq!(double.decode().unwrap(), Value::Float(2.5));
+
+        let mut bytes = (-2.5f64).to_be_bytes();
+        bytes.iter_mut().for_each(|b| *b = !*b);
+        let double = RawValue::new(
+            OracleType::BinaryDouble,
+            Some(Bytes::copy_from_slice(&bytes)),
+        );
+        assert_eq!(double.decode().unwrap(), Value::Float(-2.5));
+    }
+
+    #[test]
+    fn test_boolean_wire_form() {
+        let decode = |bytes: &'static [u8]| {
+            RawValue::new(OracleType::Boolean, Some(Bytes::from_static(bytes)))
+                .decode()
+                .unwrap()
+        };
+        // The server sends a length byte of 1 ahead of the flag byte
+        assert_eq!(decode(&[1, 1]), Value::Boolean(true));
+        assert_eq!(decode(&[1, 0]), Value::Boolean(false));
+        // A bare flag byte decodes the same way
+        assert_eq!(decode(&[1]), Value::Boolean(true));
+        assert_eq!(decode(&[0]), Value::Boolean(false));
+        assert_eq!(
+            RawValue::new(OracleType::Boolean, None).decode().unwrap(),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_national_charsets() {
+        // U+1D11E needs a surrogate pair
+        let text = "clef 𝄞";
+        let utf16 = NationalCharset::Al16Utf16.encode(text);
+        assert_eq!(&utf16[10..], [0xD8, 0x34, 0xDD, 0x1E]);
+        let cesu8 = NationalCharset::Utf8.encode(text);
+        assert_eq!(&cesu8[5..], [0xED, 0xA0, 0xB4, 0xED, 0xB4, 0x9E]);
+        assert_eq!(nchar_len(text), 7);
+
+        for (charset, bytes) in [
+            (NationalCharset::Al16Utf16, utf16),
+            (NationalCharset::Utf8, cesu8),
+        ] {
+            let raw = RawValue::new(OracleType::NVarchar2, Some(Bytes::from(bytes)))
+                .with_national_charset(charset);
+            assert_eq!(
+                raw.decode().unwrap(),
+                Value::String(text.into()),
+                "{:?}",
+                charset
+            );
+        }
+        // Plain UTF-8 still decodes as UTF8, and a lone surrogate fails
+        assert_eq!(NationalCharset::Utf8.decode("𝄞".as_bytes()).unwrap(), "𝄞");
+        assert!(NationalCharset::Utf8.decode(&[0xED, 0xA0, 0xB4]).is_err());
+        assert!(NationalCharset::Al16Utf16.decode(&[0xD8, 0x34]).is_err());
+        assert_eq!(
+            NationalCharset::from_name("al16utf16"),
+            Some(NationalCharset::Al16Utf16)
+        );
+    }
+}
@@ -0,0 +1,210 @@
+// Distributed (XA) transactions
+
+//! Two-phase commit for connections taking part in a global transaction
+//! run by an external transaction manager.
+//!
+//! A manager enlists a connection with [`Connection::enlist`], which asks
+//! the [`TransactionManager`] for the branch [`Xid`] and starts the branch.
+//! From then on the manager drives the connection through [`XaResource`],
+//! the same way for any resource it coordinates:
+//!
+//! ```rust,ignore
+//! use oracledb_rs::xa::{Vote, XaResource};
+//!
+//! let xid = conn.enlist(&manager).await?;
+//! conn.execute_dml("UPDATE accounts SET balance = balance - 10 WHERE id = 1", &[]).await?;
+//!
+//! let resource: &mut dyn XaResource = &mut conn;
+//! resource.end(&xid).await?;
+//! if resource.prepare(&xid).await? == Vote::Commit {
+//!     resource.commit(&xid, false).await?;
+//! }
+//! ```
+//!
+//! Branches prepared but never resolved, for example because the manager
+//! crashed, are listed by [`XaResource::recover`].
+
+use crate::{Connection, Error, Result};
+use futures::future::BoxFuture;
+
+/// Longest global transaction ID or branch qualifier, in bytes
+pub const MAX_XID_PART: usize = 64;
+
+/// Identifier of one branch of a global transaction
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Xid {
+    /// Format identifier chosen by the transaction manager
+    pub format_id: i32,
+    /// Global transaction ID, shared by all branches
+    pub global_transaction_id: Vec<u8>,
+    /// Branch qualifier, distinct per branch
+    pub branch_qualifier: Vec<u8>,
+}
+
+impl Xid {
+    /// Create an XID, checking the parts fit in [`MAX_XID_PART`] bytes
+    pub fn new(
+        format_id: i32,
+        global_transaction_id: impl Into<Vec<u8>>,
+        branch_qualifier: impl Into<Vec<u8>>,
+    ) -> Result<Self> {
+        let xid = Self {
+            format_id,
+            global_transaction_id: global_transaction_id.into(),
+            branch_qualifier: branch_qualifier.into(),
+        };
+        if xid.global_transaction_id.is_empty()
+            || xid.global_transaction_id.len() > MAX_XID_PART
+            || xid.branch_qualifier.len() > MAX_XID_PART
+        {
+            return Err(Error::InvalidConfiguration(format!(
+                "XID parts must be 1 to {} bytes: {:?}",
+                MAX_XID_PART, xid
+            )));
+        }
+        Ok(xid)
+    }
+}
+
+/// Outcome of preparing a branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vote {
+    /// Changes are prepared and wait for commit or rollback
+    Commit,
+    /// The branch changed nothing and is already finished
+    ReadOnly,
+}
+
+/// Transaction manager a connection can enlist with
+pub trait TransactionManager: Send + Sync {
+    /// XID of a new branch of the current global transaction
+    fn branch_xid(&self) -> Result<Xid>;
+}
+
+/// Resource a transaction manager drives through two-phase commit
+pub trait XaResource: Send {
+    /// Start work on branch `xid`
+    fn start<'a>(&'a mut self, xid: &'a Xid) -> BoxFuture<'a, Result<()>>;
+
+    /// Detach from branch `xid` once its work is done
+    fn end<'a>(&'a mut self, xid: &'a Xid) -> BoxFuture<'a, Result<()>>;
+
+    /// Prepare branch `xid` to commit
+    fn prepare<'a>(&'a mut self, xid: &'a Xid) -> BoxFuture<'a, Result<Vote>>;
+
+    /// Commit branch `xid`; with `one_phase`, without preparing it first
+    fn commit<'a>(&'a mut self, xid: &'a Xid, one_phase: bool) -> BoxFuture<'a, Result<()>>;
+
+    /// Roll back branch `xid`
+    fn rollback<'a>(&'a mut self, xid: &'a Xid) -> BoxFuture<'a, Result<()>>;
+
+    /// Branches prepared and not yet committed or rolled back
+    fn recover(&mut self) -> BoxFuture<'_, Result<Vec<Xid>>>;
+}
+
+impl XaResource for Connection {
+    fn start<'a>(&'a mut self, xid: &'a Xid) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.tpc_begin(xid))
+    }
+
+    fn end<'a>(&'a mut self, xid: &'a Xid) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.tpc_end(xid))
+    }
+
+    fn prepare<'a>(&'a mut self, xid: &'a Xid) -> BoxFuture<'a, Result<Vote>> {
+        Box::pin(self.tpc_prepare(xid))
+    }
+
+    fn commit<'a>(&'a mut self, xid: &'a Xid, one_phase: bool) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.tpc_commit(xid, one_phase))
+    }
+
+    fn rollback<'a>(&'a mut self, xid: &'a Xid) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.tpc_rollback(xid))
+    }
+
+    fn recover(&mut self) -> BoxFuture<'_, Result<Vec<Xid>>> {
+        Box::pin(self.tpc_recover())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xid_limits() {
+        assert!(Xid::new(0x1234, b"gtrid".to_vec(), b"bq".to_vec()).is_ok());
+        assert!(Xid::new(1, Vec::new(), b"bq".to_vec()).is_err());
+        assert!(Xid::new(1, vec![0; MAX_XID_PART + 1], Vec::new()).is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_two_phase_commit() {
+        use crate::testing::{column, Expectation, MockCall, MockConnection};
+        use crate::{OracleType, Value};
+
+        struct Manager;
+        impl TransactionManager for Manager {
+            fn branch_xid(&self) -> Result<Xid> {
+                Xid::new(0x1234, b"order-17".to_vec(), b"db1".to_vec())
+            }
+        }
+
+        let update = "UPDATE accounts SET balance = balance - 10 WHERE id = 1";
+        let pending = "SELECT formatid, globalid, branchid FROM dba_pending_transactions";
+        let mock = MockConnection::new()
+            .expect(Expectation::dml(update, 1))
+            .expect(Expectation::query(pending).returns(
+                vec![
+                    column("FORMATID", OracleType::Number),
+                    column("GLOBALID", OracleType::Raw),
+                    column("BRANCHID", OracleType::Raw),
+                ],
+                vec![vec![
+                    Value::Integer(0x1234),
+                    Value::Bytes(b"order-9".to_vec()),
+                    Value::Bytes(b"db1".to_vec()),
+                ]],
+            ));
+        let mut conn = mock.connect().await.unwrap();
+
+        let xid = conn.enlist(&Manager).await.unwrap();
+        conn.execute_dml(update, &[]).await.unwrap();
+        // Local commits are refused inside a branch
+        assert_eq!(conn.commit().await.unwrap_err().oracle_code(), Some(2089));
+
+        let resource: &mut dyn XaResource = &mut conn;
+        resource.end(&xid).await.unwrap();
+        assert_eq!(resource.prepare(&xid).await.unwrap(), Vote::Commit);
+        resource.commit(&xid, false).await.unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(calls[0], MockCall::TpcBegin(xid.clone()));
+        assert_eq!(
+            calls[2..],
+            [
+                MockCall::TpcEnd(xid.clone()),
+                MockCall::TpcPrepare(xid.clone()),
+                MockCall::TpcCommit {
+                    xid: xid.clone(),
+                    one_phase: false
+                },
+            ]
+        );
+
+        // A branch that changed nothing votes read-only
+        let xid = Xid::new(0x1234, b"order-18".to_vec(), b"db1".to_vec()).unwrap();
+        conn.tpc_begin(&xid).await.unwrap();
+        conn.tpc_end(&xid).await.unwrap();
+        assert_eq!(conn.tpc_prepare(&xid).await.unwrap(), Vote::ReadOnly);
+
+        let recovered = conn.recover().await.unwrap();
+        assert_eq!(
+            recovered,
+            [Xid::new(0x1234, b"order-9".to_vec(), b"db1".to_vec()).unwrap()]
+        );
+        mock.verify();
+    }
+}